@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: MIT
+
+//! Criterion benchmarks, run with `cargo bench --features=bench`.
+
+mod data;
+
+use bech32grs::primitives::decode::{CheckedHrpstring, SegwitHrpstring, UncheckedHrpstring};
+use bech32grs::{hrp, segwit, Bech32m, Hrp};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn segwit_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segwit_encode");
+    let (_, _, p2wpkh) = segwit::decode(data::SEGWIT_V0_P2WPKH).expect("valid address");
+    let (_, _, p2wsh) = segwit::decode(data::SEGWIT_V0_P2WSH).expect("valid address");
+    let (_, _, v1) = segwit::decode(data::SEGWIT_V1).expect("valid address");
+
+    group.bench_function("v0_20_bytes", |b| {
+        b.iter(|| segwit::encode_v0(hrp::GRS, black_box(&p2wpkh)).expect("valid program"))
+    });
+    group.bench_function("v0_32_bytes", |b| {
+        b.iter(|| segwit::encode_v0(hrp::GRS, black_box(&p2wsh)).expect("valid program"))
+    });
+    group.bench_function("v1_20_bytes", |b| {
+        b.iter(|| segwit::encode_v1(hrp::GRS, black_box(&v1)).expect("valid program"))
+    });
+    group.finish();
+}
+
+fn segwit_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segwit_decode");
+    for (name, address) in [
+        ("v0_20_bytes", data::SEGWIT_V0_P2WPKH),
+        ("v0_32_bytes", data::SEGWIT_V0_P2WSH),
+        ("v0_32_bytes_upper", data::SEGWIT_V0_P2WSH_UPPER),
+        ("v1_20_bytes", data::SEGWIT_V1),
+    ]
+    .iter()
+    {
+        group.bench_with_input(BenchmarkId::from_parameter(name), address, |b, address| {
+            b.iter(|| segwit::decode(black_box(address)).expect("valid address"))
+        });
+    }
+    // Decoding fails once the checksum is found to be invalid, before converting the program.
+    group.bench_function("bad_checksum", |b| {
+        b.iter(|| segwit::decode(black_box(data::SEGWIT_BAD_CHECKSUM)).unwrap_err())
+    });
+    group.finish();
+}
+
+fn segwit_validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segwit_validate");
+    // Validating does not convert or allocate the witness program, decoding does.
+    group.bench_function("validate", |b| {
+        b.iter(|| segwit::validate(black_box(data::SEGWIT_V0_P2WSH)).expect("valid address"))
+    });
+    group.bench_function("decode", |b| {
+        b.iter(|| {
+            segwit::decode(black_box(data::SEGWIT_V0_P2WSH)).map(drop).expect("valid address")
+        })
+    });
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    let hrp = Hrp::parse(data::HRP).expect("valid hrp");
+    for (chars, len) in [(90, data::PAYLOAD_LEN_90), (1000, data::PAYLOAD_LEN_1000)].iter() {
+        let payload = data::payload(*len);
+        group.throughput(Throughput::Bytes(*len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(chars), &payload, |b, payload| {
+            b.iter(|| bech32grs::encode::<Bech32m>(hrp, black_box(payload)).expect("valid data"))
+        });
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    let hrp = Hrp::parse(data::HRP).expect("valid hrp");
+    for (chars, len) in [(90, data::PAYLOAD_LEN_90), (1000, data::PAYLOAD_LEN_1000)].iter() {
+        let s = bech32grs::encode::<Bech32m>(hrp, &data::payload(*len)).expect("valid data");
+        assert_eq!(s.len(), *chars);
+        group.throughput(Throughput::Bytes(*chars as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(chars), &s, |b, s| {
+            b.iter(|| bech32grs::decode(black_box(s)).expect("valid string"))
+        });
+    }
+    group.finish();
+}
+
+fn verify_checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_checksum");
+    let hrp = Hrp::parse(data::HRP).expect("valid hrp");
+    for (chars, len) in [(90, data::PAYLOAD_LEN_90), (1000, data::PAYLOAD_LEN_1000)].iter() {
+        let s = bech32grs::encode::<Bech32m>(hrp, &data::payload(*len)).expect("valid data");
+        let unchecked = UncheckedHrpstring::new(&s).expect("valid string");
+        group.throughput(Throughput::Bytes(*chars as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(chars), &unchecked, |b, unchecked| {
+            b.iter(|| black_box(unchecked).validate_checksum::<Bech32m>().expect("valid checksum"))
+        });
+    }
+    // Same as above for a segwit address, including character validation.
+    group.bench_function("segwit", |b| {
+        b.iter(|| SegwitHrpstring::new(black_box(data::SEGWIT_V0_P2WSH)).expect("valid address"))
+    });
+    group.finish();
+}
+
+fn deferred_checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deferred_checksum");
+    // One mainnet address in every hundred, the rest testnet.
+    let addresses: Vec<String> = (0..100)
+        .map(|i| {
+            let hrp = if i == 0 { hrp::GRS } else { hrp::TGRS };
+            segwit::encode_v1(hrp, &data::payload(32)).expect("valid program")
+        })
+        .collect();
+    group.throughput(Throughput::Elements(addresses.len() as u64));
+
+    group.bench_function("verify_all", |b| {
+        b.iter(|| {
+            black_box(&addresses)
+                .iter()
+                .filter(|s| {
+                    let unchecked = UncheckedHrpstring::new(s).expect("valid characters");
+                    let checked = unchecked.validate_and_remove_checksum::<Bech32m>();
+                    checked.expect("valid checksum").hrp() == hrp::GRS
+                })
+                .count()
+        })
+    });
+    group.bench_function("verify_one_percent", |b| {
+        b.iter(|| {
+            black_box(&addresses)
+                .iter()
+                .filter(|s| {
+                    let unchecked = UncheckedHrpstring::new(s).expect("valid characters");
+                    let chars = unchecked.parse_data_chars();
+                    chars.hrp() == hrp::GRS && chars.verify::<Bech32m>().is_ok()
+                })
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn verify_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_batch");
+    // Every third address has its last checksum character changed.
+    let addresses: Vec<String> = (0..data::BATCH_LEN)
+        .map(|i| {
+            let mut address = segwit::encode_v1(hrp::GRS, &[i as u8; 32]).expect("valid program");
+            if i % 3 == 0 {
+                let last = if address.ends_with('q') { 'p' } else { 'q' };
+                address.pop();
+                address.push(last);
+            }
+            address
+        })
+        .collect();
+    let inputs: Vec<&str> = addresses.iter().map(|s| s.as_str()).collect();
+    group.throughput(Throughput::Elements(inputs.len() as u64));
+
+    group.bench_function("naive_loop", |b| {
+        b.iter(|| {
+            black_box(&inputs)
+                .iter()
+                .map(|s| CheckedHrpstring::new::<Bech32m>(s).is_ok())
+                .collect::<Vec<bool>>()
+        })
+    });
+    // Run with `--features=bench,rayon` for the parallel version.
+    let name = if cfg!(feature = "rayon") { "batch_rayon" } else { "batch" };
+    group.bench_function(name, |b| {
+        b.iter(|| bech32grs::verify_batch::<Bech32m>(black_box(&inputs)))
+    });
+    group.finish();
+}
+
+fn characters_4k(c: &mut Criterion) {
+    let mut group = c.benchmark_group("characters_4k");
+    // Compare with `RUSTFLAGS=--cfg=scalar`, which checks the characters without SSE2.
+    let lower = data::string_4k();
+    let upper = lower.to_uppercase();
+    group.throughput(Throughput::Bytes(lower.len() as u64));
+    for (name, s) in [("lower", &lower), ("upper", &upper)].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), s, |b, s| {
+            b.iter(|| UncheckedHrpstring::new(black_box(s)).expect("valid string"))
+        });
+    }
+    group.finish();
+}
+
+fn hrp_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hrp_parse");
+    for hrp in data::HRPS.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(hrp.len()), hrp, |b, hrp| {
+            b.iter(|| Hrp::parse(black_box(hrp)).expect("valid hrp"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    segwit_encode,
+    segwit_decode,
+    segwit_validate,
+    encode,
+    decode,
+    verify_checksum,
+    deferred_checksum,
+    verify_batch,
+    characters_4k,
+    hrp_parse
+);
+criterion_main!(benches);
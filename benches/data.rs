@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT
+
+//! Fixed inputs for the benchmarks, so that numbers are comparable across machines and time.
+
+/// Segwit v0 address with a 20 byte witness program (BIP-173 test vector).
+pub const SEGWIT_V0_P2WPKH: &str = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5";
+
+/// Segwit v0 address with a 32 byte witness program (BIP-173 test vector).
+pub const SEGWIT_V0_P2WSH: &str = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+
+/// [`SEGWIT_V0_P2WSH`] in uppercase, as used in QR codes.
+pub const SEGWIT_V0_P2WSH_UPPER: &str =
+    "GRS1Q2S3RJWVAM9DT2FTT4SQXQJF3TWAV0GDX0K0Q2ETXFLX38C3X8TNSLKYLAY";
+
+/// Segwit v1 address with a 20 byte witness program.
+pub const SEGWIT_V1: &str = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+
+/// [`SEGWIT_V0_P2WSH`] with the last checksum character changed.
+pub const SEGWIT_BAD_CHECKSUM: &str =
+    "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylaz";
+
+/// Human-readable parts, from short to the maximum length of 83 characters.
+pub const HRPS: [&str; 3] = [
+    "grs",
+    "lnurl",
+    "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio",
+];
+
+/// The human-readable part used for the generic encode and decode benchmarks.
+pub const HRP: &str = "test";
+
+/// Payload length that encodes (with [`HRP`] and a bech32m checksum) to a 90 character string.
+pub const PAYLOAD_LEN_90: usize = 49;
+
+/// Payload length that encodes (with [`HRP`] and a bech32m checksum) to a 1000 character string.
+pub const PAYLOAD_LEN_1000: usize = 618;
+
+/// The number of strings in the batch verification benchmarks.
+pub const BATCH_LEN: usize = 10_000;
+
+/// Returns a `len` byte payload, the same bytes every time.
+pub fn payload(len: usize) -> Vec<u8> { (0..len).map(|i| (i * 37 + 11) as u8).collect() }
+
+/// Returns a string with the hrp "grs" and a 4 KiB data part using every bech32 character.
+pub fn string_4k() -> String { format!("grs1{}", "qpzry9x8gf2tvdw0s3jn54khce6mua7l".repeat(128)) }
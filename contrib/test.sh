@@ -15,6 +15,12 @@ if cargo --version | grep "1\.48"; then
     MSRV=true
 fi
 
+# Some dev-dependencies need a newer toolchain than the MSRV, see `Cargo.toml`.
+if [ "$MSRV" = false ]; then
+    export RUSTFLAGS="${RUSTFLAGS-} --cfg=test_deps"
+    export RUSTDOCFLAGS="${RUSTDOCFLAGS-} --cfg=test_deps"
+fi
+
 build_and_test () {
     cargo build --no-default-features --features="$1"
     cargo test --no-default-features --features="$1"
@@ -48,6 +54,52 @@ if [ "${DO_FEATURE_MATRIX-false}" = true ]; then
     build_and_test "std"
     build_and_test "alloc"
     build_and_test "std alloc"
+    build_and_test "bolt11"
+    build_and_test "bolt12"
+    build_and_test "lnurl"
+    build_and_test "nip19"
+    build_and_test "sp"
+    build_and_test "codex32"
+    build_and_test "ffi"
+    build_and_test "fuzzing"
+    build_and_test "test-utils"
+    build_and_test "metrics"
+    build_and_test "std metrics"
+    build_and_test "small-tables"
+    build_and_test "subtle"
+
+    # The portable character checks, used instead of SSE2 on targets other than x86.
+    RUSTFLAGS="${RUSTFLAGS-} --cfg=scalar" cargo test --no-default-features --features="std"
+
+    # The borsh, defmt, embedded-io, heapless, rayon, rkyv, schemars, serde, tracing and zeroize
+    # dependencies, and `core::error::Error`, are not built on the MSRV toolchain.
+    if [ "$MSRV" = false ]; then
+        build_and_test "core-error"
+        build_and_test "alloc core-error"
+        build_and_test "std core-error"
+        build_and_test "heapless"
+        build_and_test "std heapless"
+        build_and_test "defmt"
+        build_and_test "alloc defmt"
+        build_and_test "embedded-io"
+        build_and_test "alloc rayon"
+        build_and_test "tracing"
+        build_and_test "borsh"
+        build_and_test "rkyv"
+        build_and_test "std rkyv"
+        build_and_test "alloc serde"
+        build_and_test "std serde"
+        build_and_test "std schemars"
+        build_and_test "wasm"
+        build_and_test "zeroize"
+        build_and_test "alloc zeroize"
+    fi
+fi
+
+# Check that invalid `hrp!` literals and ignored `#[must_use]` values fail to compile, the expected
+# errors depend on the toolchain so CI only runs these on nightly.
+if [ "${DO_UI-false}" = true ]; then
+    RUSTFLAGS='--cfg=trybuild' cargo test --test hrp_macro --test must_use
 fi
 
 # Build the docs if told to (this only works with the nightly toolchain)
@@ -71,6 +123,8 @@ then
     fi
 
     RUSTFLAGS='--cfg=bench' cargo bench
+    # Run each of the criterion benchmarks once, to check that they still work.
+    cargo bench --features=bench --bench benchmarks -- --test
 fi
 
 exit 0
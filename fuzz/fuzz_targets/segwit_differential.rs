@@ -0,0 +1,98 @@
+extern crate bech32grs;
+
+use bech32grs::fuzzing::{interesting_corpus, mutate_valid};
+use bech32grs::primitives::decode::{CheckedHrpstring, SegwitHrpstring, UncheckedHrpstring};
+use bech32grs::{segwit, Bech32, Bech32m, Fe32, Hrp};
+
+/// Decodes a segwit address the long way, from a `CheckedHrpstring` and manual checks.
+fn decode_manually(s: &str) -> Option<(Hrp, Fe32, Vec<u8>)> {
+    if s.len() > segwit::MAX_STRING_LENGTH {
+        return None;
+    }
+    // The witness version selects the checksum algorithm.
+    let version = UncheckedHrpstring::new(s).ok()?.witness_version()?;
+    let mut checked = if version == segwit::VERSION_0 {
+        CheckedHrpstring::new::<Bech32>(s).ok()?
+    } else {
+        CheckedHrpstring::new::<Bech32m>(s).ok()?
+    };
+    let version = checked.remove_witness_version()?;
+    if version.to_u8() > 16 {
+        return None;
+    }
+    checked.validate_segwit_padding().ok()?;
+
+    let program = checked.byte_iter().collect::<Vec<u8>>();
+    let valid_length = if version == segwit::VERSION_0 {
+        program.len() == 20 || program.len() == 32
+    } else {
+        (segwit::MIN_WITNESS_PROGRAM_LENGTH..=segwit::MAX_WITNESS_PROGRAM_LENGTH)
+            .contains(&program.len())
+    };
+    if !valid_length {
+        return None;
+    }
+    Some((checked.hrp(), version, program))
+}
+
+// Checks that `SegwitHrpstring` agrees with the manual checks on mutations of the corpus.
+fn do_test(corpus: &[String], data: &[u8]) {
+    let (seed, rng_bytes) = match data.split_first() {
+        Some((&i, rest)) => (&corpus[usize::from(i) % corpus.len()], rest),
+        None => return,
+    };
+    let s = mutate_valid(seed, rng_bytes);
+
+    let segwit = SegwitHrpstring::new(&s)
+        .ok()
+        .map(|segwit| (segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect()));
+    assert_eq!(segwit, decode_manually(&s), "{}", s);
+}
+
+#[cfg(feature = "afl")]
+extern crate afl;
+#[cfg(feature = "afl")]
+fn main() {
+    let corpus = interesting_corpus();
+    afl::read_stdio_bytes(|data| {
+        do_test(&corpus, &data);
+    });
+}
+
+#[cfg(feature = "honggfuzz")]
+#[macro_use]
+extern crate honggfuzz;
+#[cfg(feature = "honggfuzz")]
+fn main() {
+    let corpus = interesting_corpus();
+    loop {
+        fuzz!(|data| {
+            do_test(&corpus, data);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_agrees() {
+        let corpus = interesting_corpus();
+        for i in 0..corpus.len() {
+            do_test(&corpus, &[i as u8]);
+        }
+    }
+
+    #[test]
+    fn mutations_agree() {
+        let corpus = interesting_corpus();
+        for i in 0..corpus.len() {
+            for kind in 0..6 {
+                for pos in 0..8 {
+                    do_test(&corpus, &[i as u8, kind, pos, pos * 5]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT
+
+//! Helpers shared by the `borsh` and `rkyv` implementations of the value types.
+//!
+//! The implementations themselves live next to the types, they need access to private fields.
+//! Both validate on deserialization, so a corrupted or malicious archive can never produce a value
+//! that the type's constructors would reject.
+
+#[cfg(feature = "rkyv")]
+use core::fmt;
+
+/// Returns the error for bytes that do not deserialize into a valid value.
+#[cfg(feature = "borsh")]
+pub(crate) fn invalid_data(msg: &'static str) -> borsh::io::Error {
+    borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, msg)
+}
+
+/// Reads a length prefixed byte string, as written by `[u8]::serialize`, into `buf`.
+///
+/// Returns the bytes read, or an error if the length is greater than `buf.len()`.
+#[cfg(feature = "borsh")]
+pub(crate) fn read_prefixed<'b, R: borsh::io::Read>(
+    reader: &mut R,
+    buf: &'b mut [u8],
+    too_long: &'static str,
+) -> borsh::io::Result<&'b [u8]> {
+    use borsh::BorshDeserialize as _;
+
+    let len = u32::deserialize_reader(reader)? as usize;
+    let buf = buf.get_mut(..len).ok_or_else(|| invalid_data(too_long))?;
+    reader.read_exact(buf)?;
+    Ok(buf)
+}
+
+/// An archived value that fails the validity checks of its type.
+#[cfg(feature = "rkyv")]
+#[derive(Debug)]
+pub(crate) struct InvalidArchive(pub(crate) &'static str);
+
+#[cfg(feature = "rkyv")]
+impl fmt::Display for InvalidArchive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid archive: {}", self.0)
+    }
+}
+
+// The `rkyv` feature has a higher MSRV than the rest of the crate.
+#[cfg(feature = "rkyv")]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for InvalidArchive {}
+
+/// Returns an `InvalidArchive` error with `msg` unless `valid` is true.
+#[cfg(feature = "rkyv")]
+pub(crate) fn check<E: rkyv::rancor::Source>(valid: bool, msg: &'static str) -> Result<(), E> {
+    if valid {
+        Ok(())
+    } else {
+        Err(E::new(InvalidArchive(msg)))
+    }
+}
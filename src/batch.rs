@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+//! Checksum validation of many strings at once.
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::primitives::checksum::Checksum;
+use crate::primitives::decode::UncheckedHrpstring;
+
+/// The minimum number of strings each `rayon` task validates.
+///
+/// Validating a string takes well under a microsecond, so smaller tasks cost more to schedule
+/// than they save.
+#[cfg(feature = "rayon")]
+const MIN_TASK_LEN: usize = 1024;
+
+/// Validates the checksums of `inputs`, returning whether each one is valid.
+///
+/// Element `i` of the result is `true` exactly if `CheckedHrpstring::new::<Ck>(inputs[i])` would
+/// succeed. Only the accept or reject decision is computed, no error values are built for the
+/// rejected strings, which makes this faster than a loop over [`CheckedHrpstring::new`] when most
+/// inputs are invalid.
+///
+/// With the `rayon` feature enabled the inputs are validated in parallel. The result is the same,
+/// in the same order, either way.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::Bech32;
+///
+/// let inputs = [
+///     "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+///     "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q", // Last character changed.
+///     "not a bech32 string",
+/// ];
+/// assert_eq!(bech32grs::verify_batch::<Bech32>(&inputs), [true, false, false]);
+/// ```
+///
+/// [`CheckedHrpstring::new`]: crate::primitives::decode::CheckedHrpstring::new
+pub fn verify_batch<Ck: Checksum>(inputs: &[&str]) -> Vec<bool> {
+    #[cfg(feature = "rayon")]
+    {
+        inputs
+            .par_iter()
+            .with_min_len(MIN_TASK_LEN)
+            .map(|s| UncheckedHrpstring::is_valid::<Ck>(s))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        inputs.iter().map(|s| UncheckedHrpstring::is_valid::<Ck>(s)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::CheckedHrpstring;
+    use crate::{hrp, segwit, Bech32, Bech32m, NoChecksum};
+
+    /// Valid and invalid strings, for both checksums.
+    fn inputs() -> Vec<String> {
+        let mut inputs = vec![
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p".to_string(),
+            "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P".to_string(),
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q".to_string(),
+            "Grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p".to_string(),
+            "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc".to_string(),
+            "a12uel5l".to_string(),
+            "a1lqfn3a".to_string(),
+            "grs1".to_string(),
+            "1qqqqqq".to_string(),
+            "no separator".to_string(),
+            String::new(),
+            format!("a1{}", "q".repeat(1100)),
+        ];
+        for i in 0..40 {
+            let program = [i as u8; 32];
+            inputs.push(segwit::encode_v0(hrp::TGRS, &program).unwrap());
+            // The same address with a data character changed.
+            let mut typo = segwit::encode_v1(hrp::GRS, &program).unwrap();
+            let replacement = if &typo[10..11] == "q" { "p" } else { "q" };
+            typo.replace_range(10..11, replacement);
+            inputs.push(typo);
+        }
+        inputs
+    }
+
+    fn check<Ck: Checksum>() {
+        let inputs = inputs();
+        let inputs = inputs.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        let want =
+            inputs.iter().map(|s| CheckedHrpstring::new::<Ck>(s).is_ok()).collect::<Vec<_>>();
+        assert!(want.contains(&true) && want.contains(&false));
+        assert_eq!(verify_batch::<Ck>(&inputs), want);
+    }
+
+    #[test]
+    fn matches_checked_hrpstring_bech32() { check::<Bech32>(); }
+
+    #[test]
+    fn matches_checked_hrpstring_bech32m() { check::<Bech32m>(); }
+
+    #[test]
+    fn matches_checked_hrpstring_no_checksum() { check::<NoChecksum>(); }
+
+    #[test]
+    fn empty_batch() {
+        assert!(verify_batch::<Bech32>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: MIT
+
+//! Framing of [BOLT-11] Lightning invoices.
+//!
+//! Invoices are bech32 strings but, unlike addresses, they are not limited to 90 characters and
+//! their data is not byte aligned. This module only handles the framing: it splits the
+//! human-readable part into the currency prefix and the amount, and splits the signature from the
+//! rest of the data. Parsing the tagged fields and verifying the signature is left to the caller.
+//!
+//! Enabled by the `bolt11` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::bolt11;
+//!
+//! // From the examples in BOLT-11.
+//! let invoice = "lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rh";
+//!
+//! let raw = bolt11::parse(invoice).expect("valid invoice");
+//! assert_eq!(raw.currency_prefix(), "bc");
+//! assert_eq!(raw.amount(), "2500u");
+//! assert_eq!(raw.signature().len(), bolt11::SIGNATURE_LENGTH);
+//!
+//! assert_eq!(bolt11::encode(raw.hrp(), raw.fes()), invoice);
+//! ```
+//!
+//! [BOLT-11]: <https://github.com/lightning/bolts/blob/master/11-payment-encoding.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::Fe32IterExt;
+use crate::primitives::Bech32Unlimited;
+use crate::Checksum;
+
+/// The number of field elements in the signature at the end of the data of an invoice.
+pub const SIGNATURE_LENGTH: usize = 104;
+
+/// The start of the human-readable part of all invoices.
+const PREFIX: &str = "ln";
+
+/// Parses the framing of a BOLT-11 invoice.
+///
+/// Validates the bech32 checksum, the `ln` prefix of the human-readable part, and that the data is
+/// long enough to contain a signature. Like any bech32 string the invoice may be uppercase, the
+/// returned human-readable part is always lowercase.
+pub fn parse(s: &str) -> Result<Bolt11Raw, ParseError> {
+    let checked = CheckedHrpstring::new::<Bech32Unlimited>(s)?;
+
+    let hrp = checked.hrp();
+    let hrp = Hrp::parse_unchecked(&hrp.to_lowercase());
+    let currency_and_amount = hrp.as_str().strip_prefix(PREFIX).ok_or(ParseError::InvalidPrefix)?;
+    let currency_len = currency_and_amount
+        .bytes()
+        .position(|b| b.is_ascii_digit())
+        .unwrap_or(currency_and_amount.len());
+    if currency_len == 0 {
+        return Err(ParseError::InvalidPrefix);
+    }
+
+    let fes = checked
+        .data_part_ascii_no_checksum()
+        .iter()
+        .map(|&b| Fe32::from_char_unchecked(b))
+        .collect::<Vec<Fe32>>();
+    if fes.len() < SIGNATURE_LENGTH {
+        return Err(ParseError::TooShort(fes.len()));
+    }
+
+    Ok(Bolt11Raw { hrp, amount_start: PREFIX.len() + currency_len, fes })
+}
+
+/// Encodes a BOLT-11 invoice.
+///
+/// `fes` is all the data of the invoice, including the signature. Unlike [`crate::encode`] the
+/// data does not have to be a whole number of bytes, and there is no maximum length.
+pub fn encode(hrp: Hrp, fes: &[Fe32]) -> String {
+    let len = hrp.len() + 1 + fes.len() + Bech32Unlimited::CHECKSUM_LENGTH; // +1 for separator
+    let mut s = String::with_capacity(len);
+    s.extend(fes.iter().copied().with_checksum::<Bech32Unlimited>(&hrp).chars());
+    s
+}
+
+/// The framing of a parsed BOLT-11 invoice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bolt11Raw {
+    /// The lowercase human-readable part.
+    hrp: Hrp,
+    /// The index of the amount in the human-readable part, after the currency prefix.
+    amount_start: usize,
+    /// All the data, including the signature but not the checksum.
+    fes: Vec<Fe32>,
+}
+
+impl Bolt11Raw {
+    /// Returns the human-readable part, in lowercase.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.hrp }
+
+    /// Returns the currency prefix, for example `bc` for an `lnbc2500u` invoice.
+    #[inline]
+    pub fn currency_prefix(&self) -> &str {
+        self.hrp.as_str().get(PREFIX.len()..self.amount_start).unwrap_or_default()
+    }
+
+    /// Returns the amount, for example `2500u` for an `lnbc2500u` invoice.
+    ///
+    /// The amount is not validated and is empty if the invoice does not have one.
+    #[inline]
+    pub fn amount(&self) -> &str { self.hrp.as_str().get(self.amount_start..).unwrap_or_default() }
+
+    /// Returns all the data, including the signature.
+    #[inline]
+    pub fn fes(&self) -> &[Fe32] { &self.fes }
+
+    /// Returns the data before the signature, this is the timestamp followed by the tagged fields.
+    #[inline]
+    pub fn data(&self) -> &[Fe32] { self.split().0 }
+
+    /// Returns the signature, the last [`SIGNATURE_LENGTH`] field elements of the data.
+    #[inline]
+    pub fn signature(&self) -> &[Fe32] { self.split().1 }
+
+    /// Consumes the invoice, returning all the data including the signature.
+    #[inline]
+    pub fn into_fes(self) -> Vec<Fe32> { self.fes }
+
+    /// Splits the data into the signed data and the signature.
+    fn split(&self) -> (&[Fe32], &[Fe32]) {
+        self.fes.split_at(self.fes.len() - SIGNATURE_LENGTH) // Length checked in `parse`.
+    }
+}
+
+/// An error while parsing a BOLT-11 invoice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Parsing the bech32 string failed.
+    Parse(CheckedHrpstringError),
+    /// The human-readable part does not start with `ln` followed by a currency prefix.
+    InvalidPrefix,
+    /// The data is too short to contain a signature.
+    TooShort(usize),
+}
+
+impl ParseError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            InvalidPrefix => Code::Bolt11Prefix,
+            TooShort(_) => Code::Bolt11TooShort,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing invoice failed"; e),
+            InvalidPrefix => write!(f, "human-readable part is not an invoice prefix"),
+            TooShort(len) => write!(
+                f,
+                "invoice data is too short to contain a signature, found {} characters, must be >= {}",
+                len, SIGNATURE_LENGTH
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for ParseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            InvalidPrefix | TooShort(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for ParseError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Examples from BOLT-11.
+    const DONATION: &str = "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vjjsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql";
+    const COFFEE: &str = "lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rh";
+    const TESTNET: &str = "lntb20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfpp3x9et2e20v6pu37c5d9vax37wxq72un989qrsgqdj545axuxtnfemtpwkc45hx9d2ft7x04mt8q7y6t0k2dge9e7h8kpy9p34ytyslj3yu569aalz2xdk8xkd7ltxqld94u8h2esmsmacgpghe9k8";
+
+    #[test]
+    fn parse_spec_invoices() {
+        let invoices =
+            [(DONATION, "bc", "", 293), (COFFEE, "bc", "2500u", 255), (TESTNET, "tb", "20m", 318)];
+        for &(invoice, currency, amount, len) in invoices.iter() {
+            let raw = parse(invoice).expect("valid invoice");
+            assert_eq!(raw.currency_prefix(), currency);
+            assert_eq!(raw.amount(), amount);
+            assert_eq!(raw.fes().len(), len);
+            assert_eq!(raw.data().len(), len - SIGNATURE_LENGTH);
+            assert_eq!(raw.signature().len(), SIGNATURE_LENGTH);
+            assert_eq!(raw.data(), &raw.fes()[..len - SIGNATURE_LENGTH]);
+            assert_eq!(encode(raw.hrp(), raw.fes()), invoice);
+        }
+    }
+
+    #[test]
+    fn parse_uppercase_invoice() {
+        let raw = parse(&COFFEE.to_uppercase()).expect("valid invoice");
+        assert_eq!(raw.hrp().as_str(), "lnbc2500u");
+        assert_eq!(raw, parse(COFFEE).expect("valid invoice"));
+    }
+
+    #[test]
+    fn encode_longer_than_code_length() {
+        let hrp = Hrp::parse_unchecked("lnbc");
+        let fes = [Fe32::Q; 2000];
+        let invoice = encode(hrp, &fes);
+        assert_eq!(invoice.len(), 4 + 1 + 2000 + 6);
+        assert_eq!(invoice.capacity(), invoice.len());
+        assert_eq!(parse(&invoice).expect("valid invoice").fes(), &fes[..]);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let fes = [Fe32::Q; SIGNATURE_LENGTH];
+        for hrp in ["bc", "ln", "ln2500u"].iter() {
+            let s = encode(Hrp::parse_unchecked(hrp), &fes);
+            assert_eq!(parse(&s).unwrap_err(), ParseError::InvalidPrefix, "{}", hrp);
+        }
+
+        let s = encode(Hrp::parse_unchecked("lnbc"), &fes[1..]);
+        assert_eq!(parse(&s).unwrap_err(), ParseError::TooShort(SIGNATURE_LENGTH - 1));
+
+        let mut s = String::from(COFFEE);
+        s.pop();
+        s.push('q');
+        assert!(matches!(parse(&s), Err(ParseError::Parse(CheckedHrpstringError::Checksum(_)))));
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+
+//! Framing of [BOLT-12] offers and other Lightning strings.
+//!
+//! BOLT-12 strings use the bech32 character set and human-readable part but have no checksum and
+//! no maximum length. Because they can be long they may be split across lines: a `+` followed by
+//! optional whitespace between two data characters is removed before parsing. This module only
+//! handles the framing, parsing the TLV records is left to the caller.
+//!
+//! Enabled by the `bolt12` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::bolt12;
+//!
+//! let offer = "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg";
+//! let split = "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcn+
+//!              ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg";
+//!
+//! let (hrp, fes) = bolt12::parse(split).expect("valid offer");
+//! assert_eq!(hrp.as_str(), "lno");
+//! assert_eq!(bolt12::encode(hrp, &fes), offer);
+//! ```
+//!
+//! [BOLT-12]: <https://github.com/lightning/bolts/blob/master/12-offer-encoding.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::Fe32IterExt;
+use crate::NoChecksum;
+
+/// The character that joins the parts of a string split across lines.
+const CONTINUATION: char = '+';
+
+/// Parses a BOLT-12 string, returning the human-readable part and the data.
+///
+/// Removes any `+` continuations, then validates the characters and case. Like any bech32 string
+/// the input may be uppercase, the returned human-readable part is always lowercase.
+pub fn parse(s: &str) -> Result<(Hrp, Vec<Fe32>), ParseError> {
+    if s.contains(CONTINUATION) {
+        parse_joined(&join(s)?)
+    } else {
+        parse_joined(s)
+    }
+}
+
+/// Encodes a BOLT-12 string on a single line, without a checksum.
+pub fn encode(hrp: Hrp, fes: &[Fe32]) -> String {
+    let mut s = String::with_capacity(hrp.len() + 1 + fes.len()); // +1 for separator
+    s.extend(fes.iter().copied().with_checksum::<NoChecksum>(&hrp).chars());
+    s
+}
+
+/// Parses a BOLT-12 string that has no continuations.
+fn parse_joined(s: &str) -> Result<(Hrp, Vec<Fe32>), ParseError> {
+    let checked = CheckedHrpstring::new::<NoChecksum>(s)?;
+
+    let hrp = Hrp::parse_unchecked(&checked.hrp().to_lowercase());
+    let fes = checked
+        .data_part_ascii_no_checksum()
+        .iter()
+        .map(|&b| Fe32::from_char_unchecked(b))
+        .collect::<Vec<Fe32>>();
+
+    Ok((hrp, fes))
+}
+
+/// Removes the `+` continuations, and the whitespace after them, from `s`.
+///
+/// A continuation is only valid between two data characters.
+fn join(s: &str) -> Result<String, ParseError> {
+    // Continuations do not contain a '1' so the last one is still the separator.
+    let data_start = s.rfind('1').map(|i| i + 1).unwrap_or(s.len());
+
+    let mut joined = String::with_capacity(s.len());
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != CONTINUATION {
+            joined.push(c);
+            continue;
+        }
+
+        let after_data = match s[..i].chars().next_back() {
+            Some(prev) => i > data_start && !prev.is_whitespace() && prev != CONTINUATION,
+            None => false,
+        };
+        while chars.peek().map_or(false, |&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        let before_data = chars.peek().map_or(false, |&(_, c)| c != CONTINUATION);
+
+        if !after_data || !before_data {
+            return Err(ParseError::InvalidContinuation(i));
+        }
+    }
+    Ok(joined)
+}
+
+/// An error while parsing a BOLT-12 string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Parsing the bech32 string failed.
+    Parse(CheckedHrpstringError),
+    /// A `+` continuation, at this byte index, is not between two data characters.
+    InvalidContinuation(usize),
+}
+
+impl ParseError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            InvalidContinuation(_) => Code::Bolt12Continuation,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing bolt12 string failed"; e),
+            InvalidContinuation(i) =>
+                write!(f, "'+' at index {} is not between two data characters", i),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for ParseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use ParseError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            InvalidContinuation(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for ParseError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::{CharError, UncheckedHrpstringError};
+
+    const OFFER: &str = "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg";
+
+    #[test]
+    fn roundtrip() {
+        let (hrp, fes) = parse(OFFER).expect("valid offer");
+        assert_eq!(hrp.as_str(), "lno");
+        assert_eq!(fes.len(), OFFER.len() - 4);
+        let s = encode(hrp, &fes);
+        assert_eq!(s, OFFER);
+        assert_eq!(s.capacity(), s.len());
+
+        assert_eq!(parse(&OFFER.to_uppercase()).expect("valid offer"), (hrp, fes));
+    }
+
+    #[test]
+    fn split_offers_equal_unsplit() {
+        let unsplit = parse(OFFER).expect("valid offer");
+
+        let splits = [
+            "lno1pqps7sjqpgt+yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+5k7msjzfpy7nz5yqcn+ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+5xvxg",
+            "lno1pqps7sjqpgt+ yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+  5k7msjzfpy7nz5yqcn+\nygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+\r\n 5xvxg",
+            "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcn+
+             ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg",
+        ];
+        for s in splits.iter() {
+            assert_eq!(parse(s).expect("valid offer"), unsplit, "{}", s);
+        }
+    }
+
+    #[test]
+    fn invalid_continuations() {
+        let invalid = [
+            ("+lno1pqps7sjqpgtyzm3qv4ux", 0),    // Leading.
+            ("lno1pqps7sjqpgtyzm3qv4ux+", 24),   // Trailing.
+            ("lno1pqps7sjqpgtyzm3qv4ux+  ", 24), // Trailing, with whitespace.
+            ("lno1pqps7sjqpgt++yzm3qv4ux", 15),  // Doubled.
+            ("lno1pqps7sjqpgt+ +yzm3qv4ux", 15), // Doubled, with whitespace.
+            ("lno1+pqps7sjqpgtyzm3qv4ux", 4),    // Right after the separator.
+            ("ln+o1pqps7sjqpgtyzm3qv4ux", 2),    // In the human-readable part.
+            ("lno1pqps7sjqpgt +yzm3qv4ux", 16),  // Whitespace before.
+        ];
+        for &(s, i) in invalid.iter() {
+            assert_eq!(parse(s).unwrap_err(), ParseError::InvalidContinuation(i), "{}", s);
+        }
+    }
+
+    #[test]
+    fn whitespace_without_continuation() {
+        assert_eq!(
+            parse("lno1pqps7sjqpgt yzm3qv4ux").unwrap_err(),
+            ParseError::Parse(CheckedHrpstringError::Parse(UncheckedHrpstringError::Char(
+                CharError::InvalidChar(' ')
+            )))
+        );
+    }
+
+    #[test]
+    fn longer_than_code_length() {
+        let hrp = Hrp::parse_unchecked("lno");
+        let fes = [Fe32::P; 2000];
+        let s = encode(hrp, &fes);
+        assert_eq!(parse(&s).expect("valid offer").1, &fes[..]);
+    }
+}
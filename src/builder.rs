@@ -0,0 +1,399 @@
+// SPDX-License-Identifier: MIT
+
+//! Building the data part of a bech32 string from a mix of field elements and bytes.
+//!
+//! Formats like BOLT-11 and codex32 interleave 5 bit fields with 8 bit payloads. The builders in
+//! this module track the bit position, so that each part lands on the right bits.
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::error::Code;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+#[cfg(feature = "alloc")]
+use crate::primitives::iter::Fe32IterExt;
+use crate::Fe32;
+#[cfg(feature = "alloc")]
+use crate::{Checksum, Hrp};
+
+/// Builds the field elements of a data part, see [`DataBuilder::push_bytes`] for alignment.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{hrp, Bech32, DataBuilder, Fe32};
+///
+/// // A segwit version 0 address.
+/// let mut builder = DataBuilder::new();
+/// builder.push_fe(Fe32::Q);
+/// builder.push_bytes(&[0xe8, 0xdf, 0x01, 0x8c, 0x7e, 0x32, 0x6c, 0xc2, 0x53, 0xfa,
+///                      0xac, 0x7e, 0x46, 0xcd, 0xc5, 0x1e, 0x68, 0x54, 0x2c, 0x42])
+///     .expect("aligned");
+/// let address = builder.finish_with_checksum::<Bech32>(hrp::GRS);
+/// assert_eq!(address, "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[must_use = "a builder does nothing unless it is finished"]
+pub struct DataBuilder {
+    /// The complete field elements.
+    fes: Vec<Fe32>,
+    /// The bits pushed since the last complete field element.
+    cursor: BitCursor,
+    /// Whether bytes may be pushed when the cursor is not on a field element boundary.
+    allow_misaligned: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl DataBuilder {
+    /// Constructs an empty builder.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Allows [`Self::push_bytes`] when the cursor is not on a field element boundary.
+    ///
+    /// The bytes then continue the bit stream from the cursor, instead of starting a new field
+    /// element.
+    #[inline]
+    pub fn allow_misaligned_bytes(mut self) -> Self {
+        self.allow_misaligned = true;
+        self
+    }
+
+    /// Returns the number of bits pushed so far.
+    #[inline]
+    pub fn bit_len(&self) -> usize { self.fes.len() * 5 + usize::from(self.cursor.len) }
+
+    /// Returns true if the cursor is on a field element boundary.
+    #[inline]
+    pub fn is_aligned(&self) -> bool { self.cursor.len == 0 }
+
+    /// Pushes a field element, five bits.
+    pub fn push_fe(&mut self, fe: Fe32) -> &mut Self {
+        let fes = &mut self.fes;
+        self.cursor.push(fe.to_u8(), 5, |fe| fes.push(fe));
+        self
+    }
+
+    /// Pushes the low `bits` bits of `value`, most significant bit first.
+    ///
+    /// # Errors
+    ///
+    /// If `bits` is greater than 8 or `value` does not fit in `bits` bits.
+    pub fn push_u8_as_fes(&mut self, value: u8, bits: u8) -> Result<&mut Self, DataBuilderError> {
+        check_bits(value, bits)?;
+        let fes = &mut self.fes;
+        self.cursor.push(value, bits, |fe| fes.push(fe));
+        Ok(self)
+    }
+
+    /// Pushes `bytes`, padding the last field element with zero bits.
+    ///
+    /// The bytes start on a field element boundary and end on one, which is how every bech32
+    /// format encodes a byte payload.
+    ///
+    /// # Errors
+    ///
+    /// If bits pushed by [`Self::push_u8_as_fes`] leave the cursor in the middle of a field
+    /// element, unless [`Self::allow_misaligned_bytes`] was called.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, DataBuilderError> {
+        self.check_aligned()?;
+        self.fes.reserve((bytes.len() * 8 + 9) / 5);
+        let fes = &mut self.fes;
+        for &b in bytes {
+            self.cursor.push(b, 8, |fe| fes.push(fe));
+        }
+        self.cursor.pad(|fe| fes.push(fe));
+        Ok(self)
+    }
+
+    /// Returns the field elements, padding the last one with zero bits.
+    ///
+    /// With the `zeroize` feature the returned vector is not zeroized on drop, wrap it in
+    /// [`Zeroizing`](zeroize::Zeroizing) if it holds secret data.
+    pub fn finish_fes(mut self) -> Vec<Fe32> {
+        let fes = &mut self.fes;
+        self.cursor.pad(|fe| fes.push(fe));
+        core::mem::take(&mut self.fes)
+    }
+
+    /// Returns the lowercase bech32 string of `hrp` and the field elements, checksummed with `Ck`.
+    ///
+    /// The length is not checked against the code length of `Ck`, the string is only guaranteed
+    /// to detect errors if it is not longer than that.
+    pub fn finish_with_checksum<Ck: Checksum>(self, hrp: Hrp) -> String {
+        self.finish_fes().into_iter().with_checksum::<Ck>(&hrp).chars().collect()
+    }
+
+    /// Returns an error if bytes can not be pushed at the cursor.
+    fn check_aligned(&self) -> Result<(), DataBuilderError> {
+        if self.is_aligned() || self.allow_misaligned {
+            Ok(())
+        } else {
+            Err(DataBuilderError::Misaligned { pending_bits: self.cursor.len })
+        }
+    }
+}
+
+/// Zeroizing clears the builder, overwriting the field elements and pending bits with zeros.
+///
+/// Only the current buffer is overwritten, copies left behind when a push grew the buffer are not.
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl zeroize::Zeroize for DataBuilder {
+    fn zeroize(&mut self) {
+        self.fes.zeroize();
+        self.cursor = BitCursor::default();
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl Drop for DataBuilder {
+    fn drop(&mut self) { zeroize::Zeroize::zeroize(self) }
+}
+
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl zeroize::ZeroizeOnDrop for DataBuilder {}
+
+/// Returns an error if `value` does not fit in `bits` bits or `bits` is greater than 8.
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub(crate) fn check_bits(value: u8, bits: u8) -> Result<(), DataBuilderError> {
+    if bits > 8 || u16::from(value) >> bits != 0 {
+        Err(DataBuilderError::TooWide { value, bits })
+    } else {
+        Ok(())
+    }
+}
+
+/// The bits pushed to a builder that do not yet make up a field element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) struct BitCursor {
+    /// The pending bits, in the low `len` bits.
+    acc: u16,
+    /// The number of pending bits, always less than 5.
+    pub(crate) len: u8,
+}
+
+impl BitCursor {
+    /// Pushes the low `bits` bits of `value`, calling `f` with each completed field element.
+    ///
+    /// `bits` must be at most 8 and `value` must fit in them.
+    pub(crate) fn push<F: FnMut(Fe32)>(&mut self, value: u8, bits: u8, mut f: F) {
+        self.acc = (self.acc << bits) | u16::from(value);
+        self.len += bits;
+        while self.len >= 5 {
+            self.len -= 5;
+            f(Fe32((self.acc >> self.len) as u8 & 0x1f));
+        }
+        self.acc &= (1 << self.len) - 1;
+    }
+
+    /// Pads the pending bits with zero bits, calling `f` with the field element if there is one.
+    pub(crate) fn pad<F: FnMut(Fe32)>(&mut self, f: F) {
+        if self.len > 0 {
+            self.push(0, 5 - self.len, f);
+        }
+    }
+}
+
+/// An error while pushing to a [`DataBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DataBuilderError {
+    /// Bytes pushed when the cursor is in the middle of a field element.
+    Misaligned {
+        /// The number of bits pushed since the last complete field element.
+        pending_bits: u8,
+    },
+    /// A value that does not fit in the number of bits it is pushed as, or more than 8 bits.
+    TooWide {
+        /// The value.
+        value: u8,
+        /// The number of bits.
+        bits: u8,
+    },
+}
+
+impl DataBuilderError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use DataBuilderError::*;
+
+        match *self {
+            Misaligned { .. } => Code::BuilderMisaligned,
+            TooWide { .. } => Code::BuilderBits,
+        }
+    }
+}
+
+impl fmt::Display for DataBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DataBuilderError::*;
+
+        match *self {
+            Misaligned { pending_bits } => write!(
+                f,
+                "bytes pushed {} bits after the start of a field element, not on a boundary",
+                pending_bits
+            ),
+            TooWide { value, bits } => write!(f, "value {} does not fit in {} bits", value, bits),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for DataBuilderError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use DataBuilderError::*;
+
+        match *self {
+            Misaligned { .. } | TooWide { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod tests {
+    use super::*;
+    use crate::primitives::hrp;
+    use crate::{Bech32, ByteIterExt};
+
+    #[test]
+    fn segwit_v0_address() {
+        let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let (hrp, version, program) = crate::segwit::decode(s).expect("valid address");
+
+        let mut builder = DataBuilder::new();
+        builder.push_fe(version).push_bytes(&program).expect("aligned");
+        assert_eq!(builder.bit_len(), 165);
+        assert_eq!(builder.finish_with_checksum::<Bech32>(hrp), s);
+    }
+
+    #[test]
+    fn segwit_v0_p2wsh_is_padded() {
+        let s = "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq";
+        let (hrp, version, program) = crate::segwit::decode(s).expect("valid address");
+        assert_eq!(program.len(), 32);
+
+        let mut builder = DataBuilder::new();
+        builder.push_fe(version).push_bytes(&program).expect("aligned");
+        // 5 + 256 bits is not a multiple of 5, the last field element is padded.
+        assert_eq!(builder.bit_len(), 265);
+        assert!(builder.is_aligned());
+        assert_eq!(builder.finish_with_checksum::<Bech32>(hrp), s);
+    }
+
+    #[test]
+    #[cfg(feature = "codex32")]
+    fn codex32_share() {
+        use crate::codex32::{self, Codex32, Share};
+
+        // From the test vectors in BIP-93.
+        let s = "ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln";
+        let share = Share::parse(s).expect("valid share");
+
+        let mut builder = DataBuilder::new();
+        for c in "3cashs".chars() {
+            builder.push_fe(Fe32::from_char(c).unwrap());
+        }
+        builder.push_bytes(share.payload()).expect("aligned");
+        assert_eq!(builder.finish_with_checksum::<Codex32>(codex32::HRP), s);
+    }
+
+    #[test]
+    fn bytes_match_bytes_to_fes() {
+        let bytes = [0x00, 0xff, 0x12, 0x34, 0x56, 0x78, 0x9a];
+        for len in 0..=bytes.len() {
+            let mut builder = DataBuilder::new();
+            builder.push_bytes(&bytes[..len]).expect("aligned");
+            let want = bytes[..len].iter().copied().bytes_to_fes().collect::<Vec<_>>();
+            assert_eq!(builder.finish_fes(), want);
+        }
+    }
+
+    #[test]
+    fn partial_fields() {
+        // Bits 1, 0110, 101 followed by bits 1 1001 form the field elements 10110 and 10111 and
+        // leave 001 pending, padded to 00100.
+        let mut builder = DataBuilder::new();
+        builder
+            .push_u8_as_fes(1, 1)
+            .unwrap()
+            .push_u8_as_fes(0b0110, 4)
+            .unwrap()
+            .push_u8_as_fes(0b101, 3)
+            .unwrap()
+            .push_u8_as_fes(0b11001, 5)
+            .unwrap();
+        assert_eq!(builder.bit_len(), 13);
+        assert!(!builder.is_aligned());
+        assert_eq!(builder.finish_fes(), [Fe32(0b10110), Fe32(0b10111), Fe32(0b00100)]);
+
+        let mut builder = DataBuilder::new();
+        builder.push_u8_as_fes(0xab, 8).unwrap().push_u8_as_fes(0, 0).unwrap();
+        assert_eq!(builder.bit_len(), 8);
+        assert_eq!(builder.finish_fes(), [Fe32(0b10101), Fe32(0b01100)]);
+    }
+
+    #[test]
+    fn misaligned_bytes() {
+        let mut builder = DataBuilder::new();
+        builder.push_u8_as_fes(0b11, 2).unwrap();
+        assert_eq!(
+            builder.push_bytes(&[0xff]).unwrap_err(),
+            DataBuilderError::Misaligned { pending_bits: 2 }
+        );
+        assert_eq!(builder.bit_len(), 2, "failed push changes nothing");
+        // A field element does not change the alignment.
+        builder.push_fe(Fe32::L);
+        assert!(builder.push_bytes(&[0xff]).is_err());
+
+        // Bits 11, 11111 and 11111111 make three field elements without padding.
+        let mut builder = DataBuilder::new().allow_misaligned_bytes();
+        builder.push_u8_as_fes(0b11, 2).unwrap().push_fe(Fe32::L).push_bytes(&[0xff]).unwrap();
+        assert!(builder.is_aligned());
+        assert_eq!(builder.finish_fes(), [Fe32::L, Fe32::L, Fe32::L]);
+    }
+
+    #[test]
+    fn too_wide() {
+        let mut builder = DataBuilder::new();
+        assert_eq!(
+            builder.push_u8_as_fes(0b100, 2).unwrap_err(),
+            DataBuilderError::TooWide { value: 4, bits: 2 }
+        );
+        assert_eq!(
+            builder.push_u8_as_fes(0, 9).unwrap_err(),
+            DataBuilderError::TooWide { value: 0, bits: 9 }
+        );
+        assert_eq!(builder.bit_len(), 0);
+        let empty = crate::encode::<Bech32>(hrp::GRS, &[]).unwrap();
+        assert_eq!(builder.finish_with_checksum::<Bech32>(hrp::GRS), empty);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn zeroize() {
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+        let mut builder = DataBuilder::new();
+        builder.push_bytes(&[0xab; 32]).expect("aligned").push_u8_as_fes(0b101, 3).expect("fits");
+        assert_zeroize_on_drop(&builder);
+
+        builder.zeroize();
+        assert_eq!(builder, DataBuilder::new());
+
+        builder.push_bytes(&[0xab; 32]).expect("aligned");
+        assert_eq!(builder.clone().finish_fes().len(), 52);
+        drop(builder);
+    }
+}
@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: MIT
+
+//! Parsing and encoding of [BIP-93] codex32 shares.
+//!
+//! A codex32 string encodes a share of a secret, usually a BIP-32 master seed, split using
+//! Shamir's secret sharing. This module only parses and serializes shares, splitting and
+//! recovering secrets is left to the caller.
+//!
+//! Only the short codex32 checksum is supported so shares are at most 93 characters long, which
+//! allows payloads of up to 44 bytes. Long codex32 strings, used for 64 byte seeds, are rejected.
+//!
+//! Enabled by the `codex32` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::codex32::Share;
+//!
+//! // From the test vectors in BIP-93.
+//! let s = "ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln";
+//!
+//! let share = Share::parse(s).expect("valid share");
+//! assert_eq!(share.threshold(), 3);
+//! assert_eq!(share.identifier(), "cash");
+//! assert_eq!(share.payload()[0], 0xff);
+//! assert_eq!(share.to_string(), s);
+//! ```
+//!
+//! [BIP-93]: <https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::{fmt, str};
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError, PaddingError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::Checksum;
+
+/// The human-readable part of all codex32 strings.
+pub const HRP: Hrp = Hrp::parse_unchecked("ms");
+
+/// The share index of the secret, required for shares with a threshold of 0.
+pub const SECRET_INDEX: Fe32 = Fe32::S;
+
+/// The minimum length of the payload, in bytes.
+pub const MIN_PAYLOAD_LENGTH: usize = 16;
+
+/// The maximum length of the payload that fits in a short codex32 string, in bytes.
+pub const MAX_PAYLOAD_LENGTH: usize = 44;
+
+/// The length of the header: the threshold, the identifier and the share index.
+const HEADER_LENGTH: usize = 6;
+
+/// The short codex32 checksum algorithm, defined in BIP-93.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Codex32 {}
+
+impl Checksum for Codex32 {
+    const NAME: &'static str = "codex32";
+    type MidstateRepr = u128;
+    const CHECKSUM_LENGTH: usize = 13;
+    const CODE_LENGTH: usize = 93;
+    // Copied from BIP-93
+    const GENERATOR_SH: [u128; 5] = [
+        0x19dc500ce73fde210,
+        0x1bfae00def77fe529,
+        0x1fbd920fffe7bee52,
+        0x1739640bdeee3fdad,
+        0x07729a039cfc75f5a,
+    ];
+    const TARGET_RESIDUE: u128 = 0x10ce0795c2fd1e62a;
+}
+
+/// A codex32 share.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Share {
+    /// The threshold, 0 or 2 to 9.
+    threshold: u8,
+    /// The identifier, four lowercase bech32 characters.
+    identifier: [u8; 4],
+    /// The share index.
+    index: Fe32,
+    /// The payload.
+    payload: Vec<u8>,
+    /// The padding bits after the payload, BIP-93 does not require them to be zero.
+    padding: Fe32,
+}
+
+impl Share {
+    /// Constructs a share, validating the header rules.
+    ///
+    /// `threshold` must be 0 or 2 to 9, `identifier` must be four bech32 characters, if
+    /// `threshold` is 0 `index` must be [`SECRET_INDEX`], and the payload must be between
+    /// [`MIN_PAYLOAD_LENGTH`] and [`MAX_PAYLOAD_LENGTH`] bytes long.
+    pub fn new(
+        threshold: u8,
+        identifier: &str,
+        index: Fe32,
+        payload: &[u8],
+    ) -> Result<Self, ShareError> {
+        if threshold == 1 || threshold > 9 {
+            return Err(ShareError::InvalidThreshold(threshold));
+        }
+
+        let mut id = [0_u8; 4];
+        if identifier.len() != id.len() {
+            return Err(ShareError::InvalidIdentifier);
+        }
+        for (dst, c) in id.iter_mut().zip(identifier.chars()) {
+            let fe = Fe32::from_char(c).map_err(|_| ShareError::InvalidIdentifier)?;
+            *dst = fe.to_char() as u8;
+        }
+
+        Self::validate(threshold, index, payload.len())?;
+        Ok(Share { threshold, identifier: id, index, payload: payload.to_vec(), padding: Fe32::Q })
+    }
+
+    /// Parses a codex32 string, the string may be lowercase or uppercase.
+    pub fn parse(s: &str) -> Result<Self, ShareError> {
+        let checked = CheckedHrpstring::new::<Codex32>(s)?;
+
+        let hrp = checked.hrp();
+        if hrp != HRP {
+            return Err(ShareError::InvalidHrp(hrp));
+        }
+
+        let data = checked.data_part_ascii_no_checksum();
+        if data.len() < HEADER_LENGTH {
+            return Err(ShareError::MissingHeader);
+        }
+        let (header, data) = data.split_at(HEADER_LENGTH);
+
+        let threshold = match header[0].to_ascii_lowercase() {
+            b'0' => 0,
+            c @ b'2'..=b'9' => c - b'0',
+            c => return Err(ShareError::InvalidThresholdChar(char::from(c))),
+        };
+        let mut identifier = [0_u8; 4];
+        for (dst, src) in identifier.iter_mut().zip(&header[1..5]) {
+            *dst = src.to_ascii_lowercase();
+        }
+        let index = Fe32::from_char_unchecked(header[5]);
+
+        if data.len() * 5 % 8 > 4 {
+            return Err(ShareError::Padding(PaddingError::TooMuch));
+        }
+        let payload_len = data.len() * 5 / 8;
+        Self::validate(threshold, index, payload_len)?;
+
+        let fes = data.iter().map(|&b| Fe32::from_char_unchecked(b));
+        let payload = fes.fes_to_bytes().collect::<Vec<u8>>();
+        // The padding bits are the low bits of the last field element.
+        let padding_mask = (1_u8 << (data.len() * 5 % 8)) - 1;
+        let last = data.last().map(|&b| Fe32::from_char_unchecked(b).to_u8()).unwrap_or(0);
+        let padding = Fe32::try_from(last & padding_mask).unwrap_or(Fe32::Q);
+
+        Ok(Share { threshold, identifier, index, payload, padding })
+    }
+
+    /// Returns the threshold, the number of shares needed to recover the secret.
+    ///
+    /// A threshold of 0 means the secret is not split, the share is the secret itself.
+    #[inline]
+    pub fn threshold(&self) -> u8 { self.threshold }
+
+    /// Returns the identifier, shared by all the shares of the same secret.
+    #[inline]
+    pub fn identifier(&self) -> &str {
+        // The identifier is always ASCII, checked in the constructors.
+        str::from_utf8(&self.identifier).unwrap_or_default()
+    }
+
+    /// Returns the share index.
+    #[inline]
+    pub fn index(&self) -> Fe32 { self.index }
+
+    /// Returns true if this share is the secret.
+    #[inline]
+    pub fn is_secret(&self) -> bool { self.index == SECRET_INDEX }
+
+    /// Returns the payload, without the padding bits.
+    #[inline]
+    pub fn payload(&self) -> &[u8] { &self.payload }
+
+    /// Validates the header rules that depend on more than a single character.
+    fn validate(threshold: u8, index: Fe32, payload_len: usize) -> Result<(), ShareError> {
+        if threshold == 0 && index != SECRET_INDEX {
+            return Err(ShareError::InvalidShareIndex(index));
+        }
+        if !(MIN_PAYLOAD_LENGTH..=MAX_PAYLOAD_LENGTH).contains(&payload_len) {
+            return Err(ShareError::InvalidLength(payload_len));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Share {
+    /// Formats the share as a lowercase codex32 string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let threshold =
+            Fe32::from_char(char::from(b'0' + self.threshold)).map_err(|_| fmt::Error)?;
+        let identifier = self.identifier.iter().map(|&b| Fe32::from_char_unchecked(b));
+        let header =
+            core::iter::once(threshold).chain(identifier).chain(core::iter::once(self.index));
+
+        let last = (self.payload.len() * 8 + 4) / 5 - 1;
+        let padding = self.padding;
+        let payload = self
+            .payload
+            .iter()
+            .copied()
+            .bytes_to_fes()
+            .enumerate()
+            .map(move |(i, fe)| if i == last { fe + padding } else { fe });
+
+        for c in header.chain(payload).with_checksum::<Codex32>(&HRP).chars() {
+            fmt::Write::write_char(f, c)?;
+        }
+        Ok(())
+    }
+}
+
+impl str::FromStr for Share {
+    type Err = ShareError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::parse(s) }
+}
+
+/// An error while parsing or constructing a codex32 share.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ShareError {
+    /// Parsing the codex32 string failed.
+    Parse(CheckedHrpstringError),
+    /// The human-readable part is not `ms`.
+    InvalidHrp(Hrp),
+    /// The data is too short to contain the header.
+    MissingHeader,
+    /// The threshold is not 0 or 2 to 9.
+    InvalidThreshold(u8),
+    /// The threshold character is not a digit.
+    InvalidThresholdChar(char),
+    /// The identifier is not four bech32 characters.
+    InvalidIdentifier,
+    /// The threshold is 0 but the share index is not `s`.
+    InvalidShareIndex(Fe32),
+    /// There are more than 4 padding bits after the payload.
+    Padding(PaddingError),
+    /// The payload length, in bytes, is out of range.
+    InvalidLength(usize),
+}
+
+impl ShareError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use ShareError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            InvalidHrp(_) => Code::Codex32Hrp,
+            MissingHeader => Code::Codex32Header,
+            InvalidThreshold(_) | InvalidThresholdChar(_) => Code::Codex32Threshold,
+            InvalidIdentifier => Code::Codex32Identifier,
+            InvalidShareIndex(_) => Code::Codex32ShareIndex,
+            Padding(ref e) => e.error_code(),
+            InvalidLength(_) => Code::Codex32Length,
+        }
+    }
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ShareError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing codex32 string failed"; e),
+            InvalidHrp(ref hrp) => write!(f, "human-readable part is not ms: {}", hrp),
+            MissingHeader => write!(f, "codex32 string is too short to contain a header"),
+            InvalidThreshold(k) => write!(f, "invalid codex32 threshold: {}", k),
+            InvalidThresholdChar(c) => write!(f, "codex32 threshold is not a digit: {}", c),
+            InvalidIdentifier => write!(f, "codex32 identifier is not four bech32 characters"),
+            InvalidShareIndex(index) =>
+                write!(f, "share index must be s when the threshold is 0, found: {}", index),
+            Padding(ref e) => write_err!(f, "invalid codex32 padding"; e),
+            InvalidLength(len) => write!(
+                f,
+                "invalid codex32 payload length {} bytes, must be between {} and {}",
+                len, MIN_PAYLOAD_LENGTH, MAX_PAYLOAD_LENGTH
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for ShareError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use ShareError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            Padding(ref e) => Some(e),
+            InvalidHrp(_)
+            | MissingHeader
+            | InvalidThreshold(_)
+            | InvalidThresholdChar(_)
+            | InvalidIdentifier
+            | InvalidShareIndex(_)
+            | InvalidLength(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for ShareError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+impl From<PaddingError> for ShareError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::ChecksumError;
+
+    // Valid test vectors from BIP-93, with the expected threshold, identifier, share index, and
+    // the first and last bytes of the payload.
+    const VALID: [(&str, u8, &str, Fe32, u8, u8); 6] = [
+        ("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw", 0, "test", Fe32::S, 0x31, 0x31),
+        ("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM", 2, "name", Fe32::A, 0x8a, 0xc7),
+        ("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN", 2, "name", Fe32::C, 0xee, 0x54),
+        ("MS12NAMES6XQGUZTTXKEQNJSJZV4JV3NZ5K3KWGSPHUH6EVW", 2, "name", Fe32::S, 0xd1, 0xa5),
+        ("ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln", 3, "cash", Fe32::S, 0xff, 0x00),
+        (
+            "ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma",
+            0,
+            "leet",
+            Fe32::S,
+            0xff,
+            0x00,
+        ),
+    ];
+
+    #[test]
+    fn bip93_valid_vectors() {
+        for &(s, threshold, identifier, index, first, last) in VALID.iter() {
+            let share = Share::parse(s).expect("valid share");
+            assert_eq!(share.threshold(), threshold, "{}", s);
+            assert_eq!(share.identifier(), identifier, "{}", s);
+            assert_eq!(share.index(), index, "{}", s);
+            assert_eq!(share.payload().first(), Some(&first), "{}", s);
+            assert_eq!(share.payload().last(), Some(&last), "{}", s);
+            assert_eq!(share.to_string(), s.to_lowercase());
+            assert_eq!(s.parse::<Share>(), Ok(share));
+        }
+    }
+
+    #[test]
+    fn new_matches_parse() {
+        let parsed = Share::parse(VALID[4].0).expect("valid share");
+        let share = Share::new(3, "CASH", Fe32::S, parsed.payload()).expect("valid share");
+        assert_eq!(share, parsed);
+        assert!(share.is_secret());
+    }
+
+    #[test]
+    fn new_invalid() {
+        let payload = [0_u8; 16];
+        assert_eq!(Share::new(1, "test", Fe32::S, &payload), Err(ShareError::InvalidThreshold(1)));
+        assert_eq!(
+            Share::new(10, "test", Fe32::S, &payload),
+            Err(ShareError::InvalidThreshold(10))
+        );
+        assert_eq!(Share::new(2, "tes", Fe32::S, &payload), Err(ShareError::InvalidIdentifier));
+        assert_eq!(Share::new(2, "tesb", Fe32::S, &payload), Err(ShareError::InvalidIdentifier));
+        assert_eq!(
+            Share::new(0, "test", Fe32::A, &payload),
+            Err(ShareError::InvalidShareIndex(Fe32::A))
+        );
+        assert_eq!(
+            Share::new(2, "test", Fe32::A, &payload[..15]),
+            Err(ShareError::InvalidLength(15))
+        );
+        assert_eq!(Share::new(2, "test", Fe32::A, &[0; 45]), Err(ShareError::InvalidLength(45)));
+    }
+
+    #[test]
+    fn wrong_checksum() {
+        // BIP-93 vector 1 with the last character changed.
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlq";
+        assert_eq!(
+            Share::parse(s),
+            Err(ShareError::Parse(CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue)))
+        );
+    }
+
+    #[test]
+    fn bad_threshold_char() {
+        let s = Share::new(2, "test", Fe32::A, &[0; 16]).expect("valid share").to_string();
+        // Re-encode with a threshold that is not a digit.
+        let checked = CheckedHrpstring::new::<Codex32>(&s).expect("valid checksum");
+        let mut fes = checked.fe32_iter::<core::iter::Empty<u8>>().collect::<Vec<Fe32>>();
+        fes[0] = Fe32::A;
+        let s = fes.into_iter().with_checksum::<Codex32>(&HRP).chars().collect::<String>();
+        assert_eq!(Share::parse(&s), Err(ShareError::InvalidThresholdChar('a')));
+    }
+
+    #[test]
+    fn wrong_payload_length() {
+        let encode = |data_len: usize| -> String {
+            let header = "0tests".chars().map(|c| Fe32::from_char(c).expect("bech32 char"));
+            let data = core::iter::repeat(Fe32::Q).take(data_len);
+            header.chain(data).with_checksum::<Codex32>(&HRP).chars().collect()
+        };
+
+        assert!(Share::parse(&encode(26)).is_ok());
+        // 24 characters are 15 bytes.
+        assert_eq!(Share::parse(&encode(24)), Err(ShareError::InvalidLength(15)));
+        // 27 characters have 7 bits of padding.
+        assert_eq!(Share::parse(&encode(27)), Err(ShareError::Padding(PaddingError::TooMuch)));
+        assert_eq!(Share::parse(&encode(0)), Err(ShareError::InvalidLength(0)));
+        // Longer than the short codex32 code length.
+        assert!(matches!(
+            Share::parse(&encode(72)),
+            Err(ShareError::Parse(CheckedHrpstringError::Checksum(ChecksumError::CodeLength(_))))
+        ));
+    }
+
+    #[test]
+    fn invalid_hrp_and_header() {
+        let s = "0tests".chars().map(|c| Fe32::from_char(c).expect("bech32 char"));
+        let s = s.with_checksum::<Codex32>(&Hrp::parse_unchecked("mt")).chars().collect::<String>();
+        assert_eq!(Share::parse(&s), Err(ShareError::InvalidHrp(Hrp::parse_unchecked("mt"))));
+
+        let s = "0tes".chars().map(|c| Fe32::from_char(c).expect("bech32 char"));
+        let s = s.with_checksum::<Codex32>(&HRP).chars().collect::<String>();
+        assert_eq!(Share::parse(&s), Err(ShareError::MissingHeader));
+    }
+}
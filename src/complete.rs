@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: MIT
+
+//! Completing the checksum of a partially entered string, e.g. for character-wheel entry on a
+//! hardware wallet.
+//!
+//! The checksum is a linear function of the human-readable part and the data, so once the data has
+//! been entered every checksum character is determined by it and the characters before it. Before
+//! that, any character can still be followed by a valid checksum.
+
+use crate::primitives::checksum::{self, Checksum, PackedFe32};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+
+/// Returns the checksum of `data` with the human-readable part `hrp`.
+///
+/// This is the unique checksum that makes `hrp`, `data` and the checksum a valid string, the
+/// iterator yields [`Checksum::CHECKSUM_LENGTH`] field elements.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{Bech32, Fe32, Hrp};
+///
+/// // The BIP-173 test vector "A12UEL5L" has no data.
+/// let hrp = Hrp::parse("a").expect("valid hrp");
+/// let checksum = bech32grs::complete_checksum::<Bech32>(&hrp, &[]);
+/// assert!(checksum.map(Fe32::to_char).eq("2uel5l".chars()));
+/// ```
+pub fn complete_checksum<Ck: Checksum>(hrp: &Hrp, data: &[Fe32]) -> ChecksumIter<Ck> {
+    let mut engine = checksum::Engine::<Ck>::new();
+    engine.input_hrp(*hrp);
+    engine.input_fes(data.iter().copied());
+    engine.input_target_residue();
+    ChecksumIter { residue: *engine.residue(), remaining: Ck::CHECKSUM_LENGTH }
+}
+
+/// Returns the characters that can follow `partial` in a valid string with `remaining` more
+/// characters, including the next one.
+///
+/// `partial` is the data part entered so far, after the separator, and `remaining` the number of
+/// characters still to be entered, so the data part will be `partial.len() + remaining` long.
+///
+/// Bit `i` of the returned mask is set if the field element with value `i`, i.e. the character
+/// `Fe32::try_from(i)?.to_char()`, can be entered next. Every character is allowed until the
+/// checksum is reached, after that exactly one is. The mask is zero if no character is allowed:
+/// `remaining` is zero, the string is too short to hold a checksum or longer than
+/// [`Checksum::CODE_LENGTH`], or the checksum characters already entered are wrong.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{Bech32, Fe32, Hrp};
+///
+/// let hrp = Hrp::parse("a").expect("valid hrp");
+/// // Seven characters left, the next one is data.
+/// assert_eq!(bech32grs::valid_next_chars::<Bech32>(&hrp, &[], 7), u32::MAX);
+/// // Six characters left, the checksum is "2uel5l".
+/// let mask = bech32grs::valid_next_chars::<Bech32>(&hrp, &[], 6);
+/// assert_eq!(mask, 1 << Fe32::from_char('2').expect("valid char").to_u8());
+/// ```
+pub fn valid_next_chars<Ck: Checksum>(hrp: &Hrp, partial: &[Fe32], remaining: usize) -> u32 {
+    let data_part_len = partial.len().saturating_add(remaining);
+    let string_len = hrp.len().saturating_add(1).saturating_add(data_part_len);
+    if remaining == 0 || data_part_len < Ck::CHECKSUM_LENGTH || string_len > Ck::CODE_LENGTH {
+        return 0;
+    }
+    if remaining > Ck::CHECKSUM_LENGTH {
+        return u32::MAX;
+    }
+
+    // Cannot panic, `remaining` is at most the checksum length so the data has been entered.
+    let (data, entered) = partial.split_at(data_part_len - Ck::CHECKSUM_LENGTH);
+    let mut checksum = complete_checksum::<Ck>(hrp, data);
+    if !checksum.by_ref().take(entered.len()).eq(entered.iter().copied()) {
+        return 0;
+    }
+    checksum.next().map(|fe| 1 << fe.to_u8()).unwrap_or(0)
+}
+
+/// An iterator over the field elements of a checksum, see [`complete_checksum`].
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct ChecksumIter<Ck: Checksum> {
+    /// The checksum residue, the checksum as packed field elements.
+    residue: Ck::MidstateRepr,
+    /// The number of field elements not yet yielded.
+    remaining: usize,
+}
+
+impl<Ck: Checksum> Iterator for ChecksumIter<Ck> {
+    type Item = Fe32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Fe32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(Fe32(self.residue.unpack(self.remaining)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining, Some(self.remaining)) }
+}
+
+impl<Ck: Checksum> ExactSizeIterator for ChecksumIter<Ck> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bech32, Bech32m};
+
+    /// Returns the human-readable part and data part, including the checksum, of a valid string.
+    fn split(s: &str) -> (Hrp, Vec<Fe32>) {
+        let pos = s.rfind('1').expect("valid string");
+        let hrp = Hrp::parse(&s[..pos]).expect("valid hrp");
+        let fes = s[pos + 1..].chars().map(|c| Fe32::from_char(c).expect("valid char")).collect();
+        (hrp, fes)
+    }
+
+    /// Checks that entering `s` one character at a time only ever picks allowed characters, and
+    /// that following the mask through the checksum ends at the true checksum.
+    fn check_following_mask<Ck: Checksum>(s: &str) {
+        let (hrp, fes) = split(s);
+        let data_len = fes.len() - Ck::CHECKSUM_LENGTH;
+
+        let mut entered = fes[..data_len].to_vec();
+        for i in 0..fes.len() {
+            let mask = valid_next_chars::<Ck>(&hrp, &fes[..i], fes.len() - i);
+            assert_ne!(mask & (1 << fes[i].to_u8()), 0, "{} at {}", s, i);
+            if i < data_len {
+                assert_eq!(mask, u32::MAX, "{} at {}", s, i);
+            } else {
+                // In the checksum the mask has a single bit set, follow it.
+                let mask = valid_next_chars::<Ck>(&hrp, &entered, fes.len() - i);
+                assert_eq!(mask.count_ones(), 1, "{} at {}", s, i);
+                entered.push(Fe32(mask.trailing_zeros() as u8));
+            }
+        }
+        assert_eq!(entered, fes);
+        assert!(complete_checksum::<Ck>(&hrp, &fes[..data_len]).eq(fes[data_len..].iter().copied()));
+        assert_eq!(valid_next_chars::<Ck>(&hrp, &fes, 0), 0);
+    }
+
+    #[test]
+    fn following_mask_ends_at_checksum_bip_173() {
+        let vectors = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        for s in vectors.iter() {
+            check_following_mask::<Bech32>(s);
+        }
+    }
+
+    #[test]
+    fn following_mask_ends_at_checksum_bip_350() {
+        let vectors = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for s in vectors.iter() {
+            check_following_mask::<Bech32m>(s);
+        }
+    }
+
+    #[test]
+    fn no_valid_chars() {
+        let (hrp, fes) = split("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw");
+        let checksum_start = fes.len() - 6;
+
+        // A wrong checksum character rules out every continuation.
+        let mut wrong = fes[..=checksum_start].to_vec();
+        wrong[checksum_start] = Fe32(wrong[checksum_start].to_u8() ^ 1);
+        assert_eq!(valid_next_chars::<Bech32>(&hrp, &wrong, 5), 0);
+        // Too short to hold a checksum.
+        assert_eq!(valid_next_chars::<Bech32>(&hrp, &[], 5), 0);
+        // Longer than the code length.
+        assert_eq!(valid_next_chars::<Bech32>(&hrp, &fes, Bech32::CODE_LENGTH), 0);
+        assert_eq!(valid_next_chars::<Bech32>(&hrp, &fes, usize::MAX), 0);
+    }
+}
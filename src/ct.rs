@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT
+
+//! Comparisons of secret strings, such as codex32 shares and `nsec` keys, without early exit.
+
+/// Returns whether `a` and `b` are equal ignoring ASCII case, in time independent of their content.
+///
+/// Use this instead of `==` when the strings encode secret data, for example codex32 shares or
+/// Nostr `nsec` keys, so that the time taken does not reveal how many leading characters match.
+///
+/// # Timing
+///
+/// Only the lengths of `a` and `b` are leaked: strings of different lengths return `false`
+/// immediately, otherwise every byte of both strings is examined, with no branches depending on
+/// their values. This is best effort, the compiler gives no guarantee that the generated code is
+/// constant time, and the human-readable part and length of an encoded secret are usually public
+/// anyway.
+///
+/// # Examples
+///
+/// ```
+/// let share = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+/// assert!(bech32grs::ct_eq(share, &share.to_uppercase()));
+/// assert!(!bech32grs::ct_eq(share, "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlq"));
+/// ```
+pub fn ct_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a
+        .bytes()
+        .zip(b.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (to_ascii_lower(a) ^ to_ascii_lower(b)));
+    diff == 0
+}
+
+/// Returns whether `a` and `b` are equal, in time independent of their content.
+///
+/// The byte level partner of [`ct_eq`], for comparing decoded secret data. Only the lengths of `a`
+/// and `b` are leaked, see [`ct_eq`] for details.
+///
+/// # Examples
+///
+/// ```
+/// assert!(bech32grs::ct_eq_bytes(&[0xab; 16], &[0xab; 16]));
+/// assert!(!bech32grs::ct_eq_bytes(&[0xab; 16], &[0xab; 15]));
+/// ```
+pub fn ct_eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    diff == 0
+}
+
+/// Converts an ASCII uppercase letter to lowercase without branching on the value of `b`.
+fn to_ascii_lower(b: u8) -> u8 {
+    // The high byte of the subtraction is 0xff exactly when `b` is in 'A'..='Z'.
+    let is_upper = (u16::from(b.wrapping_sub(b'A')).wrapping_sub(26) >> 8) as u8 & 1;
+    b | (is_upper << 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_ascii_lower_matches_core() {
+        for b in 0..=u8::MAX {
+            assert_eq!(to_ascii_lower(b), b.to_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn ct_eq_agrees_with_eq_ignore_ascii_case() {
+        let nsec = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+        let cases = [
+            (nsec, nsec),
+            (nsec, "NSEC1VL029MGPSPEDVA04G90VLTKH6FVH240ZQTV9K0T9AF8935KE9LAQSNLFE5"),
+            (nsec, "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5x"),
+            (nsec, "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe6"),
+            (nsec, "msec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5"),
+            ("a1b", "A1B"),
+            ("a1b", "a1["),
+            ("@", "`"),
+            ("", ""),
+        ];
+        for &(a, b) in cases.iter() {
+            assert_eq!(ct_eq(a, b), a.eq_ignore_ascii_case(b), "{} {}", a, b);
+            assert_eq!(ct_eq_bytes(a.as_bytes(), b.as_bytes()), a == b, "{} {}", a, b);
+        }
+    }
+}
@@ -1,19 +1,57 @@
 // SPDX-License-Identifier: CC0-1.0
 
+//! Error types and macros.
+
+/// The error trait implemented by the errors of this crate.
+///
+/// [`core::error::Error`] needs Rust 1.81 so is only used with the `core-error` feature, since then
+/// [`std::error::Error`] is a re-export of it.
+#[cfg(feature = "core-error")]
+pub(crate) use core::error::Error as StdError;
+use core::fmt;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+pub(crate) use std::error::Error as StdError;
+
+#[cfg(feature = "bolt11")]
+use crate::bolt11;
+#[cfg(feature = "bolt12")]
+use crate::bolt12;
+#[cfg(feature = "codex32")]
+use crate::codex32;
+#[cfg(feature = "heapless")]
+use crate::fixed_capacity::{DecodeHeaplessError, HeaplessDataBuilderError};
+#[cfg(feature = "lnurl")]
+use crate::lnurl;
+#[cfg(feature = "nip19")]
+use crate::nip19;
+use crate::primitives::decode::{
+    CharError, CheckedHrpstringError, ChecksumError, CodeLengthError, HrpMixedCaseError,
+    HrpSplitError, PaddingError, SegwitCodeLengthError, SegwitHrpstringError,
+    UncheckedHrpstringError,
+};
+use crate::primitives::segwit::{InvalidWitnessVersionError, WitnessLengthError};
+use crate::primitives::{gf32, hrp};
+use crate::segwit::{self, CompactAddressError, ScriptPubkeyError, WitnessProgramError};
+#[cfg(feature = "sp")]
+use crate::sp;
+use crate::{CapacityError, DataBuilderError, EncodeError, EncodeSliceError, SplitError};
+#[cfg(feature = "alloc")]
+use crate::{DecodeError, DecodeErrorWithInput, RouteError};
+
 /// Formats error.
 ///
-/// If `std` feature is OFF appends error source (delimited by `: `). We do this because
-/// `e.source()` is only available in std builds, without this macro the error source is lost for
-/// no-std builds.
+/// If neither the `std` nor the `core-error` feature is on appends error source (delimited by
+/// `: `). We do this because `e.source()` is only available if the error trait is implemented,
+/// without this macro the error source is lost for other no-std builds.
 macro_rules! write_err {
     ($writer:expr, $string:literal $(, $args:expr)*; $source:expr) => {
         {
-            #[cfg(feature = "std")]
+            #[cfg(any(feature = "std", feature = "core-error"))]
             {
                 let _ = &$source;   // Prevents clippy warnings.
                 write!($writer, $string $(, $args)*)
             }
-            #[cfg(not(feature = "std"))]
+            #[cfg(not(any(feature = "std", feature = "core-error")))]
             {
                 write!($writer, concat!($string, ": {}") $(, $args)*, $source)
             }
@@ -21,3 +59,1146 @@ macro_rules! write_err {
     }
 }
 pub(crate) use write_err;
+
+/// An error from any of the functions in this crate.
+///
+/// Functions return their own specific error types, this type exists so applications that use
+/// several of them can propagate all their errors with `?` and a single error type.
+///
+/// The errors of the functions that encode to an arbitrary writer are not included because they
+/// carry the writer's error type.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32grs::{segwit, Hrp};
+///
+/// /// Returns the witness program of `address` if it is a segwit address for `network`.
+/// fn witness_program(network: &str, address: &str) -> Result<Option<Vec<u8>>, bech32grs::Error> {
+///     let network = Hrp::parse(network)?;
+///     let (hrp, _data) = bech32grs::decode(address)?;
+///     if hrp != network {
+///         return Ok(None);
+///     }
+///     let (_hrp, _version, program) = segwit::decode(address)?;
+///     Ok(Some(program))
+/// }
+///
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+/// assert!(witness_program("grs", address).unwrap().is_some());
+/// assert!(witness_program("tgrs", address).unwrap().is_none());
+/// assert!(matches!(witness_program("", address), Err(bech32grs::Error::Hrp(_))));
+/// assert!(matches!(witness_program("grs", "grs1qqqqqq"), Err(bech32grs::Error::Decode(_))));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Error {
+    /// Error parsing a human-readable part.
+    Hrp(hrp::Error),
+    /// Error converting a character to a field element.
+    FromChar(gf32::FromCharError),
+    /// Error converting an integer to a field element.
+    TryFrom(gf32::TryFromError),
+    /// Error reading fields from a slice of field elements.
+    FeReader(gf32::FeReaderError),
+    /// Error writing fields to field elements.
+    FeWriter(gf32::FeWriterError),
+    /// Invalid segwit witness version.
+    WitnessVersion(InvalidWitnessVersionError),
+    /// Invalid segwit witness program length.
+    WitnessLength(WitnessLengthError),
+    /// Error parsing an unchecked hrpstring.
+    UncheckedHrpstring(UncheckedHrpstringError),
+    /// Error parsing a checked hrpstring.
+    CheckedHrpstring(CheckedHrpstringError),
+    /// Error parsing a segwit hrpstring.
+    SegwitHrpstring(SegwitHrpstringError),
+    /// Error with the characters of a string.
+    Char(CharError),
+    /// A human-readable part that mixes upper and lower case.
+    HrpMixedCase(HrpMixedCaseError),
+    /// An invalid human-readable part of a string with more than one separator.
+    HrpSplit(HrpSplitError),
+    /// Error validating a checksum.
+    Checksum(ChecksumError),
+    /// String exceeds the checksum's code length.
+    CodeLength(CodeLengthError),
+    /// String exceeds the maximum segwit address length.
+    SegwitCodeLength(SegwitCodeLengthError),
+    /// Invalid padding on segwit witness data.
+    Padding(PaddingError),
+    /// Error parsing a compact segwit address.
+    CompactAddress(CompactAddressError),
+    /// Error constructing a segwit witness program.
+    WitnessProgram(WitnessProgramError),
+    /// Error reading a segwit witness program from a scriptPubKey.
+    ScriptPubkey(ScriptPubkeyError),
+    /// Error encoding a bech32 string.
+    Encode(EncodeError),
+    /// Error encoding a bech32 string into a byte slice.
+    EncodeSlice(EncodeSliceError),
+    /// Error encoding a segwit address into a byte slice.
+    SegwitEncodeSlice(segwit::EncodeSliceError),
+    /// Error splitting a bech32 string into its parts.
+    Split(SplitError),
+    /// Error pushing to a data builder.
+    DataBuilder(DataBuilderError),
+    /// Error decoding a bech32 string.
+    #[cfg(feature = "alloc")]
+    Decode(DecodeError),
+    /// Error encoding a segwit address.
+    #[cfg(feature = "alloc")]
+    SegwitEncode(segwit::EncodeError),
+    /// Error decoding a segwit address.
+    #[cfg(feature = "alloc")]
+    SegwitDecode(segwit::DecodeError),
+    /// Error decoding a checked hrpstring, with part of the input.
+    #[cfg(feature = "alloc")]
+    CheckedHrpstringWithInput(DecodeErrorWithInput<CheckedHrpstringError>),
+    /// Error decoding a segwit address, with part of the input.
+    #[cfg(feature = "alloc")]
+    SegwitDecodeWithInput(DecodeErrorWithInput<segwit::DecodeError>),
+    /// Error routing a string by its human-readable part.
+    #[cfg(feature = "alloc")]
+    Route(RouteError),
+    /// A fixed capacity buffer is too small.
+    Capacity(CapacityError),
+    /// Error decoding a bech32 string into a fixed capacity buffer.
+    #[cfg(feature = "heapless")]
+    DecodeHeapless(DecodeHeaplessError),
+    /// Error decoding a segwit address into a fixed capacity buffer.
+    #[cfg(feature = "heapless")]
+    SegwitDecodeHeapless(segwit::DecodeHeaplessError),
+    /// Error pushing to a fixed capacity data builder.
+    #[cfg(feature = "heapless")]
+    HeaplessDataBuilder(HeaplessDataBuilderError),
+    /// Error parsing a Lightning invoice.
+    #[cfg(feature = "bolt11")]
+    Bolt11(bolt11::ParseError),
+    /// Error parsing a Lightning offer.
+    #[cfg(feature = "bolt12")]
+    Bolt12(bolt12::ParseError),
+    /// Error decoding an LNURL.
+    #[cfg(feature = "lnurl")]
+    Lnurl(lnurl::LnurlError),
+    /// Error decoding a Nostr entity.
+    #[cfg(feature = "nip19")]
+    Nip19(nip19::Nip19Error),
+    /// Error decoding a silent payment address.
+    #[cfg(feature = "sp")]
+    Sp(sp::SpError),
+    /// Error parsing or constructing a codex32 share.
+    #[cfg(feature = "codex32")]
+    Codex32(codex32::ShareError),
+}
+
+impl Error {
+    /// Returns a machine-readable code identifying this error, for example `"E_CHECKSUM_INVALID"`.
+    ///
+    /// Every error type in this crate has a `code` method, wrapping errors return the code of the
+    /// error they wrap. Codes are stable: the code of an error never changes and codes are never
+    /// reused, so they can be used by other languages and in APIs to branch on errors.
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use Error::*;
+
+        match *self {
+            Hrp(ref e) => e.error_code(),
+            FromChar(ref e) => e.error_code(),
+            TryFrom(ref e) => e.error_code(),
+            FeReader(ref e) => e.error_code(),
+            FeWriter(ref e) => e.error_code(),
+            WitnessVersion(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+            UncheckedHrpstring(ref e) => e.error_code(),
+            CheckedHrpstring(ref e) => e.error_code(),
+            SegwitHrpstring(ref e) => e.error_code(),
+            Char(ref e) => e.error_code(),
+            HrpMixedCase(ref e) => e.error_code(),
+            HrpSplit(ref e) => e.error_code(),
+            Checksum(ref e) => e.error_code(),
+            CodeLength(ref e) => e.error_code(),
+            SegwitCodeLength(ref e) => e.error_code(),
+            Padding(ref e) => e.error_code(),
+            CompactAddress(ref e) => e.error_code(),
+            WitnessProgram(ref e) => e.error_code(),
+            ScriptPubkey(ref e) => e.error_code(),
+            Encode(ref e) => e.error_code(),
+            EncodeSlice(ref e) => e.error_code(),
+            SegwitEncodeSlice(ref e) => e.error_code(),
+            Split(ref e) => e.error_code(),
+            DataBuilder(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            Decode(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            SegwitEncode(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            SegwitDecode(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            CheckedHrpstringWithInput(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            SegwitDecodeWithInput(ref e) => e.error_code(),
+            #[cfg(feature = "alloc")]
+            Route(ref e) => e.error_code(),
+            Capacity(ref e) => e.error_code(),
+            #[cfg(feature = "heapless")]
+            DecodeHeapless(ref e) => e.error_code(),
+            #[cfg(feature = "heapless")]
+            SegwitDecodeHeapless(ref e) => e.error_code(),
+            #[cfg(feature = "heapless")]
+            HeaplessDataBuilder(ref e) => e.error_code(),
+            #[cfg(feature = "bolt11")]
+            Bolt11(ref e) => e.error_code(),
+            #[cfg(feature = "bolt12")]
+            Bolt12(ref e) => e.error_code(),
+            #[cfg(feature = "lnurl")]
+            Lnurl(ref e) => e.error_code(),
+            #[cfg(feature = "nip19")]
+            Nip19(ref e) => e.error_code(),
+            #[cfg(feature = "sp")]
+            Sp(ref e) => e.error_code(),
+            #[cfg(feature = "codex32")]
+            Codex32(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Error::*;
+
+        match *self {
+            Hrp(ref e) => fmt::Display::fmt(e, f),
+            FromChar(ref e) => fmt::Display::fmt(e, f),
+            TryFrom(ref e) => fmt::Display::fmt(e, f),
+            FeReader(ref e) => fmt::Display::fmt(e, f),
+            FeWriter(ref e) => fmt::Display::fmt(e, f),
+            WitnessVersion(ref e) => fmt::Display::fmt(e, f),
+            WitnessLength(ref e) => fmt::Display::fmt(e, f),
+            UncheckedHrpstring(ref e) => fmt::Display::fmt(e, f),
+            CheckedHrpstring(ref e) => fmt::Display::fmt(e, f),
+            SegwitHrpstring(ref e) => fmt::Display::fmt(e, f),
+            Char(ref e) => fmt::Display::fmt(e, f),
+            HrpMixedCase(ref e) => fmt::Display::fmt(e, f),
+            HrpSplit(ref e) => fmt::Display::fmt(e, f),
+            Checksum(ref e) => fmt::Display::fmt(e, f),
+            CodeLength(ref e) => fmt::Display::fmt(e, f),
+            SegwitCodeLength(ref e) => fmt::Display::fmt(e, f),
+            Padding(ref e) => fmt::Display::fmt(e, f),
+            CompactAddress(ref e) => fmt::Display::fmt(e, f),
+            WitnessProgram(ref e) => fmt::Display::fmt(e, f),
+            ScriptPubkey(ref e) => fmt::Display::fmt(e, f),
+            Encode(ref e) => fmt::Display::fmt(e, f),
+            EncodeSlice(ref e) => fmt::Display::fmt(e, f),
+            SegwitEncodeSlice(ref e) => fmt::Display::fmt(e, f),
+            Split(ref e) => fmt::Display::fmt(e, f),
+            DataBuilder(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            Decode(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            SegwitEncode(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            SegwitDecode(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            CheckedHrpstringWithInput(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            SegwitDecodeWithInput(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "alloc")]
+            Route(ref e) => fmt::Display::fmt(e, f),
+            Capacity(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "heapless")]
+            DecodeHeapless(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "heapless")]
+            SegwitDecodeHeapless(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "heapless")]
+            HeaplessDataBuilder(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "bolt11")]
+            Bolt11(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "bolt12")]
+            Bolt12(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "lnurl")]
+            Lnurl(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "nip19")]
+            Nip19(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "sp")]
+            Sp(ref e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "codex32")]
+            Codex32(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use Error::*;
+
+        match *self {
+            Hrp(ref e) => StdError::source(e),
+            FromChar(ref e) => StdError::source(e),
+            TryFrom(ref e) => StdError::source(e),
+            FeReader(ref e) => StdError::source(e),
+            FeWriter(ref e) => StdError::source(e),
+            WitnessVersion(ref e) => StdError::source(e),
+            WitnessLength(ref e) => StdError::source(e),
+            UncheckedHrpstring(ref e) => StdError::source(e),
+            CheckedHrpstring(ref e) => StdError::source(e),
+            SegwitHrpstring(ref e) => StdError::source(e),
+            Char(ref e) => StdError::source(e),
+            HrpMixedCase(ref e) => StdError::source(e),
+            HrpSplit(ref e) => StdError::source(e),
+            Checksum(ref e) => StdError::source(e),
+            CodeLength(ref e) => StdError::source(e),
+            SegwitCodeLength(ref e) => StdError::source(e),
+            Padding(ref e) => StdError::source(e),
+            CompactAddress(ref e) => StdError::source(e),
+            WitnessProgram(ref e) => StdError::source(e),
+            ScriptPubkey(ref e) => StdError::source(e),
+            Encode(ref e) => StdError::source(e),
+            EncodeSlice(ref e) => StdError::source(e),
+            SegwitEncodeSlice(ref e) => StdError::source(e),
+            Split(ref e) => StdError::source(e),
+            DataBuilder(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            Decode(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            SegwitEncode(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            SegwitDecode(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            CheckedHrpstringWithInput(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            SegwitDecodeWithInput(ref e) => StdError::source(e),
+            #[cfg(feature = "alloc")]
+            Route(ref e) => StdError::source(e),
+            Capacity(ref e) => StdError::source(e),
+            #[cfg(feature = "heapless")]
+            DecodeHeapless(ref e) => StdError::source(e),
+            #[cfg(feature = "heapless")]
+            SegwitDecodeHeapless(ref e) => StdError::source(e),
+            #[cfg(feature = "heapless")]
+            HeaplessDataBuilder(ref e) => StdError::source(e),
+            #[cfg(feature = "bolt11")]
+            Bolt11(ref e) => StdError::source(e),
+            #[cfg(feature = "bolt12")]
+            Bolt12(ref e) => StdError::source(e),
+            #[cfg(feature = "lnurl")]
+            Lnurl(ref e) => StdError::source(e),
+            #[cfg(feature = "nip19")]
+            Nip19(ref e) => StdError::source(e),
+            #[cfg(feature = "sp")]
+            Sp(ref e) => StdError::source(e),
+            #[cfg(feature = "codex32")]
+            Codex32(ref e) => StdError::source(e),
+        }
+    }
+}
+
+impl From<hrp::Error> for Error {
+    #[inline]
+    fn from(e: hrp::Error) -> Self { Self::Hrp(e) }
+}
+
+impl From<gf32::FromCharError> for Error {
+    #[inline]
+    fn from(e: gf32::FromCharError) -> Self { Self::FromChar(e) }
+}
+
+impl From<gf32::TryFromError> for Error {
+    #[inline]
+    fn from(e: gf32::TryFromError) -> Self { Self::TryFrom(e) }
+}
+
+impl From<gf32::FeReaderError> for Error {
+    #[inline]
+    fn from(e: gf32::FeReaderError) -> Self { Self::FeReader(e) }
+}
+
+impl From<gf32::FeWriterError> for Error {
+    #[inline]
+    fn from(e: gf32::FeWriterError) -> Self { Self::FeWriter(e) }
+}
+
+impl From<InvalidWitnessVersionError> for Error {
+    #[inline]
+    fn from(e: InvalidWitnessVersionError) -> Self { Self::WitnessVersion(e) }
+}
+
+impl From<WitnessLengthError> for Error {
+    #[inline]
+    fn from(e: WitnessLengthError) -> Self { Self::WitnessLength(e) }
+}
+
+impl From<UncheckedHrpstringError> for Error {
+    #[inline]
+    fn from(e: UncheckedHrpstringError) -> Self { Self::UncheckedHrpstring(e) }
+}
+
+impl From<CheckedHrpstringError> for Error {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::CheckedHrpstring(e) }
+}
+
+impl From<SegwitHrpstringError> for Error {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self { Self::SegwitHrpstring(e) }
+}
+
+impl From<CharError> for Error {
+    #[inline]
+    fn from(e: CharError) -> Self { Self::Char(e) }
+}
+
+impl From<HrpMixedCaseError> for Error {
+    #[inline]
+    fn from(e: HrpMixedCaseError) -> Self { Self::HrpMixedCase(e) }
+}
+
+impl From<HrpSplitError> for Error {
+    #[inline]
+    fn from(e: HrpSplitError) -> Self { Self::HrpSplit(e) }
+}
+
+impl From<ChecksumError> for Error {
+    #[inline]
+    fn from(e: ChecksumError) -> Self { Self::Checksum(e) }
+}
+
+impl From<CodeLengthError> for Error {
+    #[inline]
+    fn from(e: CodeLengthError) -> Self { Self::CodeLength(e) }
+}
+
+impl From<SegwitCodeLengthError> for Error {
+    #[inline]
+    fn from(e: SegwitCodeLengthError) -> Self { Self::SegwitCodeLength(e) }
+}
+
+impl From<PaddingError> for Error {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+impl From<CompactAddressError> for Error {
+    #[inline]
+    fn from(e: CompactAddressError) -> Self { Self::CompactAddress(e) }
+}
+
+impl From<WitnessProgramError> for Error {
+    #[inline]
+    fn from(e: WitnessProgramError) -> Self { Self::WitnessProgram(e) }
+}
+
+impl From<ScriptPubkeyError> for Error {
+    #[inline]
+    fn from(e: ScriptPubkeyError) -> Self { Self::ScriptPubkey(e) }
+}
+
+impl From<EncodeError> for Error {
+    #[inline]
+    fn from(e: EncodeError) -> Self { Self::Encode(e) }
+}
+
+impl From<EncodeSliceError> for Error {
+    #[inline]
+    fn from(e: EncodeSliceError) -> Self { Self::EncodeSlice(e) }
+}
+
+impl From<segwit::EncodeSliceError> for Error {
+    #[inline]
+    fn from(e: segwit::EncodeSliceError) -> Self { Self::SegwitEncodeSlice(e) }
+}
+
+impl From<SplitError> for Error {
+    #[inline]
+    fn from(e: SplitError) -> Self { Self::Split(e) }
+}
+
+impl From<DataBuilderError> for Error {
+    #[inline]
+    fn from(e: DataBuilderError) -> Self { Self::DataBuilder(e) }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeError> for Error {
+    #[inline]
+    fn from(e: DecodeError) -> Self { Self::Decode(e) }
+}
+
+#[cfg(feature = "alloc")]
+impl From<segwit::EncodeError> for Error {
+    #[inline]
+    fn from(e: segwit::EncodeError) -> Self { Self::SegwitEncode(e) }
+}
+
+#[cfg(feature = "alloc")]
+impl From<segwit::DecodeError> for Error {
+    #[inline]
+    fn from(e: segwit::DecodeError) -> Self { Self::SegwitDecode(e) }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeErrorWithInput<CheckedHrpstringError>> for Error {
+    #[inline]
+    fn from(e: DecodeErrorWithInput<CheckedHrpstringError>) -> Self {
+        Self::CheckedHrpstringWithInput(e)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeErrorWithInput<segwit::DecodeError>> for Error {
+    #[inline]
+    fn from(e: DecodeErrorWithInput<segwit::DecodeError>) -> Self { Self::SegwitDecodeWithInput(e) }
+}
+
+#[cfg(feature = "alloc")]
+impl From<RouteError> for Error {
+    #[inline]
+    fn from(e: RouteError) -> Self { Self::Route(e) }
+}
+
+impl From<CapacityError> for Error {
+    #[inline]
+    fn from(e: CapacityError) -> Self { Self::Capacity(e) }
+}
+
+#[cfg(feature = "heapless")]
+impl From<DecodeHeaplessError> for Error {
+    #[inline]
+    fn from(e: DecodeHeaplessError) -> Self { Self::DecodeHeapless(e) }
+}
+
+#[cfg(feature = "heapless")]
+impl From<segwit::DecodeHeaplessError> for Error {
+    #[inline]
+    fn from(e: segwit::DecodeHeaplessError) -> Self { Self::SegwitDecodeHeapless(e) }
+}
+
+#[cfg(feature = "heapless")]
+impl From<HeaplessDataBuilderError> for Error {
+    #[inline]
+    fn from(e: HeaplessDataBuilderError) -> Self { Self::HeaplessDataBuilder(e) }
+}
+
+#[cfg(feature = "bolt11")]
+impl From<bolt11::ParseError> for Error {
+    #[inline]
+    fn from(e: bolt11::ParseError) -> Self { Self::Bolt11(e) }
+}
+
+#[cfg(feature = "bolt12")]
+impl From<bolt12::ParseError> for Error {
+    #[inline]
+    fn from(e: bolt12::ParseError) -> Self { Self::Bolt12(e) }
+}
+
+#[cfg(feature = "lnurl")]
+impl From<lnurl::LnurlError> for Error {
+    #[inline]
+    fn from(e: lnurl::LnurlError) -> Self { Self::Lnurl(e) }
+}
+
+#[cfg(feature = "nip19")]
+impl From<nip19::Nip19Error> for Error {
+    #[inline]
+    fn from(e: nip19::Nip19Error) -> Self { Self::Nip19(e) }
+}
+
+#[cfg(feature = "sp")]
+impl From<sp::SpError> for Error {
+    #[inline]
+    fn from(e: sp::SpError) -> Self { Self::Sp(e) }
+}
+
+#[cfg(feature = "codex32")]
+impl From<codex32::ShareError> for Error {
+    #[inline]
+    fn from(e: codex32::ShareError) -> Self { Self::Codex32(e) }
+}
+
+/// Formats `e` into `buf`, for displaying errors without allocating.
+///
+/// Returns the formatted message. If it does not fit in `buf` it is cut at a character boundary
+/// and ends with `...`, buffers shorter than that hold only part of the ellipsis.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::CharError;
+///
+/// let mut buf = [0_u8; 64];
+/// assert_eq!(bech32grs::render_error(&CharError::MixedCase, &mut buf), "mixed-case strings not allowed");
+///
+/// let mut small = [0_u8; 12];
+/// assert_eq!(bech32grs::render_error(&CharError::MixedCase, &mut small), "mixed-cas...");
+/// ```
+pub fn render_error<'b>(e: &impl fmt::Display, buf: &'b mut [u8]) -> &'b str {
+    const ELLIPSIS: &str = "...";
+
+    let mut w = TruncatingWriter { buf, len: 0, truncated: false };
+    let _ = fmt::write(&mut w, format_args!("{}", e));
+    let TruncatingWriter { buf, mut len, truncated } = w;
+
+    if truncated {
+        // Make room for the ellipsis, without splitting a character.
+        let ellipsis_len = ELLIPSIS.len().min(buf.len());
+        let written = core::str::from_utf8(&buf[..len]).expect("only whole characters are written");
+        let mut cut = len.min(buf.len() - ellipsis_len);
+        while !written.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        len = cut + ellipsis_len;
+        buf[cut..len].copy_from_slice(&ELLIPSIS.as_bytes()[..ellipsis_len]);
+    }
+    core::str::from_utf8(&buf[..len]).expect("only whole characters are written")
+}
+
+/// Writes to a byte slice, keeping as many whole characters as fit.
+struct TruncatingWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    /// Whether anything was left out.
+    truncated: bool,
+}
+
+impl fmt::Write for TruncatingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let available = self.buf.len() - self.len;
+        let mut n = s.len().min(available);
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+
+        if n < s.len() {
+            self.truncated = true;
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+/// The machine-readable error codes returned by the `code` methods of the error types.
+///
+/// This is the single table of codes, the [`ffi`](crate::ffi) error codes are derived from it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(dead_code)] // Some errors only exist with some features enabled.
+pub(crate) enum Code {
+    HrpTooLong,
+    HrpEmpty,
+    HrpNonAscii,
+    HrpInvalidByte,
+    HrpMixedCase,
+    HrpNotDisplaySafe,
+    UnknownHrp,
+    WrongHrp,
+    Empty,
+    MissingSeparator,
+    NothingAfterSeparator,
+    InvalidChar,
+    ControlChar,
+    MixedCase,
+    LowercaseRequired,
+    CharNotAscii,
+    CharInvalid,
+    FeNotAByte,
+    FeInvalidByte,
+    ChecksumInvalid,
+    ChecksumLength,
+    ProbablyTruncated,
+    ExceedsCodeLength,
+    SegwitTooLong,
+    MaxLengthInvalid,
+    NoData,
+    WitnessVersionInvalid,
+    WitnessTooShort,
+    WitnessTooLong,
+    WitnessV0Length,
+    PaddingTooMuch,
+    PaddingNonZero,
+    Fmt,
+    Write,
+    Source,
+    Capacity,
+    Bolt11Prefix,
+    Bolt11TooShort,
+    LnurlHrp,
+    LnurlUtf8,
+    Nip19UnknownHrp,
+    Nip19Length,
+    SpUnknownHrp,
+    SpVersion,
+    SpLength,
+    Codex32Hrp,
+    Codex32Header,
+    Codex32Threshold,
+    Codex32Identifier,
+    Codex32ShareIndex,
+    Codex32Length,
+    Bolt12Continuation,
+    BuilderMisaligned,
+    BuilderBits,
+    ScriptOpcode,
+    ScriptPush,
+    ReaderOutOfData,
+    ReaderMisaligned,
+    ReaderBits,
+    WriterBits,
+    WriterUnpadded,
+    UppercaseRequired,
+}
+
+impl Code {
+    /// Every code, used to check that codes are unique.
+    #[cfg(test)]
+    pub(crate) const ALL: [Code; 62] = [
+        Code::HrpTooLong,
+        Code::HrpEmpty,
+        Code::HrpNonAscii,
+        Code::HrpInvalidByte,
+        Code::HrpMixedCase,
+        Code::HrpNotDisplaySafe,
+        Code::UnknownHrp,
+        Code::WrongHrp,
+        Code::Empty,
+        Code::MissingSeparator,
+        Code::NothingAfterSeparator,
+        Code::InvalidChar,
+        Code::ControlChar,
+        Code::MixedCase,
+        Code::LowercaseRequired,
+        Code::CharNotAscii,
+        Code::CharInvalid,
+        Code::FeNotAByte,
+        Code::FeInvalidByte,
+        Code::ChecksumInvalid,
+        Code::ChecksumLength,
+        Code::ProbablyTruncated,
+        Code::ExceedsCodeLength,
+        Code::SegwitTooLong,
+        Code::MaxLengthInvalid,
+        Code::NoData,
+        Code::WitnessVersionInvalid,
+        Code::WitnessTooShort,
+        Code::WitnessTooLong,
+        Code::WitnessV0Length,
+        Code::PaddingTooMuch,
+        Code::PaddingNonZero,
+        Code::Fmt,
+        Code::Write,
+        Code::Source,
+        Code::Capacity,
+        Code::Bolt11Prefix,
+        Code::Bolt11TooShort,
+        Code::LnurlHrp,
+        Code::LnurlUtf8,
+        Code::Nip19UnknownHrp,
+        Code::Nip19Length,
+        Code::SpUnknownHrp,
+        Code::SpVersion,
+        Code::SpLength,
+        Code::Codex32Hrp,
+        Code::Codex32Header,
+        Code::Codex32Threshold,
+        Code::Codex32Identifier,
+        Code::Codex32ShareIndex,
+        Code::Codex32Length,
+        Code::Bolt12Continuation,
+        Code::BuilderMisaligned,
+        Code::BuilderBits,
+        Code::ScriptOpcode,
+        Code::ScriptPush,
+        Code::ReaderOutOfData,
+        Code::ReaderMisaligned,
+        Code::ReaderBits,
+        Code::WriterBits,
+        Code::WriterUnpadded,
+        Code::UppercaseRequired,
+    ];
+
+    /// Returns the string code and the FFI error code.
+    #[rustfmt::skip]
+    const fn entry(self) -> (&'static str, i32) {
+        use Code::*;
+
+        // The FFI error codes are the `ffi::ERR_*` constants.
+        match self {
+            HrpTooLong            => ("E_HRP_TOO_LONG",               -4),
+            HrpEmpty              => ("E_HRP_EMPTY",                  -4),
+            HrpNonAscii           => ("E_HRP_NON_ASCII",              -4),
+            HrpInvalidByte        => ("E_HRP_INVALID_BYTE",           -4),
+            HrpMixedCase          => ("E_HRP_MIXED_CASE",             -4),
+            HrpNotDisplaySafe     => ("E_HRP_NOT_DISPLAY_SAFE",       -4),
+            UnknownHrp            => ("E_UNKNOWN_HRP",                -4),
+            WrongHrp              => ("E_WRONG_HRP",                  -4),
+            Empty                 => ("E_EMPTY",                      -5),
+            MissingSeparator      => ("E_MISSING_SEPARATOR",          -5),
+            NothingAfterSeparator => ("E_NOTHING_AFTER_SEPARATOR",    -5),
+            InvalidChar           => ("E_INVALID_CHAR",               -5),
+            ControlChar           => ("E_CONTROL_CHAR",               -5),
+            MixedCase             => ("E_MIXED_CASE",                 -5),
+            LowercaseRequired     => ("E_LOWERCASE_REQUIRED",         -5),
+            CharNotAscii          => ("E_CHAR_NOT_ASCII",             -5),
+            CharInvalid           => ("E_CHAR_INVALID",               -5),
+            FeNotAByte            => ("E_FE_NOT_A_BYTE",              -9),
+            FeInvalidByte         => ("E_FE_INVALID_BYTE",            -9),
+            ChecksumInvalid       => ("E_CHECKSUM_INVALID",           -6),
+            ChecksumLength        => ("E_CHECKSUM_LENGTH",            -6),
+            ProbablyTruncated     => ("E_PROBABLY_TRUNCATED",         -6),
+            ExceedsCodeLength     => ("E_CODE_LENGTH_EXCEEDED",       -8),
+            SegwitTooLong         => ("E_SEGWIT_TOO_LONG",            -8),
+            MaxLengthInvalid      => ("E_MAX_LENGTH_INVALID",         -8),
+            NoData                => ("E_NO_DATA",                    -7),
+            WitnessVersionInvalid => ("E_WITNESS_VERSION_INVALID",    -9),
+            WitnessTooShort       => ("E_WITNESS_TOO_SHORT",         -10),
+            WitnessTooLong        => ("E_WITNESS_TOO_LONG",          -10),
+            WitnessV0Length       => ("E_WITNESS_V0_LENGTH",         -10),
+            PaddingTooMuch        => ("E_PADDING_TOO_MUCH",          -11),
+            PaddingNonZero        => ("E_PADDING_NON_ZERO",          -11),
+            Fmt                   => ("E_FMT",                        -2),
+            Write                 => ("E_WRITE",                      -2),
+            Source                => ("E_SOURCE",                     -2),
+            Capacity              => ("E_CAPACITY",                   -2),
+            Bolt11Prefix          => ("E_BOLT11_PREFIX",              -4),
+            Bolt11TooShort        => ("E_BOLT11_TOO_SHORT",           -7),
+            LnurlHrp              => ("E_LNURL_HRP",                  -4),
+            LnurlUtf8             => ("E_LNURL_UTF8",                 -3),
+            Nip19UnknownHrp       => ("E_NIP19_UNKNOWN_HRP",          -4),
+            Nip19Length           => ("E_NIP19_LENGTH",               -7),
+            SpUnknownHrp          => ("E_SP_UNKNOWN_HRP",             -4),
+            SpVersion             => ("E_SP_VERSION",                 -9),
+            SpLength              => ("E_SP_LENGTH",                 -10),
+            Codex32Hrp            => ("E_CODEX32_HRP",                -4),
+            Codex32Header         => ("E_CODEX32_HEADER",             -7),
+            Codex32Threshold      => ("E_CODEX32_THRESHOLD",          -5),
+            Codex32Identifier     => ("E_CODEX32_IDENTIFIER",         -5),
+            Codex32ShareIndex     => ("E_CODEX32_SHARE_INDEX",        -5),
+            Codex32Length         => ("E_CODEX32_LENGTH",            -10),
+            Bolt12Continuation    => ("E_BOLT12_CONTINUATION",        -5),
+            BuilderMisaligned     => ("E_BUILDER_MISALIGNED",        -11),
+            BuilderBits           => ("E_BUILDER_BITS",               -9),
+            ScriptOpcode          => ("E_SCRIPT_OPCODE",              -9),
+            ScriptPush            => ("E_SCRIPT_PUSH",               -10),
+            ReaderOutOfData       => ("E_READER_OUT_OF_DATA",         -7),
+            ReaderMisaligned      => ("E_READER_MISALIGNED",         -11),
+            ReaderBits            => ("E_READER_BITS",                -9),
+            WriterBits            => ("E_WRITER_BITS",                -9),
+            WriterUnpadded        => ("E_WRITER_UNPADDED",           -11),
+            UppercaseRequired     => ("E_UPPERCASE_REQUIRED",         -5),
+        }
+    }
+
+    /// Returns the string returned by the `code` methods.
+    #[inline]
+    pub(crate) const fn as_str(self) -> &'static str { self.entry().0 }
+
+    /// Returns the FFI error code.
+    #[cfg(feature = "ffi")]
+    #[inline]
+    pub(crate) const fn ffi(self) -> i32 { self.entry().1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use super::*;
+    use crate::primitives::encode::TryWriteError;
+    use crate::primitives::gf32::Fe32;
+    use crate::Hrp;
+
+    #[test]
+    fn render_error_fits() {
+        let errors: [&dyn fmt::Display; 4] = [
+            &CharError::MixedCase,
+            &ChecksumError::InvalidResidue,
+            &PaddingError::NonZero,
+            &Error::from(hrp::Error::Empty),
+        ];
+        for e in errors.iter() {
+            let mut buf = [0_u8; 128];
+            assert_eq!(render_error(e, &mut buf), e.to_string());
+
+            // Exactly the right size.
+            let s = e.to_string();
+            let mut buf = vec![0_u8; s.len()];
+            assert_eq!(render_error(e, &mut buf), s);
+        }
+    }
+
+    #[test]
+    fn render_error_truncates() {
+        let errors: [&dyn fmt::Display; 4] = [
+            &CharError::MixedCase,
+            &ChecksumError::InvalidResidue,
+            &PaddingError::NonZero,
+            &Error::from(hrp::Error::Empty),
+        ];
+        for e in errors.iter() {
+            let s = e.to_string();
+            let mut buf = [0xff_u8; 4];
+            assert_eq!(render_error(e, &mut buf), format!("{}...", &s[..1]));
+
+            let mut buf = vec![0xff_u8; s.len() - 1];
+            assert_eq!(render_error(e, &mut buf), format!("{}...", &s[..s.len() - 4]));
+        }
+
+        let mut buf = [0_u8; 2];
+        assert_eq!(render_error(&CharError::MixedCase, &mut buf), "..");
+        assert_eq!(render_error(&CharError::MixedCase, &mut []), "");
+    }
+
+    #[test]
+    fn render_error_truncates_at_char_boundary() {
+        struct Multibyte;
+        impl fmt::Display for Multibyte {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("ab\u{20ac}\u{20ac}\u{20ac}")
+            }
+        }
+        // The euro sign is 3 bytes, with `ab` and an ellipsis there is no room for one.
+        let mut buf = [0xff_u8; 7];
+        assert_eq!(render_error(&Multibyte, &mut buf), "ab...");
+        let mut buf = [0xff_u8; 9];
+        assert_eq!(render_error(&Multibyte, &mut buf), "ab\u{20ac}...");
+        let mut buf = [0xff_u8; 11];
+        assert_eq!(render_error(&Multibyte, &mut buf), "ab\u{20ac}\u{20ac}\u{20ac}");
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        for (i, code) in Code::ALL.iter().enumerate() {
+            let s = code.as_str();
+            assert!(s.starts_with("E_"), "{}", s);
+            assert!(s.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_'));
+            for other in Code::ALL[i + 1..].iter() {
+                assert_ne!(s, other.as_str());
+            }
+        }
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn every_error_has_a_code() {
+        use hrp::Error as HrpError;
+
+        let residue = ChecksumError::InvalidResidue;
+        let code_length = CodeLengthError { encoded_length: 1024, code_length: 1023 };
+        let not_a_byte = match Fe32::try_from(256_u16) {
+            Err(gf32::TryFromError::NotAByte(e)) => e,
+            _ => panic!("256 is not a byte"),
+        };
+        let witness_length = WitnessLengthError::InvalidSegwitV0 { len: 21 };
+        let hrp_mixed_case = HrpMixedCaseError::new("Grs").expect("mixed case hrp");
+        let hrp_split = HrpSplitError::new("g s1q", HrpError::InvalidAsciiByte(b' '));
+        let capacity = CapacityError { required: 2, capacity: 1 };
+        let wrong_hrp = SegwitHrpstringError::WrongHrp {
+            expected: segwit::KnownHrp::Mainnet,
+            got: Some(segwit::KnownHrp::Testnets),
+        };
+
+        let codes = [
+            (HrpError::TooLong(84).code(), "E_HRP_TOO_LONG"),
+            (HrpError::Empty.code(), "E_HRP_EMPTY"),
+            (HrpError::NonAsciiChar('é').code(), "E_HRP_NON_ASCII"),
+            (HrpError::InvalidAsciiByte(b' ').code(), "E_HRP_INVALID_BYTE"),
+            (HrpError::MixedCase.code(), "E_HRP_MIXED_CASE"),
+            (HrpError::NotDisplaySafe(b'!').code(), "E_HRP_NOT_DISPLAY_SAFE"),
+            (gf32::FromCharError::NotAscii('é').code(), "E_CHAR_NOT_ASCII"),
+            (gf32::FromCharError::Invalid('b').code(), "E_CHAR_INVALID"),
+            (gf32::TryFromError::NotAByte(not_a_byte).code(), "E_FE_NOT_A_BYTE"),
+            (gf32::TryFromError::InvalidByte(32).code(), "E_FE_INVALID_BYTE"),
+            (gf32::FeReaderError::OutOfData { pos: 0, requested: 8, remaining: 5 }.code(), "E_READER_OUT_OF_DATA"),
+            (gf32::FeReaderError::Misaligned { pos: 3 }.code(), "E_READER_MISALIGNED"),
+            (gf32::FeReaderError::TooWide { bits: 65 }.code(), "E_READER_BITS"),
+            (gf32::FeWriterError::TooWide { value: 4, bits: 2 }.code(), "E_WRITER_BITS"),
+            (gf32::FeWriterError::Capacity { required: 2, capacity: 1 }.code(), "E_CAPACITY"),
+            (gf32::FeWriterError::Unpadded { pending_bits: 3 }.code(), "E_WRITER_UNPADDED"),
+            (InvalidWitnessVersionError(Fe32::Q).code(), "E_WITNESS_VERSION_INVALID"),
+            (WitnessLengthError::TooShort { len: 1, min: 2 }.code(), "E_WITNESS_TOO_SHORT"),
+            (WitnessLengthError::TooLong { len: 41, max: 40 }.code(), "E_WITNESS_TOO_LONG"),
+            (witness_length.code(), "E_WITNESS_V0_LENGTH"),
+            (CharError::Empty.code(), "E_EMPTY"),
+            (CharError::MissingSeparator.code(), "E_MISSING_SEPARATOR"),
+            (CharError::NothingAfterSeparator.code(), "E_NOTHING_AFTER_SEPARATOR"),
+            (CharError::InvalidChar('b').code(), "E_INVALID_CHAR"),
+            (CharError::ControlCharacter { byte: 0, pos: 0 }.code(), "E_CONTROL_CHAR"),
+            (CharError::MixedCase.code(), "E_MIXED_CASE"),
+            (CharError::Uppercase.code(), "E_LOWERCASE_REQUIRED"),
+            (CharError::NotUppercase { pos: 0 }.code(), "E_UPPERCASE_REQUIRED"),
+            (hrp_mixed_case.code(), "E_HRP_MIXED_CASE"),
+            (hrp_split.code(), "E_HRP_INVALID_BYTE"),
+            (ChecksumError::CodeLength(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED"),
+            (ChecksumError::InvalidResidue.code(), "E_CHECKSUM_INVALID"),
+            (ChecksumError::InvalidLength.code(), "E_CHECKSUM_LENGTH"),
+            (code_length.code(), "E_CODE_LENGTH_EXCEEDED"),
+            (SegwitCodeLengthError(91).code(), "E_SEGWIT_TOO_LONG"),
+            (PaddingError::TooMuch.code(), "E_PADDING_TOO_MUCH"),
+            (PaddingError::NonZero.code(), "E_PADDING_NON_ZERO"),
+            (UncheckedHrpstringError::Char(CharError::MixedCase).code(), "E_MIXED_CASE"),
+            (UncheckedHrpstringError::Hrp(HrpError::Empty).code(), "E_HRP_EMPTY"),
+            (UncheckedHrpstringError::HrpMixedCase(hrp_mixed_case.clone()).code(), "E_HRP_MIXED_CASE"),
+            (UncheckedHrpstringError::HrpSplit(hrp_split.clone()).code(), "E_HRP_INVALID_BYTE"),
+            (UncheckedHrpstringError::TooLong(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED"),
+            (CheckedHrpstringError::Parse(CharError::MixedCase.into()).code(), "E_MIXED_CASE"),
+            (CheckedHrpstringError::Checksum(residue.clone()).code(), "E_CHECKSUM_INVALID"),
+            (SegwitHrpstringError::Unchecked(CharError::MixedCase.into()).code(), "E_MIXED_CASE"),
+            (SegwitHrpstringError::NoData.code(), "E_NO_DATA"),
+            (SegwitHrpstringError::TooLong { len: 91, max: 90 }.code(), "E_SEGWIT_TOO_LONG"),
+            (SegwitHrpstringError::InvalidMaxLength(1024).code(), "E_MAX_LENGTH_INVALID"),
+            (SegwitHrpstringError::InvalidWitnessVersion(Fe32::Q).code(), "E_WITNESS_VERSION_INVALID"),
+            (SegwitHrpstringError::Padding(PaddingError::NonZero).code(), "E_PADDING_NON_ZERO"),
+            (SegwitHrpstringError::WitnessLength(witness_length.clone()).code(), "E_WITNESS_V0_LENGTH"),
+            (SegwitHrpstringError::Checksum(residue.clone()).code(), "E_CHECKSUM_INVALID"),
+            (wrong_hrp.code(), "E_WRONG_HRP"),
+            (SegwitHrpstringError::ProbablyTruncated { expected_lengths: &[43, 63], got: 42 }.code(), "E_PROBABLY_TRUNCATED"),
+            (CompactAddressError::Decode(SegwitHrpstringError::NoData).code(), "E_NO_DATA"),
+            (CompactAddressError::UnknownHrp(Hrp::parse_unchecked("a")).code(), "E_UNKNOWN_HRP"),
+            (WitnessProgramError::WitnessVersion(InvalidWitnessVersionError(Fe32::_3)).code(), "E_WITNESS_VERSION_INVALID"),
+            (WitnessProgramError::WitnessLength(witness_length.clone()).code(), "E_WITNESS_V0_LENGTH"),
+            (ScriptPubkeyError::TooShort { len: 1 }.code(), "E_SCRIPT_PUSH"),
+            (ScriptPubkeyError::InvalidOpcode(0x76).code(), "E_SCRIPT_OPCODE"),
+            (ScriptPubkeyError::PushLength { push: 0x14, len: 19 }.code(), "E_SCRIPT_PUSH"),
+            (ScriptPubkeyError::WitnessLength(witness_length.clone()).code(), "E_WITNESS_V0_LENGTH"),
+            (EncodeError::TooLong(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED"),
+            (EncodeError::Fmt(fmt::Error).code(), "E_FMT"),
+            (TryWriteError::Source(()).code(), "E_SOURCE"),
+            (TryWriteError::<()>::Fmt(fmt::Error).code(), "E_FMT"),
+            (EncodeSliceError::TooLong(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED"),
+            (EncodeSliceError::Capacity(capacity.clone()).code(), "E_CAPACITY"),
+            (segwit::EncodeSliceError::WitnessVersion(InvalidWitnessVersionError(Fe32::Q)).code(), "E_WITNESS_VERSION_INVALID"),
+            (segwit::EncodeSliceError::WitnessLength(witness_length.clone()).code(), "E_WITNESS_V0_LENGTH"),
+            (segwit::EncodeSliceError::TooLong(SegwitCodeLengthError(91)).code(), "E_SEGWIT_TOO_LONG"),
+            (segwit::EncodeSliceError::Capacity(capacity.clone()).code(), "E_CAPACITY"),
+            (capacity.code(), "E_CAPACITY"),
+            (SplitError::MissingSeparator.code(), "E_MISSING_SEPARATOR"),
+            (SplitError::TooShort { len: 5, checksum_length: 6 }.code(), "E_CHECKSUM_LENGTH"),
+            (DataBuilderError::Misaligned { pending_bits: 2 }.code(), "E_BUILDER_MISALIGNED"),
+            (DataBuilderError::TooWide { value: 4, bits: 2 }.code(), "E_BUILDER_BITS"),
+            (Error::from(SegwitHrpstringError::NoData).code(), "E_NO_DATA"),
+            (Error::from(HrpError::Empty).code(), "E_HRP_EMPTY"),
+        ];
+        for (code, want) in codes.iter() {
+            assert_eq!(code, want);
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            let segwit_decode = segwit::DecodeError(SegwitHrpstringError::NoData);
+            let codes = [
+                (DecodeError::Parse(CharError::MixedCase.into()).code(), "E_MIXED_CASE"),
+                (DecodeError::Checksum(residue.clone()).code(), "E_CHECKSUM_INVALID"),
+                (DecodeError::Padding(PaddingError::TooMuch).code(), "E_PADDING_TOO_MUCH"),
+                (segwit_decode.code(), "E_NO_DATA"),
+                (segwit::EncodeError::WitnessVersion(InvalidWitnessVersionError(Fe32::Q)).code(), "E_WITNESS_VERSION_INVALID"),
+                (segwit::EncodeError::WitnessLength(witness_length).code(), "E_WITNESS_V0_LENGTH"),
+                (segwit::EncodeError::TooLong(SegwitCodeLengthError(91)).code(), "E_SEGWIT_TOO_LONG"),
+                (segwit::EncodeError::Fmt(fmt::Error).code(), "E_FMT"),
+                (DecodeErrorWithInput::new("a", segwit_decode).code(), "E_NO_DATA"),
+                (DecodeErrorWithInput::new("a", CheckedHrpstringError::Checksum(residue.clone())).code(), "E_CHECKSUM_INVALID"),
+                (RouteError::Parse(CharError::MixedCase.into()).code(), "E_MIXED_CASE"),
+                (RouteError::UnknownHrp { hrp: Hrp::parse_unchecked("a"), registered: vec![] }.code(), "E_UNKNOWN_HRP"),
+            ];
+            for (code, want) in codes.iter() {
+                assert_eq!(code, want);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        {
+            let write = std::io::Error::new(std::io::ErrorKind::Other, "write failed");
+            assert_eq!(crate::EncodeIoError::TooLong(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED");
+            assert_eq!(crate::EncodeIoError::Write(write).code(), "E_WRITE");
+        }
+
+        #[cfg(feature = "embedded-io")]
+        {
+            assert_eq!(crate::EncodeEmbeddedError::<()>::TooLong(code_length.clone()).code(), "E_CODE_LENGTH_EXCEEDED");
+            assert_eq!(crate::EncodeEmbeddedError::Write(()).code(), "E_WRITE");
+            assert_eq!(segwit::EncodeEmbeddedError::<()>::TooLong(SegwitCodeLengthError(91)).code(), "E_SEGWIT_TOO_LONG");
+            assert_eq!(segwit::EncodeEmbeddedError::Write(()).code(), "E_WRITE");
+        }
+
+        #[cfg(feature = "heapless")]
+        {
+            assert_eq!(DecodeHeaplessError::Decode(residue.clone().into()).code(), "E_CHECKSUM_INVALID");
+            assert_eq!(DecodeHeaplessError::Capacity(capacity.clone()).code(), "E_CAPACITY");
+            assert_eq!(segwit::DecodeHeaplessError::Decode(residue.into()).code(), "E_CHECKSUM_INVALID");
+            assert_eq!(segwit::DecodeHeaplessError::Capacity(capacity).code(), "E_CAPACITY");
+            assert_eq!(HeaplessDataBuilderError::Capacity { required: 2, capacity: 1 }.code(), "E_CAPACITY");
+            assert_eq!(HeaplessDataBuilderError::Builder(DataBuilderError::TooWide { value: 4, bits: 2 }).code(), "E_BUILDER_BITS");
+        }
+
+        #[cfg(feature = "bolt11")]
+        {
+            let parse = bolt11::ParseError::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            assert_eq!(bolt11::ParseError::InvalidPrefix.code(), "E_BOLT11_PREFIX");
+            assert_eq!(bolt11::ParseError::TooShort(0).code(), "E_BOLT11_TOO_SHORT");
+        }
+
+        #[cfg(feature = "bolt12")]
+        {
+            let parse = bolt12::ParseError::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            let continuation = bolt12::ParseError::InvalidContinuation(0);
+            assert_eq!(continuation.code(), "E_BOLT12_CONTINUATION");
+        }
+
+        #[cfg(feature = "lnurl")]
+        {
+            use lnurl::LnurlError;
+
+            let parse = LnurlError::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            assert_eq!(LnurlError::InvalidHrp(Hrp::parse_unchecked("a")).code(), "E_LNURL_HRP");
+            assert_eq!(LnurlError::InvalidUtf8 { valid_up_to: 0 }.code(), "E_LNURL_UTF8");
+        }
+
+        #[cfg(feature = "nip19")]
+        {
+            use nip19::Nip19Error;
+
+            let parse = Nip19Error::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            assert_eq!(Nip19Error::UnknownHrp(Hrp::parse_unchecked("a")).code(), "E_NIP19_UNKNOWN_HRP");
+            assert_eq!(Nip19Error::InvalidLength(31).code(), "E_NIP19_LENGTH");
+        }
+
+        #[cfg(feature = "sp")]
+        {
+            use sp::SpError;
+
+            let parse = SpError::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            assert_eq!(SpError::UnknownHrp(Hrp::parse_unchecked("a")).code(), "E_SP_UNKNOWN_HRP");
+            assert_eq!(SpError::NoData.code(), "E_NO_DATA");
+            assert_eq!(SpError::InvalidVersion(Fe32::L).code(), "E_SP_VERSION");
+            assert_eq!(SpError::Padding(PaddingError::NonZero).code(), "E_PADDING_NON_ZERO");
+            assert_eq!(SpError::InvalidLength { version: Fe32::Q, len: 0 }.code(), "E_SP_LENGTH");
+        }
+
+        #[cfg(feature = "codex32")]
+        {
+            use codex32::ShareError;
+
+            let parse = ShareError::Parse(ChecksumError::InvalidResidue.into());
+            assert_eq!(parse.code(), "E_CHECKSUM_INVALID");
+            assert_eq!(ShareError::InvalidHrp(Hrp::parse_unchecked("a")).code(), "E_CODEX32_HRP");
+            assert_eq!(ShareError::MissingHeader.code(), "E_CODEX32_HEADER");
+            assert_eq!(ShareError::InvalidThreshold(1).code(), "E_CODEX32_THRESHOLD");
+            assert_eq!(ShareError::InvalidThresholdChar('a').code(), "E_CODEX32_THRESHOLD");
+            assert_eq!(ShareError::InvalidIdentifier.code(), "E_CODEX32_IDENTIFIER");
+            assert_eq!(ShareError::InvalidShareIndex(Fe32::A).code(), "E_CODEX32_SHARE_INDEX");
+            assert_eq!(ShareError::Padding(PaddingError::TooMuch).code(), "E_PADDING_TOO_MUCH");
+            assert_eq!(ShareError::InvalidLength(15).code(), "E_CODEX32_LENGTH");
+        }
+    }
+}
@@ -0,0 +1,579 @@
+// SPDX-License-Identifier: MIT
+
+//! C FFI for encoding and decoding segwit addresses.
+//!
+//! Enabled by the `ffi` feature (requires `std`). To produce a shared library for use from other
+//! languages build with `cargo rustc --release --features ffi --crate-type cdylib`.
+//!
+//! All functions return a negative error code on failure, use [`bech32grs_strerror`] to get a
+//! description of an error code. Strings are NUL terminated and, on success, the number of bytes
+//! written does not include the terminator.
+//!
+//! # C header
+//!
+//! ```c
+//! #include <stddef.h>
+//! #include <stdint.h>
+//!
+//! #define BECH32GRS_OK                     0
+//! #define BECH32GRS_ERR_NULL_POINTER      -1
+//! #define BECH32GRS_ERR_BUFFER_TOO_SMALL  -2
+//! #define BECH32GRS_ERR_INVALID_UTF8      -3
+//! #define BECH32GRS_ERR_INVALID_HRP       -4
+//! #define BECH32GRS_ERR_INVALID_CHAR      -5
+//! #define BECH32GRS_ERR_CHECKSUM          -6
+//! #define BECH32GRS_ERR_NO_DATA           -7
+//! #define BECH32GRS_ERR_TOO_LONG          -8
+//! #define BECH32GRS_ERR_WITNESS_VERSION   -9
+//! #define BECH32GRS_ERR_WITNESS_LENGTH   -10
+//! #define BECH32GRS_ERR_PADDING          -11
+//!
+//! /* Returns the address length (excluding the NUL) or a negative error code. */
+//! int32_t bech32grs_segwit_encode(const char *hrp, uint8_t version, const uint8_t *prog,
+//!                                 size_t prog_len, uint8_t *out, size_t out_cap);
+//!
+//! /* Returns the witness program length or a negative error code. */
+//! int32_t bech32grs_segwit_decode(const char *addr, uint8_t *hrp_out, size_t hrp_cap,
+//!                                 uint8_t *version_out, uint8_t *prog_out, size_t prog_cap);
+//!
+//! /* Returns the message length (excluding the NUL), 0 if `addr` is valid, or a negative error
+//!    code. */
+//! int32_t bech32grs_segwit_error_message(const char *addr, uint8_t *out, size_t out_cap);
+//!
+//! /* Returns a static, NUL terminated, description of `code`. */
+//! const char *bech32grs_strerror(int32_t code);
+//! ```
+
+use core::convert::TryFrom;
+use core::{fmt, slice};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use crate::primitives::decode::SegwitHrpstring;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::segwit;
+
+/// Success.
+pub const OK: i32 = 0;
+/// A required pointer argument was null.
+pub const ERR_NULL_POINTER: i32 = -1;
+/// An output buffer is too small.
+pub const ERR_BUFFER_TOO_SMALL: i32 = -2;
+/// An input string is not valid UTF-8.
+pub const ERR_INVALID_UTF8: i32 = -3;
+/// The human-readable part is invalid.
+pub const ERR_INVALID_HRP: i32 = -4;
+/// The address contains an invalid character, is mixed case, or has no separator.
+pub const ERR_INVALID_CHAR: i32 = -5;
+/// The address checksum is invalid.
+pub const ERR_CHECKSUM: i32 = -6;
+/// The address has no data after the checksum is removed.
+pub const ERR_NO_DATA: i32 = -7;
+/// The address is longer than 90 characters.
+pub const ERR_TOO_LONG: i32 = -8;
+/// The witness version is invalid.
+pub const ERR_WITNESS_VERSION: i32 = -9;
+/// The witness program length is invalid for the witness version.
+pub const ERR_WITNESS_LENGTH: i32 = -10;
+/// The address data has invalid padding.
+pub const ERR_PADDING: i32 = -11;
+
+/// Encodes a segwit address, lowercase and NUL terminated, into `out`.
+///
+/// Does the same validity checks as [`segwit::encode`](crate::segwit::encode).
+///
+/// # Returns
+///
+/// The length of the address, excluding the NUL terminator, or a negative error code.
+///
+/// # Safety
+///
+/// `hrp` must be null or point to a NUL terminated string. `prog` must be null or valid for reads
+/// of `prog_len` bytes (it may only be null if `prog_len` is zero). `out` must be null or valid for
+/// writes of `out_cap` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bech32grs_segwit_encode(
+    hrp: *const c_char,
+    version: u8,
+    prog: *const u8,
+    prog_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    if hrp.is_null() || out.is_null() || (prog.is_null() && prog_len != 0) {
+        return ERR_NULL_POINTER;
+    }
+    let hrp = match CStr::from_ptr(hrp).to_str() {
+        Ok(hrp) => hrp,
+        Err(_) => return ERR_INVALID_UTF8,
+    };
+    let program = if prog_len == 0 { &[] } else { slice::from_raw_parts(prog, prog_len) };
+    let out = slice::from_raw_parts_mut(out, out_cap);
+
+    match segwit_encode(hrp, version, program, out) {
+        Ok(len) => len as i32, // Addresses are at most 90 characters.
+        Err(code) => code,
+    }
+}
+
+/// Decodes a segwit address.
+///
+/// The human-readable part is written to `hrp_out` lowercase and NUL terminated, the witness
+/// version to `version_out`, and the witness program to `prog_out`. A `prog_cap` of 40 is always
+/// sufficient.
+///
+/// # Returns
+///
+/// The length of the witness program or a negative error code.
+///
+/// # Safety
+///
+/// `addr` must be null or point to a NUL terminated string. `hrp_out` must be null or valid for
+/// writes of `hrp_cap` bytes, `version_out` must be null or valid for a write of one byte, and
+/// `prog_out` must be null or valid for writes of `prog_cap` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bech32grs_segwit_decode(
+    addr: *const c_char,
+    hrp_out: *mut u8,
+    hrp_cap: usize,
+    version_out: *mut u8,
+    prog_out: *mut u8,
+    prog_cap: usize,
+) -> i32 {
+    if addr.is_null() || hrp_out.is_null() || version_out.is_null() || prog_out.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    let addr = match CStr::from_ptr(addr).to_str() {
+        Ok(addr) => addr,
+        Err(_) => return ERR_INVALID_UTF8,
+    };
+    let hrp_out = slice::from_raw_parts_mut(hrp_out, hrp_cap);
+    let prog_out = slice::from_raw_parts_mut(prog_out, prog_cap);
+
+    match segwit_decode(addr, hrp_out, prog_out) {
+        Ok((version, len)) => {
+            *version_out = version.to_u8();
+            len as i32 // Witness programs are at most 40 bytes.
+        }
+        Err(code) => code,
+    }
+}
+
+/// Writes a description of why `addr` is not a valid segwit address, NUL terminated, into `out`.
+///
+/// This is the same message as the Rust error displays, more detailed than [`bech32grs_strerror`].
+/// It is formatted with [`render_error`](crate::render_error) so a message that does not fit is
+/// cut short and ends with `...`.
+///
+/// # Returns
+///
+/// The length of the message, excluding the NUL terminator, zero if `addr` is a valid segwit
+/// address, or a negative error code.
+///
+/// # Safety
+///
+/// `addr` must be null or point to a NUL terminated string. `out` must be null or valid for writes
+/// of `out_cap` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bech32grs_segwit_error_message(
+    addr: *const c_char,
+    out: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    if addr.is_null() || out.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    if out_cap == 0 {
+        return ERR_BUFFER_TOO_SMALL;
+    }
+    let addr = match CStr::from_ptr(addr).to_str() {
+        Ok(addr) => addr,
+        Err(_) => return ERR_INVALID_UTF8,
+    };
+    let out = slice::from_raw_parts_mut(out, out_cap);
+
+    let len = match SegwitHrpstring::new(addr) {
+        Ok(_) => 0,
+        Err(e) => crate::render_error(&e, &mut out[..out_cap - 1]).len(),
+    };
+    out[len] = 0;
+    i32::try_from(len).unwrap_or(i32::MAX) // Messages are far shorter.
+}
+
+/// Returns a description of the error `code`.
+///
+/// The returned pointer is to a static NUL terminated string and must not be freed.
+#[no_mangle]
+pub extern "C" fn bech32grs_strerror(code: i32) -> *const c_char {
+    let s: &'static [u8] = match code {
+        OK => b"success\0",
+        ERR_NULL_POINTER => b"null pointer argument\0",
+        ERR_BUFFER_TOO_SMALL => b"output buffer too small\0",
+        ERR_INVALID_UTF8 => b"input string is not valid UTF-8\0",
+        ERR_INVALID_HRP => b"invalid human-readable part\0",
+        ERR_INVALID_CHAR => b"invalid character, mixed case, or missing separator\0",
+        ERR_CHECKSUM => b"invalid checksum\0",
+        ERR_NO_DATA => b"no data found after removing the checksum\0",
+        ERR_TOO_LONG => b"address exceeds 90 characters\0",
+        ERR_WITNESS_VERSION => b"invalid witness version\0",
+        ERR_WITNESS_LENGTH => b"invalid witness program length\0",
+        ERR_PADDING => b"invalid padding\0",
+        _ => b"unknown error\0",
+    };
+    s.as_ptr() as *const c_char
+}
+
+/// Encodes the address into `out` followed by a NUL, returns the address length.
+fn segwit_encode(hrp: &str, version: u8, program: &[u8], out: &mut [u8]) -> Result<usize, i32> {
+    let hrp = Hrp::parse(hrp).map_err(|e| e.error_code().ffi())?;
+    let version = Fe32::try_from(version).map_err(|e| e.error_code().ffi())?;
+    segwit::validate_witness_version(version).map_err(|e| e.error_code().ffi())?;
+    segwit::validate_witness_program_length(program.len(), version)
+        .map_err(|e| e.error_code().ffi())?;
+    let len =
+        crate::segwit::encoded_length(hrp, version, program).map_err(|e| e.error_code().ffi())?;
+
+    if len >= out.len() {
+        return Err(ERR_BUFFER_TOO_SMALL);
+    }
+    let mut w = SliceWriter { buf: out, len: 0 };
+    crate::segwit::encode_lower_to_fmt_unchecked(&mut w, hrp, version, program)
+        .map_err(|_| ERR_BUFFER_TOO_SMALL)?;
+    out[len] = 0;
+
+    Ok(len)
+}
+
+/// Decodes the address, writing the NUL terminated HRP and the program to the output buffers.
+fn segwit_decode(
+    addr: &str,
+    hrp_out: &mut [u8],
+    prog_out: &mut [u8],
+) -> Result<(Fe32, usize), i32> {
+    let segwit = SegwitHrpstring::new(addr).map_err(|e| e.error_code().ffi())?;
+
+    let hrp = segwit.hrp();
+    let program_len = segwit.byte_iter().len();
+    if hrp.len() >= hrp_out.len() || program_len > prog_out.len() {
+        return Err(ERR_BUFFER_TOO_SMALL);
+    }
+
+    for (slot, b) in hrp_out.iter_mut().zip(hrp.lowercase_byte_iter()) {
+        *slot = b;
+    }
+    hrp_out[hrp.len()] = 0;
+    for (slot, b) in prog_out.iter_mut().zip(segwit.byte_iter()) {
+        *slot = b;
+    }
+
+    Ok((segwit.witness_version(), program_len))
+}
+
+/// Writes to a byte slice, erroring if it is full.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        let dst = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dst.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::ptr;
+
+    use super::*;
+    use crate::error::Code;
+
+    const ADDRESS: &str = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+
+    fn encode(hrp: &str, version: u8, program: &[u8], out: &mut [u8]) -> i32 {
+        let hrp = CString::new(hrp).unwrap();
+        unsafe {
+            bech32grs_segwit_encode(
+                hrp.as_ptr(),
+                version,
+                program.as_ptr(),
+                program.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        }
+    }
+
+    fn decode(addr: &str, hrp: &mut [u8], version: &mut u8, program: &mut [u8]) -> i32 {
+        let addr = CString::new(addr).unwrap();
+        unsafe {
+            bech32grs_segwit_decode(
+                addr.as_ptr(),
+                hrp.as_mut_ptr(),
+                hrp.len(),
+                version,
+                program.as_mut_ptr(),
+                program.len(),
+            )
+        }
+    }
+
+    fn strerror(code: i32) -> &'static str {
+        unsafe { CStr::from_ptr(bech32grs_strerror(code)) }.to_str().unwrap()
+    }
+
+    #[test]
+    fn encode_valid() {
+        let mut out = [0xff_u8; 91];
+        let len = encode("grs", 1, &[0xab; 20], &mut out);
+        assert_eq!(len, ADDRESS.len() as i32);
+        assert_eq!(&out[..ADDRESS.len()], ADDRESS.as_bytes());
+        assert_eq!(out[ADDRESS.len()], 0);
+    }
+
+    #[test]
+    fn encode_exact_buffer() {
+        let mut out = vec![0_u8; ADDRESS.len() + 1];
+        assert_eq!(encode("grs", 1, &[0xab; 20], &mut out), ADDRESS.len() as i32);
+
+        // No room for the NUL terminator.
+        let mut out = vec![0_u8; ADDRESS.len()];
+        assert_eq!(encode("grs", 1, &[0xab; 20], &mut out), ERR_BUFFER_TOO_SMALL);
+    }
+
+    #[test]
+    fn encode_errors() {
+        let mut out = [0_u8; 91];
+        assert_eq!(encode("", 1, &[0xab; 20], &mut out), ERR_INVALID_HRP);
+        assert_eq!(encode("grs", 17, &[0xab; 20], &mut out), ERR_WITNESS_VERSION);
+        assert_eq!(encode("grs", 32, &[0xab; 20], &mut out), ERR_WITNESS_VERSION);
+        assert_eq!(encode("grs", 0, &[0xab; 21], &mut out), ERR_WITNESS_LENGTH);
+        assert_eq!(encode("grs", 1, &[0xab; 41], &mut out), ERR_WITNESS_LENGTH);
+        let long_hrp = "a".repeat(30);
+        assert_eq!(encode(&long_hrp, 1, &[0xab; 40], &mut out), ERR_TOO_LONG);
+    }
+
+    #[test]
+    fn encode_null_pointers() {
+        let hrp = CString::new("grs").unwrap();
+        let program = [0xab_u8; 20];
+        let mut out = [0_u8; 91];
+        unsafe {
+            assert_eq!(
+                bech32grs_segwit_encode(ptr::null(), 1, program.as_ptr(), 20, out.as_mut_ptr(), 91),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                bech32grs_segwit_encode(hrp.as_ptr(), 1, ptr::null(), 20, out.as_mut_ptr(), 91),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                bech32grs_segwit_encode(hrp.as_ptr(), 1, program.as_ptr(), 20, ptr::null_mut(), 91),
+                ERR_NULL_POINTER
+            );
+            // Null program is fine if it is empty, fails validation instead.
+            assert_eq!(
+                bech32grs_segwit_encode(hrp.as_ptr(), 1, ptr::null(), 0, out.as_mut_ptr(), 91),
+                ERR_WITNESS_LENGTH
+            );
+        }
+    }
+
+    #[test]
+    fn encode_invalid_utf8() {
+        let hrp = [0xff_u8, 0];
+        let mut out = [0_u8; 91];
+        let code = unsafe {
+            bech32grs_segwit_encode(
+                hrp.as_ptr() as *const c_char,
+                1,
+                [0_u8; 20].as_ptr(),
+                20,
+                out.as_mut_ptr(),
+                91,
+            )
+        };
+        assert_eq!(code, ERR_INVALID_UTF8);
+    }
+
+    #[test]
+    fn decode_valid() {
+        let mut hrp = [0xff_u8; 84];
+        let mut version = 0xff;
+        let mut program = [0_u8; 40];
+
+        let len = decode(&ADDRESS.to_uppercase(), &mut hrp, &mut version, &mut program);
+        assert_eq!(len, 20);
+        assert_eq!(&hrp[..4], b"grs\0");
+        assert_eq!(version, 1);
+        assert_eq!(&program[..20], &[0xab; 20]);
+    }
+
+    #[test]
+    fn decode_short_buffers() {
+        let mut version = 0;
+
+        let mut hrp = [0_u8; 3]; // No room for the NUL terminator.
+        let mut program = [0_u8; 40];
+        assert_eq!(decode(ADDRESS, &mut hrp, &mut version, &mut program), ERR_BUFFER_TOO_SMALL);
+
+        let mut hrp = [0_u8; 4];
+        let mut program = [0_u8; 19];
+        assert_eq!(decode(ADDRESS, &mut hrp, &mut version, &mut program), ERR_BUFFER_TOO_SMALL);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let mut hrp = [0_u8; 84];
+        let mut version = 0;
+        let mut program = [0_u8; 40];
+        let mut check = |addr: &str| decode(addr, &mut hrp, &mut version, &mut program);
+
+        assert_eq!(check("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyd"), ERR_CHECKSUM);
+        assert_eq!(check("grsp4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc"), ERR_INVALID_CHAR);
+        assert_eq!(check("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyC"), ERR_INVALID_CHAR);
+        assert_eq!(check("Grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc"), ERR_INVALID_HRP);
+        assert_eq!(check("1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc"), ERR_INVALID_HRP);
+    }
+
+    fn error_message(addr: &str, out: &mut [u8]) -> i32 {
+        let addr = CString::new(addr).unwrap();
+        unsafe { bech32grs_segwit_error_message(addr.as_ptr(), out.as_mut_ptr(), out.len()) }
+    }
+
+    #[test]
+    fn error_message_matches_display() {
+        let addr = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyd";
+        let want = SegwitHrpstring::new(addr).unwrap_err().to_string();
+
+        let mut out = [0xff_u8; 256];
+        let len = error_message(addr, &mut out);
+        assert_eq!(len, want.len() as i32);
+        assert_eq!(&out[..want.len()], want.as_bytes());
+        assert_eq!(out[want.len()], 0);
+
+        // Truncated, with room for one character, the ellipsis and the NUL terminator.
+        let mut out = [0xff_u8; 5];
+        assert_eq!(error_message(addr, &mut out), 4);
+        assert_eq!(&out[..4], format!("{}...", &want[..1]).as_bytes());
+        assert_eq!(out[4], 0);
+    }
+
+    #[test]
+    fn error_message_valid_and_errors() {
+        let mut out = [0xff_u8; 8];
+        assert_eq!(error_message(ADDRESS, &mut out), 0);
+        assert_eq!(out[0], 0);
+
+        assert_eq!(error_message(ADDRESS, &mut []), ERR_BUFFER_TOO_SMALL);
+        let invalid_utf8 = [0xff_u8, 0];
+        let code = unsafe {
+            bech32grs_segwit_error_message(
+                invalid_utf8.as_ptr() as *const c_char,
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        };
+        assert_eq!(code, ERR_INVALID_UTF8);
+        let code = unsafe { bech32grs_segwit_error_message(ptr::null(), out.as_mut_ptr(), 8) };
+        assert_eq!(code, ERR_NULL_POINTER);
+    }
+
+    #[test]
+    fn decode_null_pointers() {
+        let addr = CString::new(ADDRESS).unwrap();
+        let mut hrp = [0_u8; 84];
+        let mut version = 0_u8;
+        let mut program = [0_u8; 40];
+        unsafe {
+            assert_eq!(
+                bech32grs_segwit_decode(
+                    ptr::null(),
+                    hrp.as_mut_ptr(),
+                    84,
+                    &mut version,
+                    program.as_mut_ptr(),
+                    40
+                ),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                bech32grs_segwit_decode(
+                    addr.as_ptr(),
+                    ptr::null_mut(),
+                    84,
+                    &mut version,
+                    program.as_mut_ptr(),
+                    40
+                ),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                bech32grs_segwit_decode(
+                    addr.as_ptr(),
+                    hrp.as_mut_ptr(),
+                    84,
+                    ptr::null_mut(),
+                    program.as_mut_ptr(),
+                    40
+                ),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                bech32grs_segwit_decode(
+                    addr.as_ptr(),
+                    hrp.as_mut_ptr(),
+                    84,
+                    &mut version,
+                    ptr::null_mut(),
+                    40
+                ),
+                ERR_NULL_POINTER
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut out = [0_u8; 91];
+        for &(version, len) in &[(0_u8, 20_usize), (0, 32), (1, 32), (16, 2), (16, 40)] {
+            let program: Vec<u8> = (0..len as u8).collect();
+            let n = encode("tgrs", version, &program, &mut out);
+            assert!(n > 0);
+            let addr = std::str::from_utf8(&out[..n as usize]).unwrap();
+
+            let mut hrp = [0_u8; 84];
+            let mut got_version = 0;
+            let mut got = [0_u8; 40];
+            let n = decode(addr, &mut hrp, &mut got_version, &mut got);
+            assert_eq!(n, len as i32);
+            assert_eq!(&hrp[..5], b"tgrs\0");
+            assert_eq!(got_version, version);
+            assert_eq!(&got[..len], &program[..]);
+        }
+    }
+
+    #[test]
+    fn strerror_all_codes() {
+        assert_eq!(strerror(OK), "success");
+        for code in ERR_PADDING..=ERR_NULL_POINTER {
+            assert_ne!(strerror(code), "unknown error");
+        }
+        assert_eq!(strerror(-12), "unknown error");
+        assert_eq!(strerror(1), "unknown error");
+    }
+
+    #[test]
+    fn error_codes_are_ffi_codes() {
+        for code in Code::ALL.iter() {
+            assert!(code.ffi() < OK, "{:?}", code);
+            assert_ne!(strerror(code.ffi()), "unknown error", "{:?}", code);
+        }
+    }
+}
@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: MIT
+
+//! Decoding into, and encoding to, fixed capacity [`heapless`] buffers.
+//!
+//! Enabled by the `heapless` feature, usable without an allocator.
+
+use core::fmt;
+
+use heapless::{String, Vec};
+
+use crate::builder::{self, BitCursor, DataBuilderError};
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use crate::primitives::iter::Fe32IterExt;
+use crate::{encode_to_fmt, CapacityError, Checksum, EncodeError, Fe32, Hrp};
+
+/// Decodes a bech32 string, checksummed with `Ck`, into a [`heapless::Vec`] with capacity `N`.
+///
+/// # Returns
+///
+/// The human-readable part and the encoded data with the checksum removed.
+///
+/// # Errors
+///
+/// As well as the usual parsing and checksum errors, returns [`DecodeHeaplessError::Capacity`] if
+/// the decoded data does not fit in `N` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{Bech32m, Hrp};
+///
+/// const STRING: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+///
+/// let (hrp, data) = bech32grs::decode_heapless::<Bech32m, 20>(STRING).expect("valid string");
+/// assert_eq!(hrp, Hrp::parse("abc").unwrap());
+/// assert_eq!(data, [0xab; 20]);
+///
+/// assert!(bech32grs::decode_heapless::<Bech32m, 19>(STRING).is_err());
+/// ```
+#[inline]
+pub fn decode_heapless<Ck: Checksum, const N: usize>(
+    s: &str,
+) -> Result<(Hrp, Vec<u8, N>), DecodeHeaplessError> {
+    let checked = CheckedHrpstring::new::<Ck>(s)?;
+    let data = collect_bytes(checked.byte_iter())?;
+    Ok((checked.hrp(), data))
+}
+
+/// Encodes `data` as a lowercase bech32 string, into a [`heapless::String`] with capacity `N`.
+///
+/// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
+/// `Ck` algorithm (`NoChecksum` to exclude checksum all together).
+///
+/// # Errors
+///
+/// Returns [`EncodeError::Fmt`] if the encoded string does not fit in `N` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{Bech32m, Hrp};
+///
+/// let hrp = Hrp::parse("abc").expect("valid hrp");
+/// let s = bech32grs::encode_to_heapless_string::<Bech32m, 64>(hrp, &[0xab; 20]).expect("fits");
+/// assert_eq!(s.as_str(), "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu");
+/// ```
+#[inline]
+pub fn encode_to_heapless_string<Ck: Checksum, const N: usize>(
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<String<N>, EncodeError> {
+    let mut buf = String::new();
+    encode_to_fmt::<Ck, String<N>>(&mut buf, hrp, data)?;
+    Ok(buf)
+}
+
+/// Collects `iter` into a [`heapless::Vec`], erroring if it does not fit in `N` bytes.
+pub(crate) fn collect_bytes<I, const N: usize>(iter: I) -> Result<Vec<u8, N>, CapacityError>
+where
+    I: ExactSizeIterator<Item = u8>,
+{
+    let required = iter.len();
+    if required > N {
+        return Err(CapacityError { required, capacity: N });
+    }
+
+    let mut v = Vec::new();
+    for b in iter {
+        v.push(b).map_err(|_| CapacityError { required, capacity: N })?;
+    }
+    Ok(v)
+}
+
+/// Builds the field elements of a data part into a [`heapless::Vec`] with capacity `N`.
+///
+/// The fixed capacity twin of [`DataBuilder`](crate::DataBuilder), see it for the details. Every
+/// push checks that the field elements, including any padding, fit in `N`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{hrp, Bech32, Fe32, HeaplessDataBuilder};
+///
+/// let mut builder = HeaplessDataBuilder::<33>::new();
+/// builder.push_fe(Fe32::Q).expect("fits");
+/// builder.push_bytes(&[0xe8, 0xdf, 0x01, 0x8c, 0x7e, 0x32, 0x6c, 0xc2, 0x53, 0xfa,
+///                      0xac, 0x7e, 0x46, 0xcd, 0xc5, 0x1e, 0x68, 0x54, 0x2c, 0x42])
+///     .expect("fits");
+/// let address = builder.finish_with_checksum::<Bech32, 64>(hrp::GRS).expect("fits");
+/// assert_eq!(address.as_str(), "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[must_use = "a builder does nothing unless it is finished"]
+pub struct HeaplessDataBuilder<const N: usize> {
+    /// The complete field elements.
+    fes: Vec<Fe32, N>,
+    /// The bits pushed since the last complete field element.
+    cursor: BitCursor,
+    /// Whether bytes may be pushed when the cursor is not on a field element boundary.
+    allow_misaligned: bool,
+}
+
+impl<const N: usize> HeaplessDataBuilder<N> {
+    /// Constructs an empty builder.
+    #[inline]
+    pub fn new() -> Self {
+        HeaplessDataBuilder {
+            fes: Vec::new(),
+            cursor: BitCursor::default(),
+            allow_misaligned: false,
+        }
+    }
+
+    /// Allows [`Self::push_bytes`] when the cursor is not on a field element boundary.
+    #[inline]
+    pub fn allow_misaligned_bytes(mut self) -> Self {
+        self.allow_misaligned = true;
+        self
+    }
+
+    /// Returns the number of bits pushed so far.
+    #[inline]
+    pub fn bit_len(&self) -> usize { self.fes.len() * 5 + usize::from(self.cursor.len) }
+
+    /// Returns true if the cursor is on a field element boundary.
+    #[inline]
+    pub fn is_aligned(&self) -> bool { self.cursor.len == 0 }
+
+    /// Pushes a field element, five bits.
+    pub fn push_fe(&mut self, fe: Fe32) -> Result<&mut Self, HeaplessDataBuilderError> {
+        self.reserve(5)?;
+        let fes = &mut self.fes;
+        self.cursor.push(fe.to_u8(), 5, |fe| push(fes, fe));
+        Ok(self)
+    }
+
+    /// Pushes the low `bits` bits of `value`, most significant bit first.
+    pub fn push_u8_as_fes(
+        &mut self,
+        value: u8,
+        bits: u8,
+    ) -> Result<&mut Self, HeaplessDataBuilderError> {
+        builder::check_bits(value, bits)?;
+        self.reserve(usize::from(bits))?;
+        let fes = &mut self.fes;
+        self.cursor.push(value, bits, |fe| push(fes, fe));
+        Ok(self)
+    }
+
+    /// Pushes `bytes`, padding the last field element with zero bits.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, HeaplessDataBuilderError> {
+        if !self.is_aligned() && !self.allow_misaligned {
+            return Err(DataBuilderError::Misaligned { pending_bits: self.cursor.len }.into());
+        }
+        self.reserve(bytes.len() * 8)?;
+        let fes = &mut self.fes;
+        for &b in bytes {
+            self.cursor.push(b, 8, |fe| push(fes, fe));
+        }
+        self.cursor.pad(|fe| push(fes, fe));
+        Ok(self)
+    }
+
+    /// Returns the field elements, padding the last one with zero bits.
+    pub fn finish_fes(mut self) -> Vec<Fe32, N> {
+        let fes = &mut self.fes;
+        self.cursor.pad(|fe| push(fes, fe));
+        self.fes
+    }
+
+    /// Returns the lowercase bech32 string of `hrp` and the field elements, checksummed with `Ck`,
+    /// in a [`heapless::String`] with capacity `M`.
+    ///
+    /// # Errors
+    ///
+    /// If the string does not fit in `M` bytes.
+    pub fn finish_with_checksum<Ck: Checksum, const M: usize>(
+        self,
+        hrp: Hrp,
+    ) -> Result<String<M>, CapacityError> {
+        let fes = self.finish_fes();
+        let required = hrp.len() + 1 + fes.len() + Ck::CHECKSUM_LENGTH;
+        if required > M {
+            return Err(CapacityError { required, capacity: M });
+        }
+
+        let mut s = String::new();
+        for c in fes.into_iter().with_checksum::<Ck>(&hrp).chars() {
+            s.push(c).map_err(|_| CapacityError { required, capacity: M })?;
+        }
+        Ok(s)
+    }
+
+    /// Returns an error if pushing `bits` more bits, and padding them, exceeds the capacity.
+    fn reserve(&self, bits: usize) -> Result<(), HeaplessDataBuilderError> {
+        let required = (self.bit_len() + bits + 4) / 5;
+        if required > N {
+            Err(HeaplessDataBuilderError::Capacity { required, capacity: N })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<const N: usize> Default for HeaplessDataBuilder<N> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// Pushes `fe`, the capacity has been checked by `HeaplessDataBuilder::reserve`.
+fn push<const N: usize>(fes: &mut Vec<Fe32, N>, fe: Fe32) { let _ = fes.push(fe); }
+
+/// An error while pushing to a [`HeaplessDataBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum HeaplessDataBuilderError {
+    /// The push is invalid, as for a [`DataBuilder`](crate::DataBuilder).
+    Builder(DataBuilderError),
+    /// The field elements do not fit in the buffer.
+    Capacity {
+        /// The number of field elements required.
+        required: usize,
+        /// The capacity of the buffer, in field elements.
+        capacity: usize,
+    },
+}
+
+impl HeaplessDataBuilderError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use HeaplessDataBuilderError::*;
+
+        match *self {
+            Builder(ref e) => e.error_code(),
+            Capacity { .. } => Code::Capacity,
+        }
+    }
+}
+
+impl fmt::Display for HeaplessDataBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use HeaplessDataBuilderError::*;
+
+        match *self {
+            Builder(ref e) => write_err!(f, "invalid push"; e),
+            Capacity { required, capacity } => write!(
+                f,
+                "{} field elements required, buffer capacity is {} field elements",
+                required, capacity
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for HeaplessDataBuilderError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use HeaplessDataBuilderError::*;
+
+        match *self {
+            Builder(ref e) => Some(e),
+            Capacity { .. } => None,
+        }
+    }
+}
+
+impl From<DataBuilderError> for HeaplessDataBuilderError {
+    #[inline]
+    fn from(e: DataBuilderError) -> Self { Self::Builder(e) }
+}
+
+/// An error while decoding a bech32 string into a [`heapless::Vec`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DecodeHeaplessError {
+    /// Parsing or checksum validation failed.
+    Decode(CheckedHrpstringError),
+    /// Decoded data does not fit in the buffer.
+    Capacity(CapacityError),
+}
+
+impl DecodeHeaplessError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => e.error_code(),
+            Capacity(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeHeaplessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "decoding failed"; e),
+            Capacity(ref e) => write_err!(f, "decoded data does not fit in buffer"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for DecodeHeaplessError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            Capacity(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for DecodeHeaplessError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Decode(e) }
+}
+
+impl From<CapacityError> for DecodeHeaplessError {
+    #[inline]
+    fn from(e: CapacityError) -> Self { Self::Capacity(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bech32, Bech32m};
+
+    const STRING: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+
+    #[test]
+    fn decode_exact_capacity() {
+        let (hrp, data) = decode_heapless::<Bech32m, 20>(STRING).expect("valid string");
+        assert_eq!(hrp, Hrp::parse_unchecked("abc"));
+        assert_eq!(data, [0xab; 20]);
+    }
+
+    #[test]
+    fn decode_insufficient_capacity() {
+        let err = decode_heapless::<Bech32m, 19>(STRING).unwrap_err();
+        assert_eq!(
+            err,
+            DecodeHeaplessError::Capacity(CapacityError { required: 20, capacity: 19 })
+        );
+    }
+
+    #[test]
+    fn decode_invalid_checksum() {
+        let err = decode_heapless::<Bech32, 32>(STRING).unwrap_err();
+        assert!(matches!(err, DecodeHeaplessError::Decode(_)));
+    }
+
+    #[test]
+    fn encode_roundtrip() {
+        let hrp = Hrp::parse_unchecked("abc");
+        let s = encode_to_heapless_string::<Bech32m, 64>(hrp, &[0xab; 20]).expect("fits");
+        assert_eq!(s.as_str(), STRING);
+    }
+
+    #[test]
+    fn encode_insufficient_capacity() {
+        let hrp = Hrp::parse_unchecked("abc");
+        let err = encode_to_heapless_string::<Bech32m, 41>(hrp, &[0xab; 20]).unwrap_err();
+        assert_eq!(err, EncodeError::Fmt(fmt::Error));
+    }
+
+    #[test]
+    fn builder_exact_capacity() {
+        let mut builder = HeaplessDataBuilder::<32>::new();
+        builder.push_bytes(&[0xab; 20]).expect("fits");
+        assert_eq!(builder.bit_len(), 160);
+        let s = builder.clone().finish_with_checksum::<Bech32m, 42>(Hrp::parse_unchecked("abc"));
+        assert_eq!(s.expect("fits").as_str(), STRING);
+
+        let err = builder.finish_with_checksum::<Bech32m, 41>(Hrp::parse_unchecked("abc"));
+        assert_eq!(err.unwrap_err(), CapacityError { required: 42, capacity: 41 });
+    }
+
+    #[test]
+    fn builder_insufficient_capacity() {
+        let mut builder = HeaplessDataBuilder::<32>::new();
+        let err = builder.push_fe(Fe32::Q).unwrap().push_bytes(&[0xab; 20]).unwrap_err();
+        // One field element for the version and 32 for the bytes.
+        assert_eq!(err, HeaplessDataBuilderError::Capacity { required: 33, capacity: 32 });
+        assert_eq!(builder.bit_len(), 5, "failed push changes nothing");
+
+        // The padding of pending bits must fit too.
+        let mut builder = HeaplessDataBuilder::<1>::new();
+        builder.push_u8_as_fes(0b11, 2).unwrap().push_u8_as_fes(0b101, 3).unwrap();
+        let err = builder.push_u8_as_fes(1, 1).unwrap_err();
+        assert_eq!(err, HeaplessDataBuilderError::Capacity { required: 2, capacity: 1 });
+        assert_eq!(builder.finish_fes(), [Fe32(0b11101)]);
+    }
+
+    #[test]
+    fn builder_errors() {
+        let mut builder = HeaplessDataBuilder::<8>::new();
+        builder.push_u8_as_fes(1, 1).unwrap();
+        let err = builder.push_bytes(&[0]).unwrap_err();
+        assert_eq!(err, DataBuilderError::Misaligned { pending_bits: 1 }.into());
+        let err = builder.push_u8_as_fes(2, 1).unwrap_err();
+        assert_eq!(err, DataBuilderError::TooWide { value: 2, bits: 1 }.into());
+
+        let mut builder = HeaplessDataBuilder::<8>::new().allow_misaligned_bytes();
+        builder.push_u8_as_fes(1, 1).unwrap().push_bytes(&[0]).unwrap();
+        assert_eq!(builder.finish_fes(), [Fe32(0b10000), Fe32(0b00000)]);
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: MIT
+
+//! Helpers for structure aware fuzzing.
+//!
+//! Random bytes are rarely close to a valid bech32 string, so fuzz targets that start from them
+//! spend most of their time on inputs rejected by the first check. Instead targets can start from
+//! one of the [`interesting_corpus`] strings and apply [`mutate_valid`] to it.
+//!
+//! This module is enabled by the `fuzzing` feature and is *not* covered by semantic versioning, it
+//! may change in any release.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+use crate::{segwit, Bech32, Bech32m};
+
+/// The separator between the human-readable part and the data part.
+const SEP: char = '1';
+
+/// The number of checksum characters of the bech32 and bech32m checksums.
+const CHECKSUM_LENGTH: usize = 6;
+
+/// The number of bytes of randomness used by each mutation of [`mutate_valid`].
+pub const BYTES_PER_MUTATION: usize = 3;
+
+/// Mutates `seed`, one typed mutation for every [`BYTES_PER_MUTATION`] bytes of `rng_bytes`.
+///
+/// The first byte of each chunk selects the mutation, the second the position in the string and
+/// the third, where needed, a value. The mutations are:
+///
+/// - substituting a character with another one from the bech32 alphabet,
+/// - flipping the case of a character,
+/// - substituting a character of the checksum,
+/// - moving the separator,
+/// - inserting a character from the bech32 alphabet,
+/// - deleting a character.
+///
+/// Most mutations keep the string in the bech32 alphabet, so the result exercises the checksum,
+/// case and segwit checks rather than just the character checks. Leftover bytes are ignored.
+pub fn mutate_valid(seed: &str, rng_bytes: &[u8]) -> String {
+    let mut chars: Vec<char> = seed.chars().collect();
+
+    for chunk in rng_bytes.chunks_exact(BYTES_PER_MUTATION) {
+        let (kind, pos, value) = match *chunk {
+            [kind, pos, value] => (kind, usize::from(pos), value),
+            _ => unreachable!("chunks are exactly three bytes"),
+        };
+        let len = chars.len();
+        match kind % 6 {
+            0 => {
+                // Substitute a character of the data part.
+                let start = data_start(&chars);
+                if start < len {
+                    chars[start + pos % (len - start)] = alphabet(value);
+                }
+            }
+            1 =>
+                if len > 0 {
+                    let c = &mut chars[pos % len];
+                    *c = if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    };
+                },
+            2 => {
+                // Substitute a character of the checksum.
+                let checksum = CHECKSUM_LENGTH.min(len);
+                if checksum > 0 {
+                    chars[len - 1 - pos % checksum] = alphabet(value);
+                }
+            }
+            3 => {
+                // Move the separator, or add one if there is none.
+                if let Some(sep) = chars.iter().rposition(|&c| c == SEP) {
+                    chars.remove(sep);
+                }
+                let len = chars.len();
+                chars.insert(pos % (len + 1), SEP);
+            }
+            4 => chars.insert(pos % (len + 1), alphabet(value)),
+            _ =>
+                if len > 0 {
+                    chars.remove(pos % len);
+                },
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Returns the BIP-173 and BIP-350 test vectors plus edge case strings.
+///
+/// Both valid and invalid strings are included. The edge cases include an 83 character
+/// human-readable part, empty data, and strings of exactly the maximum length and one longer.
+pub fn interesting_corpus() -> Vec<String> {
+    const VECTORS: [&str; 45] = [
+        // BIP-173 valid bech32 strings.
+        "A12UEL5L",
+        "a12uel5l",
+        "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+        "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+        "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+        "?1ezyfcl",
+        // BIP-350 valid bech32m strings.
+        "A1LQFN3A",
+        "a1lqfn3a",
+        "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+        "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+        "11llllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllllludsr8",
+        "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+        "?1v759aa",
+        // Checksums calculated with the uppercase form of the human-readable part.
+        "A1G7SGD8",
+        "M1VUXWEZ",
+        // Valid segwit addresses.
+        "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5",
+        "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq",
+        "tgrs1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsess668a6",
+        "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x",
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+        "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+        // Invalid segwit addresses.
+        "tgrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kg3g4ty",
+        "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+        "GRS13W508D6QEJXTDG4Y5R3ZARVARY0C5XW7KN40WF2",
+        "grs1rw5uspcuh",
+        "grs10w508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kw5rljs90",
+        "GRS1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+        "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sL5k7",
+        "grs1zw508d6qejxtdg4y5r3zarvaryvqyzf3du",
+        "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3pjxtptv",
+        "grs1gmk9yu",
+        "tgrt1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+        "tgrs1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+        "GRS1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+        "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+        "tgrs1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+        "grs1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+        "GRS130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+        "grs1pw5dgrnzv",
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav",
+        "tgrs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq",
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+        "tgrs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j",
+    ];
+
+    let mut corpus: Vec<String> = VECTORS.iter().map(|&s| String::from(s)).collect();
+    corpus.extend(edge_cases());
+    corpus
+}
+
+/// Returns the edge case strings of [`interesting_corpus`].
+fn edge_cases() -> Vec<String> {
+    let mut edge = Vec::new();
+
+    edge.extend(["", "1", "grs1", "grs1qqqqqq", "1qqqqqq"].iter().map(|&s| String::from(s)));
+
+    // Empty data, with both checksums.
+    edge.extend(crate::encode::<Bech32>(hrp::GRS, &[]));
+    edge.extend(crate::encode::<Bech32m>(hrp::GRS, &[]));
+
+    // The longest human-readable part, and one character longer.
+    let longest = "x".repeat(hrp::MAX_HRP_LENGTH);
+    edge.extend(Hrp::parse(&longest).ok().and_then(|hrp| crate::encode::<Bech32m>(hrp, &[]).ok()));
+    edge.push(format!("{}x1qqqqqq", longest));
+
+    // The longest bech32 string, 1023 characters, and one character longer.
+    let hrp = Hrp::parse_unchecked("a");
+    edge.extend(crate::encode::<Bech32m>(hrp, &[0xab; 634]));
+    edge.push(format!("a1{}", "q".repeat(1022)));
+
+    // The longest segwit address, 90 characters, and one character longer.
+    for hrp in ["abcdefghijklmnopqr", "abcdefghijklmnopqrs"].iter() {
+        let mut s = String::new();
+        let hrp = Hrp::parse_unchecked(hrp);
+        if segwit::encode_to_fmt_unchecked(&mut s, hrp, segwit::VERSION_1, &[0xcd; 40]).is_ok() {
+            edge.push(s);
+        }
+    }
+
+    edge
+}
+
+/// Returns the index of the first character of the data part, after the last separator.
+fn data_start(chars: &[char]) -> usize {
+    chars.iter().rposition(|&c| c == SEP).map_or(0, |i| i + 1)
+}
+
+/// Returns the bech32 character for the low five bits of `value`.
+fn alphabet(value: u8) -> char {
+    Fe32::try_from(value & 0x1f).expect("five bits are a field element").to_char()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::{CheckedHrpstring, SegwitHrpstring};
+
+    #[test]
+    fn corpus_edge_cases() {
+        let corpus = interesting_corpus();
+        let find = |prefix: &str, len: usize| {
+            corpus.iter().find(|s| s.starts_with(prefix) && s.len() == len).expect("in corpus")
+        };
+
+        let hrp = "x".repeat(hrp::MAX_HRP_LENGTH);
+        assert!(CheckedHrpstring::new::<Bech32m>(find(&hrp, 90)).is_ok());
+        assert!(CheckedHrpstring::new::<Bech32m>(find(&hrp, 91)).is_err());
+        assert!(CheckedHrpstring::new::<Bech32m>(find("a1", 1023)).is_ok());
+        assert!(CheckedHrpstring::new::<Bech32m>(find("a1", 1024)).is_err());
+        assert!(SegwitHrpstring::new(find("abcdefghijklmnopqr1", 90)).is_ok());
+        assert!(SegwitHrpstring::new(find("abcdefghijklmnopqrs1", 91)).is_err());
+        assert!(corpus.contains(&crate::encode::<Bech32>(hrp::GRS, &[]).unwrap()));
+    }
+
+    #[test]
+    fn no_randomness_no_mutation() {
+        for s in interesting_corpus().iter() {
+            assert_eq!(&mutate_valid(s, &[]), s);
+            assert_eq!(&mutate_valid(s, &[1, 2]), s);
+        }
+    }
+
+    #[test]
+    fn mutations() {
+        let seed = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+
+        // Substitute the first data character with 'p' (5).
+        assert_eq!(mutate_valid(seed, &[0, 0, 1]), seed.replacen("1q", "1p", 1));
+        // Flip the case of the first character.
+        assert_eq!(mutate_valid(seed, &[1, 0, 0]), seed.replacen('g', "G", 1));
+        // Substitute the last character with 'q' (0).
+        assert_eq!(mutate_valid(seed, &[2, 0, 0]), seed.replacen("65p", "65q", 1));
+        // Move the separator to the start.
+        assert_eq!(mutate_valid(seed, &[3, 0, 0]), format!("1grs{}", &seed[4..]));
+        // Insert 'q' at the start.
+        assert_eq!(mutate_valid(seed, &[4, 0, 0]), format!("q{}", seed));
+        // Delete the first character.
+        assert_eq!(mutate_valid(seed, &[5, 0, 0]), &seed[1..]);
+
+        for s in ["", "1", "abc"].iter() {
+            for kind in 0..6 {
+                let _ = mutate_valid(s, &[kind, 0xff, 0xff, kind, 0, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn mutations_stay_in_alphabet() {
+        let seed = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let rng: Vec<u8> = (0..=255).collect();
+        let mutated = mutate_valid(seed, &rng);
+        assert!(mutated.chars().all(|c| c == SEP || Fe32::from_char(c).is_ok()), "{}", mutated);
+    }
+}
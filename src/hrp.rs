@@ -5,4 +5,4 @@
 //! [`primitives::hrp`]: crate::primitives::hrp
 
 #[doc(inline)]
-pub use crate::primitives::hrp::{Hrp, GRS, GRSRT, TGRS};
+pub use crate::primitives::hrp::{Hrp, GRS, GRSRT, MAX_HRP_LENGTH, TGRS};
@@ -17,6 +17,30 @@
 //!   explicit control of the checksum algorithm and the case used when encoding.
 //! - Non-segwit stuff and you do *not* have an allocator, use the [`CheckedHrpstring`] type for
 //!   decoding. For encoding we provide various top level functions of the form `encode*_to_fmt`.
+//! - None of the encoding functions panic, the `*_unchecked` ones included whatever the length of
+//!   the data. The `encode*_to_writer` and `encode*_to_embedded_writer` functions do not allocate,
+//!   they pass the encoded bytes to the writer in chunks of up to 16 bytes.
+//! - With the `heapless` feature enabled, `decode_heapless` and `encode_to_heapless_string` decode
+//!   into and encode to fixed capacity `heapless` buffers.
+//! - With the `tracing` feature enabled, a `tracing` debug event is emitted whenever parsing an
+//!   [`UncheckedHrpstring`], [`CheckedHrpstring`] or [`SegwitHrpstring`] fails. Events include the
+//!   error code and the length of the input, never the input itself.
+//! - [`ct_eq`] and [`ct_eq_bytes`] compare secret strings and data, such as codex32 shares, without
+//!   leaking where they differ. With the `subtle` feature enabled [`Fe32`] implements
+//!   `subtle::ConstantTimeEq`.
+//! - With the `zeroize` feature enabled, `decode_zeroizing` and `segwit::decode_zeroizing` return
+//!   data that is wiped on drop, and [`Fe32`], `DataBuilder` and `segwit::WitnessProgram` implement
+//!   `zeroize::Zeroize`.
+//! - With the `metrics` feature enabled, `segwit::decode_with_stats` also returns statistics about
+//!   the input and, with `std`, the time taken to decode it.
+//! - With the `borsh` or `rkyv` features enabled, [`Fe32`], [`Hrp`], [`segwit::WitnessProgram`]
+//!   and [`segwit::CompactAddress`] can be stored in archives of that format. Deserialization
+//!   validates the data, a corrupted archive can not produce an invalid value.
+//! - With the `schemars` and `alloc` features enabled, `segwit::AddressString` implements
+//!   `schemars::JsonSchema`, describing it as a string with the format `grs-address`.
+//! - With the `core-error` feature enabled, which needs Rust 1.81, the error types implement
+//!   `core::error::Error` without `std`, so they can be converted into `Box<dyn core::error::Error>`.
+//! - With the `rayon` feature enabled, `verify_batch` validates its inputs in parallel.
 //! - To define your own checksum algorithm implement [`Checksum`] (see example below).
 //!
 //! The original description in [BIP-173] has more details. See also [BIP-350].
@@ -102,6 +126,7 @@
 //! pub enum Codex32 {}
 //!
 //! impl Checksum for Codex32 {
+//!     const NAME: &'static str = "codex32";
 //!     type MidstateRepr = u128;
 //!     const CHECKSUM_LENGTH: usize = 13;
 //!     const CODE_LENGTH: usize = 93;
@@ -123,6 +148,8 @@
 //! [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
 //! [`CheckedHrpstring`]: crate::primitives::decode::CheckedHrpstring
 //! [`Checksum::CODE_LENGTH`]: crate::primitives::checksum::Checksum::CODE_LENGTH
+//! [`SegwitHrpstring`]: crate::primitives::decode::SegwitHrpstring
+//! [`UncheckedHrpstring`]: crate::primitives::decode::UncheckedHrpstring
 
 #![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 // Experimental features we need.
@@ -140,42 +167,118 @@ extern crate alloc;
 #[cfg(any(test, feature = "std"))]
 extern crate core;
 
+#[cfg(any(feature = "borsh", feature = "rkyv"))]
+mod archive;
+#[cfg(feature = "alloc")]
+mod batch;
+#[cfg(feature = "bolt11")]
+pub mod bolt11;
+#[cfg(feature = "bolt12")]
+pub mod bolt12;
+mod builder;
+#[cfg(feature = "codex32")]
+pub mod codex32;
+mod complete;
+mod ct;
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "heapless")]
+mod fixed_capacity;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
 pub mod hrp;
+#[cfg(feature = "lnurl")]
+pub mod lnurl;
+#[cfg(feature = "nip19")]
+pub mod nip19;
 pub mod primitives;
+#[cfg(feature = "alloc")]
+mod router;
 pub mod segwit;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod serde;
+#[cfg(feature = "sp")]
+pub mod sp;
+mod split;
+#[cfg(feature = "test-utils")]
+pub mod testutils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
 use alloc::{string::String, vec::Vec};
+use core::cmp::Ordering;
 use core::fmt;
 
-use crate::error::write_err;
-#[cfg(doc)]
-use crate::primitives::decode::CheckedHrpstring;
-use crate::primitives::decode::CodeLengthError;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
 #[cfg(feature = "alloc")]
-use crate::primitives::decode::{ChecksumError, UncheckedHrpstring, UncheckedHrpstringError};
+use crate::primitives::decode::PaddingError;
+use crate::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, ChecksumError, CodeLengthError, UncheckedHrpstring,
+    UncheckedHrpstringError,
+};
 
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
 pub use {
+    crate::complete::{complete_checksum, valid_next_chars, ChecksumIter},
+    crate::ct::{ct_eq, ct_eq_bytes},
+    crate::error::{render_error, Error},
     crate::primitives::checksum::Checksum,
+    crate::primitives::decode::{CasePolicy, DecodeProfile},
     crate::primitives::gf32::Fe32,
     crate::primitives::hrp::Hrp,
-    crate::primitives::iter::{ByteIterExt, Fe32IterExt},
+    crate::primitives::iter::{
+        ByteIterExt, ByteRefIterExt, Fe32IterExt, TryByteIterExt, TryFe32IterExt,
+    },
     crate::primitives::{Bech32, Bech32m, NoChecksum},
 };
 
-// Write to fmt buffer, small during testing to exercise full code path.
-#[cfg(not(test))]
-const BUF_LENGTH: usize = 1024;
-#[cfg(test)]
-const BUF_LENGTH: usize = 10;
+/// The field element type of `0.9.1`, which is now [`Fe32`].
+#[deprecated(note = "use `Fe32` instead")]
+#[allow(non_camel_case_types)]
+pub type u5 = Fe32;
+
+/// The checksum variants of `0.9.1`, which are now the [`Bech32`] and [`Bech32m`] checksum types.
+#[deprecated(note = "use the `Bech32` or `Bech32m` checksum type instead")]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Variant {
+    /// The original checksum of BIP-173, now [`Bech32`].
+    Bech32,
+    /// The improved checksum of BIP-350, now [`Bech32m`].
+    Bech32m,
+}
+
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::batch::verify_batch;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::builder::DataBuilder;
+#[doc(inline)]
+pub use crate::builder::DataBuilderError;
+#[cfg(feature = "heapless")]
+#[doc(inline)]
+pub use crate::fixed_capacity::{
+    decode_heapless, encode_to_heapless_string, DecodeHeaplessError, HeaplessDataBuilder,
+    HeaplessDataBuilderError,
+};
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::router::{HrpRouter, RouteError};
+#[doc(inline)]
+pub use crate::split::{split, split_with_checksum_length, Spans, SplitError};
 
 /// Decodes a bech32 encoded string.
 ///
 /// If this function succeeds the input string was found to be well formed (hrp, separator, bech32
-/// characters), and to have either a valid bech32m checksum or a valid bech32 checksum.
+/// characters), and to have either a valid bech32m checksum or a valid bech32 checksum. Any
+/// trailing bits that do not make up a whole byte are dropped, use [`decode_strict`] to check them.
+/// The data part of a segwit address starts with the witness version, so its trailing bits are not
+/// padding, use [`segwit::decode`] to decode addresses.
 ///
 /// If your input string has no checksum use the [`CheckedHrpstring`] constructor, which allows
 /// selecting the checksum algorithm explicitly.
@@ -207,7 +310,7 @@ const BUF_LENGTH: usize = 10;
 #[cfg(feature = "alloc")]
 #[inline]
 pub fn decode(s: &str) -> Result<(Hrp, Vec<u8>), DecodeError> {
-    let unchecked = UncheckedHrpstring::new(s)?;
+    let unchecked = UncheckedHrpstring::new_requiring_data(s, true)?;
 
     if let Err(e) = unchecked.validate_checksum::<Bech32m>() {
         if !unchecked.has_valid_checksum::<Bech32>() {
@@ -221,6 +324,228 @@ pub fn decode(s: &str) -> Result<(Hrp, Vec<u8>), DecodeError> {
     Ok((checked.hrp(), checked.byte_iter().collect()))
 }
 
+/// Decodes a bech32 encoded string, enforcing the BIP-173 padding rules.
+///
+/// Like [`decode`] but the bits left over after the last byte must be 4 bits or less and all zero,
+/// as they are for data encoded by [`encode`]. Do not use this for segwit addresses, the witness
+/// version shifts the program so that a valid address may appear to have too much padding.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::PaddingError;
+/// use bech32grs::{decode_strict, DecodeError};
+///
+/// const BECH32M: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+///
+/// let (hrp, data) = decode_strict(BECH32M).expect("valid bech32m string");
+/// assert_eq!(hrp.as_str(), "abc");
+/// assert_eq!(data, [0xab; 20]);
+///
+/// // Non-zero padding, from the BIP-350 invalid address test vectors.
+/// let err = decode_strict("tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j");
+/// assert_eq!(err.unwrap_err(), DecodeError::Padding(PaddingError::NonZero));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_strict(s: &str) -> Result<(Hrp, Vec<u8>), DecodeError> {
+    let unchecked = UncheckedHrpstring::new_requiring_data(s, true)?;
+
+    if let Err(e) = unchecked.validate_checksum::<Bech32m>() {
+        if !unchecked.has_valid_checksum::<Bech32>() {
+            return Err(DecodeError::Checksum(e));
+        }
+    };
+    let checked = unchecked.remove_checksum::<Bech32m>();
+    let data = checked
+        .fe32_iter::<core::iter::Empty<u8>>()
+        .fes_to_bytes_strict()
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    Ok((checked.hrp(), data))
+}
+
+/// Decodes a bech32 string that has no checksum, e.g. one made by [`encode_no_checksum`].
+///
+/// The string must be well formed (hrp, separator, bech32 characters), the data part may be empty.
+/// As for [`decode`], any trailing bits that do not make up a whole byte are dropped, so this
+/// decodes every string the no checksum encoders emit, including those encoding field elements
+/// rather than bytes. Every data character is data, a string that does have a checksum decodes with
+/// the checksum characters as part of the data.
+///
+/// # Examples
+///
+/// ```
+/// const NO_CHECKSUM: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at";
+///
+/// let (hrp, data) = bech32grs::decode_no_checksum(NO_CHECKSUM).expect("valid string");
+/// assert_eq!(hrp.as_str(), "abc");
+/// assert_eq!(data, [0xab; 20]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_no_checksum(s: &str) -> Result<(Hrp, Vec<u8>), DecodeError> {
+    let checked = UncheckedHrpstring::new(s)?.remove_checksum::<NoChecksum>();
+
+    Ok((checked.hrp(), checked.byte_iter().collect()))
+}
+
+/// Decodes a bech32 encoded string that has no checksum into its human-readable part, in
+/// lowercase, and its field elements.
+///
+/// The `decode_without_checksum` of `0.9.1`, with `u5` renamed to [`Fe32`] and its errors
+/// converted into [`Error`].
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use `decode_no_checksum`, which returns `Hrp` and bytes, instead")]
+#[inline]
+pub fn decode_without_checksum(s: &str) -> Result<(String, Vec<Fe32>), Error> {
+    let checked = UncheckedHrpstring::new(s)?.remove_checksum::<NoChecksum>();
+    let data = checked.data_part_ascii_no_checksum().iter().copied();
+
+    Ok((checked.hrp().to_lowercase(), data.map(Fe32::from_char_unchecked).collect()))
+}
+
+/// Decodes a bech32 encoded string, returning the data in a buffer that is zeroized on drop.
+///
+/// Like [`decode`] but for secret data, such as private keys, that should not linger in memory.
+/// The data is written directly into a buffer of exactly the right size, so it is never
+/// reallocated. Nothing else is wiped: not the input string, nor the few bytes of conversion and
+/// checksum state that decoding keeps on the stack.
+///
+/// # Examples
+///
+/// ```
+/// const BECH32M: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+///
+/// let (hrp, data) = bech32grs::decode_zeroizing(BECH32M).expect("valid bech32m string");
+/// assert_eq!(hrp.as_str(), "abc");
+/// assert_eq!(*data, [0xab; 20]);
+/// ```
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+pub fn decode_zeroizing(s: &str) -> Result<(Hrp, zeroize::Zeroizing<Vec<u8>>), DecodeError> {
+    let unchecked = UncheckedHrpstring::new_requiring_data(s, true)?;
+
+    if let Err(e) = unchecked.validate_checksum::<Bech32m>() {
+        if !unchecked.has_valid_checksum::<Bech32>() {
+            return Err(DecodeError::Checksum(e));
+        }
+    };
+    let checked = unchecked.remove_checksum::<Bech32m>();
+
+    let iter = checked.byte_iter();
+    let mut data = zeroize::Zeroizing::new(Vec::with_capacity(iter.len()));
+    data.extend(iter);
+    Ok((checked.hrp(), data))
+}
+
+/// Decodes a bech32 encoded string that may be up to `max_len` characters long.
+///
+/// Formats built on bech32 set their own maximum length, for example silent payment addresses
+/// may be up to 1023 characters long. Strings longer than `max_len` fail with a
+/// [`ChecksumError::CodeLength`] error, the checksum's own code length still applies so a
+/// `max_len` longer than `Ck::CODE_LENGTH` has no effect.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{decode_long, Bech32m};
+///
+/// // A BIP-352 silent payment address, longer than the 90 character limit of segwit addresses.
+/// const SP: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+///
+/// let checked = decode_long::<Bech32m>(SP, 1023).expect("valid bech32m string");
+/// assert_eq!(checked.hrp().as_str(), "sp");
+/// assert!(decode_long::<Bech32m>(SP, 90).is_err());
+/// ```
+#[inline]
+pub fn decode_long<Ck: Checksum>(
+    s: &str,
+    max_len: usize,
+) -> Result<CheckedHrpstring<'_>, CheckedHrpstringError> {
+    let unchecked = UncheckedHrpstring::new_requiring_data(s, Ck::CHECKSUM_LENGTH > 0)?;
+
+    // All characters are ASCII, checked by `UncheckedHrpstring::new`.
+    if s.len() > max_len {
+        let e = CodeLengthError { encoded_length: s.len(), code_length: max_len };
+        return Err(CheckedHrpstringError::Checksum(ChecksumError::CodeLength(e)));
+    }
+
+    Ok(unchecked.validate_and_remove_checksum::<Ck>()?)
+}
+
+/// Decodes a bech32 string with either a bech32m or a bech32 checksum, without allocating.
+///
+/// Tries bech32m first. If neither checksum is valid, the error returned is the more informative
+/// of the two, see [`most_informative`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::{CheckedHrpstringError, ChecksumError};
+///
+/// let checked = bech32grs::decode_any("abc14w46h2at4w46h2at4w46h2at4w46h2atsghld7").expect("valid");
+/// assert_eq!(checked.hrp().as_str(), "abc");
+///
+/// let err = bech32grs::decode_any("abc14w46h2at4w46h2at4w46h2at4w46h2atsghld8").unwrap_err();
+/// assert_eq!(err, CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue));
+/// ```
+#[inline]
+pub fn decode_any(s: &str) -> Result<CheckedHrpstring<'_>, CheckedHrpstringError> {
+    let bech32m = match CheckedHrpstring::new::<Bech32m>(s) {
+        Ok(checked) => return Ok(checked),
+        Err(e) => e,
+    };
+    let bech32 = match CheckedHrpstring::new::<Bech32>(s) {
+        Ok(checked) => return Ok(checked),
+        Err(e) => e,
+    };
+
+    if most_informative(&bech32, &bech32m) == Ordering::Greater {
+        Err(bech32)
+    } else {
+        Err(bech32m)
+    }
+}
+
+/// Compares two decoding errors by how useful they are to a user trying to fix their input.
+///
+/// Returns [`Ordering::Greater`] if `a` is more informative than `b`. When a string is decoded
+/// several ways, for example with different checksum algorithms, use this to pick which error to
+/// report instead of reporting whichever was tried last. From most to least informative:
+///
+/// 1. Character and human-readable part errors, these point at something specific in the input.
+/// 2. An invalid checksum, the input has a typo somewhere.
+/// 3. Length errors, the input is too long or too short for the checksum.
+///
+/// Errors in the same category compare equal. [`CheckedHrpstringError`] does not hold padding
+/// errors, which are only checked when decoding segwit addresses, after the checksum.
+///
+/// # Examples
+///
+/// ```
+/// use core::cmp::Ordering;
+///
+/// use bech32grs::primitives::decode::{CharError, CheckedHrpstringError, ChecksumError};
+///
+/// let invalid_char = CheckedHrpstringError::Parse(CharError::InvalidChar('b').into());
+/// let residue = CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue);
+/// assert_eq!(bech32grs::most_informative(&invalid_char, &residue), Ordering::Greater);
+/// ```
+pub fn most_informative(a: &CheckedHrpstringError, b: &CheckedHrpstringError) -> Ordering {
+    /// Returns the priority of `e`, higher is more informative.
+    fn priority(e: &CheckedHrpstringError) -> u8 {
+        use CheckedHrpstringError::{Checksum, Parse};
+        use UncheckedHrpstringError::{Char, Hrp, HrpMixedCase, HrpSplit, TooLong};
+
+        match *e {
+            Parse(Char(_)) | Parse(Hrp(_)) | Parse(HrpSplit(_)) | Parse(HrpMixedCase(_)) => 2,
+            Checksum(ChecksumError::InvalidResidue) => 1,
+            Parse(TooLong(_))
+            | Checksum(ChecksumError::CodeLength(_))
+            | Checksum(ChecksumError::InvalidLength) => 0,
+        }
+    }
+    priority(a).cmp(&priority(b))
+}
+
 /// Encodes `data` as a lowercase bech32 encoded string.
 ///
 /// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
@@ -238,7 +563,7 @@ pub fn encode<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<String, EncodeError
 #[cfg(feature = "alloc")]
 #[inline]
 pub fn encode_lower<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<String, EncodeError> {
-    let mut buf = String::new();
+    let mut buf = String::with_capacity(encoded_length::<Ck>(hrp, data)?);
     encode_lower_to_fmt::<Ck, String>(&mut buf, hrp, data)?;
     Ok(buf)
 }
@@ -250,11 +575,165 @@ pub fn encode_lower<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<String, Encod
 #[cfg(feature = "alloc")]
 #[inline]
 pub fn encode_upper<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<String, EncodeError> {
-    let mut buf = String::new();
+    let mut buf = String::with_capacity(encoded_length::<Ck>(hrp, data)?);
     encode_upper_to_fmt::<Ck, String>(&mut buf, hrp, data)?;
     Ok(buf)
 }
 
+/// Encodes `data` as a lowercase bech32 string without a checksum.
+///
+/// The string is the `hrp`, the separator '1' and the data characters, the same as
+/// [`encode::<NoChecksum>`](encode) but without having to name the checksum type. Decode it with
+/// [`decode_no_checksum`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::Hrp;
+///
+/// let hrp = Hrp::parse("abc").expect("valid hrp");
+/// let s = bech32grs::encode_no_checksum(hrp, &[0xab; 20]).expect("valid data");
+/// assert_eq!(s, "abc14w46h2at4w46h2at4w46h2at4w46h2at");
+/// assert_eq!(bech32grs::decode_no_checksum(&s).expect("valid string"), (hrp, vec![0xab; 20]));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_no_checksum(hrp: Hrp, data: &[u8]) -> Result<String, EncodeError> {
+    encode_lower::<NoChecksum>(hrp, data)
+}
+
+/// Encodes field elements as a lowercase bech32 string without a checksum.
+///
+/// The `encode_without_checksum` of `0.9.1`, with `u5` renamed to [`Fe32`] and its errors
+/// converted into [`Error`]. Like it, this does not limit the length of the data.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use `encode_no_checksum`, which takes `Hrp` and bytes, instead")]
+#[inline]
+pub fn encode_without_checksum<T: AsRef<[Fe32]>>(hrp: &str, data: T) -> Result<String, Error> {
+    let mut buf = String::new();
+    #[allow(deprecated)]
+    encode_without_checksum_to_fmt(&mut buf, hrp, data)?.expect("writing to a string never fails");
+    Ok(buf)
+}
+
+/// Encodes field elements to a writer ([`fmt::Write`]) as a lowercase bech32 string without a
+/// checksum.
+///
+/// The `encode_without_checksum_to_fmt` of `0.9.1`, with `u5` renamed to [`Fe32`] and its errors
+/// converted into [`Error`]. Like it, this does not limit the length of the data.
+#[deprecated(note = "use `encode_no_checksum_to_fmt`, which takes `Hrp` and bytes, instead")]
+#[inline]
+pub fn encode_without_checksum_to_fmt<T: AsRef<[Fe32]>>(
+    fmt: &mut dyn fmt::Write,
+    hrp: &str,
+    data: T,
+) -> Result<fmt::Result, Error> {
+    let hrp = Hrp::parse(hrp)?;
+    let iter = data.as_ref().iter().copied().with_checksum::<NoChecksum>(&hrp);
+
+    Ok(iter.chars().try_for_each(|c| fmt.write_char(c)))
+}
+
+/// Encodes field elements to a writer ([`fmt::Write`]) as a bech32 string, writing the
+/// human-readable part in the case it was parsed in and the rest in lowercase.
+///
+/// The `encode_to_fmt_anycase` of `0.10.0-alpha`, with `u5` renamed to [`Fe32`] and its errors
+/// converted into [`Error`]. Like it, this does not limit the length of the data, and never returns
+/// the outer error.
+#[deprecated(
+    note = "use `encode_lower_to_fmt` or `encode_upper_to_fmt`, which take bytes, instead"
+)]
+#[allow(deprecated)]
+#[inline]
+pub fn encode_to_fmt_anycase<T: AsRef<[Fe32]>>(
+    fmt: &mut dyn fmt::Write,
+    hrp: Hrp,
+    data: T,
+    variant: Variant,
+) -> Result<fmt::Result, Error> {
+    let data = data.as_ref().iter().copied();
+    // The encoding iterators yield the hrp in lowercase, skip it and write the original instead.
+    let rest: &mut dyn Iterator<Item = char> = match variant {
+        Variant::Bech32 => &mut data.with_checksum::<Bech32>(&hrp).chars().skip(hrp.len()),
+        Variant::Bech32m => &mut data.with_checksum::<Bech32m>(&hrp).chars().skip(hrp.len()),
+    };
+
+    Ok(hrp.char_iter().chain(rest).try_for_each(|c| fmt.write_char(c)))
+}
+
+/// Converts a slice of `from` bit values to `to` bit values, padding the last value with zero bits
+/// if `pad` is true.
+///
+/// The `convert_bits` of `0.9.1`, with its errors converted into [`Error`]: a value wider than
+/// `from` bits is a [`FeWriterError::TooWide`](primitives::gf32::FeWriterError::TooWide), and
+/// unexpected padding when `pad` is false is a [`PaddingError`].
+///
+/// # Panics
+///
+/// If `from` or `to` is 0 or greater than 8.
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use `ByteIterExt::bytes_to_fes` or `Fe32IterExt::fes_to_bytes` instead")]
+pub fn convert_bits<T>(data: &[T], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Error>
+where
+    T: Into<u8> + Copy,
+{
+    use crate::primitives::gf32::FeWriterError;
+
+    assert!((1..=8).contains(&from) && (1..=8).contains(&to), "bit widths must be 1 to 8");
+
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut ret = Vec::new();
+    let maxv: u32 = (1 << to) - 1;
+    for &value in data {
+        let v = u32::from(value.into());
+        if v >> from != 0 {
+            return Err(FeWriterError::TooWide { value: u64::from(v), bits: from as usize }.into());
+        }
+        acc = (acc << from) | v;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            ret.push(((acc >> bits) & maxv) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to - bits)) & maxv) as u8);
+        }
+    } else if bits >= from {
+        return Err(PaddingError::TooMuch.into());
+    } else if (acc << (to - bits)) & maxv != 0 {
+        return Err(PaddingError::NonZero.into());
+    }
+    Ok(ret)
+}
+
+/// Encodes `data` to a writer ([`fmt::Write`]) as a lowercase bech32 string without a checksum.
+///
+/// The same as [`encode_to_fmt::<NoChecksum, W>`](encode_to_fmt), see [`encode_no_checksum`].
+#[inline]
+pub fn encode_no_checksum_to_fmt<W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<(), EncodeError> {
+    encode_lower_to_fmt::<NoChecksum, W>(fmt, hrp, data)
+}
+
+/// Encodes `data` to a writer ([`fmt::Write`]) as an uppercase bech32 string without a checksum.
+///
+/// The same as [`encode_upper_to_fmt::<NoChecksum, W>`](encode_upper_to_fmt), see
+/// [`encode_no_checksum`].
+#[inline]
+pub fn encode_no_checksum_upper_to_fmt<W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<(), EncodeError> {
+    encode_upper_to_fmt::<NoChecksum, W>(fmt, hrp, data)
+}
+
 /// Encodes `data` to a writer ([`fmt::Write`]) as a lowercase bech32 encoded string.
 ///
 /// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
@@ -279,27 +758,7 @@ pub fn encode_lower_to_fmt<Ck: Checksum, W: fmt::Write>(
     data: &[u8],
 ) -> Result<(), EncodeError> {
     let _ = encoded_length::<Ck>(hrp, data)?;
-
-    let mut buf = [0u8; BUF_LENGTH];
-    let mut pos = 0;
-
-    let iter = data.iter().copied().bytes_to_fes();
-    let chars = iter.with_checksum::<Ck>(&hrp).chars();
-
-    for c in chars {
-        buf[pos] = c as u8;
-        pos += 1;
-
-        if pos == BUF_LENGTH {
-            let s = core::str::from_utf8(&buf).expect("we only write ASCII");
-            fmt.write_str(s)?;
-            pos = 0;
-        }
-    }
-
-    let s = core::str::from_utf8(&buf[..pos]).expect("we only write ASCII");
-    fmt.write_str(s)?;
-
+    encode_lower_to_fmt_unchecked::<Ck, W>(fmt, hrp, data)?;
     Ok(())
 }
 
@@ -314,26 +773,76 @@ pub fn encode_upper_to_fmt<Ck: Checksum, W: fmt::Write>(
     data: &[u8],
 ) -> Result<(), EncodeError> {
     let _ = encoded_length::<Ck>(hrp, data)?;
+    encode_upper_to_fmt_unchecked::<Ck, W>(fmt, hrp, data)?;
+    Ok(())
+}
+
+/// Encodes `data` to a writer ([`fmt::Write`]) as a lowercase bech32 encoded string, without
+/// checking the code length.
+///
+/// There are no guarantees that the written string is valid if it is longer than the code length
+/// of `Ck`, see [`encoded_length`] for the check done by [`encode_to_fmt`].
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the string was written before the error is unspecified.
+#[inline]
+pub fn encode_to_fmt_unchecked<Ck: Checksum, W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> fmt::Result {
+    encode_lower_to_fmt_unchecked::<Ck, W>(fmt, hrp, data)
+}
+
+/// Encodes `data` to a writer ([`fmt::Write`]) as a lowercase bech32 encoded string, without
+/// checking the code length.
+///
+/// There are no guarantees that the written string is valid if it is longer than the code length
+/// of `Ck`, see [`encoded_length`] for the check done by [`encode_lower_to_fmt`].
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the string was written before the error is unspecified.
+pub fn encode_lower_to_fmt_unchecked<Ck: Checksum, W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> fmt::Result {
+    encode_to_fmt_with_case_unchecked::<Ck, W, _>(fmt, hrp, data, |c| c.to_ascii_lowercase())
+}
 
-    let mut buf = [0u8; BUF_LENGTH];
-    let mut pos = 0;
+/// Encodes `data` to a writer ([`fmt::Write`]) as an uppercase bech32 encoded string, without
+/// checking the code length.
+///
+/// There are no guarantees that the written string is valid if it is longer than the code length
+/// of `Ck`, see [`encoded_length`] for the check done by [`encode_upper_to_fmt`].
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the string was written before the error is unspecified.
+pub fn encode_upper_to_fmt_unchecked<Ck: Checksum, W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> fmt::Result {
+    encode_to_fmt_with_case_unchecked::<Ck, W, _>(fmt, hrp, data, |c| c.to_ascii_uppercase())
+}
 
+/// Encodes `data` to a writer ([`fmt::Write`]), passing each character through `case`.
+///
+/// Shared by the lowercase and uppercase functions so that they cannot diverge, in particular in
+/// stopping at the first error returned by `fmt`.
+fn encode_to_fmt_with_case_unchecked<Ck: Checksum, W: fmt::Write, F: Fn(char) -> char>(
+    fmt: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+    case: F,
+) -> fmt::Result {
     let iter = data.iter().copied().bytes_to_fes();
     let chars = iter.with_checksum::<Ck>(&hrp).chars();
 
     for c in chars {
-        buf[pos] = c.to_ascii_uppercase() as u8;
-        pos += 1;
-        if pos == BUF_LENGTH {
-            let s = core::str::from_utf8(&buf).expect("we only write ASCII");
-            fmt.write_str(s)?;
-            pos = 0;
-        }
+        fmt.write_char(case(c))?;
     }
 
-    let s = core::str::from_utf8(&buf[..pos]).expect("we only write ASCII");
-    fmt.write_str(s)?;
-
     Ok(())
 }
 
@@ -368,22 +877,10 @@ pub fn encode_lower_to_writer<Ck: Checksum, W: std::io::Write>(
 ) -> Result<(), EncodeIoError> {
     let _ = encoded_length::<Ck>(hrp, data)?;
 
-    let mut buf = [0u8; BUF_LENGTH];
-    let mut pos = 0;
-
     let iter = data.iter().copied().bytes_to_fes();
-    let chars = iter.with_checksum::<Ck>(&hrp).chars();
-
-    for c in chars {
-        buf[pos] = c as u8;
-        pos += 1;
-        if pos == BUF_LENGTH {
-            w.write_all(&buf)?;
-            pos = 0;
-        }
-    }
+    let bytes = iter.with_checksum::<Ck>(&hrp).bytes();
 
-    w.write_all(&buf[..pos])?;
+    write_chunked(bytes, |chunk| w.write_all(chunk))?;
 
     Ok(())
 }
@@ -403,55 +900,223 @@ pub fn encode_upper_to_writer<Ck: Checksum, W: std::io::Write>(
 ) -> Result<(), EncodeIoError> {
     let _ = encoded_length::<Ck>(hrp, data)?;
 
-    let mut buf = [0u8; BUF_LENGTH];
-    let mut pos = 0;
-
     let iter = data.iter().copied().bytes_to_fes();
-    let chars = iter.with_checksum::<Ck>(&hrp).chars();
-
-    for c in chars {
-        buf[pos] = c.to_ascii_uppercase() as u8;
-        pos += 1;
-        if pos == BUF_LENGTH {
-            w.write_all(&buf)?;
-            pos = 0;
-        }
-    }
+    let bytes = iter.with_checksum::<Ck>(&hrp).bytes();
 
-    w.write_all(&buf[..pos])?;
+    write_chunked(bytes.map(|b| b.to_ascii_uppercase()), |chunk| w.write_all(chunk))?;
 
     Ok(())
 }
 
-/// Checks that encoding `hrp` and `data` creates a code that is less than the code length for `Ck`.
+/// Encodes `data` to an [`embedded_io::Write`] writer as a lowercase bech32 encoded string.
 ///
-/// The length of the code is how long a coded message can be (including the checksum!) for the code
-/// to retain its error-correcting properties.
-///
-/// # Returns
+/// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
+/// `Ck` algorithm (`NoChecksum` to exclude checksum all together).
 ///
-/// `Ok(encoded_string_length)` if the encoded length is less than or equal to `Ck::CODE_LENGTH`
-/// otherwise a [`CodeLengthError`] containing the encoded length and the maximum allowed.
-pub fn encoded_length<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<usize, CodeLengthError> {
-    let iter = data.iter().copied().bytes_to_fes();
-    let len = hrp.len() + 1 + iter.len() + Ck::CHECKSUM_LENGTH; // +1 for separator
-    if len > Ck::CODE_LENGTH {
-        Err(CodeLengthError { encoded_length: len, code_length: Ck::CODE_LENGTH })
-    } else {
-        Ok(len)
-    }
+/// Errors from the writer are returned unchanged in [`EncodeEmbeddedError::Write`].
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_to_embedded_writer<Ck: Checksum, W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<(), EncodeEmbeddedError<W::Error>> {
+    encode_lower_to_embedded_writer::<Ck, W>(w, hrp, data)
 }
 
-/// An error while decoding a bech32 string.
-#[cfg(feature = "alloc")]
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[non_exhaustive]
-pub enum DecodeError {
-    /// Parsing failed.
-    Parse(UncheckedHrpstringError),
-    /// No valid bech32 or bech32m checksum.
-    Checksum(ChecksumError),
-}
+/// Encodes `data` to an [`embedded_io::Write`] writer as a lowercase bech32 encoded string.
+///
+/// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
+/// `Ck` algorithm (`NoChecksum` to exclude checksum all together).
+///
+/// Errors from the writer are returned unchanged in [`EncodeEmbeddedError::Write`].
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_lower_to_embedded_writer<Ck: Checksum, W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<(), EncodeEmbeddedError<W::Error>> {
+    let _ = encoded_length::<Ck>(hrp, data)?;
+
+    let iter = data.iter().copied().bytes_to_fes();
+    let bytes = iter.with_checksum::<Ck>(&hrp).bytes();
+
+    write_chunked(bytes, |chunk| w.write_all(chunk)).map_err(EncodeEmbeddedError::Write)
+}
+
+/// Encodes `data` to an [`embedded_io::Write`] writer as an uppercase bech32 encoded string.
+///
+/// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
+/// `Ck` algorithm (`NoChecksum` to exclude checksum all together).
+///
+/// Errors from the writer are returned unchanged in [`EncodeEmbeddedError::Write`].
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_upper_to_embedded_writer<Ck: Checksum, W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    data: &[u8],
+) -> Result<(), EncodeEmbeddedError<W::Error>> {
+    let _ = encoded_length::<Ck>(hrp, data)?;
+
+    let iter = data.iter().copied().bytes_to_fes();
+    let bytes = iter.with_checksum::<Ck>(&hrp).bytes().map(|b| b.to_ascii_uppercase());
+
+    write_chunked(bytes, |chunk| w.write_all(chunk)).map_err(EncodeEmbeddedError::Write)
+}
+
+/// Writes `bytes` with `write_all`, buffering them on the stack so it is called once per chunk.
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+pub(crate) fn write_chunked<I, E>(
+    mut bytes: I,
+    mut write_all: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E>
+where
+    I: Iterator<Item = u8>,
+{
+    let mut buf = [0_u8; 16];
+    loop {
+        // Zip the buffer first so that no byte is pulled from `bytes` once the buffer is full.
+        let mut len = 0;
+        for (slot, b) in buf.iter_mut().zip(&mut bytes) {
+            *slot = b;
+            len += 1;
+        }
+        if len == 0 {
+            return Ok(());
+        }
+        write_all(&buf[..len])?;
+    }
+}
+
+/// Encodes `data` as a lowercase bech32 string, writing the ASCII bytes into `out`.
+///
+/// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
+/// `Ck` algorithm (`NoChecksum` to exclude checksum all together).
+///
+/// For targets with a byte buffer but no [`fmt::Write`] implementation, this does not use the
+/// formatting machinery at all. The encoded length is checked before anything is written, so if
+/// `out` is too small it is left untouched.
+///
+/// # Returns
+///
+/// The number of bytes written, the encoded string is `out[..len]`.
+///
+/// # Errors
+///
+/// Returns [`EncodeSliceError::Capacity`], holding the required length, if the encoded string
+/// does not fit in `out`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{Bech32m, Hrp};
+///
+/// let hrp = Hrp::parse("abc").expect("valid hrp");
+/// let mut buf = [0_u8; 64];
+/// let len = bech32grs::encode_to_slice::<Bech32m>(hrp, &[0xab; 20], &mut buf).expect("fits");
+/// assert_eq!(&buf[..len], b"abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu");
+///
+/// let err = bech32grs::encode_to_slice::<Bech32m>(hrp, &[0xab; 20], &mut [0_u8; 16]).unwrap_err();
+/// assert_eq!(err.required_len(), Some(42));
+/// ```
+pub fn encode_to_slice<Ck: Checksum>(
+    hrp: Hrp,
+    data: &[u8],
+    out: &mut [u8],
+) -> Result<usize, EncodeSliceError> {
+    let len = encoded_length::<Ck>(hrp, data)?;
+    let capacity = out.len();
+    let out = out.get_mut(..len).ok_or(CapacityError { required: len, capacity })?;
+
+    let iter = data.iter().copied().bytes_to_fes();
+    let bytes = iter.with_checksum::<Ck>(&hrp).bytes();
+
+    for (slot, b) in out.iter_mut().zip(bytes) {
+        *slot = b;
+    }
+
+    Ok(len)
+}
+
+/// Checks that encoding `hrp` and `data` creates a code that is less than the code length for `Ck`.
+///
+/// The length of the code is how long a coded message can be (including the checksum!) for the code
+/// to retain its error-correcting properties.
+///
+/// # Returns
+///
+/// `Ok(encoded_string_length)` if the encoded length is less than or equal to `Ck::CODE_LENGTH`
+/// otherwise a [`CodeLengthError`] containing the encoded length and the maximum allowed. The data
+/// is short enough if it is at most [`max_data_length`] bytes.
+pub fn encoded_length<Ck: Checksum>(hrp: Hrp, data: &[u8]) -> Result<usize, CodeLengthError> {
+    let iter = data.iter().copied().bytes_to_fes();
+    let len = hrp.len() + 1 + iter.len() + Ck::CHECKSUM_LENGTH; // +1 for separator
+    if len > Ck::CODE_LENGTH {
+        Err(CodeLengthError { encoded_length: len, code_length: Ck::CODE_LENGTH })
+    } else {
+        Ok(len)
+    }
+}
+
+/// Returns the maximum number of bytes of data that can be encoded with `hrp` and checksum `Ck`.
+///
+/// Encoding any more data fails with a [`CodeLengthError`], see [`encoded_length`]. Zero if `hrp`
+/// leaves no room for a single byte, in which case even empty data can not be encoded if the
+/// human-readable part, separator and checksum are already longer than [`Checksum::CODE_LENGTH`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{max_data_length, Bech32m, Hrp};
+///
+/// let hrp = Hrp::parse("grs").unwrap();
+/// let max = max_data_length::<Bech32m>(hrp);
+/// assert_eq!(max, 633);
+/// # #[cfg(feature = "alloc")] {
+/// assert!(bech32grs::encode::<Bech32m>(hrp, &[0; 633]).is_ok());
+/// assert!(bech32grs::encode::<Bech32m>(hrp, &[0; 634]).is_err());
+/// # }
+/// ```
+pub fn max_data_length<Ck: Checksum>(hrp: Hrp) -> usize {
+    // +1 for separator.
+    let fes = Ck::CODE_LENGTH.saturating_sub(hrp.len() + 1 + Ck::CHECKSUM_LENGTH);
+    // Each byte takes 8/5 field elements, rounded up. Split to avoid overflow for long codes.
+    fes / 8 * 5 + fes % 8 * 5 / 8
+}
+
+/// An error while decoding a bech32 string.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Parsing failed.
+    Parse(UncheckedHrpstringError),
+    /// No valid bech32 or bech32m checksum.
+    Checksum(ChecksumError),
+    /// The data has invalid padding.
+    Padding(PaddingError),
+}
+
+#[cfg(feature = "alloc")]
+impl DecodeError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use DecodeError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            Checksum(ref e) => e.error_code(),
+            Padding(ref e) => e.error_code(),
+        }
+    }
+}
 
 #[cfg(feature = "alloc")]
 impl fmt::Display for DecodeError {
@@ -460,31 +1125,141 @@ impl fmt::Display for DecodeError {
 
         match *self {
             Parse(ref e) => write_err!(f, "parsing failed"; e),
-            Checksum(ref e) => write_err!(f, "no valid bech32 or bech32m checksum"; e),
+            Checksum(ref e) =>
+                write_err!(f, "no valid {} or {} checksum", Bech32::NAME, Bech32m::NAME; e),
+            Padding(ref e) => write_err!(f, "invalid padding"; e),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for DecodeError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(feature = "alloc")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for DecodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use DecodeError::*;
 
         match *self {
             Parse(ref e) => Some(e),
             Checksum(ref e) => Some(e),
+            Padding(ref e) => Some(e),
         }
     }
 }
 
+/// Converts to an [`std::io::Error`] of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// The original error can be recovered with [`get_ref`](std::io::Error::get_ref) and a downcast.
+#[cfg(feature = "std")]
+impl From<DecodeError> for std::io::Error {
+    #[inline]
+    fn from(e: DecodeError) -> Self { std::io::Error::new(std::io::ErrorKind::InvalidData, e) }
+}
+
 #[cfg(feature = "alloc")]
 impl From<UncheckedHrpstringError> for DecodeError {
     #[inline]
     fn from(e: UncheckedHrpstringError) -> Self { Self::Parse(e) }
 }
 
+#[cfg(feature = "alloc")]
+impl From<PaddingError> for DecodeError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+/// A decoding error together with a truncated copy of the input that caused it.
+///
+/// Returned by the `_verbose` decoding functions, for example [`segwit::decode_verbose`], so that
+/// logged errors identify the failing input. Only the first 16 and the last 8 characters of long
+/// inputs are kept, this bounds the size of the error and avoids logging whole strings.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecodeErrorWithInput<E> {
+    /// The (possibly truncated) input string.
+    input: String,
+    /// The error returned while decoding the input.
+    inner: E,
+}
+
+#[cfg(feature = "alloc")]
+impl<E> DecodeErrorWithInput<E> {
+    /// Number of characters kept from the start of long inputs.
+    const PREFIX_CHARS: usize = 16;
+    /// Number of characters kept from the end of long inputs.
+    const SUFFIX_CHARS: usize = 8;
+
+    /// Wraps `inner`, keeping a truncated copy of `input`.
+    pub(crate) fn new(input: &str, inner: E) -> Self {
+        if input.chars().count() <= Self::PREFIX_CHARS + Self::SUFFIX_CHARS {
+            return Self { input: String::from(input), inner };
+        }
+        let prefix_end = input.char_indices().nth(Self::PREFIX_CHARS).map_or(0, |(i, _)| i);
+        let suffix_start =
+            input.char_indices().rev().nth(Self::SUFFIX_CHARS - 1).map_or(0, |(i, _)| i);
+
+        let mut truncated = String::with_capacity(prefix_end + 3 + (input.len() - suffix_start));
+        truncated.push_str(&input[..prefix_end]);
+        truncated.push_str("...");
+        truncated.push_str(&input[suffix_start..]);
+        Self { input: truncated, inner }
+    }
+
+    /// Returns the input string, truncated if it was long.
+    #[inline]
+    pub fn input(&self) -> &str { &self.input }
+
+    /// Returns the error returned while decoding the input.
+    #[inline]
+    pub fn inner(&self) -> &E { &self.inner }
+
+    /// Consumes the error, returning the error returned while decoding the input.
+    #[inline]
+    pub fn into_inner(self) -> E { self.inner }
+}
+
+#[cfg(feature = "alloc")]
+impl DecodeErrorWithInput<CheckedHrpstringError> {
+    /// Returns the machine-readable code of the inner error, see [`Error::code`].
+    #[inline]
+    pub fn code(&self) -> &'static str { self.inner.code() }
+
+    /// Returns the code of the inner error.
+    pub(crate) fn error_code(&self) -> Code { self.inner.error_code() }
+}
+
+#[cfg(feature = "alloc")]
+impl DecodeErrorWithInput<segwit::DecodeError> {
+    /// Returns the machine-readable code of the inner error, see [`Error::code`].
+    #[inline]
+    pub fn code(&self) -> &'static str { self.inner.code() }
+
+    /// Returns the code of the inner error.
+    pub(crate) fn error_code(&self) -> Code { self.inner.error_code() }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: fmt::Display> fmt::Display for DecodeErrorWithInput<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to decode \"{}\"", self.input; self.inner)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for DecodeErrorWithInput<E> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "failed to decode \"{=str}\": {}", self.input.as_str(), self.inner)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: StdError + 'static> StdError for DecodeErrorWithInput<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { Some(&self.inner) }
+}
+
 /// An error while encoding a bech32 string.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum EncodeError {
     /// Encoding HRP and data into a bech32 string exceeds maximum allowed.
@@ -493,6 +1268,22 @@ pub enum EncodeError {
     Fmt(fmt::Error),
 }
 
+impl EncodeError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeError::*;
+
+        match *self {
+            TooLong(ref e) => e.error_code(),
+            Fmt(_) => Code::Fmt,
+        }
+    }
+}
+
 impl fmt::Display for EncodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use EncodeError::*;
@@ -504,9 +1295,21 @@ impl fmt::Display for EncodeError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for EncodeError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(feature = "defmt")]
+impl defmt::Format for EncodeError {
+    fn format(&self, f: defmt::Formatter) {
+        use EncodeError::*;
+
+        match *self {
+            TooLong(ref e) => defmt::write!(f, "encode error: {}", e),
+            Fmt(_) => defmt::write!(f, "encode to formatter failed"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for EncodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use EncodeError::*;
 
         match *self {
@@ -516,6 +1319,24 @@ impl std::error::Error for EncodeError {
     }
 }
 
+/// Converts to an [`std::io::Error`], the original error can be recovered with
+/// [`get_ref`](std::io::Error::get_ref) and a downcast.
+///
+/// Input that is too long to encode is [`InvalidInput`](std::io::ErrorKind::InvalidInput), a
+/// failed write is [`Other`](std::io::ErrorKind::Other).
+#[cfg(feature = "std")]
+impl From<EncodeError> for std::io::Error {
+    fn from(e: EncodeError) -> Self {
+        use EncodeError::*;
+
+        let kind = match e {
+            TooLong(_) => std::io::ErrorKind::InvalidInput,
+            Fmt(_) => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
 impl From<CodeLengthError> for EncodeError {
     #[inline]
     fn from(e: CodeLengthError) -> Self { Self::TooLong(e) }
@@ -537,6 +1358,23 @@ pub enum EncodeIoError {
     Write(std::io::Error),
 }
 
+#[cfg(feature = "std")]
+impl EncodeIoError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeIoError::*;
+
+        match *self {
+            TooLong(ref e) => e.error_code(),
+            Write(_) => Code::Write,
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 impl fmt::Display for EncodeIoError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -550,8 +1388,8 @@ impl fmt::Display for EncodeIoError {
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for EncodeIoError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl StdError for EncodeIoError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use EncodeIoError::*;
 
         match *self {
@@ -573,6 +1411,167 @@ impl From<std::io::Error> for EncodeIoError {
     fn from(e: std::io::Error) -> Self { Self::Write(e) }
 }
 
+/// An error while encoding a bech32 string to an [`embedded_io::Write`] writer.
+///
+/// Generic over the writer's error type so that it is not lost.
+#[cfg(feature = "embedded-io")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EncodeEmbeddedError<E> {
+    /// Encoding HRP and data into a bech32 string exceeds maximum allowed.
+    TooLong(CodeLengthError),
+    /// Encode to writer failed.
+    Write(E),
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> EncodeEmbeddedError<E> {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            TooLong(ref e) => e.error_code(),
+            Write(_) => Code::Write,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E: fmt::Debug> fmt::Display for EncodeEmbeddedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            TooLong(ref e) => write_err!(f, "encode error"; e),
+            Write(ref e) => write!(f, "encode to writer failed: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: StdError + 'static> StdError for EncodeEmbeddedError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            TooLong(ref e) => Some(e),
+            Write(ref e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> From<CodeLengthError> for EncodeEmbeddedError<E> {
+    #[inline]
+    fn from(e: CodeLengthError) -> Self { Self::TooLong(e) }
+}
+
+/// An error while encoding a bech32 string into a byte slice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EncodeSliceError {
+    /// Encoding HRP and data into a bech32 string exceeds maximum allowed.
+    TooLong(CodeLengthError),
+    /// The slice is too small to hold the encoded string.
+    Capacity(CapacityError),
+}
+
+impl EncodeSliceError {
+    /// Returns the length of slice required to hold the encoded string, if it was too small.
+    #[inline]
+    pub fn required_len(&self) -> Option<usize> {
+        match *self {
+            EncodeSliceError::Capacity(ref e) => Some(e.required),
+            EncodeSliceError::TooLong(_) => None,
+        }
+    }
+
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeSliceError::*;
+
+        match *self {
+            TooLong(ref e) => e.error_code(),
+            Capacity(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for EncodeSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeSliceError::*;
+
+        match *self {
+            TooLong(ref e) => write_err!(f, "encode error"; e),
+            Capacity(ref e) => write_err!(f, "encode to slice failed"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for EncodeSliceError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use EncodeSliceError::*;
+
+        match *self {
+            TooLong(ref e) => Some(e),
+            Capacity(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<CodeLengthError> for EncodeSliceError {
+    #[inline]
+    fn from(e: CodeLengthError) -> Self { Self::TooLong(e) }
+}
+
+impl From<CapacityError> for EncodeSliceError {
+    #[inline]
+    fn from(e: CapacityError) -> Self { Self::Capacity(e) }
+}
+
+/// Data is longer than the capacity of a fixed size buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct CapacityError {
+    /// The number of bytes required to hold the data.
+    pub required: usize,
+    /// The capacity of the buffer.
+    pub capacity: usize,
+}
+
+impl CapacityError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { Code::Capacity }
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes required, buffer capacity is {} bytes", self.required, self.capacity)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for CapacityError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { None }
+}
+
 #[cfg(test)]
 #[cfg(feature = "alloc")]
 mod tests {
@@ -636,6 +1635,376 @@ mod tests {
         assert_eq!(got, want);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn encode_to_writer_writes_in_chunks() {
+        struct CountingWriter {
+            buf: Vec<u8>,
+            calls: usize,
+        }
+
+        impl std::io::Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.calls += 1;
+                self.buf.write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+        }
+
+        let hrp = Hrp::parse_unchecked("test");
+        let mut w = CountingWriter { buf: Vec::new(), calls: 0 };
+        encode_to_writer::<Bech32, _>(&mut w, hrp, &DATA).expect("failed to encode");
+
+        assert_eq!(w.buf, b"test1lu08d6qejxtdg4y5r3zarvary0c5xw7kw79nnx");
+        // 43 characters are written in chunks of 16 bytes.
+        assert_eq!(w.calls, 3);
+    }
+
+    #[test]
+    fn encode_to_fmt_matches_encode() {
+        fn check<Ck: Checksum>(hrp: Hrp, data: &[u8]) {
+            let lower = encode_lower::<Ck>(hrp, data).expect("failed to encode");
+            let upper = encode_upper::<Ck>(hrp, data).expect("failed to encode");
+
+            let mut buf = String::new();
+            encode_to_fmt::<Ck, _>(&mut buf, hrp, data).expect("failed to encode");
+            assert_eq!(buf, lower);
+
+            let mut buf = String::new();
+            encode_lower_to_fmt::<Ck, _>(&mut buf, hrp, data).expect("failed to encode");
+            assert_eq!(buf, lower);
+
+            let mut buf = String::new();
+            encode_upper_to_fmt::<Ck, _>(&mut buf, hrp, data).expect("failed to encode");
+            assert_eq!(buf, upper);
+
+            let mut buf = String::new();
+            encode_to_fmt_unchecked::<Ck, _>(&mut buf, hrp, data).expect("writing to a string");
+            assert_eq!(buf, lower);
+
+            let mut buf = String::new();
+            encode_lower_to_fmt_unchecked::<Ck, _>(&mut buf, hrp, data)
+                .expect("writing to a string");
+            assert_eq!(buf, lower);
+
+            let mut buf = String::new();
+            encode_upper_to_fmt_unchecked::<Ck, _>(&mut buf, hrp, data)
+                .expect("writing to a string");
+            assert_eq!(buf, upper);
+        }
+
+        let hrp = Hrp::parse_unchecked("test");
+        for data in [&DATA[..], &[], &[0x00], &[0xff; 40]].iter() {
+            check::<NoChecksum>(hrp, data);
+            check::<Bech32>(hrp, data);
+            check::<Bech32m>(hrp, data);
+        }
+
+        let mut buf = String::new();
+        encode_to_fmt::<Bech32m, _>(&mut buf, hrp, &DATA).expect("failed to encode");
+        assert_eq!(buf, "test1lu08d6qejxtdg4y5r3zarvary0c5xw7kmz4lky");
+        let mut buf = String::new();
+        encode_to_fmt::<NoChecksum, _>(&mut buf, hrp, &DATA).expect("failed to encode");
+        assert_eq!(buf, "test1lu08d6qejxtdg4y5r3zarvary0c5xw7k");
+    }
+
+    #[test]
+    fn encode_no_checksum_wrappers() {
+        let hrp = Hrp::parse_unchecked("test");
+        assert_eq!(
+            encode_no_checksum(hrp, &DATA).unwrap(),
+            "test1lu08d6qejxtdg4y5r3zarvary0c5xw7k"
+        );
+
+        let mut buf = String::new();
+        encode_no_checksum_to_fmt(&mut buf, hrp, &DATA).expect("failed to encode");
+        assert_eq!(buf, "test1lu08d6qejxtdg4y5r3zarvary0c5xw7k");
+        let mut buf = String::new();
+        encode_no_checksum_upper_to_fmt(&mut buf, hrp, &DATA).expect("failed to encode");
+        assert_eq!(buf, "TEST1LU08D6QEJXTDG4Y5R3ZARVARY0C5XW7K");
+    }
+
+    #[test]
+    fn no_checksum_roundtrips() {
+        let hrp = Hrp::parse_unchecked("abc");
+        for len in 1..=64_u8 {
+            let data = (0..len).map(|i| i.wrapping_mul(37).wrapping_add(11)).collect::<Vec<u8>>();
+            let s = encode_no_checksum(hrp, &data).expect("valid data");
+            assert_eq!(s.len(), encoded_length::<NoChecksum>(hrp, &data).unwrap());
+            assert_eq!(decode_no_checksum(&s).expect("valid string"), (hrp, data.clone()));
+            assert_eq!(decode_no_checksum(&s.to_uppercase()).expect("valid string"), (hrp, data));
+        }
+
+        for len in 0..=16 {
+            let fes = Fe32::iter_alpha().skip(5).take(len).collect::<Vec<Fe32>>();
+            let s =
+                fes.iter().copied().with_checksum::<NoChecksum>(&hrp).chars().collect::<String>();
+            let (got_hrp, data) = decode_no_checksum(&s).expect("valid string");
+            assert_eq!(got_hrp, hrp);
+            assert_eq!(data, fes.iter().copied().fes_to_bytes().collect::<Vec<u8>>());
+        }
+    }
+
+    #[test]
+    fn no_checksum_does_not_emit_a_checksum() {
+        let hrp = Hrp::parse_unchecked("abc");
+        for len in 0..=40_u8 {
+            let data = (0..len).map(|i| i.wrapping_mul(101).wrapping_add(7)).collect::<Vec<u8>>();
+            let s = encode_no_checksum(hrp, &data).expect("valid data");
+
+            // The bech32 encoding is the encoding without a checksum plus the 6 checksum characters.
+            let bech32 = encode::<Bech32>(hrp, &data).expect("valid data");
+            assert_eq!(bech32.len(), s.len() + 6);
+            assert!(bech32.starts_with(&s));
+            assert!(CheckedHrpstring::new::<Bech32>(&bech32).is_ok());
+
+            // So appending any other 6 characters is not valid bech32, it differs from the checksum
+            // in at least one character, and any single substitution is always detected.
+            let checksum = &bech32.as_bytes()[s.len()..];
+            for pos in 0..6 {
+                for fe in Fe32::iter_alpha() {
+                    if fe.to_char() as u8 == checksum[pos] {
+                        continue;
+                    }
+                    let mut appended = s.clone();
+                    for (i, &c) in checksum.iter().enumerate() {
+                        appended.push(if i == pos { fe.to_char() } else { char::from(c) });
+                    }
+                    assert!(CheckedHrpstring::new::<Bech32>(&appended).is_err(), "{}", appended);
+                }
+            }
+        }
+    }
+
+    /// A writer that fails any write which would take it over `limit` bytes.
+    struct FailingWriter {
+        buf: String,
+        limit: usize,
+        failed_calls: usize,
+    }
+
+    impl FailingWriter {
+        fn new(limit: usize) -> Self {
+            FailingWriter { buf: String::new(), limit, failed_calls: 0 }
+        }
+    }
+
+    impl fmt::Write for FailingWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if self.buf.len() + s.len() > self.limit {
+                self.failed_calls += 1;
+                return Err(fmt::Error);
+            }
+            self.buf.push_str(s);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn most_informative_ordering() {
+        use crate::primitives::decode::CharError;
+        use crate::primitives::hrp::Error as HrpError;
+
+        let code_length = CodeLengthError { encoded_length: 91, code_length: 90 };
+        // From most to least informative, errors in the same inner slice are equally informative.
+        let categories: [&[CheckedHrpstringError]; 3] = [
+            &[
+                CheckedHrpstringError::Parse(CharError::InvalidChar('b').into()),
+                CheckedHrpstringError::Parse(CharError::MixedCase.into()),
+                CheckedHrpstringError::Parse(CharError::MissingSeparator.into()),
+                CheckedHrpstringError::Parse(HrpError::Empty.into()),
+            ],
+            &[CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue)],
+            &[
+                CheckedHrpstringError::Parse(UncheckedHrpstringError::TooLong(code_length.clone())),
+                CheckedHrpstringError::Checksum(ChecksumError::CodeLength(code_length)),
+                CheckedHrpstringError::Checksum(ChecksumError::InvalidLength),
+            ],
+        ];
+
+        for (i, more) in categories.iter().enumerate() {
+            for a in more.iter() {
+                for b in more.iter() {
+                    assert_eq!(most_informative(a, b), Ordering::Equal, "{:?} {:?}", a, b);
+                }
+                for less in categories[i + 1..].iter() {
+                    for b in less.iter() {
+                        assert_eq!(most_informative(a, b), Ordering::Greater, "{:?} {:?}", a, b);
+                        assert_eq!(most_informative(b, a), Ordering::Less, "{:?} {:?}", b, a);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decode_any_either_checksum() {
+        const BECH32: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2atsghld7";
+        const BECH32M: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+
+        for s in [BECH32, BECH32M].iter() {
+            let checked = decode_any(s).expect("valid checksum");
+            assert_eq!(checked.byte_iter().collect::<Vec<u8>>(), [0xab; 20]);
+        }
+
+        assert_eq!(
+            decode_any("abc14w46h2at4w46h2at4w46h2at4w46h2at958ngv").unwrap_err(),
+            CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue)
+        );
+        assert_eq!(
+            decode_any("abc14w46h2at4w46h2at4w46h2at4w46h2at958ngb").unwrap_err(),
+            CheckedHrpstringError::Parse(
+                crate::primitives::decode::CharError::InvalidChar('b').into()
+            )
+        );
+    }
+
+    #[test]
+    fn encode_to_slice_matches_encode() {
+        let hrp = Hrp::parse_unchecked("test");
+        let want = encode::<Bech32m>(hrp, &DATA).expect("failed to encode");
+
+        // Exact size.
+        let mut exact = vec![0_u8; want.len()];
+        let len = encode_to_slice::<Bech32m>(hrp, &DATA, &mut exact).expect("fits");
+        assert_eq!(len, want.len());
+        assert_eq!(exact, want.as_bytes());
+
+        // Oversize, the rest of the buffer is not touched.
+        let mut over = [0xff_u8; 128];
+        let len = encode_to_slice::<Bech32m>(hrp, &DATA, &mut over).expect("fits");
+        assert_eq!(&over[..len], want.as_bytes());
+        assert!(over[len..].iter().all(|&b| b == 0xff));
+
+        // Undersize, nothing is written.
+        let mut under = vec![0xff_u8; want.len() - 1];
+        let err = encode_to_slice::<Bech32m>(hrp, &DATA, &mut under).unwrap_err();
+        assert_eq!(
+            err,
+            EncodeSliceError::Capacity(CapacityError {
+                required: want.len(),
+                capacity: want.len() - 1
+            })
+        );
+        assert_eq!(err.required_len(), Some(want.len()));
+        assert!(under.iter().all(|&b| b == 0xff));
+
+        // Too long for the checksum, whatever the size of the buffer.
+        let mut buf = [0_u8; 256];
+        let err = encode_to_slice::<Bech32m>(hrp, &[0_u8; 1000], &mut buf).unwrap_err();
+        assert!(matches!(err, EncodeSliceError::TooLong(_)));
+        assert_eq!(err.required_len(), None);
+    }
+
+    #[test]
+    fn encode_to_fmt_unchecked_stops_on_error() {
+        let hrp = Hrp::parse_unchecked("test");
+        let lower = encode_lower::<Bech32m>(hrp, &DATA).expect("failed to encode");
+        let upper = encode_upper::<Bech32m>(hrp, &DATA).expect("failed to encode");
+
+        // Fail within the hrp, at the separator, within the data and within the checksum.
+        for &limit in [0, 2, 4, 5, 6, lower.len() - 6, lower.len() - 1].iter() {
+            let mut w = FailingWriter::new(limit);
+            assert!(encode_lower_to_fmt_unchecked::<Bech32m, _>(&mut w, hrp, &DATA).is_err());
+            assert_eq!(w.buf, lower[..limit]);
+            assert_eq!(w.failed_calls, 1);
+
+            let mut w = FailingWriter::new(limit);
+            assert!(encode_upper_to_fmt_unchecked::<Bech32m, _>(&mut w, hrp, &DATA).is_err());
+            assert_eq!(w.buf, upper[..limit]);
+            assert_eq!(w.failed_calls, 1);
+        }
+
+        let mut w = FailingWriter::new(lower.len());
+        assert!(encode_to_fmt_unchecked::<Bech32m, _>(&mut w, hrp, &DATA).is_ok());
+        assert_eq!(w.buf, lower);
+        assert_eq!(w.failed_calls, 0);
+    }
+
+    #[test]
+    fn decode_segwit_v0_address() {
+        // The witness version and 20 byte program make 165 bits, the last 5 are program bits.
+        let s = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5";
+        let (hrp, data) = decode(s).expect("valid P2WPKH address");
+        assert_eq!(hrp, crate::hrp::GRS);
+        assert_eq!(data.len(), 20);
+        assert_eq!(data, decode(&s.to_uppercase()).unwrap().1);
+        assert_eq!(decode_strict(s).unwrap_err(), DecodeError::Padding(PaddingError::TooMuch));
+
+        let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(decode(s).expect("valid P2WPKH address").1.len(), 20);
+    }
+
+    #[test]
+    fn decode_strict_rejects_invalid_padding() {
+        // BIP-350 invalid vector: non-zero padding in 8-to-5 conversion.
+        let s = "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j";
+        assert_eq!(decode_strict(s).unwrap_err(), DecodeError::Padding(PaddingError::NonZero));
+        assert!(decode(s).is_ok());
+
+        // BIP-350 invalid vector: zero padding of more than 4 bits. The generic decoder also
+        // converts the witness version, which leaves only 2 bits of padding, so drop the witness
+        // version and recompute the checksum to test the same program bytes.
+        let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf";
+        assert!(decode_strict(s).is_ok());
+        let checked = CheckedHrpstring::new::<Bech32m>(s).unwrap();
+        let hrp = checked.hrp();
+        let without_version = checked
+            .fe32_iter::<core::iter::Empty<u8>>()
+            .skip(1)
+            .with_checksum::<Bech32m>(&hrp)
+            .chars()
+            .collect::<String>();
+        assert_eq!(
+            decode_strict(&without_version).unwrap_err(),
+            DecodeError::Padding(PaddingError::TooMuch)
+        );
+
+        let valid = [encode::<Bech32>(hrp, &DATA).unwrap(), encode::<Bech32m>(hrp, &[]).unwrap()];
+        for s in valid.iter() {
+            assert_eq!(decode_strict(s).unwrap(), decode(s).unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn decode_zeroizing_matches_decode() {
+        let hrp = Hrp::parse_unchecked("test");
+        for s in [
+            encode::<Bech32>(hrp, &DATA).unwrap(),
+            encode::<Bech32m>(hrp, &DATA).unwrap(),
+            encode::<Bech32m>(hrp, &[]).unwrap(),
+        ]
+        .iter()
+        {
+            let (got_hrp, data) = decode_zeroizing(s).unwrap();
+            assert_eq!(data.capacity(), data.len());
+            assert_eq!((got_hrp, data.to_vec()), decode(s).unwrap());
+        }
+        assert_eq!(
+            decode_zeroizing("test1qqqqqq").unwrap_err(),
+            decode("test1qqqqqq").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn encode_to_fmt_unchecked_ignores_code_length() {
+        let hrp = Hrp::parse_unchecked("test");
+        let data = [0xab; 1000];
+
+        let mut buf = String::new();
+        assert!(matches!(
+            encode_lower_to_fmt::<Bech32, _>(&mut buf, hrp, &data),
+            Err(EncodeError::TooLong(_))
+        ));
+        assert!(buf.is_empty());
+
+        encode_lower_to_fmt_unchecked::<Bech32, _>(&mut buf, hrp, &data)
+            .expect("writing to a string");
+        assert_eq!(buf.len(), 4 + 1 + (1000 * 8 + 4) / 5 + 6);
+    }
+
     #[test]
     fn decode_bech32m() {
         let s = "test1lu08d6qejxtdg4y5r3zarvary0c5xw7kmz4lky";
@@ -675,6 +2044,119 @@ mod tests {
         assert_eq!(got, want);
     }
 
+    #[test]
+    fn max_data_length_boundary() {
+        let hrps = [Hrp::parse("a").unwrap(), Hrp::parse(&"a".repeat(83)).unwrap()];
+        for &hrp in &hrps {
+            let max = max_data_length::<Bech32m>(hrp);
+            let data = vec![0xab; max + 1];
+
+            assert!(encoded_length::<Bech32m>(hrp, &data[..max - 1]).is_ok());
+            let s = encode::<Bech32m>(hrp, &data[..max]).expect("max data fits");
+            assert!(s.len() <= Bech32m::CODE_LENGTH);
+            assert_eq!(decode(&s).expect("valid string"), (hrp, data[..max].to_vec()));
+
+            let err = encoded_length::<Bech32m>(hrp, &data).unwrap_err();
+            assert_eq!(err.code_length, Bech32m::CODE_LENGTH);
+            assert!(err.encoded_length > Bech32m::CODE_LENGTH);
+            assert!(matches!(encode::<Bech32m>(hrp, &data), Err(EncodeError::TooLong(_))));
+        }
+        // 1023 - 83 - 1 - 6 = 933 field elements, 933 * 5 / 8 bytes.
+        assert_eq!(max_data_length::<Bech32m>(hrps[1]), 583);
+        assert_eq!(max_data_length::<Bech32>(hrps[1]), 583);
+    }
+
+    #[test]
+    fn max_data_length_no_room() {
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        enum Short {}
+        impl Checksum for Short {
+            const NAME: &'static str = "short";
+            type MidstateRepr = u32;
+            const CODE_LENGTH: usize = 89;
+            const CHECKSUM_LENGTH: usize = 6;
+            const GENERATOR_SH: [u32; 5] = Bech32m::GENERATOR_SH;
+            const TARGET_RESIDUE: u32 = Bech32m::TARGET_RESIDUE;
+        }
+
+        let hrp = Hrp::parse(&"a".repeat(83)).unwrap();
+        assert_eq!(max_data_length::<Short>(hrp), 0);
+        assert!(encoded_length::<Short>(hrp, &[]).is_err());
+
+        // 89 - 1 - 1 - 6 = 81 field elements for a one character hrp, 50.625 bytes.
+        assert_eq!(max_data_length::<Short>(Hrp::parse("a").unwrap()), 50);
+        assert!(encoded_length::<Short>(Hrp::parse("a").unwrap(), &[0; 50]).is_ok());
+        assert!(encoded_length::<Short>(Hrp::parse("a").unwrap(), &[0; 51]).is_err());
+        // Does not overflow for a code of unlimited length.
+        assert!(max_data_length::<NoChecksum>(hrp) > usize::MAX / 2);
+    }
+
+    #[test]
+    fn decode_error_with_input_keeps_short_input() {
+        let input = "a1qqqqqqqqqqqqqqqqqqqqqq"; // 24 chars, the longest input kept whole.
+        let err =
+            DecodeErrorWithInput::new(input, DecodeError::Checksum(ChecksumError::InvalidResidue));
+        assert_eq!(err.input(), input);
+        assert_eq!(err.inner(), &DecodeError::Checksum(ChecksumError::InvalidResidue));
+        #[cfg(feature = "std")]
+        assert_eq!(err.to_string(), "failed to decode \"a1qqqqqqqqqqqqqqqqqqqqqq\"");
+    }
+
+    #[test]
+    fn decode_error_with_input_truncates_long_input() {
+        let input = "a1qqqqqqqqqqqqqqqqqqqqqqp"; // 25 chars.
+        let err = DecodeErrorWithInput::new(input, ChecksumError::InvalidResidue);
+        assert_eq!(err.input(), "a1qqqqqqqqqqqqqq...qqqqqqqp");
+
+        let input = format!("grs1{}xyz", "q".repeat(1000));
+        let err = DecodeErrorWithInput::new(&input, ChecksumError::InvalidResidue);
+        assert_eq!(err.input(), "grs1qqqqqqqqqqqq...qqqqqxyz");
+        assert_eq!(err.into_inner(), ChecksumError::InvalidResidue);
+
+        // Truncation is on char boundaries.
+        let input = format!("{}1{}", "é".repeat(20), "ü".repeat(20));
+        let err = DecodeErrorWithInput::new(&input, ChecksumError::InvalidResidue);
+        assert_eq!(err.input(), format!("{}...{}", "é".repeat(16), "ü".repeat(8)));
+    }
+
+    #[test]
+    fn decode_verbose_includes_input() {
+        let input = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj6";
+        let err = segwit::decode_verbose(input).unwrap_err();
+        assert_eq!(err.input(), "grs1qw508d6qejxt...7k3k4sj6");
+        assert_eq!(err.inner(), &segwit::decode(input).unwrap_err());
+
+        let err = CheckedHrpstring::new_verbose::<Bech32>(input).unwrap_err();
+        assert_eq!(err.input(), "grs1qw508d6qejxt...7k3k4sj6");
+        assert_eq!(err.into_inner(), CheckedHrpstring::new::<Bech32>(input).unwrap_err());
+
+        #[cfg(feature = "std")]
+        {
+            use std::error::Error as _;
+
+            let err = segwit::decode_verbose(input).unwrap_err();
+            assert_eq!(err.to_string(), "failed to decode \"grs1qw508d6qejxt...7k3k4sj6\"");
+            assert_eq!(err.source().unwrap().to_string(), err.inner().to_string());
+        }
+    }
+
+    #[test]
+    fn encode_allocates_exact_capacity() {
+        let hrp = Hrp::parse_unchecked("test");
+        for len in 0..=100 {
+            let data = vec![0xab; len];
+            for s in [
+                encode::<Bech32>(hrp, &data).expect("valid data"),
+                encode_upper::<Bech32m>(hrp, &data).expect("valid data"),
+                encode::<NoChecksum>(hrp, &data).expect("valid data"),
+            ]
+            .iter()
+            {
+                assert_eq!(s.capacity(), s.len(), "data length {}", len);
+            }
+        }
+    }
+
     #[test]
     fn can_encode_maximum_length_string() {
         let data = [0_u8; 632];
@@ -696,10 +2178,334 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encode_does_not_panic() {
+        let hrps = [
+            Hrp::parse_unchecked("abcd"),
+            Hrp::parse_unchecked(
+                "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio",
+            ),
+        ];
+        let data = [0xab_u8; 640];
+
+        for hrp in &hrps {
+            for len in 0..=data.len() {
+                let data = &data[..len];
+                match encode::<Bech32m>(*hrp, data) {
+                    Ok(s) => assert_eq!(Ok(s.len()), encoded_length::<Bech32m>(*hrp, data)),
+                    Err(e) => assert!(matches!(e, EncodeError::TooLong(_))),
+                }
+            }
+        }
+    }
+
     #[test]
     fn can_decode_segwit_too_long_string() {
         // A  91 character long string, greater than the segwit enforced maximum of 90.
         let s = "abcd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqrw9z3s";
         assert!(decode(s).is_ok());
     }
+
+    #[test]
+    fn decode_long_enforces_max_len() {
+        let data = [0_u8; 632];
+        let hrp = Hrp::parse_unchecked("abcd");
+        let s = encode::<Bech32m>(hrp, &data).expect("valid data");
+
+        let checked = decode_long::<Bech32m>(&s, 1023).expect("valid string");
+        assert_eq!(checked.byte_iter().collect::<Vec<u8>>(), &data[..]);
+        assert!(decode_long::<Bech32m>(&s, usize::MAX).is_ok());
+
+        let e = CodeLengthError { encoded_length: 1023, code_length: 1022 };
+        assert_eq!(
+            decode_long::<Bech32m>(&s, 1022).unwrap_err(),
+            CheckedHrpstringError::Checksum(ChecksumError::CodeLength(e))
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "defmt")]
+mod defmt_tests {
+    use core::convert::TryFrom;
+
+    use super::*;
+    use crate::primitives::decode::{
+        CharError, CheckedHrpstringError, ChecksumError, HrpMixedCaseError, HrpSplitError,
+        PaddingError, SegwitCodeLengthError, SegwitHrpstringError, UncheckedHrpstringError,
+    };
+    use crate::primitives::gf32::{FeReaderError, FeWriterError, FromCharError, TryFromError};
+    use crate::primitives::hrp::Error as HrpError;
+    use crate::primitives::segwit::{InvalidWitnessVersionError, WitnessLengthError};
+
+    // Rendering requires a global defmt logger, which firmware provides but tests do not. Instead
+    // construct every variant and pass it where `defmt::Format` is required.
+    fn format<T: defmt::Format>(_: T) {}
+
+    #[test]
+    fn errors_implement_format() {
+        let code_length = CodeLengthError { encoded_length: 91, code_length: 90 };
+        let checksum = ChecksumError::CodeLength(code_length.clone());
+        let unchecked = UncheckedHrpstringError::Char(CharError::MissingSeparator);
+
+        format(HrpError::TooLong(84));
+        format(HrpError::Empty);
+        format(HrpError::NonAsciiChar('\u{e9}'));
+        format(HrpError::InvalidAsciiByte(0x7f));
+        format(HrpError::MixedCase);
+        format(HrpError::NotDisplaySafe(b'!'));
+        // Held by the errors of the formats built on bech32, e.g. `LnurlError::InvalidHrp`.
+        format(crate::hrp::GRS);
+
+        format(FromCharError::NotAscii('\u{e9}'));
+        format(FromCharError::Invalid('b'));
+        format(TryFromError::InvalidByte(32));
+        format(TryFromError::NotAByte(u8::try_from(256_u16).unwrap_err()));
+        format(FeReaderError::OutOfData { pos: 0, requested: 8, remaining: 5 });
+        format(FeReaderError::Misaligned { pos: 3 });
+        format(FeReaderError::TooWide { bits: 65 });
+        format(FeWriterError::TooWide { value: 4, bits: 2 });
+        format(FeWriterError::Capacity { required: 2, capacity: 1 });
+        format(FeWriterError::Unpadded { pending_bits: 3 });
+
+        format(CharError::Empty);
+        format(CharError::MissingSeparator);
+        format(CharError::NothingAfterSeparator);
+        format(CharError::InvalidChar('b'));
+        format(CharError::MixedCase);
+        format(CharError::Uppercase);
+        format(CharError::NotUppercase { pos: 0 });
+        format(HrpMixedCaseError::new("Grs").unwrap());
+        format(HrpSplitError::new("g s1q", HrpError::InvalidAsciiByte(b' ')));
+
+        format(checksum.clone());
+        format(ChecksumError::InvalidResidue);
+        format(ChecksumError::InvalidLength);
+        format(code_length.clone());
+        format(SegwitCodeLengthError(91));
+        format(PaddingError::TooMuch);
+        format(PaddingError::NonZero);
+
+        format(UncheckedHrpstringError::Hrp(HrpError::Empty));
+        format(UncheckedHrpstringError::TooLong(code_length.clone()));
+        format(unchecked.clone());
+        format(CheckedHrpstringError::Parse(unchecked.clone()));
+        format(CheckedHrpstringError::Checksum(checksum.clone()));
+
+        format(SegwitHrpstringError::Unchecked(unchecked));
+        format(SegwitHrpstringError::NoData);
+        format(SegwitHrpstringError::TooLong { len: 91, max: 90 });
+        format(SegwitHrpstringError::InvalidMaxLength(1024));
+        format(SegwitHrpstringError::InvalidWitnessVersion(Fe32::P));
+        format(SegwitHrpstringError::Padding(PaddingError::TooMuch));
+        format(SegwitHrpstringError::WitnessLength(WitnessLengthError::TooShort {
+            len: 1,
+            min: 2,
+        }));
+        format(SegwitHrpstringError::Checksum(checksum));
+        format(SegwitHrpstringError::WrongHrp {
+            expected: segwit::KnownHrp::Mainnet,
+            got: Some(segwit::KnownHrp::Testnets),
+        });
+        format(SegwitHrpstringError::WrongHrp { expected: segwit::KnownHrp::Mainnet, got: None });
+        format(SegwitHrpstringError::ProbablyTruncated { expected_lengths: &[43, 63], got: 42 });
+
+        format(InvalidWitnessVersionError(Fe32::P));
+        format(segwit::WitnessProgramError::WitnessVersion(InvalidWitnessVersionError(Fe32::P)));
+        format(segwit::EncodeSliceError::TooLong(SegwitCodeLengthError(91)));
+        #[cfg(feature = "embedded-io")]
+        format(segwit::EncodeEmbeddedError::Write(FromCharError::Invalid('b')));
+        format(segwit::CompactAddressError::Decode(SegwitHrpstringError::NoData));
+        format(segwit::CompactAddressError::UnknownHrp(crate::hrp::GRS));
+        format(segwit::ScriptPubkeyError::TooShort { len: 1 });
+        format(segwit::ScriptPubkeyError::InvalidOpcode(0x76));
+        format(segwit::ScriptPubkeyError::PushLength { push: 0x14, len: 19 });
+        format(segwit::ScriptPubkeyError::WitnessLength(WitnessLengthError::TooShort {
+            len: 1,
+            min: 2,
+        }));
+        format(WitnessLengthError::TooShort { len: 1, min: 2 });
+        format(WitnessLengthError::TooLong { len: 41, max: 40 });
+        format(WitnessLengthError::InvalidSegwitV0 { len: 21 });
+
+        format(EncodeError::TooLong(code_length.clone()));
+        format(EncodeError::Fmt(fmt::Error));
+        format(EncodeSliceError::TooLong(code_length));
+        format(EncodeSliceError::Capacity(CapacityError { required: 42, capacity: 16 }));
+
+        format(SplitError::MissingSeparator);
+        format(SplitError::TooShort { len: 5, checksum_length: 6 });
+        format(DataBuilderError::Misaligned { pending_bits: 2 });
+        format(DataBuilderError::TooWide { value: 4, bits: 2 });
+        format(crate::primitives::encode::TryWriteError::Source(FromCharError::Invalid('b')));
+        format(crate::primitives::encode::TryWriteError::<FromCharError>::Fmt(fmt::Error));
+
+        format(crate::Error::Char(CharError::MissingSeparator));
+        format(crate::Error::Checksum(ChecksumError::InvalidResidue));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn alloc_errors_implement_format() {
+        let unchecked = UncheckedHrpstringError::Char(CharError::MixedCase);
+
+        format(DecodeError::Parse(unchecked.clone()));
+        format(DecodeError::Checksum(ChecksumError::InvalidResidue));
+        format(DecodeError::Padding(PaddingError::NonZero));
+
+        format(segwit::DecodeError(SegwitHrpstringError::Unchecked(unchecked)));
+        format(segwit::EncodeError::WitnessVersion(InvalidWitnessVersionError(Fe32::P)));
+        format(segwit::EncodeError::WitnessLength(WitnessLengthError::TooLong {
+            len: 41,
+            max: 40,
+        }));
+        format(segwit::EncodeError::TooLong(SegwitCodeLengthError(91)));
+        format(segwit::EncodeError::Fmt(fmt::Error));
+
+        format(DecodeErrorWithInput::new("grs1qqqqqq", ChecksumError::InvalidLength));
+        format(crate::Error::Route(RouteError::Parse(CharError::Empty.into())));
+
+        format(RouteError::Parse(UncheckedHrpstringError::Char(CharError::Empty)));
+        format(RouteError::UnknownHrp { hrp: crate::hrp::TGRS, registered: vec![crate::hrp::GRS] });
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "embedded-io")]
+mod embedded_io_tests {
+    use embedded_io::{ErrorKind, ErrorType, Write};
+
+    use super::*;
+
+    const HRP: Hrp = Hrp::parse_unchecked("abc");
+    const DATA: [u8; 20] = [0xab; 20];
+    const STRING: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+    const TAP_ADDR: &str = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+
+    /// Writer that accepts at most `limit` bytes and errors once they have been written.
+    struct MockWriter {
+        buf: [u8; 128],
+        len: usize,
+        limit: usize,
+        calls: usize,
+    }
+
+    impl MockWriter {
+        fn new(limit: usize) -> Self { Self { buf: [0; 128], len: 0, limit, calls: 0 } }
+
+        fn written(&self) -> &[u8] { &self.buf[..self.len] }
+    }
+
+    impl ErrorType for MockWriter {
+        type Error = ErrorKind;
+    }
+
+    impl Write for MockWriter {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.calls += 1;
+            if self.len == self.limit {
+                return Err(ErrorKind::WriteZero);
+            }
+            let n = buf.len().min(self.limit - self.len);
+            self.buf[self.len..self.len + n].copy_from_slice(&buf[..n]);
+            self.len += n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    }
+
+    #[test]
+    fn encode_lower() {
+        let mut w = MockWriter::new(128);
+        encode_to_embedded_writer::<Bech32m, _>(&mut w, HRP, &DATA).expect("failed to encode");
+        assert_eq!(w.written(), STRING.as_bytes());
+        // 42 characters are written in chunks of 16 bytes.
+        assert_eq!(w.calls, 3);
+    }
+
+    #[test]
+    fn encode_upper() {
+        let mut w = MockWriter::new(128);
+        encode_upper_to_embedded_writer::<Bech32m, _>(&mut w, HRP, &DATA)
+            .expect("failed to encode");
+        assert_eq!(w.written(), STRING.to_ascii_uppercase().as_bytes());
+    }
+
+    #[test]
+    fn encode_too_long() {
+        let mut w = MockWriter::new(128);
+        let data = [0_u8; 1024];
+        let err = encode_to_embedded_writer::<Bech32m, _>(&mut w, HRP, &data).unwrap_err();
+        assert!(matches!(err, EncodeEmbeddedError::TooLong(_)));
+        assert!(w.written().is_empty());
+    }
+
+    #[test]
+    fn writer_error_is_preserved() {
+        for limit in 0..STRING.len() {
+            let mut w = MockWriter::new(limit);
+            let err = encode_to_embedded_writer::<Bech32m, _>(&mut w, HRP, &DATA).unwrap_err();
+            assert_eq!(err, EncodeEmbeddedError::Write(ErrorKind::WriteZero));
+            assert_eq!(w.written(), &STRING.as_bytes()[..limit]);
+        }
+    }
+
+    #[test]
+    fn segwit_encode() {
+        let mut w = MockWriter::new(128);
+        segwit::encode_to_embedded_writer_unchecked(&mut w, hrp::GRS, segwit::VERSION_1, &DATA)
+            .expect("failed to encode");
+        assert_eq!(w.written(), TAP_ADDR.as_bytes());
+
+        let mut w = MockWriter::new(128);
+        segwit::encode_upper_to_embedded_writer_unchecked(
+            &mut w,
+            hrp::GRS,
+            segwit::VERSION_1,
+            &DATA,
+        )
+        .expect("failed to encode");
+        assert_eq!(w.written(), TAP_ADDR.to_ascii_uppercase().as_bytes());
+    }
+
+    #[test]
+    fn segwit_encode_checked() {
+        let mut w = MockWriter::new(128);
+        segwit::encode_to_embedded_writer(&mut w, hrp::GRS, segwit::VERSION_1, &DATA)
+            .expect("failed to encode");
+        assert_eq!(w.written(), TAP_ADDR.as_bytes());
+
+        let mut w = MockWriter::new(128);
+        let err = segwit::encode_to_embedded_writer(&mut w, hrp::GRS, Fe32::_3, &DATA);
+        assert!(matches!(err, Err(segwit::EncodeEmbeddedError::WitnessVersion(_))));
+        let err =
+            segwit::encode_to_embedded_writer(&mut w, hrp::GRS, segwit::VERSION_0, &DATA[..1]);
+        assert!(matches!(err, Err(segwit::EncodeEmbeddedError::WitnessLength(_))));
+        let long = Hrp::parse_unchecked(&"a".repeat(60));
+        let err = segwit::encode_to_embedded_writer(&mut w, long, segwit::VERSION_1, &DATA);
+        assert!(matches!(err, Err(segwit::EncodeEmbeddedError::TooLong(_))));
+        assert!(w.written().is_empty());
+
+        let mut w = MockWriter::new(10);
+        let err = segwit::encode_to_embedded_writer(&mut w, hrp::GRS, segwit::VERSION_1, &DATA);
+        assert_eq!(err, Err(segwit::EncodeEmbeddedError::Write(ErrorKind::WriteZero)));
+        assert_eq!(w.written(), &TAP_ADDR.as_bytes()[..10]);
+    }
+
+    #[test]
+    fn segwit_writer_error_is_preserved() {
+        for limit in 0..TAP_ADDR.len() {
+            let mut w = MockWriter::new(limit);
+            let err = segwit::encode_to_embedded_writer_unchecked(
+                &mut w,
+                hrp::GRS,
+                segwit::VERSION_1,
+                &DATA,
+            )
+            .unwrap_err();
+            assert_eq!(err, ErrorKind::WriteZero);
+            assert_eq!(w.written(), &TAP_ADDR.as_bytes()[..limit]);
+        }
+    }
 }
@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: MIT
+
+//! Encoding and decoding of [LNURL] strings.
+//!
+//! An LNURL is the bech32 encoding, with the original bech32 checksum, of the UTF-8 bytes of a URL
+//! using the `lnurl` human-readable part. Unlike addresses there is no maximum length. LNURLs are
+//! usually uppercase when displayed as a QR code because that allows a more compact encoding.
+//!
+//! Enabled by the `lnurl` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::lnurl;
+//!
+//! let url = "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df";
+//!
+//! let encoded = lnurl::encode_upper(url);
+//! assert!(encoded.starts_with("LNURL1"));
+//! assert_eq!(lnurl::decode(&encoded).expect("valid lnurl"), url);
+//! ```
+//!
+//! [LNURL]: <https://github.com/lnurl/luds/blob/luds/01.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::primitives::Bech32Unlimited;
+use crate::Checksum;
+
+/// The human-readable part of all LNURLs.
+pub const HRP: Hrp = Hrp::parse_unchecked("lnurl");
+
+/// Encodes `url` as a lowercase LNURL.
+#[inline]
+pub fn encode(url: &str) -> String { encode_chars(url, |c| c) }
+
+/// Encodes `url` as an uppercase LNURL, for use in QR codes.
+#[inline]
+pub fn encode_upper(url: &str) -> String { encode_chars(url, |c| c.to_ascii_uppercase()) }
+
+/// Encodes `url` mapping each character of the LNURL with `f`.
+fn encode_chars(url: &str, f: impl Fn(char) -> char) -> String {
+    let data_len = (url.len() * 8 + 4) / 5;
+    let len = HRP.len() + 1 + data_len + Bech32Unlimited::CHECKSUM_LENGTH; // +1 for separator
+    let mut s = String::with_capacity(len);
+    s.extend(url.bytes().bytes_to_fes().with_checksum::<Bech32Unlimited>(&HRP).chars().map(f));
+    s
+}
+
+/// Decodes an LNURL, returning the URL.
+///
+/// The LNURL may be lowercase or uppercase. Validates the checksum, that the human-readable part
+/// is `lnurl`, and that the data is a UTF-8 string. The URL itself is not validated.
+pub fn decode(s: &str) -> Result<String, LnurlError> {
+    let checked = CheckedHrpstring::new::<Bech32Unlimited>(s)?;
+
+    let hrp = checked.hrp();
+    if hrp != HRP {
+        return Err(LnurlError::InvalidHrp(hrp));
+    }
+
+    String::from_utf8(checked.byte_iter().collect())
+        .map_err(|e| LnurlError::InvalidUtf8 { valid_up_to: e.utf8_error().valid_up_to() })
+}
+
+/// An error while decoding an LNURL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum LnurlError {
+    /// Parsing the bech32 string failed.
+    Parse(CheckedHrpstringError),
+    /// The human-readable part is not `lnurl`.
+    InvalidHrp(Hrp),
+    /// The decoded data is not a UTF-8 string.
+    InvalidUtf8 {
+        /// The number of bytes of the decoded data that are valid UTF-8.
+        valid_up_to: usize,
+    },
+}
+
+impl LnurlError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use LnurlError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            InvalidHrp(_) => Code::LnurlHrp,
+            InvalidUtf8 { .. } => Code::LnurlUtf8,
+        }
+    }
+}
+
+impl fmt::Display for LnurlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LnurlError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing lnurl failed"; e),
+            InvalidHrp(ref hrp) => write!(f, "human-readable part is not lnurl: {}", hrp),
+            InvalidUtf8 { valid_up_to } =>
+                write!(f, "lnurl data is not valid UTF-8 after {} bytes", valid_up_to),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for LnurlError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use LnurlError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            InvalidHrp(_) | InvalidUtf8 { .. } => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for LnurlError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bech32;
+
+    // Example from LUD-01.
+    const URL: &str = "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df";
+    const LNURL: &str = "LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS";
+
+    #[test]
+    fn lud01_example() {
+        assert_eq!(encode_upper(URL), LNURL);
+        assert_eq!(encode(URL), LNURL.to_lowercase());
+        assert_eq!(decode(LNURL).expect("valid lnurl"), URL);
+        assert_eq!(decode(&LNURL.to_lowercase()).expect("valid lnurl"), URL);
+    }
+
+    #[test]
+    fn roundtrip_long_urls() {
+        // 300 characters is a typical long URL, 1000 characters exceeds the bech32 code length.
+        for &len in [300, 1000].iter() {
+            let mut url = String::from("https://example.com/");
+            while url.len() < len {
+                url.push('a');
+            }
+
+            for encoded in [encode(&url), encode_upper(&url)].iter() {
+                assert_eq!(encoded.capacity(), encoded.len());
+                assert_eq!(decode(encoded).expect("valid lnurl"), url);
+            }
+        }
+        assert!(encode(&"a".repeat(1000)).len() > Bech32::CODE_LENGTH);
+    }
+
+    #[test]
+    fn invalid_hrp() {
+        let hrp = Hrp::parse_unchecked("lnurlp");
+        let s = crate::encode::<Bech32>(hrp, URL.as_bytes()).expect("valid length");
+        assert_eq!(decode(&s).unwrap_err(), LnurlError::InvalidHrp(hrp));
+        assert_eq!(decode(&s).unwrap_err().to_string(), "human-readable part is not lnurl: lnurlp");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let data = b"https://\xffexample.com";
+        let s = crate::encode::<Bech32>(HRP, data).expect("valid length");
+        assert_eq!(decode(&s).unwrap_err(), LnurlError::InvalidUtf8 { valid_up_to: 8 });
+    }
+
+    #[test]
+    fn invalid_checksum() {
+        let mut s = encode(URL);
+        s.pop();
+        s.push('q');
+        assert!(matches!(decode(&s), Err(LnurlError::Parse(CheckedHrpstringError::Checksum(_)))));
+    }
+}
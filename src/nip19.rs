@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: MIT
+
+//! Encoding and decoding of [NIP-19] Nostr entities.
+//!
+//! Public keys, private keys and note ids (`npub`, `nsec` and `note`) are the bech32 encoding of
+//! 32 bytes. The other entities (for example `nprofile`) encode a list of TLV records and are
+//! often longer than 90 characters, [`decode_raw`] returns their bytes for the caller to parse.
+//!
+//! Enabled by the `nip19` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::nip19::{self, Nip19Kind};
+//!
+//! // From the examples in NIP-19.
+//! let npub = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+//!
+//! let (kind, key) = nip19::decode(npub).expect("valid npub");
+//! assert_eq!(kind, Nip19Kind::Npub);
+//! assert_eq!(nip19::encode(kind, &key), npub);
+//! ```
+//!
+//! [NIP-19]: <https://github.com/nostr-protocol/nips/blob/master/19.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::primitives::Bech32Unlimited;
+use crate::Checksum;
+
+/// The length in bytes of the data of the fixed length entities.
+pub const DATA_LENGTH: usize = 32;
+
+/// The kind of a fixed length NIP-19 entity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Nip19Kind {
+    /// A public key (`npub`).
+    Npub,
+    /// A private key (`nsec`).
+    Nsec,
+    /// A note id (`note`).
+    Note,
+}
+
+impl Nip19Kind {
+    /// Returns the human-readable part used for this kind of entity.
+    #[inline]
+    pub fn hrp(self) -> Hrp {
+        use Nip19Kind::*;
+
+        match self {
+            Npub => Hrp::parse_unchecked("npub"),
+            Nsec => Hrp::parse_unchecked("nsec"),
+            Note => Hrp::parse_unchecked("note"),
+        }
+    }
+
+    /// Returns the kind of entity that uses `hrp`, if `hrp` is for a fixed length entity.
+    ///
+    /// The comparison is case-insensitive.
+    pub fn from_hrp(hrp: &Hrp) -> Option<Self> {
+        use Nip19Kind::*;
+
+        [Npub, Nsec, Note].iter().copied().find(|kind| kind.hrp() == *hrp)
+    }
+}
+
+impl fmt::Display for Nip19Kind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(&self.hrp(), f) }
+}
+
+/// Encodes `data` as a lowercase NIP-19 entity of `kind`.
+pub fn encode(kind: Nip19Kind, data: &[u8; DATA_LENGTH]) -> String {
+    let hrp = kind.hrp();
+    let data_len = (DATA_LENGTH * 8 + 4) / 5;
+    let len = hrp.len() + 1 + data_len + Bech32Unlimited::CHECKSUM_LENGTH; // +1 for separator
+    let mut s = String::with_capacity(len);
+    s.extend(data.iter().copied().bytes_to_fes().with_checksum::<Bech32Unlimited>(&hrp).chars());
+    s
+}
+
+/// Decodes a fixed length NIP-19 entity (`npub`, `nsec` or `note`).
+///
+/// The entity may be lowercase or uppercase. Use [`decode_raw`] for the TLV entities.
+pub fn decode(s: &str) -> Result<(Nip19Kind, [u8; DATA_LENGTH]), Nip19Error> {
+    let checked = CheckedHrpstring::new::<Bech32Unlimited>(s)?;
+
+    let hrp = checked.hrp();
+    let kind = Nip19Kind::from_hrp(&hrp).ok_or(Nip19Error::UnknownHrp(hrp))?;
+
+    let iter = checked.byte_iter();
+    if iter.len() != DATA_LENGTH {
+        return Err(Nip19Error::InvalidLength(iter.len()));
+    }
+    let mut data = [0_u8; DATA_LENGTH];
+    for (dst, src) in data.iter_mut().zip(iter) {
+        *dst = src;
+    }
+
+    Ok((kind, data))
+}
+
+/// Decodes any NIP-19 entity, returning the human-readable part and the data.
+///
+/// Unlike [`decode`] the human-readable part is not checked and the data may have any length, this
+/// is how the TLV entities (`nprofile`, `nevent`, `naddr` ...) are decoded.
+pub fn decode_raw(s: &str) -> Result<(Hrp, Vec<u8>), Nip19Error> {
+    let checked = CheckedHrpstring::new::<Bech32Unlimited>(s)?;
+    Ok((checked.hrp(), checked.byte_iter().collect()))
+}
+
+/// An error while decoding a NIP-19 entity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Nip19Error {
+    /// Parsing the bech32 string failed.
+    Parse(CheckedHrpstringError),
+    /// The human-readable part is not one of the fixed length entities.
+    UnknownHrp(Hrp),
+    /// The data is not [`DATA_LENGTH`] bytes long.
+    InvalidLength(usize),
+}
+
+impl Nip19Error {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use Nip19Error::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            UnknownHrp(_) => Code::Nip19UnknownHrp,
+            InvalidLength(_) => Code::Nip19Length,
+        }
+    }
+}
+
+impl fmt::Display for Nip19Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Nip19Error::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing nip19 entity failed"; e),
+            UnknownHrp(ref hrp) => write!(f, "unknown nip19 human-readable part: {}", hrp),
+            InvalidLength(len) =>
+                write!(f, "invalid nip19 data length {}, must be {} bytes", len, DATA_LENGTH),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for Nip19Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use Nip19Error::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            UnknownHrp(_) | InvalidLength(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for Nip19Error {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bech32;
+
+    // Examples from NIP-19.
+    const NPUB: &str = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+    const NPUB_KEY: [u8; 32] = [
+        0x7e, 0x7e, 0x9c, 0x42, 0xa9, 0x1b, 0xfe, 0xf1, 0x9f, 0xa9, 0x29, 0xe5, 0xfd, 0xa1, 0xb7,
+        0x2e, 0x0e, 0xbc, 0x1a, 0x4c, 0x11, 0x41, 0x67, 0x3e, 0x27, 0x94, 0x23, 0x4d, 0x86, 0xad,
+        0xdf, 0x4e,
+    ];
+    const NSEC: &str = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+    const NSEC_KEY: [u8; 32] = [
+        0x67, 0xde, 0xa2, 0xed, 0x01, 0x80, 0x72, 0xd6, 0x75, 0xf5, 0x41, 0x5e, 0xcf, 0xae, 0xd7,
+        0xd2, 0x59, 0x75, 0x55, 0xe2, 0x02, 0xd8, 0x5b, 0x3d, 0x65, 0xea, 0x4e, 0x58, 0xd2, 0xd9,
+        0x2f, 0xfa,
+    ];
+    const NPROFILE_NPUB: &str = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+    const NPROFILE: &str = "nprofile1qqsrhuxx8l9ex335q7he0f09aej04zpazpl0ne2cgukyawd24mayt8gpp4mhxue69uhhytnc9e3k7mgpz4mhxue69uhkg6nzv9ejuumpv34kytnrdaksjlyr9p";
+
+    #[test]
+    fn spec_vectors() {
+        for &(s, kind, key) in
+            [(NPUB, Nip19Kind::Npub, NPUB_KEY), (NSEC, Nip19Kind::Nsec, NSEC_KEY)].iter()
+        {
+            assert_eq!(decode(s).expect("valid entity"), (kind, key));
+            assert_eq!(decode(&s.to_uppercase()).expect("valid entity"), (kind, key));
+            assert_eq!(encode(kind, &key), s);
+        }
+    }
+
+    #[test]
+    fn note_roundtrip() {
+        let s = encode(Nip19Kind::Note, &NPUB_KEY);
+        assert!(s.starts_with("note1"));
+        assert_eq!(s.capacity(), s.len());
+        assert_eq!(decode(&s).expect("valid entity"), (Nip19Kind::Note, NPUB_KEY));
+    }
+
+    #[test]
+    fn nprofile_longer_than_90_chars() {
+        assert!(NPROFILE.len() > 90);
+        assert_eq!(
+            decode(NPROFILE).unwrap_err(),
+            Nip19Error::UnknownHrp(Hrp::parse_unchecked("nprofile"))
+        );
+
+        let (hrp, data) = decode_raw(NPROFILE).expect("valid entity");
+        assert_eq!(hrp.as_str(), "nprofile");
+        // TLV: the first record is the public key (type 0, length 32) followed by two relays.
+        assert_eq!(&data[..2], &[0, 32]);
+        let (_, key) = decode(NPROFILE_NPUB).expect("valid entity");
+        assert_eq!(&data[2..34], &key);
+        assert_eq!(&data[34..36], &[1, 13]);
+        assert_eq!(&data[36..49], b"wss://r.x.com");
+    }
+
+    #[test]
+    fn invalid_length() {
+        let hrp = Nip19Kind::Npub.hrp();
+        for &len in [0, 31, 33].iter() {
+            let s = crate::encode::<Bech32>(hrp, &[0xab; 33][..len]).expect("valid length");
+            assert_eq!(decode(&s).unwrap_err(), Nip19Error::InvalidLength(len));
+        }
+    }
+
+    #[test]
+    fn from_hrp() {
+        assert_eq!(Nip19Kind::from_hrp(&Hrp::parse_unchecked("NSEC")), Some(Nip19Kind::Nsec));
+        assert_eq!(Nip19Kind::from_hrp(&Hrp::parse_unchecked("nevent")), None);
+    }
+}
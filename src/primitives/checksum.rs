@@ -4,7 +4,10 @@
 //!
 //! [BCH]: <https://en.wikipedia.org/wiki/BCH_code>
 
-use core::{mem, ops};
+#![cfg_attr(not(test), deny(clippy::indexing_slicing, clippy::unwrap_used, clippy::expect_used))]
+
+use core::marker::PhantomData;
+use core::{fmt, mem, ops};
 
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::Hrp;
@@ -13,7 +16,17 @@ use crate::primitives::hrp::Hrp;
 ///
 /// For users, this can be treated as a marker trait; none of the associated data
 /// are end-user relevant.
+///
+/// The trait is deliberately open: formats with their own BCH code, such as codex32, implement it
+/// on an uninhabited marker type to use the encoding and decoding machinery of this crate. Adding
+/// a required item to the trait is a breaking change and is only done in a major release.
 pub trait Checksum {
+    /// The name of the checksum, e.g. `"bech32m"`, for displaying which checksum a string was
+    /// validated with.
+    ///
+    /// Defaults to `"custom"` so that implementations predating it keep compiling.
+    const NAME: &'static str = "custom";
+
     /// An unsigned integer type capable of holding a packed version of the generator
     /// polynomial (without its leading 1) and target residue (which will have the
     /// same width).
@@ -25,7 +38,7 @@ pub trait Checksum {
     /// The smallest type possible should be used, for efficiency reasons, but the
     /// only operations we do on these types are bitwise xor and shifts, so it should
     /// be pretty efficient no matter what.
-    type MidstateRepr: PackedFe32;
+    type MidstateRepr: PackedFe32 + 'static;
 
     /// The length of the code.
     ///
@@ -55,6 +68,14 @@ pub trait Checksum {
     /// The residue, modulo the generator polynomial, that a valid codeword will have.
     const TARGET_RESIDUE: Self::MidstateRepr;
 
+    /// Optional table used to input two field elements into the checksum engine at once.
+    ///
+    /// Entry `32 * a + b` is what gets added to the residue when the two field elements `a` then
+    /// `b` are shifted out of it, i.e. [`pair_shift::<Self>(a, b)`](pair_shift). If this is `None`
+    /// the engine inputs one field element at a time, which gives the same residues but is
+    /// slower. To check the values for consistency, run `Self::sanity_check()`.
+    const PAIR_SHIFTS: Option<&'static [Self::MidstateRepr; 1024]> = None;
+
     /// Sanity checks that the various constants of the trait are set in a way that they
     /// are consistent with each other.
     ///
@@ -65,10 +86,12 @@ pub trait Checksum {
         assert!(Self::CHECKSUM_LENGTH <= Self::MidstateRepr::WIDTH);
 
         // Check that the provided generator polynomials are, indeed, the same polynomial just shifted.
-        for i in 1..5 {
+        let generator_sh = Self::GENERATOR_SH;
+        let shifts = generator_sh.iter().zip(generator_sh.iter().skip(1));
+        for (i, (last_sh, curr_sh)) in (1..5).zip(shifts) {
             for j in 0..Self::MidstateRepr::WIDTH {
-                let last = Self::GENERATOR_SH[i - 1].unpack(j);
-                let curr = Self::GENERATOR_SH[i].unpack(j);
+                let last = last_sh.unpack(j);
+                let curr = curr_sh.unpack(j);
                 // GF32 is defined by extending GF2 with a root of x^5 + x^3 + 1 = 0
                 // which when written as bit coefficients is 41 = 0. Hence xoring
                 // (adding, in GF32) by 41 is the way to reduce x^5.
@@ -80,6 +103,128 @@ pub trait Checksum {
                 );
             }
         }
+
+        // Check that the pair shift table, if provided, matches the generator.
+        if let Some(table) = Self::PAIR_SHIFTS {
+            for a in 0..32 {
+                for b in 0..32 {
+                    assert!(
+                        table.get(usize::from(a) * 32 + usize::from(b))
+                            == Some(&pair_shift::<Self>(a, b)),
+                        "Pair shift table entry for ({}, {}) was incorrectly computed",
+                        a,
+                        b,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Computes what gets added to a residue when the field elements `a` then `b` are shifted out of
+/// it, the entries of [`Checksum::PAIR_SHIFTS`].
+///
+/// # Panics
+///
+/// If either `a` or `b` is not a valid field element (i.e. is greater than 31).
+pub fn pair_shift<Ck: Checksum + ?Sized>(a: u8, b: u8) -> Ck::MidstateRepr {
+    assert!(a < 32 && b < 32, "field elements must be less than 32");
+    // Shift `a` out of a residue with `b` as its top coefficient, just like `Engine::input_fe`
+    // does, then shift out whatever ended up as the top coefficient.
+    let mut residue = generator_shift::<Ck>(a);
+    let b = b ^ residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, 0);
+    residue ^ generator_shift::<Ck>(b)
+}
+
+/// Returns the sum of the generator shifts selected by the bits of `xn`, see `Engine::input_fe`.
+fn generator_shift<Ck: Checksum + ?Sized>(xn: u8) -> Ck::MidstateRepr {
+    let mut ret = Ck::MidstateRepr::ZERO;
+    for (i, generator) in Ck::GENERATOR_SH.iter().enumerate() {
+        if xn & (1 << i) != 0 {
+            ret = ret ^ *generator;
+        }
+    }
+    ret
+}
+
+/// A checksum chosen at runtime.
+///
+/// [`Checksum`] fixes the checksum at compile time, which does not suit tooling that handles
+/// several formats side by side, e.g. bech32, blech32 and codex32, with checksums of different
+/// lengths. Such tooling can instead pass a `&dyn ChecksumSpec` to
+/// [`CheckedHrpstring::new_with_spec`], either implementing the trait for its own codes or using
+/// [`StaticChecksum`] for any [`Checksum`].
+///
+/// [`CheckedHrpstring::new_with_spec`]: crate::primitives::decode::CheckedHrpstring::new_with_spec
+pub trait ChecksumSpec {
+    /// The name of the checksum, see [`Checksum::NAME`].
+    fn name(&self) -> &'static str;
+
+    /// The length of the code, see [`Checksum::CODE_LENGTH`].
+    fn code_length(&self) -> usize;
+
+    /// The number of characters in the checksum, see [`Checksum::CHECKSUM_LENGTH`].
+    fn checksum_length(&self) -> usize;
+
+    /// Returns whether `data`, the data part of a string including the checksum, has a valid
+    /// checksum for the human-readable part `hrp`.
+    ///
+    /// Only called with at least [`Self::checksum_length`] field elements.
+    fn verify(&self, hrp: Hrp, data: &mut dyn Iterator<Item = Fe32>) -> bool;
+}
+
+/// The [`ChecksumSpec`] of a [`Checksum`] known at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::checksum::{ChecksumSpec, StaticChecksum};
+/// use bech32grs::{Bech32, Bech32m};
+///
+/// let specs: [&dyn ChecksumSpec; 2] =
+///     [&StaticChecksum::<Bech32>::new(), &StaticChecksum::<Bech32m>::new()];
+/// assert_eq!(specs[1].name(), "bech32m");
+/// assert_eq!(specs[1].checksum_length(), 6);
+/// ```
+pub struct StaticChecksum<Ck>(PhantomData<Ck>);
+
+impl<Ck> StaticChecksum<Ck> {
+    /// Constructs the [`ChecksumSpec`] of `Ck`.
+    #[inline]
+    pub const fn new() -> Self { StaticChecksum(PhantomData) }
+}
+
+impl<Ck> Default for StaticChecksum<Ck> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<Ck> Clone for StaticChecksum<Ck> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<Ck> Copy for StaticChecksum<Ck> {}
+
+impl<Ck: Checksum> fmt::Debug for StaticChecksum<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("StaticChecksum").field(&Ck::NAME).finish()
+    }
+}
+
+impl<Ck: Checksum> ChecksumSpec for StaticChecksum<Ck> {
+    #[inline]
+    fn name(&self) -> &'static str { Ck::NAME }
+
+    #[inline]
+    fn code_length(&self) -> usize { Ck::CODE_LENGTH }
+
+    #[inline]
+    fn checksum_length(&self) -> usize { Ck::CHECKSUM_LENGTH }
+
+    fn verify(&self, hrp: Hrp, data: &mut dyn Iterator<Item = Fe32>) -> bool {
+        let mut engine = Engine::<Ck>::new();
+        engine.input_hrp(hrp);
+        engine.input_fes(data);
+        engine.residue() == &Ck::TARGET_RESIDUE
     }
 }
 
@@ -102,12 +247,10 @@ impl<Ck: Checksum> Engine<Ck> {
     pub fn new() -> Self { Engine { residue: Ck::MidstateRepr::ONE } }
 
     /// Feeds `hrp` into the checksum engine.
+    ///
+    /// Equivalent to feeding [`Hrp::expansion_iter`] to [`Self::input_fes`].
     #[inline]
-    pub fn input_hrp(&mut self, hrp: Hrp) {
-        for fe in HrpFe32Iter::new(&hrp) {
-            self.input_fe(fe)
-        }
-    }
+    pub fn input_hrp(&mut self, hrp: Hrp) { self.input_fes(hrp.expansion_iter()) }
 
     /// Adds a single gf32 element to the checksum engine.
     ///
@@ -115,9 +258,38 @@ impl<Ck: Checksum> Engine<Ck> {
     #[inline]
     pub fn input_fe(&mut self, e: Fe32) {
         let xn = self.residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, e.into());
-        for i in 0..5 {
+        for (i, generator) in Ck::GENERATOR_SH.iter().enumerate() {
             if xn & (1 << i) != 0 {
-                self.residue = self.residue ^ Ck::GENERATOR_SH[i];
+                self.residue = self.residue ^ *generator;
+            }
+        }
+    }
+
+    /// Adds a sequence of gf32 elements to the checksum engine.
+    ///
+    /// Gives the same result as calling [`Self::input_fe`] for each element but if the checksum
+    /// provides [`Checksum::PAIR_SHIFTS`] inputs two elements at a time, which is significantly
+    /// faster for long inputs.
+    #[inline]
+    pub fn input_fes<I: IntoIterator<Item = Fe32>>(&mut self, fes: I) {
+        let mut fes = fes.into_iter();
+        let table = match Ck::PAIR_SHIFTS {
+            Some(table) if Ck::CHECKSUM_LENGTH > 1 => table,
+            _ => return fes.for_each(|fe| self.input_fe(fe)),
+        };
+        while let Some(first) = fes.next() {
+            let second = match fes.next() {
+                Some(fe) => fe,
+                None => return self.input_fe(first),
+            };
+            let index = self.residue.mul_by_x_squared_then_add(
+                Ck::CHECKSUM_LENGTH,
+                first.into(),
+                second.into(),
+            );
+            // Index is always in bounds since it is made of two field elements.
+            if let Some(shift) = table.get(index) {
+                self.residue = self.residue ^ *shift;
             }
         }
     }
@@ -138,6 +310,166 @@ impl<Ck: Checksum> Engine<Ck> {
     /// Returns for the current checksum residue.
     #[inline]
     pub fn residue(&self) -> &Ck::MidstateRepr { &self.residue }
+
+    /// Returns the state of the engine as bytes, to resume the computation later with
+    /// [`Self::from_midstate`].
+    ///
+    /// The midstate is the residue in big-endian byte order, so it is as long as
+    /// [`Checksum::MidstateRepr`] e.g., 4 bytes for bech32 and bech32m. The format is stable within
+    /// a major version of this crate.
+    #[inline]
+    pub fn midstate(&self) -> Midstate<Ck> { self.residue.to_bytes() }
+
+    /// Constructs a checksum engine that resumes from a `midstate` returned by [`Self::midstate`].
+    ///
+    /// Any bits of `midstate` beyond the length of the checksum are ignored.
+    pub fn from_midstate(midstate: Midstate<Ck>) -> Self {
+        let packed = Ck::MidstateRepr::from_bytes(midstate);
+        let mut residue = Ck::MidstateRepr::ZERO;
+        for i in (0..Ck::CHECKSUM_LENGTH).rev() {
+            residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, packed.unpack(i));
+        }
+        Engine { residue }
+    }
+}
+
+/// The serialized state of an [`Engine`], see [`Engine::midstate`].
+pub type Midstate<Ck> = <<Ck as Checksum>::MidstateRepr as PackedFe32>::Bytes;
+
+/// A checksum engine that is fed bytes, converting them to field elements as they arrive.
+///
+/// This is for encoders that cannot hold the whole data part at once, e.g. a hardware signer that
+/// streams an address over several messages. Each call returns the field elements to write out,
+/// and the state between calls can be saved with [`Self::midstate`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::checksum::{ByteEngine, Engine};
+/// use bech32grs::{hrp, Bech32, Fe32};
+///
+/// let program = [
+///     0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94,
+///     0x1c, 0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+/// ];
+///
+/// let mut engine = Engine::<Bech32>::new();
+/// engine.input_hrp(hrp::GRS);
+/// engine.input_fe(Fe32::Q); // The witness version.
+///
+/// let mut address = String::from("grs1q");
+/// let mut bytes = ByteEngine::from_engine(engine);
+/// for byte in program[..7].iter() {
+///     address.extend(bytes.input_byte(*byte).map(Fe32::to_char));
+/// }
+///
+/// // Save the state, e.g. between messages, and carry on where we left off.
+/// let (midstate, pending) = bytes.midstate();
+/// let mut bytes = ByteEngine::<Bech32>::from_midstate(midstate, pending).expect("valid state");
+/// for byte in program[7..].iter() {
+///     address.extend(bytes.input_byte(*byte).map(Fe32::to_char));
+/// }
+/// address.extend(bytes.finish().map(Fe32::to_char));
+///
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!(address, bech32grs::segwit::encode_v0(hrp::GRS, &program).unwrap());
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct ByteEngine<Ck: Checksum> {
+    engine: Engine<Ck>,
+    /// The bits of the last byte that are not yet part of a field element.
+    buffer: u8,
+    /// The number of bits in `buffer`, always less than 5.
+    buffered_bits: u8,
+}
+
+impl<Ck: Checksum> fmt::Debug for ByteEngine<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ByteEngine")
+            .field("midstate", &self.engine.midstate())
+            .field("buffer", &self.buffer)
+            .field("buffered_bits", &self.buffered_bits)
+            .finish()
+    }
+}
+
+impl<Ck: Checksum> Default for ByteEngine<Ck> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<Ck: Checksum> ByteEngine<Ck> {
+    /// Constructs a new byte engine with no data input.
+    #[inline]
+    pub fn new() -> Self { Self::from_engine(Engine::new()) }
+
+    /// Constructs a byte engine that inputs bytes after the data already input to `engine`, e.g.
+    /// the human-readable part and a witness version.
+    #[inline]
+    pub fn from_engine(engine: Engine<Ck>) -> Self {
+        ByteEngine { engine, buffer: 0, buffered_bits: 0 }
+    }
+
+    /// Inputs `byte` and returns the one or two field elements it completed.
+    ///
+    /// The returned field elements have already been input to the checksum, they are returned so
+    /// that the caller can write them out.
+    pub fn input_byte(&mut self, byte: u8) -> impl Iterator<Item = Fe32> {
+        let bits = u16::from(self.buffer) << 8 | u16::from(byte);
+        let mut remaining = self.buffered_bits + 8;
+
+        let mut next_fe = |remaining: &mut u8| {
+            *remaining -= 5;
+            let fe = Fe32((bits >> *remaining) as u8 & 0x1f);
+            self.engine.input_fe(fe);
+            fe
+        };
+        let first = next_fe(&mut remaining);
+        let second = if remaining >= 5 { Some(next_fe(&mut remaining)) } else { None };
+
+        self.buffer = (bits & ((1 << remaining) - 1)) as u8;
+        self.buffered_bits = remaining;
+        Some(first).into_iter().chain(second)
+    }
+
+    /// Pads the last byte with zero bits and computes the checksum.
+    ///
+    /// Returns the padded field element, if there were bits left over, followed by the checksum.
+    pub fn finish(mut self) -> impl Iterator<Item = Fe32> {
+        let padded = if self.buffered_bits > 0 {
+            let fe = Fe32((self.buffer << (5 - self.buffered_bits)) & 0x1f);
+            self.engine.input_fe(fe);
+            Some(fe)
+        } else {
+            None
+        };
+        self.engine.input_target_residue();
+        let residue = *self.engine.residue();
+        let checksum = (0..Ck::CHECKSUM_LENGTH).rev().map(move |i| Fe32(residue.unpack(i)));
+        padded.into_iter().chain(checksum)
+    }
+
+    /// Returns the state of the byte engine, to resume later with [`Self::from_midstate`].
+    ///
+    /// This is the [`Engine::midstate`] of the checksum engine and a byte holding the bits of the
+    /// last byte that are not yet part of a field element: their count in the high four bits and
+    /// the bits themselves in the low four bits. The format is stable within a major version of
+    /// this crate.
+    #[inline]
+    pub fn midstate(&self) -> (Midstate<Ck>, u8) {
+        (self.engine.midstate(), self.buffered_bits << 4 | self.buffer)
+    }
+
+    /// Constructs a byte engine that resumes from the state returned by [`Self::midstate`].
+    ///
+    /// Returns `None` if `pending` is not a valid buffer of left over bits.
+    pub fn from_midstate(midstate: Midstate<Ck>, pending: u8) -> Option<Self> {
+        let buffered_bits = pending >> 4;
+        let buffer = pending & 0x0f;
+        if buffered_bits >= 5 || buffer >> buffered_bits != 0 {
+            return None;
+        }
+        Some(ByteEngine { engine: Engine::from_midstate(midstate), buffer, buffered_bits })
+    }
 }
 
 /// Trait describing an integer type which can be used as a "packed" sequence of Fe32s.
@@ -148,6 +480,12 @@ impl<Ck: Checksum> Engine<Ck> {
 /// This is useful because then multiplication by x reduces to simply left-shifting by 5,
 /// and addition of entire polynomials can be done by xor.
 pub trait PackedFe32: Copy + PartialEq + Eq + ops::BitXor<Self, Output = Self> {
+    /// The bytes of the type, a byte array as long as the type.
+    type Bytes: Copy + Default + AsRef<[u8]> + AsMut<[u8]> + fmt::Debug + PartialEq + Eq;
+
+    /// The zero constant, for which stdlib provides no existing trait.
+    const ZERO: Self;
+
     /// The one constant, for which stdlib provides no existing trait.
     const ONE: Self;
 
@@ -163,6 +501,23 @@ pub trait PackedFe32: Copy + PartialEq + Eq + ops::BitXor<Self, Output = Self> {
     /// Takes the degree of the polynomial as an input; for checksum applications
     /// this should basically always be `Checksum::CHECKSUM_WIDTH`.
     fn mul_by_x_then_add(&mut self, degree: usize, add: u8) -> u8;
+
+    /// Returns the packed polynomial as big-endian bytes.
+    fn to_bytes(&self) -> Self::Bytes;
+
+    /// Constructs a packed polynomial from big-endian bytes.
+    fn from_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Multiply the polynomial by x^2, drop its two highest coefficients (and return them as
+    /// `32 * first + second`), and add two new field elements to the now-0 coefficients.
+    ///
+    /// Same as calling [`Self::mul_by_x_then_add`] twice, `degree` must be at least 2.
+    #[inline]
+    fn mul_by_x_squared_then_add(&mut self, degree: usize, add_1: u8, add_2: u8) -> usize {
+        let first = self.mul_by_x_then_add(degree, add_1);
+        let second = self.mul_by_x_then_add(degree, add_2);
+        usize::from(first) * 32 + usize::from(second)
+    }
 }
 
 /// A placeholder type used as part of the [`NoChecksum`] "checksum".
@@ -178,16 +533,24 @@ impl ops::BitXor<PackedNull> for PackedNull {
 }
 
 impl PackedFe32 for PackedNull {
+    type Bytes = [u8; 0];
+    const ZERO: Self = PackedNull;
     const ONE: Self = PackedNull;
     #[inline]
     fn unpack(&self, _: usize) -> u8 { 0 }
     #[inline]
     fn mul_by_x_then_add(&mut self, _: usize, _: u8) -> u8 { 0 }
+    #[inline]
+    fn to_bytes(&self) -> [u8; 0] { [] }
+    #[inline]
+    fn from_bytes(_: [u8; 0]) -> Self { PackedNull }
 }
 
 macro_rules! impl_packed_fe32 {
-    ($ty:ident) => {
+    ($ty:ident, $len:literal) => {
         impl PackedFe32 for $ty {
+            type Bytes = [u8; $len];
+            const ZERO: Self = 0;
             const ONE: Self = 1;
 
             #[inline]
@@ -207,14 +570,35 @@ macro_rules! impl_packed_fe32 {
                 *self |= Self::from(add);
                 ret
             }
+
+            #[inline]
+            fn mul_by_x_squared_then_add(&mut self, degree: usize, add_1: u8, add_2: u8) -> usize {
+                debug_assert!(degree > 1);
+                debug_assert!(degree <= Self::WIDTH);
+                debug_assert!(add_1 < 32 && add_2 < 32);
+                let ret = (*self >> ((degree - 2) * 5)) as usize & 0x3ff;
+                *self &= !(0x3ff << ((degree - 2) * 5));
+                *self <<= 10;
+                *self |= Self::from(add_1) << 5 | Self::from(add_2);
+                ret
+            }
+
+            #[inline]
+            fn to_bytes(&self) -> [u8; $len] { self.to_be_bytes() }
+
+            #[inline]
+            fn from_bytes(bytes: [u8; $len]) -> Self { Self::from_be_bytes(bytes) }
         }
     };
 }
-impl_packed_fe32!(u32);
-impl_packed_fe32!(u64);
-impl_packed_fe32!(u128);
+impl_packed_fe32!(u32, 4);
+impl_packed_fe32!(u64, 8);
+impl_packed_fe32!(u128, 16);
 
 /// Iterator that yields the field elements that are input into a checksum algorithm for an [`Hrp`].
+///
+/// Created by [`Hrp::expansion_iter`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct HrpFe32Iter<'hrp> {
     /// `None` once the hrp high fes have been yielded.
     high_iter: Option<crate::primitives::hrp::LowercaseByteIter<'hrp>>,
@@ -270,9 +654,49 @@ impl<'hrp> Iterator for HrpFe32Iter<'hrp> {
             None => (0, Some(0)),
         };
 
-        let min = high.0 + 1 + low.0;
-        let max = high.1.zip(low.1).map(|(high, low)| high + 1 + low);
+        let min = high.0 + low.0;
+        let max = high.1.zip(low.1).map(|(high, low)| high + low);
 
         (min, max)
     }
 }
+
+#[cfg(bench)]
+mod benches {
+    use test::{black_box, Bencher};
+
+    use super::*;
+    use crate::Bech32;
+
+    fn fes(len: usize) -> Vec<Fe32> { (0..len).map(|i| Fe32(i as u8 & 0x1f)).collect() }
+
+    fn bench_single(bh: &mut Bencher, len: usize) {
+        let fes = fes(len);
+        bh.iter(|| {
+            let mut engine = Engine::<Bech32>::new();
+            black_box(&fes).iter().for_each(|&fe| engine.input_fe(fe));
+            black_box(engine.residue());
+        });
+    }
+
+    fn bench_pairs(bh: &mut Bencher, len: usize) {
+        let fes = fes(len);
+        bh.iter(|| {
+            let mut engine = Engine::<Bech32>::new();
+            engine.input_fes(black_box(&fes).iter().copied());
+            black_box(engine.residue());
+        });
+    }
+
+    #[bench]
+    fn bench_input_fe_90(bh: &mut Bencher) { bench_single(bh, 90) }
+
+    #[bench]
+    fn bench_input_fes_90(bh: &mut Bencher) { bench_pairs(bh, 90) }
+
+    #[bench]
+    fn bench_input_fe_1000(bh: &mut Bencher) { bench_single(bh, 1000) }
+
+    #[bench]
+    fn bench_input_fes_1000(bh: &mut Bencher) { bench_pairs(bh, 1000) }
+}
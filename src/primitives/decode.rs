@@ -27,6 +27,8 @@
 //! addresses using it.*
 //!
 //! - `UncheckedHrpstring`: Parses the general checksummed base32 format and provides checksum validation.
+//! - `CharCheckedHrpstring`: An `UncheckedHrpstring` whose checksum is yet to be verified, for
+//!   routing by human-readable part before paying for checksum verification.
 //! - `CheckedHrpstring`: Provides access to the data encoded by a general checksummed base32 string and segwit checks.
 //! - `SegwitHrpstring`: Provides access to the data encoded by a segwit address.
 //!
@@ -73,19 +75,155 @@
 //! [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
 //! [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
 
-use core::{fmt, iter, slice, str};
-
-use crate::error::write_err;
-use crate::primitives::checksum::{self, Checksum};
-use crate::primitives::gf32::Fe32;
+mod simd;
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::{fmt, iter, str};
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::checksum::{self, Checksum, ChecksumSpec};
+use crate::primitives::gf32::{
+    Fe32, ASCII_BECH32, ASCII_LOWER, ASCII_TABLE, ASCII_UPPER, ASCII_VALUE,
+};
 use crate::primitives::hrp::{self, Hrp};
 use crate::primitives::iter::{Fe32IterExt, FesToBytes};
-use crate::primitives::segwit::{self, WitnessLengthError, VERSION_0};
+use crate::primitives::segwit::{self, WitnessLengthError, VERSION_0, VERSION_1};
+use crate::segwit::KnownHrp;
+#[cfg(feature = "alloc")]
+use crate::DecodeErrorWithInput;
 use crate::{Bech32, Bech32m};
 
 /// Separator between the hrp and payload (as defined by BIP-173).
 const SEP: char = '1';
 
+/// How strictly to decode a bech32 string, see [`UncheckedHrpstring::new_with_profile`].
+///
+/// The plain constructors (e.g. [`UncheckedHrpstring::new`]) are not affected by profiles, use one
+/// of the presets or build your own to relax or tighten what they accept.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::{CasePolicy, DecodeProfile, UncheckedHrpstring};
+///
+/// let s = "A12uEL5L"; // Mixed case.
+/// assert!(UncheckedHrpstring::new_with_profile(s, DecodeProfile::BIP173).is_err());
+/// assert!(UncheckedHrpstring::new_with_profile(s, DecodeProfile::PERMISSIVE).is_ok());
+///
+/// let lowercase_only = DecodeProfile { case: CasePolicy::Canonical, ..DecodeProfile::BIP173 };
+/// assert!(UncheckedHrpstring::new_with_profile("A12UEL5L", lowercase_only).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DecodeProfile {
+    /// The maximum length of the whole string, `None` for no limit.
+    pub max_length: Option<usize>,
+    /// Which character cases are accepted.
+    pub case: CasePolicy,
+    /// Whether to reject strings longer than the code length of the checksum algorithm.
+    pub enforce_code_length: bool,
+    /// Whether the data part (everything after the separator) may be empty.
+    pub allow_empty_data: bool,
+    /// Whether to reject human-readable parts that contain anything other than ASCII letters and
+    /// digits, see [`Hrp::is_alphanumeric`].
+    ///
+    /// The specification allows any printable ASCII character so none of the presets set this.
+    pub display_safe_hrp: bool,
+}
+
+impl DecodeProfile {
+    /// The rules of [BIP-173]: at most 90 characters, not mixed case, and a non-empty data part.
+    ///
+    /// [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
+    pub const BIP173: Self = DecodeProfile {
+        max_length: Some(segwit::MAX_STRING_LENGTH),
+        case: CasePolicy::AcceptUpper,
+        enforce_code_length: true,
+        allow_empty_data: false,
+        display_safe_hrp: false,
+    };
+
+    /// The rules for segwit addresses from [BIP-350], which only changes the checksum algorithm
+    /// so this is the same as [`DecodeProfile::BIP173`].
+    ///
+    /// [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
+    pub const BIP350_SEGWIT: Self = Self::BIP173;
+
+    /// The rules for Lightning invoices: like BIP-173 but with no maximum length.
+    pub const LIGHTNING: Self = DecodeProfile {
+        max_length: None,
+        case: CasePolicy::AcceptUpper,
+        enforce_code_length: false,
+        allow_empty_data: false,
+        display_safe_hrp: false,
+    };
+
+    /// Accepts anything made of bech32 characters with a separator, in any case and of any length.
+    pub const PERMISSIVE: Self = DecodeProfile {
+        max_length: None,
+        case: CasePolicy::AcceptMixedDangerous,
+        enforce_code_length: false,
+        allow_empty_data: true,
+        display_safe_hrp: false,
+    };
+
+    /// The profile of the plain constructors with the case rules of `case`.
+    pub(crate) fn with_case(case: CasePolicy, allow_empty_data: bool) -> Self {
+        DecodeProfile {
+            max_length: None,
+            case,
+            enforce_code_length: true,
+            allow_empty_data,
+            display_safe_hrp: false,
+        }
+    }
+}
+
+/// Which character cases a decoder accepts, see [`DecodeProfile::case`] and the `new_with_case`
+/// constructors e.g., [`UncheckedHrpstring::new_with_case`].
+///
+/// The default is [`CasePolicy::AcceptUpper`], the rules of BIP-173. More policies may be added in
+/// any release.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CasePolicy {
+    /// The string must be lowercase, the canonical form of a bech32 string.
+    Canonical,
+    /// The string may be lowercase or uppercase but not mixed case, as specified by BIP-173.
+    AcceptUpper,
+    /// The string must be uppercase, the form used in QR codes. Digits and symbols have no case
+    /// and are accepted.
+    ///
+    /// For input that is known to come from a QR code, where lowercase means the code was not
+    /// generated in the compact alphanumeric mode.
+    RequireUpper,
+    /// The string may be any case, including mixed case.
+    ///
+    /// BIP-173 forbids mixed case because the checksum does not cover case, so a mixed case string
+    /// has been altered since it was encoded, by a person or a buggy program, and may not be what
+    /// its author meant. Only use this to recover data that is known to be damaged, e.g. in
+    /// forensic tooling, never to accept user input.
+    AcceptMixedDangerous,
+}
+
+impl Default for CasePolicy {
+    fn default() -> Self { CasePolicy::AcceptUpper }
+}
+
+/// One of the two standard checksum algorithms, as found by [`UncheckedHrpstring::checksum_variant`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChecksumVariant {
+    /// The bech32 checksum algorithm, see [`Bech32`].
+    Bech32,
+    /// The bech32m checksum algorithm, see [`Bech32m`].
+    Bech32m,
+}
+
 /// An HRP string that has been parsed but not yet had the checksum checked.
 ///
 /// Parsing an HRP string only checks validity of the characters, it does not validate the
@@ -112,7 +250,10 @@ const SEP: char = '1';
 /// }
 /// ```
 #[derive(Debug)]
+#[must_use = "the checksum is only validated by `validate_checksum` and similar methods"]
 pub struct UncheckedHrpstring<'s> {
+    /// The parsed string.
+    original: &'s str,
     /// The human-readable part, guaranteed to be lowercase ASCII characters.
     hrp: Hrp,
     /// This is ASCII byte values of the parsed string, guaranteed to be valid bech32 characters.
@@ -121,24 +262,146 @@ pub struct UncheckedHrpstring<'s> {
     data_part_ascii: &'s [u8],
     /// The length of the parsed hrpstring.
     hrpstring_length: usize,
+    /// Whether [`Self::validate_checksum`] checks the code length of the checksum algorithm.
+    enforce_code_length: bool,
 }
 
 impl<'s> UncheckedHrpstring<'s> {
     /// Parses an bech32 encode string and constructs a [`UncheckedHrpstring`] object.
     ///
     /// Checks for valid ASCII values, does not validate the checksum.
+    ///
+    /// An empty string and a string without a separator are reported as [`CharError::Empty`] and
+    /// [`CharError::MissingSeparator`] respectively, before any other check. The data part may be
+    /// empty, as it is for a string without a checksum that encodes no data.
     #[inline]
     pub fn new(s: &'s str) -> Result<Self, UncheckedHrpstringError> {
-        let sep_pos = check_characters(s)?;
-        let (hrp, rest) = s.split_at(sep_pos);
+        Self::parse(s, true).map_err(|e| e.traced(s))
+    }
 
-        let ret = UncheckedHrpstring {
-            hrp: Hrp::parse(hrp)?,
-            data_part_ascii: rest[1..].as_bytes(), // Skip the separator.
-            hrpstring_length: s.len(),
+    /// Parses `s` with the rules of [`Self::new`], rejecting an empty data part if `require_data`.
+    ///
+    /// Used by the constructors that expect a checksum or a witness version, so that e.g. `"grs1"`
+    /// is reported as [`CharError::NothingAfterSeparator`] before any other error.
+    #[inline]
+    pub(crate) fn new_requiring_data(
+        s: &'s str,
+        require_data: bool,
+    ) -> Result<Self, UncheckedHrpstringError> {
+        Self::parse(s, !require_data).map_err(|e| e.traced(s))
+    }
+
+    /// Parses `s` with the rules of [`Self::new`], without tracing errors.
+    #[inline]
+    fn parse(s: &'s str, allow_empty_data: bool) -> Result<Self, UncheckedHrpstringError> {
+        check_separator(s, allow_empty_data)?;
+        let sep = check_characters(s).map_err(|e| hrp_case_error(s, e))?;
+        let hrp = sep.parse_hrp(s).map_err(|e| hrp_split_error(s, sep.pos, e))?;
+        Ok(Self::from_parts(s, hrp, sep.pos, true))
+    }
+
+    /// Returns whether `s` parses and has a valid `Ck` checksum.
+    ///
+    /// The same as `CheckedHrpstring::new::<Ck>(s).is_ok()` but skips the work done to describe
+    /// and trace errors, for callers that only need to accept or reject.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn is_valid<Ck: Checksum>(s: &'s str) -> bool {
+        if check_separator(s, Ck::CHECKSUM_LENGTH == 0).is_err() {
+            return false;
+        }
+        let sep = match check_characters(s) {
+            Ok(sep) => sep,
+            Err(_) => return false,
+        };
+        match sep.parse_hrp(s) {
+            Ok(hrp) => Self::from_parts(s, hrp, sep.pos, true).has_valid_checksum::<Ck>(),
+            Err(_) => false,
+        }
+    }
+
+    /// Parses an bech32 encode string with the rules of `profile`.
+    ///
+    /// Like [`Self::new`] this does not validate the checksum, however if `profile` does not
+    /// enforce the code length then neither will [`Self::validate_checksum`].
+    pub fn new_with_profile(
+        s: &'s str,
+        profile: DecodeProfile,
+    ) -> Result<Self, UncheckedHrpstringError> {
+        Self::parse_with_profile(s, profile).map_err(|e| e.traced(s))
+    }
+
+    /// Parses an bech32 encode string accepting the character cases allowed by `case`.
+    ///
+    /// The same as [`Self::new`] except for the case rules, with [`CasePolicy::AcceptUpper`] this
+    /// accepts the same strings. The human-readable part is lowercase whatever the case of `s`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::decode::UncheckedHrpstring;
+    /// use bech32grs::CasePolicy;
+    ///
+    /// let s = "A12uEL5L";
+    /// assert!(UncheckedHrpstring::new_with_case(s, CasePolicy::AcceptUpper).is_err());
+    /// assert!(UncheckedHrpstring::new_with_case(s, CasePolicy::AcceptMixedDangerous).is_ok());
+    /// ```
+    #[inline]
+    pub fn new_with_case(s: &'s str, case: CasePolicy) -> Result<Self, UncheckedHrpstringError> {
+        Self::new_with_profile(s, DecodeProfile::with_case(case, true))
+    }
+
+    /// Parses `s` with the rules of `profile`, without tracing errors.
+    fn parse_with_profile(
+        s: &'s str,
+        profile: DecodeProfile,
+    ) -> Result<Self, UncheckedHrpstringError> {
+        if let Some(max) = profile.max_length {
+            if s.len() > max {
+                let e = CodeLengthError { encoded_length: s.len(), code_length: max };
+                return Err(UncheckedHrpstringError::TooLong(e));
+            }
+        }
+        check_separator(s, profile.allow_empty_data)?;
+        if profile.case == CasePolicy::RequireUpper {
+            if let Some(pos) = s.bytes().position(|b| b.is_ascii_lowercase()) {
+                return Err(CharError::NotUppercase { pos }.into());
+            }
+        }
+
+        let (pos, hrp) = match check_characters(s) {
+            Ok(sep) => {
+                if profile.case == CasePolicy::Canonical
+                    && s.bytes().any(|b| b.is_ascii_uppercase())
+                {
+                    return Err(CharError::Uppercase.into());
+                }
+                (sep.pos, sep.parse_hrp(s).map_err(|e| hrp_split_error(s, sep.pos, e))?)
+            }
+            // Mixed case is only reported once all the data part characters are known to be valid.
+            Err(CharError::MixedCase) if profile.case == CasePolicy::AcceptMixedDangerous => {
+                let pos = s.rfind(SEP).ok_or(CharError::MissingSeparator)?;
+                (pos, parse_hrp_insensitive(&s[..pos]).map_err(|e| hrp_split_error(s, pos, e))?)
+            }
+            Err(e) => return Err(hrp_case_error(s, e)),
         };
+        if profile.display_safe_hrp {
+            if let Some(&b) = hrp.as_bytes().iter().find(|b| !b.is_ascii_alphanumeric()) {
+                return Err(hrp::Error::NotDisplaySafe(b).into());
+            }
+        }
 
-        Ok(ret)
+        Ok(Self::from_parts(s, hrp, pos, profile.enforce_code_length))
+    }
+
+    /// Constructs an [`UncheckedHrpstring`] from the already validated parts of `s`.
+    fn from_parts(s: &'s str, hrp: Hrp, sep_pos: usize, enforce_code_length: bool) -> Self {
+        UncheckedHrpstring {
+            original: s,
+            hrp,
+            data_part_ascii: &s.as_bytes()[sep_pos + 1..], // Skip the separator.
+            hrpstring_length: s.len(),
+            enforce_code_length,
+        }
     }
 
     /// Returns the human-readable part.
@@ -164,6 +427,21 @@ impl<'s> UncheckedHrpstring<'s> {
     #[inline]
     pub fn data_part_ascii(&self) -> &'s [u8] { self.data_part_ascii }
 
+    /// Returns the number of characters in the data part, everything after the separator.
+    ///
+    /// Includes the checksum if one was present in the parsed string.
+    #[inline]
+    pub fn data_part_len(&self) -> usize { self.data_part_ascii.len() }
+
+    /// Returns the length of the parsed string, including the human-readable part and separator.
+    ///
+    /// Known before the checksum is validated, see [`segwit::plausible_lengths`] for using it to
+    /// reject strings that can not be segwit addresses.
+    ///
+    /// [`segwit::plausible_lengths`]: crate::segwit::plausible_lengths
+    #[inline]
+    pub fn total_len(&self) -> usize { self.hrpstring_length }
+
     /// Attempts to remove the first byte of the data part, treating it as a witness version.
     ///
     /// If [`Self::witness_version`] succeeds this function removes the first character (witness
@@ -228,12 +506,39 @@ impl<'s> UncheckedHrpstring<'s> {
     }
 
     /// Validates that data has a valid checksum for the `Ck` algorithm and returns a [`CheckedHrpstring`].
+    ///
+    /// This is equivalent to `self.parse_data_chars().verify::<Ck>()`.
     #[inline]
     pub fn validate_and_remove_checksum<Ck: Checksum>(
         self,
     ) -> Result<CheckedHrpstring<'s>, ChecksumError> {
-        self.validate_checksum::<Ck>()?;
-        Ok(self.remove_checksum::<Ck>())
+        self.parse_data_chars().verify::<Ck>()
+    }
+
+    /// Returns a [`CharCheckedHrpstring`], the first of two steps to a [`CheckedHrpstring`].
+    ///
+    /// The characters and case of the whole string are validated when an [`UncheckedHrpstring`] is
+    /// parsed, so this step is free and cannot fail. It exists so that the checksum verification
+    /// done by [`CharCheckedHrpstring::verify`] can be deferred, or skipped, for strings that are
+    /// not interesting once their human-readable part is known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::decode::UncheckedHrpstring;
+    /// use bech32grs::{hrp, Bech32};
+    ///
+    /// let addr = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+    /// let chars = UncheckedHrpstring::new(addr).unwrap().parse_data_chars();
+    /// assert_eq!(chars.hrp(), hrp::GRS);
+    /// assert_eq!(chars.data_len(), 39);
+    ///
+    /// let checked = chars.verify::<Bech32>().expect("valid checksum");
+    /// assert_eq!(checked.data_part_ascii_no_checksum().len(), 33);
+    /// ```
+    #[inline]
+    pub fn parse_data_chars(self) -> CharCheckedHrpstring<'s> {
+        CharCheckedHrpstring { inner: self }
     }
 
     /// Validates that data has a valid checksum for the `Ck` algorithm (this may mean an empty
@@ -253,29 +558,17 @@ impl<'s> UncheckedHrpstring<'s> {
     pub fn validate_checksum<Ck: Checksum>(&self) -> Result<(), ChecksumError> {
         use ChecksumError::*;
 
-        if self.hrpstring_length > Ck::CODE_LENGTH {
-            return Err(ChecksumError::CodeLength(CodeLengthError {
-                encoded_length: self.hrpstring_length,
-                code_length: Ck::CODE_LENGTH,
-            }));
-        }
-
         if Ck::CHECKSUM_LENGTH == 0 {
             // Called with NoChecksum
-            return Ok(());
-        }
-
-        if self.data_part_ascii.len() < Ck::CHECKSUM_LENGTH {
-            return Err(InvalidLength);
+            return self.validate_checksum_length(Ck::CODE_LENGTH, 0);
         }
+        self.validate_checksum_length(Ck::CODE_LENGTH, Ck::CHECKSUM_LENGTH)?;
 
         let mut checksum_eng = checksum::Engine::<Ck>::new();
         checksum_eng.input_hrp(self.hrp());
 
         // Unwrap ok since we checked all characters in our constructor.
-        for fe in self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b)) {
-            checksum_eng.input_fe(fe);
-        }
+        checksum_eng.input_fes(self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b)));
 
         if checksum_eng.residue() != &Ck::TARGET_RESIDUE {
             return Err(InvalidResidue);
@@ -284,6 +577,83 @@ impl<'s> UncheckedHrpstring<'s> {
         Ok(())
     }
 
+    /// Returns which of the bech32 and bech32m checksums the string has, if either.
+    ///
+    /// Unlike calling [`Self::validate_checksum`] for each algorithm this makes a single pass over
+    /// the data, the two algorithms only differ in the residue they expect. A string with a valid
+    /// checksum for the other algorithm can then be told apart from a corrupted string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::decode::{ChecksumVariant, UncheckedHrpstring};
+    ///
+    /// let v0 = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5";
+    /// let v0 = UncheckedHrpstring::new(v0).unwrap();
+    /// assert_eq!(v0.checksum_variant(), Some(ChecksumVariant::Bech32));
+    ///
+    /// let v1 = "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at";
+    /// let v1 = UncheckedHrpstring::new(v1).unwrap();
+    /// assert_eq!(v1.checksum_variant(), Some(ChecksumVariant::Bech32m));
+    ///
+    /// let corrupt = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj6";
+    /// let corrupt = UncheckedHrpstring::new(corrupt).unwrap();
+    /// assert_eq!(corrupt.checksum_variant(), None);
+    /// ```
+    pub fn checksum_variant(&self) -> Option<ChecksumVariant> {
+        self.validate_checksum_length(Bech32::CODE_LENGTH, Bech32::CHECKSUM_LENGTH).ok()?;
+
+        // Bech32 and bech32m share the generator, so one engine computes the residue for both.
+        let mut checksum_eng = checksum::Engine::<Bech32>::new();
+        checksum_eng.input_hrp(self.hrp());
+        checksum_eng.input_fes(self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b)));
+
+        let residue = *checksum_eng.residue();
+        if residue == Bech32::TARGET_RESIDUE {
+            Some(ChecksumVariant::Bech32)
+        } else if residue == Bech32m::TARGET_RESIDUE {
+            Some(ChecksumVariant::Bech32m)
+        } else {
+            None
+        }
+    }
+
+    /// Validates that data has a valid checksum for the checksum described by `spec`.
+    ///
+    /// Like [`Self::validate_checksum`] but with the checksum chosen at runtime.
+    pub fn validate_checksum_spec(&self, spec: &dyn ChecksumSpec) -> Result<(), ChecksumError> {
+        let checksum_length = spec.checksum_length();
+        self.validate_checksum_length(spec.code_length(), checksum_length)?;
+        if checksum_length == 0 {
+            return Ok(());
+        }
+
+        let mut fes = self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b));
+        if !spec.verify(self.hrp(), &mut fes) {
+            return Err(ChecksumError::InvalidResidue);
+        }
+        Ok(())
+    }
+
+    /// Checks the string is not longer than `code_length` (if enforced) and the data part is long
+    /// enough to hold a checksum of `checksum_length` characters.
+    fn validate_checksum_length(
+        &self,
+        code_length: usize,
+        checksum_length: usize,
+    ) -> Result<(), ChecksumError> {
+        if self.enforce_code_length && self.hrpstring_length > code_length {
+            return Err(ChecksumError::CodeLength(CodeLengthError {
+                encoded_length: self.hrpstring_length,
+                code_length,
+            }));
+        }
+        if self.data_part_ascii.len() < checksum_length {
+            return Err(ChecksumError::InvalidLength);
+        }
+        Ok(())
+    }
+
     /// Removes the checksum for the `Ck` algorithm and returns an [`CheckedHrpstring`].
     ///
     /// Data must be valid (ie, first call `has_valid_checksum` or `validate_checksum()`). This
@@ -295,16 +665,82 @@ impl<'s> UncheckedHrpstring<'s> {
     /// May panic if data is not valid.
     #[inline]
     pub fn remove_checksum<Ck: Checksum>(self) -> CheckedHrpstring<'s> {
-        let end = self.data_part_ascii.len() - Ck::CHECKSUM_LENGTH;
+        self.remove_checksum_length(Ck::CHECKSUM_LENGTH)
+    }
+
+    /// Removes the last `checksum_length` characters, see [`Self::remove_checksum`].
+    fn remove_checksum_length(self, checksum_length: usize) -> CheckedHrpstring<'s> {
+        let end = self.data_part_ascii.len() - checksum_length;
 
         CheckedHrpstring {
+            original: self.original,
             hrp: self.hrp(),
             ascii: &self.data_part_ascii[..end],
+            checksum_ascii: &self.data_part_ascii[end..],
             hrpstring_length: self.hrpstring_length,
         }
     }
 }
 
+/// An HRP string with valid characters and case, whose checksum is yet to be verified.
+///
+/// Returned by [`UncheckedHrpstring::parse_data_chars`]. Gives access to the human-readable part
+/// and the length of the data part, so that strings can be routed before paying for checksum
+/// verification with [`Self::verify`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::UncheckedHrpstring;
+/// use bech32grs::{hrp, Bech32m};
+///
+/// let addrs = [
+///     "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x",
+///     "grs1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq38vfpt",
+/// ];
+/// for addr in addrs.iter() {
+///     let chars = UncheckedHrpstring::new(addr).expect("valid characters").parse_data_chars();
+///     // Only verify the checksum of mainnet addresses.
+///     if chars.hrp() != hrp::GRS {
+///         continue;
+///     }
+///     assert_eq!(chars.data_len(), 59);
+///     assert!(chars.verify::<Bech32m>().is_ok());
+/// }
+/// ```
+#[derive(Debug)]
+#[must_use = "the checksum is only verified by calling `verify`"]
+pub struct CharCheckedHrpstring<'s> {
+    /// The parsed string, all characters are already known to be valid.
+    inner: UncheckedHrpstring<'s>,
+}
+
+impl<'s> CharCheckedHrpstring<'s> {
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.inner.hrp() }
+
+    /// Returns the data part as ASCII bytes i.e., everything after the separator '1'.
+    ///
+    /// The byte values are guaranteed to be valid bech32 characters. Includes the checksum.
+    #[inline]
+    pub fn data_part_ascii(&self) -> &'s [u8] { self.inner.data_part_ascii() }
+
+    /// Returns the number of characters in the data part, including the checksum.
+    #[inline]
+    pub fn data_len(&self) -> usize { self.inner.data_part_ascii().len() }
+
+    /// Verifies that the checksum is valid for the `Ck` algorithm and returns a [`CheckedHrpstring`].
+    #[inline]
+    pub fn verify<Ck: Checksum>(self) -> Result<CheckedHrpstring<'s>, ChecksumError> {
+        if let Err(e) = self.inner.validate_checksum::<Ck>() {
+            trace_error("CheckedHrpstring", e.code(), self.inner.hrpstring_length, None);
+            return Err(e);
+        }
+        Ok(self.inner.remove_checksum::<Ck>())
+    }
+}
+
 /// An HRP string that has been parsed and had the checksum validated.
 ///
 /// This type does not treat the first byte of the data part in any special way i.e., as the witness
@@ -328,14 +764,19 @@ impl<'s> UncheckedHrpstring<'s> {
 /// // Do something with the encoded data.
 /// let _ = checked.byte_iter();
 /// ```
-#[derive(Debug)]
+#[derive(Clone)]
+#[must_use]
 pub struct CheckedHrpstring<'s> {
+    /// The parsed string, including the checksum.
+    original: &'s str,
     /// The human-readable part, guaranteed to be lowercase ASCII characters.
     hrp: Hrp,
     /// This is ASCII byte values of the parsed string, guaranteed to be valid bech32 characters.
     ///
     /// The characters after the '1' separator and the before the checksum.
     ascii: &'s [u8],
+    /// The checksum characters, everything after `ascii`.
+    checksum_ascii: &'s [u8],
     /// The length of the parsed hrpstring.
     hrpstring_length: usize, // Guaranteed to be <= CK::CODE_LENGTH
 }
@@ -345,18 +786,91 @@ impl<'s> CheckedHrpstring<'s> {
     ///
     /// If you are validating the checksum multiple times consider using [`UncheckedHrpstring`].
     ///
-    /// This is equivalent to `UncheckedHrpstring::new().validate_and_remove_checksum::<CK>()`.
+    /// This is equivalent to `UncheckedHrpstring::new().parse_data_chars().verify::<CK>()`.
     #[inline]
     pub fn new<Ck: Checksum>(s: &'s str) -> Result<Self, CheckedHrpstringError> {
-        let unchecked = UncheckedHrpstring::new(s)?;
-        let checked = unchecked.validate_and_remove_checksum::<Ck>()?;
+        let unchecked = UncheckedHrpstring::new_requiring_data(s, Ck::CHECKSUM_LENGTH > 0)?;
+        let checked = unchecked.parse_data_chars().verify::<Ck>()?;
         Ok(checked)
     }
 
+    /// Parses and validates an HRP string with the checksum chosen at runtime.
+    ///
+    /// Like [`CheckedHrpstring::new`] but for tooling that handles several checksums, possibly of
+    /// different lengths, without knowing which at compile time. The returned value is the same
+    /// as for a [`Checksum`] known at compile time, [`Self::data_part_ascii_no_checksum`] excludes
+    /// the `spec.checksum_length()` checksum characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::checksum::{ChecksumSpec, StaticChecksum};
+    /// use bech32grs::primitives::decode::CheckedHrpstring;
+    /// use bech32grs::{Bech32, Bech32m};
+    ///
+    /// let specs: [&dyn ChecksumSpec; 2] =
+    ///     [&StaticChecksum::<Bech32>::new(), &StaticChecksum::<Bech32m>::new()];
+    ///
+    /// let s = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+    /// let spec = specs.iter().find(|spec| CheckedHrpstring::new_with_spec(s, **spec).is_ok());
+    /// assert_eq!(spec.map(|spec| spec.name()), Some("bech32m"));
+    /// ```
+    #[inline]
+    pub fn new_with_spec(
+        s: &'s str,
+        spec: &dyn ChecksumSpec,
+    ) -> Result<Self, CheckedHrpstringError> {
+        let unchecked = UncheckedHrpstring::new_requiring_data(s, spec.checksum_length() > 0)?;
+        if let Err(e) = unchecked.validate_checksum_spec(spec) {
+            trace_error("CheckedHrpstring", e.code(), unchecked.hrpstring_length, None);
+            return Err(e.into());
+        }
+        Ok(unchecked.remove_checksum_length(spec.checksum_length()))
+    }
+
+    /// Parses and validates an HRP string, including part of the string in the error.
+    ///
+    /// Like [`CheckedHrpstring::new`] but the returned error includes a truncated copy of `s`,
+    /// which is useful when logging errors for many strings.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn new_verbose<Ck: Checksum>(
+        s: &'s str,
+    ) -> Result<Self, DecodeErrorWithInput<CheckedHrpstringError>> {
+        Self::new::<Ck>(s).map_err(|e| DecodeErrorWithInput::new(s, e))
+    }
+
     /// Returns the human-readable part.
     #[inline]
     pub fn hrp(&self) -> Hrp { self.hrp }
 
+    /// Returns the string this was parsed from, exactly as it was given.
+    ///
+    /// Includes the checksum and, if it has been removed with [`Self::remove_witness_version`],
+    /// the witness version.
+    #[inline]
+    pub fn as_original_str(&self) -> &'s str { self.original }
+
+    /// Returns the canonical, lowercase, form of the parsed string.
+    ///
+    /// The checksum is already valid so this only lowercases the original string, it does not
+    /// re-encode. The result is guaranteed to equal re-encoding the human-readable part and data
+    /// part with the same checksum algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::{Bech32, primitives::decode::CheckedHrpstring};
+    ///
+    /// let addr = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+    /// let checked = CheckedHrpstring::new::<Bech32>(addr).unwrap();
+    /// assert_eq!(checked.as_original_str(), addr);
+    /// assert_eq!(checked.to_canonical_string(), "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_canonical_string(&self) -> String { self.original.to_ascii_lowercase() }
+
     /// Returns a partial slice of the data part, as ASCII bytes, everything after the separator '1'
     /// before the checksum.
     ///
@@ -442,8 +956,8 @@ impl<'s> CheckedHrpstring<'s> {
     ///
     /// Converts the ASCII bytes representing field elements to the respective field elements.
     #[inline]
-    pub fn fe32_iter<I: Iterator<Item = u8>>(&self) -> AsciiToFe32Iter {
-        AsciiToFe32Iter { iter: self.ascii.iter().copied() }
+    pub fn fe32_iter<I: Iterator<Item = u8>>(&self) -> AsciiToFe32Iter<'_> {
+        AsciiToFe32Iter::new(self.ascii)
     }
 
     /// Returns an iterator that yields the data part of the parsed bech32 encoded string.
@@ -451,19 +965,35 @@ impl<'s> CheckedHrpstring<'s> {
     /// Converts the ASCII bytes representing field elements to the respective field elements, then
     /// converts the stream of field elements to a stream of bytes.
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter {
-        ByteIter { iter: AsciiToFe32Iter { iter: self.ascii.iter().copied() }.fes_to_bytes() }
+    pub fn byte_iter(&self) -> ByteIter<'_> {
+        ByteIter { iter: AsciiToFe32Iter::new(self.ascii).fes_to_bytes() }
     }
 
     /// Converts this type to a [`SegwitHrpstring`] after validating the witness and HRP.
     #[inline]
-    pub fn validate_segwit(mut self) -> Result<SegwitHrpstring<'s>, SegwitHrpstringError> {
+    pub fn validate_segwit(self) -> Result<SegwitHrpstring<'s>, SegwitHrpstringError> {
+        self.validate_segwit_with_max_length(segwit::MAX_STRING_LENGTH)
+    }
+
+    /// Converts this type to a [`SegwitHrpstring`] like [`Self::validate_segwit`], with a maximum
+    /// length of `max` instead of [`segwit::MAX_STRING_LENGTH`].
+    fn validate_segwit_with_max_length(
+        self,
+        max: usize,
+    ) -> Result<SegwitHrpstring<'s>, SegwitHrpstringError> {
+        let len = self.hrpstring_length;
+        self.parse_segwit(max).map_err(|e| e.traced(len))
+    }
+
+    /// Converts this type to a [`SegwitHrpstring`] like [`Self::validate_segwit_with_max_length`],
+    /// without tracing errors.
+    fn parse_segwit(mut self, max: usize) -> Result<SegwitHrpstring<'s>, SegwitHrpstringError> {
         if self.ascii.is_empty() {
             return Err(SegwitHrpstringError::NoData);
         }
 
-        if self.hrpstring_length > segwit::MAX_STRING_LENGTH {
-            return Err(SegwitHrpstringError::TooLong(self.hrpstring_length));
+        if self.hrpstring_length > max {
+            return Err(SegwitHrpstringError::TooLong { len: self.hrpstring_length, max });
         }
 
         // Unwrap ok since check_characters checked the bech32-ness of this char.
@@ -473,7 +1003,13 @@ impl<'s> CheckedHrpstring<'s> {
         self.validate_segwit_padding()?;
         self.validate_witness_program_length(witness_version)?;
 
-        Ok(SegwitHrpstring { hrp: self.hrp(), witness_version, ascii: self.ascii })
+        Ok(SegwitHrpstring {
+            original: self.original,
+            hrp: self.hrp(),
+            witness_version,
+            ascii: self.ascii,
+            checksum_ascii: self.checksum_ascii,
+        })
     }
 
     /// Validates the segwit padding rules.
@@ -489,14 +1025,13 @@ impl<'s> CheckedHrpstring<'s> {
             return Ok(()); // Empty data implies correct padding.
         }
 
-        let fe_iter = AsciiToFe32Iter { iter: self.ascii.iter().copied() };
-        let padding_len = fe_iter.len() * 5 % 8;
+        let padding_len = self.ascii.len() * 5 % 8;
 
         if padding_len > 4 {
             return Err(PaddingError::TooMuch)?;
         }
 
-        let last_fe = fe_iter.last().expect("checked above");
+        let last_fe = Fe32::from_char_unchecked(*self.ascii.last().expect("checked above"));
         let last_byte = last_fe.0;
 
         let padding_contains_non_zero_bits = match padding_len {
@@ -526,6 +1061,38 @@ impl<'s> CheckedHrpstring<'s> {
     }
 }
 
+impl fmt::Debug for CheckedHrpstring<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CheckedHrpstring")
+            .field("hrp", &self.hrp.as_str())
+            .field("data_len", &self.ascii.len())
+            .finish()
+    }
+}
+
+/// Case insensitive comparison of the human-readable part and the data part, including the checksum.
+impl PartialEq for CheckedHrpstring<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hrp == other.hrp
+            && self.ascii.eq_ignore_ascii_case(other.ascii)
+            && self.checksum_ascii.eq_ignore_ascii_case(other.checksum_ascii)
+    }
+}
+
+impl Eq for CheckedHrpstring<'_> {}
+
+/// Returns `true` if `other` is the canonical, lowercase, form of the parsed string.
+impl PartialEq<str> for CheckedHrpstring<'_> {
+    fn eq(&self, other: &str) -> bool {
+        eq_lowercase(other, &self.hrp, &[self.ascii, self.checksum_ascii])
+    }
+}
+
+impl PartialEq<&str> for CheckedHrpstring<'_> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool { *self == **other }
+}
+
 /// An valid length HRP string that has been parsed, had the checksum validated, had the witness
 /// version validated, had the witness data length checked, and the had witness version and checksum
 /// removed.
@@ -542,8 +1109,11 @@ impl<'s> CheckedHrpstring<'s> {
 /// // Do something with the encoded data.
 /// let _ = segwit.byte_iter();
 /// ```
-#[derive(Debug)]
+#[derive(Clone)]
+#[must_use]
 pub struct SegwitHrpstring<'s> {
+    /// The parsed string, including the witness version and checksum.
+    original: &'s str,
     /// The human-readable part, valid for segwit addresses.
     hrp: Hrp,
     /// The first byte of the parsed data part.
@@ -552,6 +1122,8 @@ pub struct SegwitHrpstring<'s> {
     ///
     /// The characters after the witness version and before the checksum.
     ascii: &'s [u8],
+    /// The checksum characters, everything after `ascii`.
+    checksum_ascii: &'s [u8],
 }
 
 impl<'s> SegwitHrpstring<'s> {
@@ -566,29 +1138,112 @@ impl<'s> SegwitHrpstring<'s> {
     pub fn new(s: &'s str) -> Result<Self, SegwitHrpstringError> {
         let len = s.len();
         if len > segwit::MAX_STRING_LENGTH {
-            return Err(SegwitHrpstringError::TooLong(len));
+            let max = segwit::MAX_STRING_LENGTH;
+            return Err(SegwitHrpstringError::TooLong { len, max }.traced(len));
+        }
+
+        let unchecked = UncheckedHrpstring::new_requiring_data(s, true)?;
+        Self::from_unchecked(unchecked, segwit::MAX_STRING_LENGTH)
+    }
+
+    /// Parses an HRP string with the rules of `profile`, treating the first data character as a
+    /// witness version.
+    ///
+    /// Like [`Self::new`] but the maximum length of the string is taken from `profile` instead of
+    /// being [`segwit::MAX_STRING_LENGTH`]. Parsing with [`DecodeProfile::BIP350_SEGWIT`] accepts
+    /// the same addresses as [`Self::new`].
+    #[inline]
+    pub fn new_with_profile(
+        s: &'s str,
+        profile: DecodeProfile,
+    ) -> Result<Self, SegwitHrpstringError> {
+        let max = profile.max_length.unwrap_or(usize::MAX);
+        Self::from_unchecked(UncheckedHrpstring::new_with_profile(s, profile)?, max)
+    }
+
+    /// Parses an HRP string accepting the character cases allowed by `case`, treating the first
+    /// data character as a witness version.
+    ///
+    /// The same as [`Self::new`] except for the case rules, with [`CasePolicy::AcceptUpper`] this
+    /// accepts the same addresses.
+    #[inline]
+    pub fn new_with_case(s: &'s str, case: CasePolicy) -> Result<Self, SegwitHrpstringError> {
+        let len = s.len();
+        if len > segwit::MAX_STRING_LENGTH {
+            let max = segwit::MAX_STRING_LENGTH;
+            return Err(SegwitHrpstringError::TooLong { len, max }.traced(len));
         }
 
-        let unchecked = UncheckedHrpstring::new(s)?;
+        let profile = DecodeProfile::with_case(case, false);
+        let unchecked = UncheckedHrpstring::new_with_profile(s, profile)?;
+        Self::from_unchecked(unchecked, segwit::MAX_STRING_LENGTH)
+    }
+
+    /// Parses an HRP string that may be up to `max` characters long, treating the first data
+    /// character as a witness version.
+    ///
+    /// Like [`Self::new`] but with a maximum length of `max` instead of
+    /// [`segwit::MAX_STRING_LENGTH`], for chains that use segwit addresses with longer HRPs.
+    /// Strings longer than `max` fail with [`SegwitHrpstringError::TooLong`], like strings longer
+    /// than 90 characters do in [`Self::new`].
+    ///
+    /// Raising the limit weakens error detection: the checksum is only guaranteed to detect any
+    /// error affecting at most 4 characters in strings of up to 90 characters, longer strings are
+    /// more likely to have a mistyped address accepted as valid. Longer strings than the code
+    /// length of [`Bech32m`] (1023 characters) cannot be checksummed, a `max` above it fails with
+    /// [`SegwitHrpstringError::InvalidMaxLength`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::decode::SegwitHrpstring;
+    ///
+    /// // A 95 character address with a 55 character HRP.
+    /// let address = "asidechainwithaverylonghumanreadablepartforitsaddresses1qw508d6qejxtdg4y5r3zarvary0c5xw7k5hv5xn";
+    /// assert!(SegwitHrpstring::new(address).is_err());
+    /// assert!(SegwitHrpstring::new_with_max_length(address, 100).is_ok());
+    /// ```
+    #[inline]
+    pub fn new_with_max_length(s: &'s str, max: usize) -> Result<Self, SegwitHrpstringError> {
+        let len = s.len();
+        if max > Bech32m::CODE_LENGTH {
+            return Err(SegwitHrpstringError::InvalidMaxLength(max).traced(len));
+        }
+        if len > max {
+            return Err(SegwitHrpstringError::TooLong { len, max }.traced(len));
+        }
+
+        let profile = DecodeProfile { max_length: None, ..DecodeProfile::BIP350_SEGWIT };
+        Self::from_unchecked(UncheckedHrpstring::new_with_profile(s, profile)?, max)
+    }
 
+    /// Validates the witness version, checksum and witness program of `unchecked`, which may be up
+    /// to `max` characters long.
+    fn from_unchecked(
+        unchecked: UncheckedHrpstring<'s>,
+        max: usize,
+    ) -> Result<Self, SegwitHrpstringError> {
         let data_part = unchecked.data_part_ascii();
+        let len = unchecked.hrpstring_length;
 
         if data_part.is_empty() {
-            return Err(SegwitHrpstringError::NoData);
+            return Err(SegwitHrpstringError::NoData.traced(len));
         }
 
         // Unwrap ok since check_characters (in `Self::new`) checked the bech32-ness of this char.
         let witness_version = Fe32::from_char(data_part[0].into()).unwrap();
         if witness_version.to_u8() > 16 {
-            return Err(SegwitHrpstringError::InvalidWitnessVersion(witness_version));
+            return Err(SegwitHrpstringError::InvalidWitnessVersion(witness_version).traced(len));
         }
 
-        let checked: CheckedHrpstring<'s> = match witness_version {
-            VERSION_0 => unchecked.validate_and_remove_checksum::<Bech32>()?,
-            _ => unchecked.validate_and_remove_checksum::<Bech32m>()?,
+        let hrp = unchecked.hrp();
+        let checked = match witness_version {
+            VERSION_0 => unchecked.validate_and_remove_checksum::<Bech32>(),
+            _ => unchecked.validate_and_remove_checksum::<Bech32m>(),
         };
+        let checked = checked.map_err(|e| checksum_error(e, hrp, witness_version, len))?;
 
-        checked.validate_segwit()
+        checked.validate_segwit_with_max_length(max)
     }
 
     /// Parses an HRP string, treating the first data character as a witness version.
@@ -605,13 +1260,15 @@ impl<'s> SegwitHrpstring<'s> {
     /// [BIP-350]: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
     #[inline]
     pub fn new_bech32(s: &'s str) -> Result<Self, SegwitHrpstringError> {
-        let unchecked = UncheckedHrpstring::new(s)?;
+        let unchecked = UncheckedHrpstring::new_requiring_data(s, true)?;
         let data_part = unchecked.data_part_ascii();
 
         // Unwrap ok since check_characters (in `Self::new`) checked the bech32-ness of this char.
         let witness_version = Fe32::from_char(data_part[0].into()).unwrap();
         if witness_version.to_u8() > 16 {
-            return Err(SegwitHrpstringError::InvalidWitnessVersion(witness_version));
+            return Err(
+                SegwitHrpstringError::InvalidWitnessVersion(witness_version).traced(s.len())
+            );
         }
 
         let checked = unchecked.validate_and_remove_checksum::<Bech32>()?;
@@ -634,6 +1291,31 @@ impl<'s> SegwitHrpstring<'s> {
     #[inline]
     pub fn witness_version(&self) -> Fe32 { self.witness_version }
 
+    /// Returns the address this was parsed from, exactly as it was given.
+    #[inline]
+    pub fn as_original_str(&self) -> &'s str { self.original }
+
+    /// Returns the canonical, lowercase, form of the parsed address.
+    ///
+    /// The checksum is already valid so this only lowercases the original string, it does not
+    /// re-encode. The result is guaranteed to equal re-encoding the human-readable part, witness
+    /// version and witness program with the same checksum algorithm, i.e. [`crate::segwit::encode`]
+    /// unless parsed by [`Self::new_bech32`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::decode::SegwitHrpstring;
+    ///
+    /// let addr = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+    /// let segwit = SegwitHrpstring::new(addr).unwrap();
+    /// assert_eq!(segwit.as_original_str(), addr);
+    /// assert_eq!(segwit.to_canonical_string(), "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_canonical_string(&self) -> String { self.original.to_ascii_lowercase() }
+
     /// Returns a partial slice of the data part, as ASCII bytes, everything after the witness
     /// version and before the checksum.
     ///
@@ -661,39 +1343,460 @@ impl<'s> SegwitHrpstring<'s> {
     ///
     /// Use `self.witness_version()` to get the witness version.
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter {
-        ByteIter { iter: AsciiToFe32Iter { iter: self.ascii.iter().copied() }.fes_to_bytes() }
+    pub fn byte_iter(&self) -> ByteIter<'_> {
+        ByteIter { iter: AsciiToFe32Iter::new(self.ascii).fes_to_bytes() }
     }
 }
 
-/// Checks whether a given HRP string has data part characters in the bech32 alphabet (incl.
-/// checksum characters), and that the whole string has consistent casing (hrp and data part).
+impl fmt::Debug for SegwitHrpstring<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SegwitHrpstring")
+            .field("hrp", &self.hrp.as_str())
+            .field("witness_version", &self.witness_version)
+            .field("data_len", &self.ascii.len())
+            .finish()
+    }
+}
+
+/// Case insensitive comparison of the human-readable part and the data part, including the witness
+/// version and the checksum.
+impl PartialEq for SegwitHrpstring<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hrp == other.hrp
+            && self.witness_version == other.witness_version
+            && self.ascii.eq_ignore_ascii_case(other.ascii)
+            && self.checksum_ascii.eq_ignore_ascii_case(other.checksum_ascii)
+    }
+}
+
+impl Eq for SegwitHrpstring<'_> {}
+
+/// Returns `true` if `other` is the canonical, lowercase, form of the parsed address.
+impl PartialEq<str> for SegwitHrpstring<'_> {
+    fn eq(&self, other: &str) -> bool {
+        let witness_version = [self.witness_version.to_char() as u8];
+        eq_lowercase(other, &self.hrp, &[&witness_version, self.ascii, self.checksum_ascii])
+    }
+}
+
+impl PartialEq<&str> for SegwitHrpstring<'_> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool { *self == **other }
+}
+
+/// Returns the error for a segwit address of length `len` whose checksum failed with `e`.
+///
+/// If the address is shorter than the usual addresses of its witness version it was probably
+/// truncated, which is reported instead of the checksum error.
+fn checksum_error(
+    e: ChecksumError,
+    hrp: Hrp,
+    witness_version: Fe32,
+    len: usize,
+) -> SegwitHrpstringError {
+    let expected_lengths: &'static [usize] = match witness_version {
+        VERSION_0 => &VERSION_0_ADDRESS_LENGTHS[hrp.len()],
+        VERSION_1 => &VERSION_1_ADDRESS_LENGTHS[hrp.len()],
+        _ => return SegwitHrpstringError::Checksum(e),
+    };
+    let longest = expected_lengths[expected_lengths.len() - 1];
+    if len < longest && !expected_lengths.contains(&len) {
+        SegwitHrpstringError::ProbablyTruncated { expected_lengths, got: len }
+    } else {
+        SegwitHrpstringError::Checksum(e)
+    }
+}
+
+/// The lengths of segwit version 0 addresses, with 20 and 32 byte programs, indexed by the length
+/// of the human-readable part.
+static VERSION_0_ADDRESS_LENGTHS: [[usize; 2]; hrp::MAX_HRP_LENGTH + 1] = {
+    let mut table = [[0; 2]; hrp::MAX_HRP_LENGTH + 1];
+    let mut i = 0;
+    while i <= hrp::MAX_HRP_LENGTH {
+        table[i] = [
+            address_length(i, segwit::VERSION_0_PROGRAM_LENGTHS[0]),
+            address_length(i, segwit::VERSION_0_PROGRAM_LENGTHS[1]),
+        ];
+        i += 1;
+    }
+    table
+};
+
+/// The lengths of segwit version 1 (taproot) addresses, with 32 byte programs, indexed by the
+/// length of the human-readable part.
+static VERSION_1_ADDRESS_LENGTHS: [[usize; 1]; hrp::MAX_HRP_LENGTH + 1] = {
+    let mut table = [[0; 1]; hrp::MAX_HRP_LENGTH + 1];
+    let mut i = 0;
+    while i <= hrp::MAX_HRP_LENGTH {
+        table[i] = [address_length(i, 32)];
+        i += 1;
+    }
+    table
+};
+
+/// The lengths of segwit addresses, with programs of every valid length, indexed by the length of
+/// the human-readable part. Sorted, the longer ones exceed the maximum address length.
+static ADDRESS_LENGTHS: [[usize; PROGRAM_LENGTH_COUNT]; hrp::MAX_HRP_LENGTH + 1] = {
+    let mut table = [[0; PROGRAM_LENGTH_COUNT]; hrp::MAX_HRP_LENGTH + 1];
+    let mut i = 0;
+    while i <= hrp::MAX_HRP_LENGTH {
+        let mut j = 0;
+        while j < PROGRAM_LENGTH_COUNT {
+            table[i][j] = address_length(i, segwit::MIN_WITNESS_PROGRAM_LENGTH + j);
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+};
+
+/// The number of valid witness program lengths.
+const PROGRAM_LENGTH_COUNT: usize =
+    segwit::MAX_WITNESS_PROGRAM_LENGTH - segwit::MIN_WITNESS_PROGRAM_LENGTH + 1;
+
+/// Returns the lengths of segwit addresses, of any witness version, with a human-readable part of
+/// `hrp_len` characters. Sorted, and none are longer than [`segwit::MAX_STRING_LENGTH`].
+pub(crate) fn address_lengths(hrp_len: usize) -> &'static [usize] {
+    let lengths = &ADDRESS_LENGTHS[hrp_len];
+    let n = lengths.iter().take_while(|&&len| len <= segwit::MAX_STRING_LENGTH).count();
+    &lengths[..n]
+}
+
+/// Returns the length of a segwit address with a human-readable part of `hrp_len` characters and
+/// a witness program of `program_len` bytes.
+const fn address_length(hrp_len: usize, program_len: usize) -> usize {
+    // The separator, the witness version, the program and the checksum.
+    hrp_len + 1 + 1 + (program_len * 8 + 4) / 5 + segwit::CHECKSUM_LENGTH
+}
+
+/// Returns `true` if `s` is the lowercase hrpstring made of `hrp`, the separator and `data_part`.
+fn eq_lowercase(s: &str, hrp: &Hrp, data_part: &[&[u8]]) -> bool {
+    let data_part = data_part.iter().flat_map(|ascii| ascii.iter().map(u8::to_ascii_lowercase));
+    s.bytes().eq(hrp.lowercase_byte_iter().chain(iter::once(SEP as u8)).chain(data_part))
+}
+
+/// Emits a `tracing` debug event for a decode error, if the `tracing` feature is enabled.
+///
+/// Logs the error code, the length of the input and, when known, the byte position of an invalid
+/// character. Never logs the input itself.
+#[inline]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn trace_error(
+    parser: &'static str,
+    code: &'static str,
+    input_len: usize,
+    position: Option<usize>,
+) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(parser, code, input_len, position, "decode failed");
+}
+
+/// Checks whether a given HRP string has data part characters in the bech32 alphabet (incl.
+/// checksum characters), and that the whole string has consistent casing (hrp and data part).
+///
+/// Valid strings are checked [`simd::LANES`] bytes at a time using [`simd::classify`] if the CPU
+/// supports it, a chunk at a time by [`check_characters_chunked`] otherwise. Only if the string is
+/// invalid do we fall back to [`check_characters_scalar`] to find out which error to return. All
+/// these functions therefore always return the same result.
+///
+/// Also checks whether the hrp is made of valid hrp characters, see [`Separator`].
 ///
 /// # Returns
 ///
 /// The byte-index into the string where the '1' separator occurs, or an error if it does not.
-fn check_characters(s: &str) -> Result<usize, CharError> {
+#[inline]
+fn check_characters(s: &str) -> Result<Separator, CharError> {
+    if simd::available() {
+        check_characters_simd(s, simd::classify)
+    } else {
+        check_characters_chunked(s)
+    }
+}
+
+/// Implements [`check_characters`] with the given block classifier, so both can be tested.
+#[inline]
+fn check_characters_simd<F>(s: &str, classify: F) -> Result<Separator, CharError>
+where
+    F: Fn(&[u8; simd::LANES]) -> simd::Masks,
+{
+    // Bits of the bytes that are in the wrong class, and of the bytes that have a case.
+    let mut not_bech32 = 0;
+    let mut not_hrp = 0;
+    let mut upper = 0;
+    let mut lower = 0;
+    let mut sep_pos = None;
+    // Walk backwards from the end of the string, the data part is everything after the last '1'.
+    // Most of it is in whole blocks of bech32 characters, the first block that is not (usually the
+    // one with the separator) and everything before it is classified again below.
+    let mut chunk_pos = s.len();
+    for chunk in s.as_bytes().rchunks_exact(simd::LANES) {
+        let masks = classify(&simd::load(chunk));
+        if masks.bech32 != !0 {
+            break;
+        }
+        upper |= masks.upper;
+        lower |= masks.lower;
+        chunk_pos -= simd::LANES;
+    }
+    for chunk in s.as_bytes()[..chunk_pos].rchunks(simd::LANES) {
+        chunk_pos -= chunk.len();
+        let masks = classify(&simd::load(chunk));
+        // The bits of the data part bytes in this block, the rest are hrp.
+        let data = match sep_pos {
+            Some(_) => 0,
+            None if masks.sep != 0 => {
+                let pos = 15 - masks.sep.leading_zeros();
+                sep_pos = Some(chunk_pos + pos as usize);
+                // Two shifts since shifting a `u16` by 16 overflows.
+                !0_u16 << pos << 1
+            }
+            None => !0,
+        };
+        not_bech32 |= data & !masks.bech32;
+        not_hrp |= !data & !masks.hrp;
+        upper |= masks.upper;
+        lower |= masks.lower;
+    }
+
+    match sep_pos {
+        Some(pos) if not_bech32 == 0 && (upper == 0 || lower == 0) =>
+            Ok(Separator { pos, valid_hrp_bytes: not_hrp == 0 }),
+        // Only use the hrp flag from the fast path, this is a bit slower but always correct.
+        _ => check_characters_scalar(s).map(|pos| Separator { pos, valid_hrp_bytes: false }),
+    }
+}
+
+/// Implements [`check_characters`] a chunk of bytes at a time with branch free code the compiler
+/// can vectorize, used when [`simd`] is not available.
+fn check_characters_chunked(s: &str) -> Result<Separator, CharError> {
+    // Flags common to all data part characters, and flags set by any character in the string.
+    let mut all = CLASS_BECH32;
+    let mut any = 0;
+    let mut sep_pos = None;
+    // Walk backwards from the end of the string, the data part is everything after the last '1'.
+    let mut chunk_pos = s.len();
+    for chunk in s.as_bytes().rchunks(CHUNK_SIZE) {
+        chunk_pos -= chunk.len();
+        let block = pad_chunk(chunk);
+        // Once the separator is found the rest of the string is hrp, see `classify_block`.
+        let data_start = if sep_pos.is_some() { CHUNK_SIZE } else { 0 };
+        let (mut chunk_all, mut chunk_any) = classify_block(&block, data_start);
+        if sep_pos.is_none() && chunk_any & CLASS_SEP != 0 {
+            // Only bytes after the separator have to be in the bech32 alphabet.
+            let pos = rfind_separator(&block).unwrap_or(0);
+            let (data_all, data_any) = classify_block(&block, pos + 1);
+            chunk_all = data_all;
+            chunk_any = data_any;
+            sep_pos = Some(chunk_pos + pos);
+        }
+        all &= chunk_all;
+        any |= chunk_any;
+    }
+
+    match sep_pos {
+        Some(pos) if all & CLASS_BECH32 != 0 && any & CLASS_CASE != CLASS_CASE =>
+            Ok(Separator { pos, valid_hrp_bytes: all & CLASS_HRP != 0 }),
+        // Only use the hrp flag from the fast path, this is a bit slower but always correct.
+        _ => check_characters_scalar(s).map(|pos| Separator { pos, valid_hrp_bytes: false }),
+    }
+}
+
+/// Checks the errors that only depend on where the separator is.
+///
+/// These are checked before anything else (other than the maximum length of a [`DecodeProfile`]),
+/// so that e.g. `""`, `"1"` and `"grs1"` always give [`CharError::Empty`],
+/// [`CharError::NothingAfterSeparator`] and [`CharError::NothingAfterSeparator`] respectively,
+/// whatever else is wrong with them.
+fn check_separator(s: &str, allow_empty_data: bool) -> Result<(), CharError> {
+    match s.as_bytes().last() {
+        None => Err(CharError::Empty),
+        Some(&last) if last == SEP as u8 =>
+            if allow_empty_data {
+                Ok(())
+            } else {
+                Err(CharError::NothingAfterSeparator)
+            },
+        Some(_) if !s.as_bytes().contains(&(SEP as u8)) => Err(CharError::MissingSeparator),
+        Some(_) => Ok(()),
+    }
+}
+
+/// The result of [`check_characters`].
+struct Separator {
+    /// The byte-index into the string where the '1' separator occurs.
+    pos: usize,
+    /// Whether every byte before the separator is a valid hrp character (ASCII 33 to 126).
+    ///
+    /// If `false` the hrp may still be valid, it was just not checked.
+    valid_hrp_bytes: bool,
+}
+
+impl Separator {
+    /// Parses the hrp of `s`, the string this separator was found in.
+    #[inline]
+    fn parse_hrp(&self, s: &str) -> Result<Hrp, hrp::Error> {
+        let hrp = &s[..self.pos];
+        // Only parse the hrp again if it is invalid, to get the error.
+        if self.valid_hrp_bytes && (1..=hrp::MAX_HRP_LENGTH).contains(&hrp.len()) {
+            Ok(Hrp::parse_unchecked(hrp))
+        } else {
+            Hrp::parse(hrp)
+        }
+    }
+}
+
+/// Returns [`UncheckedHrpstringError::HrpMixedCase`] if `e` is a mixed case error caused by the hrp.
+///
+/// Only called once [`check_characters`] has failed, so parsing valid strings is not slowed down.
+fn hrp_case_error(s: &str, e: CharError) -> UncheckedHrpstringError {
+    if e == CharError::MixedCase {
+        if let Some(e) = s.rfind(SEP).and_then(|pos| HrpMixedCaseError::new(&s[..pos])) {
+            return UncheckedHrpstringError::HrpMixedCase(e);
+        }
+    }
+    e.into()
+}
+
+/// Returns [`UncheckedHrpstringError::HrpSplit`] if the hrp of `s`, which ends at `sep_pos`,
+/// contains other separators, otherwise [`UncheckedHrpstringError::Hrp`].
+fn hrp_split_error(s: &str, sep_pos: usize, e: hrp::Error) -> UncheckedHrpstringError {
+    let hrp = s.get(..sep_pos).unwrap_or_default();
+    if let hrp::Error::InvalidAsciiByte(byte) = e {
+        // The hrp starts the string and `e` is for the first invalid byte, so this is its position.
+        if let Some(pos) = hrp.bytes().position(|b| b == byte).filter(|_| is_control(byte)) {
+            return CharError::ControlCharacter { byte, pos }.into();
+        }
+    }
+    if hrp.contains(SEP) {
+        HrpSplitError::new(hrp, e).into()
+    } else {
+        e.into()
+    }
+}
+
+/// Parses `hrp` as if it were lowercase, so it may be mixed case.
+fn parse_hrp_insensitive(hrp: &str) -> Result<Hrp, hrp::Error> {
+    let mut buf = [0_u8; hrp::MAX_HRP_LENGTH];
+    match buf.get_mut(..hrp.len()) {
+        Some(lower) => {
+            lower.copy_from_slice(hrp.as_bytes());
+            lower.make_ascii_lowercase();
+            Hrp::parse(str::from_utf8(lower).expect("lowercasing ASCII keeps UTF-8 valid"))
+        }
+        None => Hrp::parse(hrp), // Too long, for the error.
+    }
+}
+
+/// Returns the index of the last separator in `block`, searching a word at a time.
+#[inline]
+fn rfind_separator(block: &[u8; CHUNK_SIZE]) -> Option<usize> {
+    const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+    const SEPS: u64 = u64::from_le_bytes([SEP as u8; 8]);
+
+    for (i, chunk) in block.chunks_exact(8).enumerate().rev() {
+        let mut word = [0; 8];
+        word.copy_from_slice(chunk);
+        // Bytes equal to the separator are zero after the xor.
+        let x = u64::from_le_bytes(word) ^ SEPS;
+        // Sets the high bit of the bytes of `x` that are zero, and only those bytes (unlike the
+        // well known `(x - 0x01..) & !x & 0x80..` which can have false positives above a match).
+        let zeros = !(((x & LOW_BITS) + LOW_BITS) | x | LOW_BITS);
+        if zeros != 0 {
+            return Some(i * 8 + (63 - zeros.leading_zeros() as usize) / 8);
+        }
+    }
+    None
+}
+
+/// Copies `chunk` into a full size block, a fixed length is what lets the compiler vectorize.
+///
+/// Pads with '0' which is in the bech32 alphabet and has no case, i.e. does not change the flags.
+#[inline]
+fn pad_chunk(chunk: &[u8]) -> [u8; CHUNK_SIZE] {
+    match <[u8; CHUNK_SIZE]>::try_from(chunk) {
+        Ok(block) => block,
+        Err(_) => {
+            let mut block = [b'0'; CHUNK_SIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            block
+        }
+    }
+}
+
+/// Returns the flags common to all bytes in `block`, and the flags set by any byte in `block`.
+///
+/// Bytes before `data_start` are part of the hrp, they are treated as if in the bech32 alphabet.
+/// Bytes after it are treated as if they were valid hrp characters, which they are if they are in
+/// the bech32 alphabet.
+#[inline]
+fn classify_block(block: &[u8; CHUNK_SIZE], data_start: usize) -> (u8, u8) {
+    let mut all = CLASS_BECH32;
+    let mut any = 0;
+    for (i, &b) in block.iter().enumerate() {
+        let in_hrp = u8::from(i < data_start);
+        let class = classify(b) | (in_hrp * CLASS_BECH32) | ((1 - in_hrp) * CLASS_HRP);
+        all &= class;
+        any |= class;
+    }
+    (all, any)
+}
+
+/// Returns the character class flags of `b`.
+///
+/// The bech32 and case flags come from [`ASCII_TABLE`], the same lookup that converts characters
+/// to field elements, the separator and hrp flags fill the bits of the numeric value.
+#[inline]
+fn classify(b: u8) -> u8 {
+    (ASCII_TABLE[usize::from(b)] & !ASCII_VALUE)
+        | u8::from(b == SEP as u8)
+        | u8::from(b.wrapping_sub(33) < 94) << 1
+}
+
+/// Number of bytes classified at a time by [`check_characters_chunked`].
+const CHUNK_SIZE: usize = 32;
+
+/// Set by [`classify`] for characters in the bech32 alphabet (either case).
+const CLASS_BECH32: u8 = ASCII_BECH32;
+/// Set by [`classify`] for ASCII uppercase characters.
+const CLASS_UPPER: u8 = ASCII_UPPER;
+/// Set by [`classify`] for ASCII lowercase characters.
+const CLASS_LOWER: u8 = ASCII_LOWER;
+/// Both case flags, if both are set in a string it is mixed case.
+const CLASS_CASE: u8 = CLASS_UPPER | CLASS_LOWER;
+/// Set by [`classify`] for the separator character.
+const CLASS_SEP: u8 = 0b0001;
+/// Set by [`classify`] for valid hrp characters.
+const CLASS_HRP: u8 = 0b0010;
+
+/// Returns whether `b` is an ASCII control character, reported as [`CharError::ControlCharacter`].
+fn is_control(b: u8) -> bool { b < 0x20 || b == 0x7f }
+
+/// The reference implementation of [`check_characters`], one character at a time.
+fn check_characters_scalar(s: &str) -> Result<usize, CharError> {
     use CharError::*;
 
-    let mut has_upper = false;
-    let mut has_lower = false;
+    // Case bits are OR-accumulated and only checked once the whole string has been seen.
+    let mut case = 0;
     let mut req_bech32 = true;
     let mut sep_pos = None;
-    for (n, ch) in s.char_indices().rev() {
-        if ch == SEP && sep_pos.is_none() {
+    for (n, &b) in s.as_bytes().iter().enumerate().rev() {
+        if b == SEP as u8 && sep_pos.is_none() {
             req_bech32 = false;
             sep_pos = Some(n);
         }
-        if req_bech32 {
-            Fe32::from_char(ch).map_err(|_| InvalidChar(ch))?;
-        }
-        if ch.is_ascii_uppercase() {
-            has_upper = true;
-        } else if ch.is_ascii_lowercase() {
-            has_lower = true;
+        // Bytes of non-ASCII chars are in no class, so they are rejected in the data part.
+        let class = classify(b);
+        if req_bech32 && class & CLASS_BECH32 == 0 {
+            // Iterating backwards, the first byte of the data part we reject ends a char.
+            if is_control(b) {
+                return Err(ControlCharacter { byte: b, pos: n });
+            }
+            let ch = s[..=n].chars().next_back().expect("n is a valid index");
+            return Err(InvalidChar(ch));
         }
+        case |= class;
     }
-    if has_upper && has_lower {
+    if case & CLASS_CASE == CLASS_CASE {
         Err(MixedCase)
     } else if let Some(pos) = sep_pos {
         Ok(pos)
@@ -703,6 +1806,7 @@ fn check_characters(s: &str) -> Result<usize, CharError> {
 }
 
 /// An iterator over a parsed HRP string data as bytes.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct ByteIter<'s> {
     iter: FesToBytes<AsciiToFe32Iter<'s>>,
 }
@@ -721,6 +1825,7 @@ impl<'s> ExactSizeIterator for ByteIter<'s> {
 }
 
 /// An iterator over a parsed HRP string data as field elements.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct Fe32Iter<'s> {
     iter: AsciiToFe32Iter<'s>,
 }
@@ -736,32 +1841,61 @@ impl<'s> Iterator for Fe32Iter<'s> {
 /// Iterator adaptor that maps an iterator of valid bech32 character ASCII bytes to an
 /// iterator of field elements.
 ///
-/// # Panics
+/// Uppercase and lowercase characters are mapped the same way, uppercase input is not converted to
+/// lowercase first. [`Iterator::fold`], and the methods that use it such as `for_each`, converts
+/// the characters 16 at a time.
 ///
-/// If any `u8` in the input iterator is out of range for an [`Fe32`]. Should only be used on data
-/// that has already been checked for validity (eg, by using `check_characters`).
+/// Only created for data that has already been checked for validity (eg, by `check_characters`),
+/// the field elements yielded for any other bytes are unspecified.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct AsciiToFe32Iter<'s> {
-    iter: iter::Copied<slice::Iter<'s, u8>>,
+    ascii: &'s [u8],
+}
+
+impl<'s> AsciiToFe32Iter<'s> {
+    /// Creates an iterator over the field elements of the bech32 characters `ascii`.
+    #[inline]
+    fn new(ascii: &'s [u8]) -> Self { Self { ascii } }
 }
 
 impl<'s> Iterator for AsciiToFe32Iter<'s> {
     type Item = Fe32;
     #[inline]
-    fn next(&mut self) -> Option<Fe32> { self.iter.next().map(Fe32::from_char_unchecked) }
+    fn next(&mut self) -> Option<Fe32> {
+        let (&b, rest) = self.ascii.split_first()?;
+        self.ascii = rest;
+        Some(Fe32::from_char_unchecked(b))
+    }
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         // Each ASCII character is an fe32 so iterators are the same size.
-        self.iter.size_hint()
+        (self.ascii.len(), Some(self.ascii.len()))
+    }
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Fe32) -> B,
+    {
+        let mut acc = init;
+        let mut chunks = self.ascii.chunks_exact(simd::LANES);
+        for chunk in &mut chunks {
+            // `simd::to_values` only returns values less than 32.
+            for &value in simd::to_values(&simd::load(chunk)).iter() {
+                acc = f(acc, Fe32(value));
+            }
+        }
+        chunks.remainder().iter().fold(acc, |acc, &b| f(acc, Fe32::from_char_unchecked(b)))
     }
 }
 
 impl<'s> ExactSizeIterator for AsciiToFe32Iter<'s> {
     #[inline]
-    fn len(&self) -> usize { self.iter.len() }
+    fn len(&self) -> usize { self.ascii.len() }
 }
 
 /// An error while constructing a [`SegwitHrpstring`] type.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum SegwitHrpstringError {
     /// Error while parsing the encoded address string.
@@ -769,7 +1903,15 @@ pub enum SegwitHrpstringError {
     /// No data found after removing the checksum.
     NoData,
     /// String exceeds maximum allowed length.
-    TooLong(usize),
+    TooLong {
+        /// The length of the string.
+        len: usize,
+        /// The maximum length of the string.
+        max: usize,
+    },
+    /// The maximum length passed to [`SegwitHrpstring::new_with_max_length`] is greater than the
+    /// code length of [`Bech32m`].
+    InvalidMaxLength(usize),
     /// Invalid witness version (must be 0-16 inclusive).
     InvalidWitnessVersion(Fe32),
     /// Invalid padding on the witness data.
@@ -778,6 +1920,78 @@ pub enum SegwitHrpstringError {
     WitnessLength(WitnessLengthError),
     /// Invalid checksum.
     Checksum(ChecksumError),
+    /// A valid address for a different human-readable part than the one expected, for example a
+    /// testnet address where a mainnet address was expected.
+    WrongHrp {
+        /// The network whose human-readable part was expected.
+        expected: KnownHrp,
+        /// The network of the address, `None` if its human-readable part is not a known network.
+        got: Option<KnownHrp>,
+    },
+    /// Invalid checksum on an address shorter than the usual addresses of its witness version,
+    /// probably because the end of the address was lost when it was copied.
+    ///
+    /// Only reported for witness versions 0 and 1, the versions with usual program lengths.
+    ProbablyTruncated {
+        /// The lengths of addresses with the same human-readable part and witness version.
+        expected_lengths: &'static [usize],
+        /// The length of the address.
+        got: usize,
+    },
+}
+
+impl SegwitHrpstringError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Emits a `tracing` event for this error on an input of length `input_len`, see [`trace_error`].
+    fn traced(self, input_len: usize) -> Self {
+        trace_error("SegwitHrpstring", self.code(), input_len, None);
+        self
+    }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use SegwitHrpstringError::*;
+
+        match *self {
+            Unchecked(ref e) => e.error_code(),
+            NoData => Code::NoData,
+            TooLong { .. } => Code::SegwitTooLong,
+            InvalidMaxLength(_) => Code::MaxLengthInvalid,
+            InvalidWitnessVersion(_) => Code::WitnessVersionInvalid,
+            Padding(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+            Checksum(ref e) => e.error_code(),
+            WrongHrp { .. } => Code::WrongHrp,
+            ProbablyTruncated { .. } => Code::ProbablyTruncated,
+        }
+    }
+
+    /// Returns the coarse category of this error.
+    ///
+    /// Error variants may be added in future releases, matching on the returned kind instead of on
+    /// the error itself keeps downstream code working when they are.
+    pub fn kind(&self) -> SegwitErrorKind {
+        use SegwitHrpstringError::*;
+
+        match *self {
+            Unchecked(UncheckedHrpstringError::Char(_)) => SegwitErrorKind::Char,
+            Unchecked(UncheckedHrpstringError::Hrp(_))
+            | Unchecked(UncheckedHrpstringError::HrpSplit(_))
+            | Unchecked(UncheckedHrpstringError::HrpMixedCase(_))
+            | WrongHrp { .. } => SegwitErrorKind::Hrp,
+            Unchecked(UncheckedHrpstringError::TooLong(_)) => SegwitErrorKind::TooLong,
+            // Without any data there is no witness version.
+            NoData | InvalidWitnessVersion(_) => SegwitErrorKind::WitnessVersion,
+            TooLong { .. } | InvalidMaxLength(_) => SegwitErrorKind::TooLong,
+            Padding(_) => SegwitErrorKind::Padding,
+            WitnessLength(_) => SegwitErrorKind::WitnessLength,
+            Checksum(ChecksumError::CodeLength(_)) => SegwitErrorKind::TooLong,
+            Checksum(_) | ProbablyTruncated { .. } => SegwitErrorKind::Checksum,
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -788,20 +2002,35 @@ impl fmt::Display for SegwitHrpstringError {
         match *self {
             Unchecked(ref e) => write_err!(f, "parsing unchecked hrpstring failed"; e),
             NoData => write!(f, "no data found after removing the checksum"),
-            TooLong(len) =>
-                write!(f, "encoded length {} exceeds spec limit {} chars", len, segwit::MAX_STRING_LENGTH),
+            TooLong { len, max } => write!(f, "encoded length {} exceeds the maximum length {}", len, max),
+            InvalidMaxLength(max) =>
+                write!(f, "maximum length {} exceeds the bech32m code length {}", max, Bech32m::CODE_LENGTH),
             InvalidWitnessVersion(fe) =>
                 write!(f, "invalid segwit witness version: {} (bech32 character: '{}')", fe.to_u8(), fe),
             Padding(ref e) => write_err!(f, "invalid padding on the witness data"; e),
             WitnessLength(ref e) => write_err!(f, "invalid witness length"; e),
             Checksum(ref e) => write_err!(f, "invalid checksum"; e),
+            WrongHrp { expected, got: Some(got) } =>
+                write!(f, "address is for {} but expected {}", got.to_hrp(), expected.to_hrp()),
+            WrongHrp { expected, got: None } =>
+                write!(f, "address is for an unknown network but expected {}", expected.to_hrp()),
+            ProbablyTruncated { expected_lengths, got } => {
+                write!(f, "invalid checksum, the address is {} characters long but expected ", got)?;
+                for (i, len) in expected_lengths.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" or ")?;
+                    }
+                    write!(f, "{}", len)?;
+                }
+                f.write_str("; the address is probably truncated")
+            }
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for SegwitHrpstringError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for SegwitHrpstringError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use SegwitHrpstringError::*;
 
         match *self {
@@ -809,11 +2038,27 @@ impl std::error::Error for SegwitHrpstringError {
             Padding(ref e) => Some(e),
             WitnessLength(ref e) => Some(e),
             Checksum(ref e) => Some(e),
-            NoData | TooLong(_) | InvalidWitnessVersion(_) => None,
+            NoData
+            | TooLong { .. }
+            | InvalidMaxLength(_)
+            | InvalidWitnessVersion(_)
+            | WrongHrp { .. }
+            | ProbablyTruncated { .. } => None,
         }
     }
 }
 
+/// Converts to an [`std::io::Error`] of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// The original error can be recovered with [`get_ref`](std::io::Error::get_ref) and a downcast.
+#[cfg(feature = "std")]
+impl From<SegwitHrpstringError> for std::io::Error {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
 impl From<UncheckedHrpstringError> for SegwitHrpstringError {
     #[inline]
     fn from(e: UncheckedHrpstringError) -> Self { Self::Unchecked(e) }
@@ -834,8 +2079,31 @@ impl From<ChecksumError> for SegwitHrpstringError {
     fn from(e: ChecksumError) -> Self { Self::Checksum(e) }
 }
 
+/// The category of a [`SegwitHrpstringError`], see [`SegwitHrpstringError::kind`].
+///
+/// Unlike the error types this enum is exhaustive, new kinds are only added in breaking releases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SegwitErrorKind {
+    /// The human-readable part is invalid.
+    Hrp,
+    /// The string contains an invalid character, mixed case, or is missing the separator.
+    Char,
+    /// The checksum is invalid.
+    Checksum,
+    /// The witness program has invalid padding.
+    Padding,
+    /// The witness version is missing or invalid.
+    WitnessVersion,
+    /// The witness program has an invalid length.
+    WitnessLength,
+    /// The string exceeds the maximum allowed length.
+    TooLong,
+}
+
 /// An error while constructing a [`CheckedHrpstring`] type.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum CheckedHrpstringError {
     /// Error while parsing the encoded address string.
@@ -844,6 +2112,22 @@ pub enum CheckedHrpstringError {
     Checksum(ChecksumError),
 }
 
+impl CheckedHrpstringError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use CheckedHrpstringError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            Checksum(ref e) => e.error_code(),
+        }
+    }
+}
+
 impl fmt::Display for CheckedHrpstringError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use CheckedHrpstringError::*;
@@ -855,9 +2139,9 @@ impl fmt::Display for CheckedHrpstringError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for CheckedHrpstringError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for CheckedHrpstringError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use CheckedHrpstringError::*;
 
         match *self {
@@ -867,6 +2151,17 @@ impl std::error::Error for CheckedHrpstringError {
     }
 }
 
+/// Converts to an [`std::io::Error`] of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// The original error can be recovered with [`get_ref`](std::io::Error::get_ref) and a downcast.
+#[cfg(feature = "std")]
+impl From<CheckedHrpstringError> for std::io::Error {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
 impl From<UncheckedHrpstringError> for CheckedHrpstringError {
     #[inline]
     fn from(e: UncheckedHrpstringError) -> Self { Self::Parse(e) }
@@ -878,13 +2173,78 @@ impl From<ChecksumError> for CheckedHrpstringError {
 }
 
 /// Errors when parsing a bech32 encoded string.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum UncheckedHrpstringError {
     /// An error with the characters of the input string.
     Char(CharError),
     /// The human-readable part is invalid.
     Hrp(hrp::Error),
+    /// The human-readable part is invalid and the string contains more than one separator.
+    ///
+    /// Returned instead of [`Self::Hrp`] when the string could have been split elsewhere, so that
+    /// the error can say where it was split.
+    HrpSplit(HrpSplitError),
+    /// The human-readable part mixes upper and lower case.
+    ///
+    /// Returned instead of [`CharError::MixedCase`] when the human-readable part is otherwise valid,
+    /// so that the error can name it.
+    HrpMixedCase(HrpMixedCaseError),
+    /// The string exceeds the maximum length of the [`DecodeProfile`].
+    TooLong(CodeLengthError),
+}
+
+impl UncheckedHrpstringError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Emits a `tracing` event for this error while parsing `s`, see [`trace_error`].
+    fn traced(self, s: &str) -> Self {
+        trace_error("UncheckedHrpstring", self.code(), s.len(), self.position(s));
+        self
+    }
+
+    /// Returns the byte position in `s` of the character this error is for, if it is for one.
+    ///
+    /// Data part characters are checked from the end of the string, so an invalid one is the last
+    /// occurrence of that character, while human-readable part characters are checked from the
+    /// start. For mixed case this is the first letter with a different case to the first letter.
+    pub(crate) fn position(&self, s: &str) -> Option<usize> {
+        use UncheckedHrpstringError::*;
+
+        let hrp_error = match *self {
+            Hrp(ref e) => Some(e),
+            HrpSplit(ref e) => Some(e.hrp_error()),
+            _ => None,
+        };
+        match (self, hrp_error) {
+            (Char(CharError::InvalidChar(c)), _) => s.rfind(*c),
+            (_, Some(hrp::Error::NonAsciiChar(c))) => s.find(*c),
+            (_, Some(hrp::Error::InvalidAsciiByte(b))) => s.bytes().position(|x| x == *b),
+            (Char(CharError::ControlCharacter { pos, .. }), _)
+            | (Char(CharError::NotUppercase { pos }), _) => Some(*pos),
+            (Char(CharError::MixedCase), _) | (HrpMixedCase(_), _) => {
+                let upper = s.bytes().find(u8::is_ascii_alphabetic)?.is_ascii_uppercase();
+                s.bytes().position(|b| b.is_ascii_alphabetic() && b.is_ascii_uppercase() != upper)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use UncheckedHrpstringError::*;
+
+        match *self {
+            Char(ref e) => e.error_code(),
+            Hrp(ref e) => e.error_code(),
+            HrpSplit(ref e) => e.error_code(),
+            HrpMixedCase(ref e) => e.error_code(),
+            TooLong(ref e) => e.error_code(),
+        }
+    }
 }
 
 impl fmt::Display for UncheckedHrpstringError {
@@ -894,22 +2254,39 @@ impl fmt::Display for UncheckedHrpstringError {
         match *self {
             Char(ref e) => write_err!(f, "character error"; e),
             Hrp(ref e) => write_err!(f, "invalid human-readable part"; e),
+            HrpSplit(ref e) => write_err!(f, "invalid human-readable part"; e),
+            HrpMixedCase(ref e) => write_err!(f, "invalid human-readable part"; e),
+            TooLong(ref e) => write_err!(f, "string exceeds maximum allowed length"; e),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for UncheckedHrpstringError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for UncheckedHrpstringError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use UncheckedHrpstringError::*;
 
         match *self {
             Char(ref e) => Some(e),
             Hrp(ref e) => Some(e),
+            HrpSplit(ref e) => Some(e),
+            HrpMixedCase(ref e) => Some(e),
+            TooLong(ref e) => Some(e),
         }
     }
 }
 
+/// Converts to an [`std::io::Error`] of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// The original error can be recovered with [`get_ref`](std::io::Error::get_ref) and a downcast.
+#[cfg(feature = "std")]
+impl From<UncheckedHrpstringError> for std::io::Error {
+    #[inline]
+    fn from(e: UncheckedHrpstringError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
 impl From<CharError> for UncheckedHrpstringError {
     #[inline]
     fn from(e: CharError) -> Self { Self::Char(e) }
@@ -920,18 +2297,70 @@ impl From<hrp::Error> for UncheckedHrpstringError {
     fn from(e: hrp::Error) -> Self { Self::Hrp(e) }
 }
 
+impl From<HrpSplitError> for UncheckedHrpstringError {
+    #[inline]
+    fn from(e: HrpSplitError) -> Self { Self::HrpSplit(e) }
+}
+
+impl From<HrpMixedCaseError> for UncheckedHrpstringError {
+    #[inline]
+    fn from(e: HrpMixedCaseError) -> Self { Self::HrpMixedCase(e) }
+}
+
 /// Character errors in a bech32 encoded string.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum CharError {
+    /// The string is empty.
+    Empty,
     /// String does not contain the separator character.
     MissingSeparator,
     /// No characters after the separator.
     NothingAfterSeparator,
     /// Some part of the string contains an invalid character.
     InvalidChar(char),
+    /// The string contains an ASCII control character, for example an interior NUL.
+    ///
+    /// Returned instead of [`Self::InvalidChar`] or [`hrp::Error::InvalidAsciiByte`] for the bytes
+    /// 0x00 to 0x1f and 0x7f, which are escaped when displayed.
+    ControlCharacter {
+        /// The control character.
+        byte: u8,
+        /// The byte-index into the string of the control character.
+        pos: usize,
+    },
     /// The whole string must be of one case.
     MixedCase,
+    /// The string must be lowercase.
+    Uppercase,
+    /// The string must be uppercase, see [`CasePolicy::RequireUpper`].
+    NotUppercase {
+        /// The byte-index into the string of the first lowercase letter.
+        pos: usize,
+    },
+}
+
+impl CharError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use CharError::*;
+
+        match *self {
+            Empty => Code::Empty,
+            MissingSeparator => Code::MissingSeparator,
+            NothingAfterSeparator => Code::NothingAfterSeparator,
+            InvalidChar(_) => Code::InvalidChar,
+            ControlCharacter { .. } => Code::ControlChar,
+            MixedCase => Code::MixedCase,
+            Uppercase => Code::LowercaseRequired,
+            NotUppercase { .. } => Code::UppercaseRequired,
+        }
+    }
 }
 
 impl fmt::Display for CharError {
@@ -939,72 +2368,274 @@ impl fmt::Display for CharError {
         use CharError::*;
 
         match *self {
+            Empty => write!(f, "empty string"),
             MissingSeparator => write!(f, "missing human-readable separator, \"{}\"", SEP),
             NothingAfterSeparator => write!(f, "invalid data - no characters after the separator"),
             InvalidChar(n) => write!(f, "invalid character (code={})", n),
+            ControlCharacter { byte, pos } =>
+                write!(f, "control character \\x{:02x} at position {}", byte, pos),
             MixedCase => write!(f, "mixed-case strings not allowed"),
+            Uppercase => write!(f, "uppercase strings not allowed"),
+            NotUppercase { pos } => write!(f, "lowercase character at position {}", pos),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for CharError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for CharError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use CharError::*;
 
         match *self {
-            MissingSeparator | NothingAfterSeparator | InvalidChar(_) | MixedCase => None,
+            Empty
+            | MissingSeparator
+            | NothingAfterSeparator
+            | InvalidChar(_)
+            | ControlCharacter { .. }
+            | MixedCase
+            | Uppercase
+            | NotUppercase { .. } => None,
         }
     }
 }
 
-/// Errors in the checksum of a bech32 encoded string.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[non_exhaustive]
-pub enum ChecksumError {
-    /// String exceeds maximum allowed length.
-    CodeLength(CodeLengthError),
-    /// The checksum residue is not valid for the data.
-    InvalidResidue,
-    /// The checksummed string is not a valid length.
-    InvalidLength,
+/// The human-readable part of a string with more than one separator is invalid.
+///
+/// A string is split at its last '1', the characters of the data part do not include '1' so there
+/// is only ever one valid split point. But if the human-readable part turns out to be invalid, the
+/// other '1's show where the string could have been split, for example if two strings were
+/// concatenated.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::{UncheckedHrpstring, UncheckedHrpstringError};
+///
+/// let s = "gr s1qq1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// match UncheckedHrpstring::new(s) {
+///     Err(UncheckedHrpstringError::HrpSplit(e)) => {
+///         assert_eq!(e.separator(), 7);
+///         # #[cfg(feature = "alloc")]
+///         assert_eq!(e.other_separators(), &[4]);
+///     }
+///     _ => panic!("expected a split error"),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HrpSplitError {
+    /// The error parsing the human-readable part.
+    error: hrp::Error,
+    /// The byte index of the separator the string was split at.
+    separator: usize,
+    /// The byte indices of the other separators, all in the human-readable part.
+    #[cfg(feature = "alloc")]
+    other_separators: Vec<usize>,
 }
 
-impl fmt::Display for ChecksumError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use ChecksumError::*;
-
-        match *self {
-            CodeLength(ref e) => write_err!(f, "string exceeds maximum allowed length"; e),
-            InvalidResidue => write!(f, "the checksum residue is not valid for the data"),
-            InvalidLength => write!(f, "the checksummed string is not a valid length"),
+impl HrpSplitError {
+    /// Constructs the error for `hrp`, everything before the separator, failing with `error`.
+    pub(crate) fn new(hrp: &str, error: hrp::Error) -> Self {
+        HrpSplitError {
+            error,
+            separator: hrp.len(),
+            #[cfg(feature = "alloc")]
+            other_separators: hrp.match_indices(SEP).map(|(pos, _)| pos).collect(),
         }
     }
-}
 
-#[cfg(feature = "std")]
-impl std::error::Error for ChecksumError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        use ChecksumError::*;
+    /// Returns the error parsing the human-readable part.
+    #[inline]
+    pub fn hrp_error(&self) -> &hrp::Error { &self.error }
 
-        match *self {
-            CodeLength(ref e) => Some(e),
-            InvalidResidue | InvalidLength => None,
-        }
-    }
-}
+    /// Returns the byte index of the separator the string was split at, the last '1'.
+    #[inline]
+    pub fn separator(&self) -> usize { self.separator }
 
-/// Encoding HRP and data into a bech32 string exceeds the checksum code length.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[non_exhaustive]
-pub struct CodeLengthError {
-    /// The length of the string if encoded with checksum.
-    pub encoded_length: usize,
-    /// The checksum specific code length.
-    pub code_length: usize,
+    /// Returns the byte indices of the other '1's in the string, in ascending order.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn other_separators(&self) -> &[usize] { &self.other_separators }
+
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error, the code of the human-readable part error.
+    pub(crate) fn error_code(&self) -> Code { self.error.error_code() }
 }
 
-impl fmt::Display for CodeLengthError {
+impl fmt::Display for HrpSplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the string was split at position {}", self.separator)?;
+        #[cfg(feature = "alloc")]
+        for (i, pos) in self.other_separators.iter().enumerate() {
+            let prefix = if i == 0 { "; other possible split points: " } else { ", " };
+            write!(f, "{}{}", prefix, pos)?;
+        }
+        write_err!(f, ""; self.error)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for HrpSplitError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "the string was split at position {=usize}: {}",
+            self.separator,
+            self.error
+        )
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for HrpSplitError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { Some(&self.error) }
+}
+
+/// The human-readable part of a string mixes upper and lower case, for example `Grs1...`.
+///
+/// Keeps a copy of the human-readable part, it is at most [`hrp::MAX_HRP_LENGTH`] bytes long.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HrpMixedCaseError {
+    /// The human-readable part, zero padded.
+    buf: [u8; hrp::MAX_HRP_LENGTH],
+    /// The length of the human-readable part, a `u8` keeps the size of decode errors down.
+    len: u8,
+}
+
+impl HrpMixedCaseError {
+    /// Returns the error for `hrp`, or `None` if it is not a valid hrp that only fails on its case.
+    pub(crate) fn new(hrp: &str) -> Option<Self> {
+        let bytes = hrp.as_bytes();
+        let valid = !bytes.is_empty()
+            && bytes.len() <= hrp::MAX_HRP_LENGTH
+            && bytes.iter().all(|b| (33..=126).contains(b));
+        let mixed =
+            bytes.iter().any(u8::is_ascii_uppercase) && bytes.iter().any(u8::is_ascii_lowercase);
+        if !(valid && mixed) {
+            return None;
+        }
+
+        let mut buf = [0_u8; hrp::MAX_HRP_LENGTH];
+        buf.iter_mut().zip(bytes).for_each(|(dst, &src)| *dst = src);
+        Some(HrpMixedCaseError { buf, len: bytes.len() as u8 }) // Cast OK, checked above.
+    }
+
+    /// Returns the human-readable part, in the case it had in the input string.
+    #[inline]
+    pub fn hrp(&self) -> &str {
+        let bytes = self.buf.get(..usize::from(self.len)).unwrap_or_default();
+        str::from_utf8(bytes).expect("the hrp is ASCII")
+    }
+
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { Code::HrpMixedCase }
+}
+
+impl fmt::Debug for HrpMixedCaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HrpMixedCaseError").field("hrp", &self.hrp()).finish()
+    }
+}
+
+impl fmt::Display for HrpMixedCaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the address prefix '{}' mixes upper and lower case", self.hrp())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for HrpMixedCaseError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "the address prefix '{=str}' mixes upper and lower case", self.hrp())
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for HrpMixedCaseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { None }
+}
+
+/// Errors in the checksum of a bech32 encoded string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ChecksumError {
+    /// String exceeds maximum allowed length.
+    CodeLength(CodeLengthError),
+    /// The checksum residue is not valid for the data.
+    InvalidResidue,
+    /// The checksummed string is not a valid length.
+    InvalidLength,
+}
+
+impl ChecksumError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use ChecksumError::*;
+
+        match *self {
+            CodeLength(ref e) => e.error_code(),
+            InvalidResidue => Code::ChecksumInvalid,
+            InvalidLength => Code::ChecksumLength,
+        }
+    }
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ChecksumError::*;
+
+        match *self {
+            CodeLength(ref e) => write_err!(f, "string exceeds maximum allowed length"; e),
+            InvalidResidue => write!(f, "the checksum residue is not valid for the data"),
+            InvalidLength => write!(f, "the checksummed string is not a valid length"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for ChecksumError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use ChecksumError::*;
+
+        match *self {
+            CodeLength(ref e) => Some(e),
+            InvalidResidue | InvalidLength => None,
+        }
+    }
+}
+
+/// Encoding HRP and data into a bech32 string exceeds the checksum code length.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct CodeLengthError {
+    /// The length of the string if encoded with checksum.
+    pub encoded_length: usize,
+    /// The checksum specific code length.
+    pub code_length: usize,
+}
+
+impl CodeLengthError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { Code::ExceedsCodeLength }
+}
+
+impl fmt::Display for CodeLengthError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -1014,16 +2645,26 @@ impl fmt::Display for CodeLengthError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for CodeLengthError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for CodeLengthError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { None }
 }
 
 /// Encoding HRP, witver, and program into an address exceeds maximum allowed.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub struct SegwitCodeLengthError(pub usize);
 
+impl SegwitCodeLengthError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { Code::SegwitTooLong }
+}
+
 impl fmt::Display for SegwitCodeLengthError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -1035,9 +2676,9 @@ impl fmt::Display for SegwitCodeLengthError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for SegwitCodeLengthError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for SegwitCodeLengthError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { None }
 }
 
 impl From<CodeLengthError> for SegwitCodeLengthError {
@@ -1045,7 +2686,8 @@ impl From<CodeLengthError> for SegwitCodeLengthError {
 }
 
 /// Error validating the padding bits on the witness data.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum PaddingError {
     /// The data payload has too many bits of padding.
@@ -1054,6 +2696,22 @@ pub enum PaddingError {
     NonZero,
 }
 
+impl PaddingError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use PaddingError::*;
+
+        match *self {
+            TooMuch => Code::PaddingTooMuch,
+            NonZero => Code::PaddingNonZero,
+        }
+    }
+}
+
 impl fmt::Display for PaddingError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use PaddingError::*;
@@ -1065,9 +2723,9 @@ impl fmt::Display for PaddingError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for PaddingError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for PaddingError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use PaddingError::*;
 
         match *self {
@@ -1089,11 +2747,14 @@ mod tests {
              // TODO: Rust >= 1.59.0 use Hrp(hrp::Error::InvalidAsciiByte('\u{20}'.try_into().unwrap()))),
              Hrp(hrp::Error::InvalidAsciiByte(32))),
             ("\u{7F}1axkwrx",
-             Hrp(hrp::Error::InvalidAsciiByte(127))),
+             Char(CharError::ControlCharacter { byte: 0x7f, pos: 0 })),
             ("\u{80}1eym55h",
              Hrp(hrp::Error::NonAsciiChar('\u{80}'))),
             ("an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11d6pts4",
-             Hrp(hrp::Error::TooLong(84))),
+             HrpSplit(HrpSplitError::new(
+                 "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber1",
+                 hrp::Error::TooLong(84),
+             ))),
             ("pzry9x0s0muk",
              Char(CharError::MissingSeparator)),
             ("1pzry9x0s0muk",
@@ -1142,11 +2803,14 @@ mod tests {
              // TODO: Rust >= 1.59.0 use Hrp(hrp::Error::InvalidAsciiByte('\u{20}'.try_into().unwrap()))),
              Hrp(hrp::Error::InvalidAsciiByte(32))),
             ("\u{7F}1g6xzxy",
-             Hrp(hrp::Error::InvalidAsciiByte(127))),
+             Char(CharError::ControlCharacter { byte: 0x7f, pos: 0 })),
             ("\u{80}1g6xzxy",
              Hrp(hrp::Error::NonAsciiChar('\u{80}'))),
             ("an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
-             Hrp(hrp::Error::TooLong(84))),
+             HrpSplit(HrpSplitError::new(
+                 "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio",
+                 hrp::Error::TooLong(84),
+             ))),
             ("qyrz8wqd2c9m",
              Char(CharError::MissingSeparator)),
             ("1qyrz8wqd2c9m",
@@ -1194,6 +2858,151 @@ mod tests {
         assert_eq!(err, InvalidResidue);
     }
 
+    #[test]
+    fn two_step_verification_matches_one_step() {
+        let valid = "grs1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq38vfpt";
+        let invalid = "grs1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq38vfpq";
+
+        for &s in [valid, invalid].iter() {
+            let chars = UncheckedHrpstring::new(s).unwrap().parse_data_chars();
+            assert_eq!(chars.hrp(), Hrp::parse_unchecked("grs"));
+            assert_eq!(chars.data_len(), s.len() - 4);
+            assert_eq!(chars.data_part_ascii(), &s.as_bytes()[4..]);
+
+            let one_step =
+                UncheckedHrpstring::new(s).unwrap().validate_and_remove_checksum::<Bech32m>();
+            assert_eq!(chars.verify::<Bech32m>(), one_step);
+        }
+
+        let chars = UncheckedHrpstring::new(invalid).unwrap().parse_data_chars();
+        assert_eq!(chars.verify::<Bech32m>().unwrap_err(), ChecksumError::InvalidResidue);
+        let chars = UncheckedHrpstring::new(valid).unwrap().parse_data_chars();
+        assert_eq!(chars.verify::<Bech32>().unwrap_err(), ChecksumError::InvalidResidue);
+    }
+
+    // A 95 character segwit v0 address, the HRP is 55 characters long.
+    const LONG_HRP_ADDRESS: &str =
+        "asidechainwithaverylonghumanreadablepartforitsaddresses1qw508d6qejxtdg4y5r3zarvary0c5xw7k5hv5xn";
+
+    #[test]
+    fn segwit_max_length() {
+        let s = LONG_HRP_ADDRESS;
+        assert_eq!(s.len(), 95);
+
+        let segwit = SegwitHrpstring::new_with_max_length(s, 95).expect("valid address");
+        assert_eq!(segwit.hrp().len(), 55);
+        assert_eq!(segwit.witness_version(), VERSION_0);
+        assert_eq!(segwit.byte_iter().len(), 20);
+
+        // The default limit is 90 characters.
+        let err = SegwitHrpstring::new(s).unwrap_err();
+        assert_eq!(err, SegwitHrpstringError::TooLong { len: 95, max: 90 });
+        assert_eq!(err.to_string(), "encoded length 95 exceeds the maximum length 90");
+        assert_eq!(err.kind(), SegwitErrorKind::TooLong);
+
+        // As is a raised limit that is still too low, with the same error.
+        let err = SegwitHrpstring::new_with_max_length(s, 94).unwrap_err();
+        assert_eq!(err, SegwitHrpstringError::TooLong { len: 95, max: 94 });
+
+        // Profiles without a maximum length are no longer limited to 90 characters either.
+        assert!(SegwitHrpstring::new_with_profile(s, DecodeProfile::LIGHTNING).is_ok());
+        assert!(SegwitHrpstring::new_with_profile(s, DecodeProfile::BIP350_SEGWIT).is_err());
+    }
+
+    #[test]
+    fn segwit_max_length_above_code_length() {
+        let max = Bech32m::CODE_LENGTH;
+        assert!(SegwitHrpstring::new_with_max_length(LONG_HRP_ADDRESS, max).is_ok());
+
+        // Limits the checksum can not cover are refused, even for short strings.
+        let err = SegwitHrpstring::new_with_max_length(LONG_HRP_ADDRESS, max + 1).unwrap_err();
+        assert_eq!(err, SegwitHrpstringError::InvalidMaxLength(max + 1));
+        assert_eq!(err.to_string(), "maximum length 1024 exceeds the bech32m code length 1023");
+        assert_eq!(err.code(), "E_MAX_LENGTH_INVALID");
+    }
+
+    #[test]
+    fn control_characters() {
+        use CharError::ControlCharacter;
+
+        let valid = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let cases = [
+            (0, 0x00), // Start of the hrp.
+            (2, 0x00), // Inside the hrp.
+            (2, 0x7f),
+            (10, 0x00), // Inside the data part.
+            (10, 0x1b),
+            (valid.len(), 0x00), // Final byte.
+        ];
+        for &(pos, byte) in cases.iter() {
+            let mut s = String::from(valid);
+            s.insert(pos, char::from(byte));
+            let want = UncheckedHrpstringError::Char(ControlCharacter { byte, pos });
+            assert_eq!(UncheckedHrpstring::new(&s).unwrap_err(), want, "{:?}", s);
+            let profile = DecodeProfile::PERMISSIVE;
+            assert_eq!(UncheckedHrpstring::new_with_profile(&s, profile).unwrap_err(), want);
+        }
+
+        // An earlier invalid hrp byte is reported first.
+        assert_eq!(
+            UncheckedHrpstring::new("g \0s1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").unwrap_err(),
+            UncheckedHrpstringError::Hrp(hrp::Error::InvalidAsciiByte(b' '))
+        );
+
+        let e = ControlCharacter { byte: 0, pos: 3 };
+        assert_eq!(e.code(), "E_CONTROL_CHAR");
+        #[cfg(feature = "alloc")]
+        assert_eq!(e.to_string(), "control character \\x00 at position 3");
+    }
+
+    #[test]
+    fn hrp_error_reports_split_points() {
+        use UncheckedHrpstringError::*;
+
+        let s = "gr s1qq1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let e = match UncheckedHrpstring::new(s).unwrap_err() {
+            HrpSplit(e) => e,
+            e => panic!("unexpected error: {:?}", e),
+        };
+        assert_eq!(*e.hrp_error(), hrp::Error::InvalidAsciiByte(b' '));
+        assert_eq!(e.separator(), 7);
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(e.other_separators(), &[4]);
+            // Without `std` the inner error is appended to the message.
+            assert!(e
+                .to_string()
+                .starts_with("the string was split at position 7; other possible split points: 4"));
+        }
+        assert_eq!(e.code(), "E_HRP_INVALID_BYTE");
+
+        // Too long once split at the last '1', but not at the others.
+        let s = format!("{}1{}1{}1qqqqqq", "a".repeat(10), "b".repeat(40), "c".repeat(40));
+        match UncheckedHrpstring::new(&s).unwrap_err() {
+            HrpSplit(e) => {
+                assert_eq!(*e.hrp_error(), hrp::Error::TooLong(92));
+                assert_eq!(e.separator(), 92);
+                #[cfg(feature = "alloc")]
+                assert_eq!(e.other_separators(), &[10, 51]);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // Profiles report the split too.
+        let profile = DecodeProfile::PERMISSIVE;
+        let s = "Gr s1qq1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert!(matches!(UncheckedHrpstring::new_with_profile(s, profile), Err(HrpSplit(_))));
+
+        // A single separator keeps the plain error.
+        let s = "gr s1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(
+            UncheckedHrpstring::new(s).unwrap_err(),
+            Hrp(hrp::Error::InvalidAsciiByte(b' '))
+        );
+        // And a valid hrp containing '1' is not an error.
+        assert!(UncheckedHrpstring::new("grs1qq1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").is_ok());
+    }
+
     #[test]
     fn check_hrp_uppercase_returns_lower() {
         let addr = "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5";
@@ -1225,6 +3034,210 @@ mod tests {
         }
     }
 
+    #[test]
+    fn classify_all_bytes() {
+        for b in 0..=255_u8 {
+            let class = classify(b);
+            let is_bech32 = b.is_ascii() && Fe32::from_char(char::from(b)).is_ok();
+            assert_eq!(class & CLASS_BECH32 != 0, is_bech32, "byte {}", b);
+            assert_eq!(class & CLASS_UPPER != 0, b.is_ascii_uppercase(), "byte {}", b);
+            assert_eq!(class & CLASS_LOWER != 0, b.is_ascii_lowercase(), "byte {}", b);
+            assert_eq!(class & CLASS_SEP != 0, b == b'1', "byte {}", b);
+            assert_eq!(class & CLASS_HRP != 0, (33..=126).contains(&b), "byte {}", b);
+        }
+    }
+
+    /// Tiny xorshift PRNG so the differential tests are deterministic and need no dependencies.
+    pub(super) struct XorShift(pub(super) u64);
+
+    impl XorShift {
+        pub(super) fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        pub(super) fn pick<T: Copy>(&mut self, from: &[T]) -> T {
+            from[self.next() as usize % from.len()]
+        }
+    }
+
+    fn assert_check_characters_matches_scalar(s: &str) {
+        let implementations = vec![
+            check_characters(s),
+            check_characters_simd(s, simd::classify),
+            check_characters_simd(s, simd::classify_scalar),
+            check_characters_chunked(s),
+        ];
+        for got in implementations {
+            match (got, check_characters_scalar(s)) {
+                (Ok(sep), Ok(pos)) => {
+                    assert_eq!(sep.pos, pos, "{:?}", s);
+                    if sep.valid_hrp_bytes {
+                        let hrp = &s.as_bytes()[..pos];
+                        assert!(hrp.iter().all(|b| (33..=126).contains(b)), "{:?}", s);
+                    }
+                    let data = &s.as_bytes()[pos + 1..];
+                    let want: Vec<Fe32> =
+                        data.iter().map(|&b| Fe32::from_char(char::from(b)).unwrap()).collect();
+                    // `next` and `fold` convert the characters differently.
+                    assert!(AsciiToFe32Iter::new(data).eq(want.iter().copied()), "{:?}", s);
+                    let mut folded = vec![];
+                    AsciiToFe32Iter::new(data).for_each(|fe| folded.push(fe));
+                    assert_eq!(folded, want, "{:?}", s);
+                }
+                (Err(got), Err(want)) => assert_eq!(got, want, "{:?}", s),
+                (got, want) => panic!("{:?}: got {:?} want {:?}", s, got.map(|sep| sep.pos), want),
+            }
+        }
+    }
+
+    #[test]
+    fn rfind_separator_finds_last_separator() {
+        for len in 0..=CHUNK_SIZE {
+            let mut block = [b'q'; CHUNK_SIZE];
+            assert_eq!(rfind_separator(&block), None);
+            // Separators and bytes that only differ from it in the high bit, before `len`.
+            for (i, b) in block.iter_mut().take(len).enumerate() {
+                *b = if i % 3 == 0 { SEP as u8 } else { SEP as u8 | 0x80 };
+            }
+            let want = block.iter().rposition(|&b| b == SEP as u8);
+            assert_eq!(rfind_separator(&block), want, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn check_characters_finds_separator_in_every_lane() {
+        for len in 0..=3 * simd::LANES {
+            for sep in 0..len {
+                let mut s = vec![b'q'; len];
+                s[sep] = SEP as u8;
+                // Bytes before the separator that are not in the bech32 alphabet.
+                if sep > 0 {
+                    s[0] = b'b';
+                }
+                assert_check_characters_matches_scalar(str::from_utf8(&s).unwrap());
+                // And after it.
+                s[len - 1] = b'b';
+                assert_check_characters_matches_scalar(str::from_utf8(&s).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_to_fe32_iter_len() {
+        let data = "qpzry9x8gf2tvdw0s3jn54khce6mua7l".repeat(3);
+        for len in 0..data.len() {
+            let mut iter = AsciiToFe32Iter::new(&data.as_bytes()[..len]);
+            for remaining in (0..=len).rev() {
+                assert_eq!(iter.len(), remaining);
+                assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+                assert_eq!(iter.next().is_some(), remaining > 0);
+            }
+            assert_eq!(iter.next(), None);
+        }
+    }
+
+    #[test]
+    fn long_string_with_separator_near_front() {
+        let data = "qpzry9x8gf2tvdw0s3jn54khce6mua7l".repeat(320); // 10 KiB
+
+        let s = format!("a1{}", data);
+        let unchecked = UncheckedHrpstring::new(&s).expect("valid string");
+        assert_eq!(unchecked.hrp(), Hrp::parse_unchecked("a"));
+        assert_eq!(unchecked.data_part_ascii(), data.as_bytes());
+
+        // Multiple separators, the last one splits the string.
+        let s = format!("a1b1{}", data);
+        let unchecked = UncheckedHrpstring::new(&s).expect("valid string");
+        assert_eq!(unchecked.hrp(), Hrp::parse_unchecked("a1b"));
+        assert_eq!(unchecked.data_part_ascii(), data.as_bytes());
+
+        // Separator as first character.
+        let s = format!("1{}", data);
+        assert_eq!(
+            UncheckedHrpstring::new(&s).unwrap_err(),
+            UncheckedHrpstringError::Hrp(hrp::Error::Empty)
+        );
+
+        // No separator.
+        assert_eq!(
+            UncheckedHrpstring::new(&data).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::MissingSeparator)
+        );
+
+        // Invalid hrp character.
+        let s = format!("a b1{}", data);
+        assert_eq!(
+            UncheckedHrpstring::new(&s).unwrap_err(),
+            UncheckedHrpstringError::Hrp(hrp::Error::InvalidAsciiByte(b' '))
+        );
+
+        // Hrp too long.
+        let s = format!("{}1{}", "a".repeat(84), data);
+        assert_eq!(
+            UncheckedHrpstring::new(&s).unwrap_err(),
+            UncheckedHrpstringError::Hrp(hrp::Error::TooLong(84))
+        );
+
+        for s in [format!("a1{}", data), format!("1{}", data), data.clone()].iter() {
+            assert_check_characters_matches_scalar(s);
+        }
+    }
+
+    #[test]
+    fn check_characters_matches_scalar_on_random_strings() {
+        // Mostly valid characters so that many strings are valid, plus every kind of invalid one.
+        let chars: Vec<char> = "qpzry9x8gf2tvdw0s3jn54khce6mua7lQPZRY9X8GF2TVDW0S3JN54KHCE6MUA7L1bio \0\u{7f}\u{80}\u{ff}€"
+            .chars()
+            .collect();
+        let valid: Vec<char> = "qpzry9x8gf2tvdw0s3jn54khce6mua7l".chars().collect();
+
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        for i in 0..20_000 {
+            let len = (rng.next() % 200) as usize;
+            let mut s: String = (0..len).map(|_| rng.pick(&valid)).collect();
+            // Sprinkle in a few arbitrary characters, including separators and other cases.
+            for _ in 0..(rng.next() % 4) {
+                let ch = rng.pick(&chars);
+                match s.char_indices().nth(rng.next() as usize % (len + 1)) {
+                    Some((pos, _)) => s.insert(pos, ch),
+                    None => s.push(ch),
+                }
+            }
+            if i % 2 == 0 {
+                s = s.to_uppercase();
+            }
+            assert_check_characters_matches_scalar(&s);
+        }
+    }
+
+    #[test]
+    fn check_characters_matches_scalar_on_test_vectors() {
+        let vectors = [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "23451QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZLKULZK",
+            "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc",
+            "A12UEL5L",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "\u{20}1nwldj5",
+            "\u{80}1eym55h",
+            "pzry9x0s0muk",
+            "x1b4n0q5v",
+            "de1lg7wt\u{ff}",
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p\0",
+            "grs1qaR0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "Grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "1",
+            "",
+        ];
+        for s in vectors.iter() {
+            assert_check_characters_matches_scalar(s);
+        }
+    }
+
     macro_rules! check_invalid_segwit_addresses {
         ($($test_name:ident, $reason:literal, $address:literal);* $(;)?) => {
             $(
@@ -1246,4 +3259,739 @@ mod tests {
         invalid_segwit_address_4, "missing data", "grs1qwf5mdq";
         invalid_segwit_address_5, "invalid program length", "grs14r0srrr7xfkvy5l643lydnw9rencg65p";
     }
+
+    #[test]
+    fn segwit_error_kind() {
+        use SegwitErrorKind as Kind;
+        use SegwitHrpstringError::*;
+
+        let errors = [
+            (Unchecked(UncheckedHrpstringError::Char(CharError::MixedCase)), Kind::Char),
+            (Unchecked(UncheckedHrpstringError::Hrp(hrp::Error::Empty)), Kind::Hrp),
+            (
+                Unchecked(UncheckedHrpstringError::TooLong(CodeLengthError {
+                    encoded_length: 91,
+                    code_length: 90,
+                })),
+                Kind::TooLong,
+            ),
+            (NoData, Kind::WitnessVersion),
+            (TooLong { len: 91, max: 90 }, Kind::TooLong),
+            (InvalidMaxLength(1024), Kind::TooLong),
+            (InvalidWitnessVersion(Fe32::Q), Kind::WitnessVersion),
+            (Padding(PaddingError::NonZero), Kind::Padding),
+            (WitnessLength(WitnessLengthError::TooShort { len: 1, min: 2 }), Kind::WitnessLength),
+            (Checksum(ChecksumError::InvalidResidue), Kind::Checksum),
+            (Checksum(ChecksumError::InvalidLength), Kind::Checksum),
+            (
+                Checksum(ChecksumError::CodeLength(CodeLengthError {
+                    encoded_length: 91,
+                    code_length: 90,
+                })),
+                Kind::TooLong,
+            ),
+        ];
+        for (error, kind) in errors.iter() {
+            assert_eq!(error.kind(), *kind, "{:?}", error);
+        }
+
+        let addresses = [
+            ("grs1qWf5mdq", Kind::Char),
+            ("1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p", Kind::Hrp),
+            ("grs14r0srrr7xfkvy5l643lydnw9re59gtzzncg65p", Kind::WitnessVersion),
+            ("grs1q5mdq", Kind::Checksum),
+            ("grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3lh6z5", Kind::Checksum),
+        ];
+        for (address, kind) in addresses.iter() {
+            let error = SegwitHrpstring::new(address).unwrap_err();
+            assert_eq!(error.kind(), *kind, "{}: {:?}", address, error);
+        }
+    }
+
+    // A valid bech32 string longer than the bech32 code length.
+    fn longer_than_code_length() -> String {
+        let hrp = Hrp::parse_unchecked("a");
+        iter::repeat(Fe32::Q).take(1100).with_checksum::<Bech32>(&hrp).chars().collect()
+    }
+
+    #[test]
+    fn profile_bip173() {
+        let profile = DecodeProfile::BIP173;
+        for s in ["a12uel5l", "A12UEL5L", "?1ezyfcl"].iter() {
+            let unchecked = UncheckedHrpstring::new_with_profile(s, profile).expect("valid string");
+            unchecked.validate_checksum::<Bech32>().expect("valid checksum");
+        }
+
+        let too_long = format!("{}1{}", "a", "q".repeat(89));
+        assert_eq!(
+            UncheckedHrpstring::new_with_profile(&too_long, profile).unwrap_err(),
+            UncheckedHrpstringError::TooLong(CodeLengthError {
+                encoded_length: 91,
+                code_length: 90
+            })
+        );
+
+        let invalid = [
+            ("A12uEL5L", CharError::MixedCase),
+            ("a1", CharError::NothingAfterSeparator),
+            ("a12uel5b", CharError::InvalidChar('b')),
+            ("a2uel5l", CharError::MissingSeparator),
+        ];
+        for &(s, ref e) in invalid.iter() {
+            assert_eq!(
+                UncheckedHrpstring::new_with_profile(s, profile).unwrap_err(),
+                UncheckedHrpstringError::Char(e.clone()),
+                "{}",
+                s
+            );
+        }
+        // Without a profile an empty data part is only rejected if a checksum is expected.
+        assert!(UncheckedHrpstring::new("a1").is_ok());
+        assert_eq!(
+            CheckedHrpstring::new::<Bech32>("a1").unwrap_err(),
+            CheckedHrpstringError::Parse(CharError::NothingAfterSeparator.into())
+        );
+    }
+
+    #[test]
+    fn empty_data_part_without_checksum_roundtrips() {
+        let hrp = Hrp::parse_unchecked("abc");
+        let s = core::iter::empty()
+            .with_checksum::<crate::NoChecksum>(&hrp)
+            .chars()
+            .collect::<String>();
+        assert_eq!(s, "abc1");
+
+        let checked = CheckedHrpstring::new::<crate::NoChecksum>(&s).expect("valid string");
+        assert_eq!(checked.hrp(), hrp);
+        assert_eq!(checked.byte_iter().len(), 0);
+        assert!(UncheckedHrpstring::new(&s).is_ok());
+    }
+
+    #[test]
+    fn separator_errors_are_stable() {
+        use CharError::*;
+
+        // Each of these strings has other problems too, the separator error is always the one
+        // reported.
+        let tcs = [
+            ("", Empty),
+            ("1", NothingAfterSeparator),
+            ("grs1", NothingAfterSeparator),
+            ("GRS1", NothingAfterSeparator),
+            ("Grs1", NothingAfterSeparator),
+            ("gr s1", NothingAfterSeparator),
+            ("grs11", NothingAfterSeparator),
+            ("\u{7f}1", NothingAfterSeparator),
+            ("grs", MissingSeparator),
+            ("Grsqar0srrr7xfkvy5l643lydnw9re59gtzz", MissingSeparator),
+            ("grs\u{e9}qqqq", MissingSeparator),
+            ("\0", MissingSeparator),
+        ];
+        for &(s, ref want) in tcs.iter() {
+            // Without a checksum an empty data part is valid, these fail on the hrp instead.
+            let needs_data = *want == NothingAfterSeparator;
+            let want = UncheckedHrpstringError::Char(want.clone());
+            if !needs_data {
+                assert_eq!(UncheckedHrpstring::new(s).unwrap_err(), want, "{:?}", s);
+                assert_eq!(
+                    CheckedHrpstring::new::<crate::NoChecksum>(s).unwrap_err(),
+                    CheckedHrpstringError::Parse(want.clone()),
+                    "{:?}",
+                    s
+                );
+            }
+            assert_eq!(
+                CheckedHrpstring::new::<Bech32>(s).unwrap_err(),
+                CheckedHrpstringError::Parse(want.clone()),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                SegwitHrpstring::new(s).unwrap_err(),
+                SegwitHrpstringError::Unchecked(want.clone()),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                SegwitHrpstring::new_bech32(s).unwrap_err(),
+                SegwitHrpstringError::Unchecked(want.clone()),
+                "{:?}",
+                s
+            );
+            #[cfg(feature = "alloc")]
+            assert_eq!(
+                crate::segwit::decode(s).unwrap_err(),
+                crate::segwit::DecodeError(SegwitHrpstringError::Unchecked(want.clone())),
+                "{:?}",
+                s
+            );
+            #[cfg(feature = "alloc")]
+            assert_eq!(crate::decode(s).unwrap_err(), crate::DecodeError::Parse(want), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn profile_bip350_segwit_matches_new() {
+        let addresses = [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P",
+            "grs1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq38vfpt",
+            "grs1qAr0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q",
+            "grs14r0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "grs1qwf5mdq",
+        ];
+        for s in addresses.iter() {
+            let got = SegwitHrpstring::new_with_profile(s, DecodeProfile::BIP350_SEGWIT);
+            match SegwitHrpstring::new(s) {
+                Ok(want) => {
+                    let got = got.expect("valid address");
+                    assert_eq!(got.hrp(), want.hrp());
+                    assert_eq!(got.witness_version(), want.witness_version());
+                    assert!(got.byte_iter().eq(want.byte_iter()));
+                }
+                Err(want) => assert_eq!(got.unwrap_err().kind(), want.kind(), "{}", s),
+            }
+        }
+
+        let too_long = format!("grs1q{}", "q".repeat(86));
+        let err = SegwitHrpstring::new_with_profile(&too_long, DecodeProfile::BIP350_SEGWIT);
+        assert_eq!(err.unwrap_err().kind(), SegwitErrorKind::TooLong);
+    }
+
+    #[test]
+    fn profile_lightning() {
+        let s = longer_than_code_length();
+        assert_eq!(
+            CheckedHrpstring::new::<Bech32>(&s).unwrap_err(),
+            CheckedHrpstringError::Checksum(ChecksumError::CodeLength(CodeLengthError {
+                encoded_length: s.len(),
+                code_length: Bech32::CODE_LENGTH,
+            }))
+        );
+
+        let unchecked = UncheckedHrpstring::new_with_profile(&s, DecodeProfile::LIGHTNING)
+            .expect("no maximum length");
+        let checked = unchecked.validate_and_remove_checksum::<Bech32>().expect("valid checksum");
+        assert_eq!(checked.data_part_ascii_no_checksum().len(), 1100);
+
+        let upper = s.to_uppercase();
+        assert!(UncheckedHrpstring::new_with_profile(&upper, DecodeProfile::LIGHTNING).is_ok());
+
+        for &(s, ref e) in
+            [("A12uEL5L", CharError::MixedCase), ("a1", CharError::NothingAfterSeparator)].iter()
+        {
+            assert_eq!(
+                UncheckedHrpstring::new_with_profile(s, DecodeProfile::LIGHTNING).unwrap_err(),
+                UncheckedHrpstringError::Char(e.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn profile_permissive() {
+        let profile = DecodeProfile::PERMISSIVE;
+
+        let valid = [
+            ("A12uEL5L", "a"),
+            ("SpLit1checkupstagehandshakeupstreamerranterredcaperred2Y9E3W", "split"),
+            ("a12uel5l", "a"),
+        ];
+        for &(s, hrp) in valid.iter() {
+            let unchecked = UncheckedHrpstring::new_with_profile(s, profile).expect("any case");
+            assert_eq!(unchecked.hrp().as_str(), hrp);
+            unchecked.validate_checksum::<Bech32>().expect("valid checksum");
+        }
+
+        let s = longer_than_code_length();
+        let unchecked = UncheckedHrpstring::new_with_profile(&s, profile).expect("any length");
+        unchecked.validate_checksum::<Bech32>().expect("valid checksum");
+
+        let unchecked = UncheckedHrpstring::new_with_profile("a1", profile).expect("empty data");
+        assert!(unchecked.data_part_ascii().is_empty());
+
+        let invalid =
+            [("A12uEL5b", CharError::InvalidChar('b')), ("Aa2uEL5L", CharError::MissingSeparator)];
+        for &(s, ref e) in invalid.iter() {
+            assert_eq!(
+                UncheckedHrpstring::new_with_profile(s, profile).unwrap_err(),
+                UncheckedHrpstringError::Char(e.clone())
+            );
+        }
+
+        // Mixed case segwit addresses are accepted too.
+        let s = "grs1qAr0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let segwit = SegwitHrpstring::new_with_profile(s, profile).expect("valid address");
+        assert_eq!(segwit.witness_version(), VERSION_0);
+    }
+
+    #[test]
+    fn profile_lowercase() {
+        let profile = DecodeProfile { case: CasePolicy::Canonical, ..DecodeProfile::BIP173 };
+
+        assert!(UncheckedHrpstring::new_with_profile("a12uel5l", profile).is_ok());
+        assert!(UncheckedHrpstring::new_with_profile("?1ezyfcl", profile).is_ok());
+        assert_eq!(
+            UncheckedHrpstring::new_with_profile("A12UEL5L", profile).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::Uppercase)
+        );
+        assert_eq!(
+            UncheckedHrpstring::new_with_profile("A12uEL5L", profile).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::MixedCase)
+        );
+    }
+
+    #[test]
+    fn case_policies() {
+        let lower = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let upper = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+        let mixed = "grs1qAr0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+
+        assert_eq!(CasePolicy::default(), CasePolicy::AcceptUpper);
+
+        // (policy, accepts lower, accepts upper, accepts mixed)
+        for &(case, lower_ok, upper_ok, mixed_ok) in [
+            (CasePolicy::Canonical, true, false, false),
+            (CasePolicy::AcceptUpper, true, true, false),
+            (CasePolicy::RequireUpper, false, true, false),
+            (CasePolicy::AcceptMixedDangerous, true, true, true),
+        ]
+        .iter()
+        {
+            for &(s, ok) in [(lower, lower_ok), (upper, upper_ok), (mixed, mixed_ok)].iter() {
+                let unchecked = UncheckedHrpstring::new_with_case(s, case);
+                assert_eq!(unchecked.is_ok(), ok, "{:?} {}", case, s);
+                let segwit = SegwitHrpstring::new_with_case(s, case);
+                assert_eq!(segwit.is_ok(), ok, "{:?} {}", case, s);
+                if let Ok(segwit) = segwit {
+                    assert_eq!(segwit.hrp(), crate::hrp::GRS);
+                }
+            }
+        }
+
+        assert_eq!(
+            UncheckedHrpstring::new_with_case(upper, CasePolicy::Canonical).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::Uppercase)
+        );
+        assert_eq!(
+            SegwitHrpstring::new_with_case(mixed, CasePolicy::AcceptUpper).unwrap_err(),
+            SegwitHrpstring::new(mixed).unwrap_err()
+        );
+        assert_eq!(
+            UncheckedHrpstring::new_with_case(mixed, CasePolicy::RequireUpper).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::NotUppercase { pos: 0 })
+        );
+        let lower_q = upper.replace('Q', "q");
+        let err =
+            UncheckedHrpstring::new_with_case(&lower_q, CasePolicy::RequireUpper).unwrap_err();
+        assert_eq!(err, UncheckedHrpstringError::Char(CharError::NotUppercase { pos: 4 }));
+        assert_eq!(err.position(&lower_q), Some(4));
+    }
+
+    #[test]
+    fn profile_display_safe_hrp() {
+        let profile = DecodeProfile { display_safe_hrp: true, ..DecodeProfile::BIP173 };
+
+        for &(s, byte) in [("?1ezyfcl", b'?'), ("grs!1qqqqqqqq", b'!')].iter() {
+            assert!(UncheckedHrpstring::new_with_profile(s, DecodeProfile::BIP173).is_ok());
+            assert_eq!(
+                UncheckedHrpstring::new_with_profile(s, profile).unwrap_err(),
+                UncheckedHrpstringError::Hrp(hrp::Error::NotDisplaySafe(byte))
+            );
+        }
+        assert!(UncheckedHrpstring::new_with_profile("a12uel5l", profile).is_ok());
+
+        // Uppercase is a question of case policy, not of the characters in the hrp.
+        assert!(UncheckedHrpstring::new_with_profile("A12UEL5L", profile).is_ok());
+        let lowercase = DecodeProfile { case: CasePolicy::Canonical, ..profile };
+        assert_eq!(
+            UncheckedHrpstring::new_with_profile("A12UEL5L", lowercase).unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::Uppercase)
+        );
+    }
+
+    #[test]
+    fn uppercase_data_is_not_case_folded() {
+        let lower = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+        let upper = lower.to_ascii_uppercase();
+
+        let want = SegwitHrpstring::new(lower).unwrap();
+        let got = SegwitHrpstring::new(&upper).unwrap();
+        // The stored data part keeps its case, the lookup table maps both cases to the same value.
+        let want_ascii = want.data_part_ascii_no_witver_no_checksum().to_ascii_uppercase();
+        assert_eq!(got.data_part_ascii_no_witver_no_checksum(), want_ascii.as_slice());
+        assert!(got.byte_iter().eq(want.byte_iter()));
+
+        // The table accepting both cases does not make mixed case strings valid.
+        for i in 4..lower.len() {
+            let mut mixed = String::from(lower);
+            mixed.replace_range(i..=i, &upper[i..=i]);
+            if mixed == lower {
+                continue; // A digit.
+            }
+            assert_eq!(
+                SegwitHrpstring::new(&mixed).unwrap_err(),
+                SegwitHrpstringError::Unchecked(CharError::MixedCase.into()),
+                "{}",
+                mixed
+            );
+            assert_eq!(
+                CheckedHrpstring::new::<Bech32>(&mixed).unwrap_err(),
+                CheckedHrpstringError::Parse(CharError::MixedCase.into())
+            );
+        }
+    }
+
+    #[test]
+    fn hrp_mixed_case() {
+        for &(s, hrp) in [
+            ("Grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p", "Grs"),
+            ("gRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P", "gRS"),
+        ]
+        .iter()
+        {
+            let e = match UncheckedHrpstring::new(s).unwrap_err() {
+                UncheckedHrpstringError::HrpMixedCase(e) => e,
+                e => panic!("unexpected error for {}: {:?}", s, e),
+            };
+            assert_eq!(e.hrp(), hrp);
+            assert_eq!(
+                e.to_string(),
+                format!("the address prefix '{}' mixes upper and lower case", hrp)
+            );
+            assert_eq!(SegwitHrpstring::new(s).unwrap_err().kind(), SegwitErrorKind::Hrp);
+        }
+
+        assert!(UncheckedHrpstring::new("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").is_ok());
+        assert!(UncheckedHrpstring::new("GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P").is_ok());
+        // A single case hrp with a mixed case data part is still a character error.
+        assert_eq!(
+            UncheckedHrpstring::new("grs1qAr0srrr7xfkvy5l643lydnw9re59gtzzncg65p").unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::MixedCase)
+        );
+        // The data part is checked first.
+        assert_eq!(
+            UncheckedHrpstring::new("Grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65b").unwrap_err(),
+            UncheckedHrpstringError::Char(CharError::InvalidChar('b'))
+        );
+        // Mixed case is allowed by a case insensitive profile.
+        let s = "Grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert!(UncheckedHrpstring::new_with_profile(s, DecodeProfile::PERMISSIVE).is_ok());
+    }
+
+    const ADDR: &str = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+    const ADDR_UPPER: &str = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+
+    #[test]
+    fn checked_compares_to_source() {
+        let lower = CheckedHrpstring::new::<Bech32>(ADDR).expect("valid bech32");
+        let upper = CheckedHrpstring::new::<Bech32>(ADDR_UPPER).expect("valid bech32");
+
+        assert_eq!(lower, ADDR);
+        assert_eq!(upper, ADDR);
+        assert_eq!(upper, *ADDR);
+        assert_ne!(upper, ADDR_UPPER);
+        assert_eq!(lower, upper);
+        assert_eq!(lower.clone(), lower);
+
+        // Same hrp and data part as `ADDR`, but a different checksum.
+        let mut other = String::from(ADDR);
+        other.replace_range(ADDR.len() - 6.., "qqqqqq");
+        let other = UncheckedHrpstring::new(&other).unwrap().remove_checksum::<Bech32>();
+        assert!(other.data_part_ascii_no_checksum().eq(lower.data_part_ascii_no_checksum()));
+        assert_ne!(other, lower);
+        assert_ne!(lower, "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65");
+        assert_ne!(lower, "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65pp");
+    }
+
+    #[test]
+    fn segwit_compares_to_source() {
+        let lower = SegwitHrpstring::new(ADDR).expect("valid address");
+        let upper = SegwitHrpstring::new(ADDR_UPPER).expect("valid address");
+
+        assert_eq!(lower, ADDR);
+        assert_eq!(upper, ADDR);
+        assert_ne!(upper, ADDR_UPPER);
+        assert_eq!(lower, upper);
+        assert_eq!(lower.clone(), lower);
+
+        let taproot = SegwitHrpstring::new("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc").unwrap();
+        assert_ne!(taproot, lower);
+        assert_ne!(lower, "tgrs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn original_and_canonical_strings() {
+        let mut checked = CheckedHrpstring::new::<Bech32>(ADDR_UPPER).unwrap();
+        checked.remove_witness_version();
+        assert_eq!(checked.as_original_str(), ADDR_UPPER);
+        assert_eq!(checked.to_canonical_string(), ADDR);
+
+        let mixed = "Grs1QaR0SrRr7xFkVy5L643lYdNw9Re59gTzZnCg65P";
+        let segwit = SegwitHrpstring::new_with_profile(mixed, DecodeProfile::PERMISSIVE).unwrap();
+        assert_eq!(segwit.as_original_str(), mixed);
+        assert_eq!(segwit.to_canonical_string(), ADDR);
+    }
+
+    #[test]
+    fn probably_truncated() {
+        assert_eq!(VERSION_0_ADDRESS_LENGTHS[3], [ADDR.len(), 63]);
+        assert_eq!(VERSION_1_ADDRESS_LENGTHS[3], [63]);
+
+        let truncated = &ADDR[..ADDR.len() - 1];
+        assert_eq!(
+            SegwitHrpstring::new(truncated).unwrap_err(),
+            SegwitHrpstringError::ProbablyTruncated { expected_lengths: &[43, 63], got: 42 }
+        );
+        // Too short for a checksum at all.
+        assert_eq!(
+            SegwitHrpstring::new("grs1qar0s").unwrap_err(),
+            SegwitHrpstringError::ProbablyTruncated { expected_lengths: &[43, 63], got: 9 }
+        );
+
+        // A typo in an address of the expected length.
+        let typo = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+        assert!(matches!(
+            SegwitHrpstring::new(typo).unwrap_err(),
+            SegwitHrpstringError::Checksum(ChecksumError::InvalidResidue)
+        ));
+        // Longer than any version 0 address.
+        let long = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65par0srrr7xfkvy5l643lydnw9r";
+        assert!(matches!(
+            SegwitHrpstring::new(long).unwrap_err(),
+            SegwitHrpstringError::Checksum(ChecksumError::InvalidResidue)
+        ));
+        // Version 2 addresses have no usual length.
+        let v2 = "grs1zar0srrr7xfkvy5l643lydnw9re59gtzzncg6";
+        assert!(matches!(
+            SegwitHrpstring::new(v2).unwrap_err(),
+            SegwitHrpstringError::Checksum(ChecksumError::InvalidResidue)
+        ));
+
+        let err = SegwitHrpstring::new(truncated).unwrap_err();
+        assert_eq!(err.kind(), SegwitErrorKind::Checksum);
+        assert_eq!(
+            err.to_string(),
+            "invalid checksum, the address is 42 characters long but expected 43 or 63; \
+             the address is probably truncated"
+        );
+    }
+
+    #[test]
+    fn address_lengths_include_usual_lengths() {
+        for hrp_len in 0..=hrp::MAX_HRP_LENGTH {
+            let lengths = address_lengths(hrp_len);
+            let v0 = VERSION_0_ADDRESS_LENGTHS[hrp_len].iter();
+            let usual = v0.chain(&VERSION_1_ADDRESS_LENGTHS[hrp_len]);
+            for len in usual.filter(|&&len| len <= segwit::MAX_STRING_LENGTH) {
+                assert!(lengths.contains(len));
+            }
+        }
+    }
+
+    #[test]
+    fn unchecked_lengths() {
+        let unchecked = UncheckedHrpstring::new(ADDR).unwrap();
+        assert_eq!(unchecked.total_len(), 43);
+        assert_eq!(unchecked.data_part_len(), 39);
+        assert_eq!(unchecked.data_part_len(), unchecked.data_part_ascii().len());
+
+        let unchecked = UncheckedHrpstring::new("a1lqfn3a").unwrap();
+        assert_eq!((unchecked.total_len(), unchecked.data_part_len()), (8, 6));
+    }
+
+    #[test]
+    fn debug_shows_parts() {
+        let checked = CheckedHrpstring::new::<Bech32>(ADDR).unwrap();
+        assert_eq!(format!("{:?}", checked), "CheckedHrpstring { hrp: \"grs\", data_len: 33 }");
+        let segwit = SegwitHrpstring::new(ADDR_UPPER).unwrap();
+        assert_eq!(
+            format!("{:?}", segwit),
+            "SegwitHrpstring { hrp: \"GRS\", witness_version: Fe32(0), data_len: 32 }"
+        );
+    }
+
+    /// A 13 character checksum, valid if all the data field elements sum to zero.
+    struct Sum13;
+
+    impl ChecksumSpec for Sum13 {
+        fn name(&self) -> &'static str { "sum13" }
+        fn code_length(&self) -> usize { 127 }
+        fn checksum_length(&self) -> usize { 13 }
+        fn verify(&self, _: Hrp, data: &mut dyn Iterator<Item = Fe32>) -> bool {
+            data.fold(0, |sum, fe| sum ^ fe.to_u8()) == 0
+        }
+    }
+
+    #[test]
+    fn new_with_spec_matches_new() {
+        use crate::primitives::checksum::StaticChecksum;
+
+        let bech32 = StaticChecksum::<Bech32>::new();
+        let bech32m = StaticChecksum::<Bech32m>::new();
+        let strings = [
+            ADDR,
+            ADDR_UPPER,
+            "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc",
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q",
+            "a12uel5l",
+            "a1lqfn3a",
+            "grs1",
+            "no separator",
+        ];
+        for s in strings.iter() {
+            assert_eq!(
+                CheckedHrpstring::new_with_spec(s, &bech32),
+                CheckedHrpstring::new::<Bech32>(s)
+            );
+            assert_eq!(
+                CheckedHrpstring::new_with_spec(s, &bech32m),
+                CheckedHrpstring::new::<Bech32m>(s)
+            );
+        }
+
+        let checked = CheckedHrpstring::new_with_spec(
+            "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc",
+            &bech32m,
+        )
+        .expect("valid bech32m");
+        assert_eq!(checked.data_part_ascii_no_checksum().len(), 33);
+        assert_eq!(checked.witness_version(), Some(Fe32::P));
+        assert_eq!(checked.byte_iter().len(), 20);
+    }
+
+    #[test]
+    fn new_with_spec_thirteen_character_checksum() {
+        // The data characters are the field elements 0 to 15, which sum to zero, as do the 13
+        // checksum characters.
+        let s = "dummy1qpzry9x8gf2tvdw0qqqqqqqqqqqqq";
+        let checked = CheckedHrpstring::new_with_spec(s, &Sum13).expect("valid checksum");
+        assert_eq!(checked.hrp(), Hrp::parse("dummy").unwrap());
+        assert_eq!(checked.data_part_ascii_no_checksum(), b"qpzry9x8gf2tvdw0");
+        assert_eq!(checked.fe32_iter::<iter::Empty<u8>>().len(), 16);
+        assert_eq!(checked.byte_iter().len(), 10);
+
+        assert_eq!(
+            CheckedHrpstring::new_with_spec("dummy1qpzry9x8gf2tvdw0qqqqqqqqqqqql", &Sum13),
+            Err(CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue))
+        );
+        // Only the checksum, with no data.
+        let checked = CheckedHrpstring::new_with_spec("dummy1qqqqqqqqqqqqq", &Sum13).unwrap();
+        assert!(checked.data_part_ascii_no_checksum().is_empty());
+        // Too short to hold the checksum.
+        assert_eq!(
+            CheckedHrpstring::new_with_spec("dummy1qqqqqqqqqqqq", &Sum13),
+            Err(CheckedHrpstringError::Checksum(ChecksumError::InvalidLength))
+        );
+    }
+}
+
+#[cfg(bench)]
+mod benches {
+    use test::{black_box, Bencher};
+
+    use super::*;
+
+    /// A string with a 4 KiB data part.
+    fn long_string() -> String {
+        let data = "qpzry9x8gf2tvdw0s3jn54khce6mua7l".repeat(128);
+        format!("grs1{}", data)
+    }
+
+    #[bench]
+    fn bench_segwit_hrpstring_new(bh: &mut Bencher) {
+        let s = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+        bh.iter(|| {
+            let res = SegwitHrpstring::new(black_box(s)).expect("valid address");
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_segwit_hrpstring_new_uppercase(bh: &mut Bencher) {
+        // Uppercase, as used in QR codes.
+        let s = "GRS1Q2S3RJWVAM9DT2FTT4SQXQJF3TWAV0GDX0K0Q2ETXFLX38C3X8TNSLKYLAY";
+        bh.iter(|| {
+            let res = SegwitHrpstring::new(black_box(s)).expect("valid address");
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_segwit_byte_iter_uppercase(bh: &mut Bencher) {
+        // Uppercase characters are looked up directly, without converting them to lowercase.
+        let s = "GRS1Q2S3RJWVAM9DT2FTT4SQXQJF3TWAV0GDX0K0Q2ETXFLX38C3X8TNSLKYLAY";
+        let segwit = SegwitHrpstring::new(s).expect("valid address");
+        bh.iter(|| {
+            let res = black_box(&segwit).byte_iter().fold(0_u8, |acc, b| acc ^ b);
+            black_box(res);
+        });
+    }
+
+    #[bench]
+    fn bench_check_characters_scalar_uppercase(bh: &mut Bencher) {
+        let s = "GRS1Q2S3RJWVAM9DT2FTT4SQXQJF3TWAV0GDX0K0Q2ETXFLX38C3X8TNSLKYLAY";
+        bh.iter(|| {
+            let res = check_characters_scalar(black_box(s));
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_unchecked_hrpstring_new_4k(bh: &mut Bencher) {
+        let s = long_string();
+        bh.iter(|| {
+            let res = UncheckedHrpstring::new(black_box(&s)).expect("valid string");
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_check_characters_4k(bh: &mut Bencher) {
+        let s = long_string();
+        bh.iter(|| {
+            let res = check_characters(black_box(&s));
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_check_characters_chunked_4k(bh: &mut Bencher) {
+        let s = long_string();
+        bh.iter(|| {
+            let res = check_characters_chunked(black_box(&s));
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_check_characters_scalar_4k(bh: &mut Bencher) {
+        let s = long_string();
+        bh.iter(|| {
+            let res = check_characters_scalar(black_box(&s));
+            black_box(&res);
+        });
+    }
+
+    #[bench]
+    fn bench_fe32_iter_4k(bh: &mut Bencher) {
+        let s = long_string();
+        let unchecked = UncheckedHrpstring::new(&s).expect("valid string");
+        bh.iter(|| {
+            let iter = AsciiToFe32Iter::new(black_box(&unchecked).data_part_ascii());
+            black_box(iter.fold(0_u8, |acc, fe| acc ^ fe.to_u8()));
+        });
+    }
+
+    #[bench]
+    fn bench_fe32_iter_scalar_4k(bh: &mut Bencher) {
+        let s = long_string();
+        let unchecked = UncheckedHrpstring::new(&s).expect("valid string");
+        bh.iter(|| {
+            let iter = black_box(&unchecked).data_part_ascii().iter().copied();
+            black_box(iter.map(Fe32::from_char_unchecked).fold(0_u8, |acc, fe| acc ^ fe.to_u8()));
+        });
+    }
 }
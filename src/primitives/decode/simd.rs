@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: MIT
+
+//! Classifying and converting bech32 characters 16 at a time.
+//!
+//! On x86 and x86-64 the character classes are computed with SSE2 and the conversion to field
+//! element values with SSSE3, when the CPU supports them. SSE2 is always available on x86-64, other
+//! features are used if enabled at compile time or, with the `std` feature, detected at runtime.
+//!
+//! The scalar versions are always compiled and are what the vector versions are tested against,
+//! but the parent module only classifies blocks when [`available`], otherwise it uses its own
+//! portable code. Build with `--cfg=scalar` to disable the vector versions.
+
+use core::convert::TryFrom;
+
+use super::{CLASS_BECH32, CLASS_HRP, CLASS_LOWER, CLASS_SEP, CLASS_UPPER};
+use crate::primitives::gf32::{ASCII_TABLE, ASCII_VALUE};
+
+/// Number of bytes classified or converted at a time.
+pub(super) const LANES: usize = 16;
+
+/// The character classes of a block of [`LANES`] bytes, bit `i` of each mask is for byte `i`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) struct Masks {
+    /// Bytes in the bech32 alphabet (either case).
+    pub(super) bech32: u16,
+    /// ASCII uppercase letters.
+    pub(super) upper: u16,
+    /// ASCII lowercase letters.
+    pub(super) lower: u16,
+    /// The separator '1'.
+    pub(super) sep: u16,
+    /// Valid hrp characters, ASCII 33 to 126.
+    pub(super) hrp: u16,
+}
+
+/// Returns whether [`classify`] is vectorised on this CPU, if not it is slower than the scalar
+/// alternatives in the parent module.
+#[inline]
+pub(super) fn available() -> bool {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+    {
+        if has_sse2() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Copies `chunk`, at most [`LANES`] bytes, into a block.
+///
+/// Pads with '0' which is in the bech32 alphabet, is a valid hrp character and has no case, i.e.
+/// does not change the result of checking the characters.
+#[inline]
+pub(super) fn load(chunk: &[u8]) -> [u8; LANES] {
+    match <[u8; LANES]>::try_from(chunk) {
+        Ok(block) => block,
+        Err(_) => {
+            let mut block = [b'0'; LANES];
+            block[..chunk.len()].copy_from_slice(chunk);
+            block
+        }
+    }
+}
+
+/// Returns the character classes of `block`.
+#[inline]
+pub(super) fn classify(block: &[u8; LANES]) -> Masks {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+    {
+        if has_sse2() {
+            // SAFETY: the CPU supports SSE2.
+            return unsafe { x86::classify(block) };
+        }
+    }
+    classify_scalar(block)
+}
+
+/// The scalar version of [`classify`], one byte at a time using [`super::classify`].
+pub(super) fn classify_scalar(block: &[u8; LANES]) -> Masks {
+    let mut masks = Masks { bech32: 0, upper: 0, lower: 0, sep: 0, hrp: 0 };
+    for (i, &b) in block.iter().enumerate() {
+        let class = super::classify(b);
+        let bit = |flag: u8| u16::from(class & flag != 0) << i;
+        masks.bech32 |= bit(CLASS_BECH32);
+        masks.upper |= bit(CLASS_UPPER);
+        masks.lower |= bit(CLASS_LOWER);
+        masks.sep |= bit(CLASS_SEP);
+        masks.hrp |= bit(CLASS_HRP);
+    }
+    masks
+}
+
+/// Converts the bech32 characters in `block` to field element values.
+///
+/// The values of bytes not in the bech32 alphabet are unspecified, and may differ between the
+/// vector and scalar versions.
+#[inline]
+pub(super) fn to_values(block: &[u8; LANES]) -> [u8; LANES] {
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+    {
+        if has_ssse3() {
+            // SAFETY: the CPU supports SSSE3.
+            return unsafe { x86::to_values(block) };
+        }
+    }
+    to_values_scalar(block)
+}
+
+/// The scalar version of [`to_values`], using [`ASCII_TABLE`].
+pub(super) fn to_values_scalar(block: &[u8; LANES]) -> [u8; LANES] {
+    let mut values = [0; LANES];
+    for (value, &b) in values.iter_mut().zip(block.iter()) {
+        *value = ASCII_TABLE[usize::from(b)] & ASCII_VALUE;
+    }
+    values
+}
+
+/// Returns whether the CPU supports SSE2.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+#[inline]
+fn has_sse2() -> bool {
+    if cfg!(target_feature = "sse2") {
+        return true;
+    }
+    detect_sse2()
+}
+
+/// Returns whether the CPU supports SSSE3.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+#[inline]
+fn has_ssse3() -> bool {
+    if cfg!(target_feature = "ssse3") {
+        return true;
+    }
+    detect_ssse3()
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(scalar),
+    not(kani),
+    feature = "std"
+))]
+fn detect_sse2() -> bool { std::is_x86_feature_detected!("sse2") }
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(scalar),
+    not(kani),
+    feature = "std"
+))]
+fn detect_ssse3() -> bool { std::is_x86_feature_detected!("ssse3") }
+
+// Without `std` there is no runtime detection, only the features enabled at compile time are used.
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(scalar),
+    not(kani),
+    not(feature = "std")
+))]
+fn detect_sse2() -> bool { false }
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(scalar),
+    not(kani),
+    not(feature = "std")
+))]
+fn detect_ssse3() -> bool { false }
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(scalar), not(kani)))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::{Masks, LANES};
+
+    /// Field element values of the characters '0' to '9' (after clearing bit 5, i.e. `0x10` to
+    /// `0x19`) indexed by the low nibble, 0 for the characters not in the alphabet.
+    const DIGITS: [u8; 16] = [15, 0, 10, 17, 21, 20, 26, 30, 7, 5, 0, 0, 0, 0, 0, 0];
+    /// Field element values of the characters '@' to 'O' indexed by the low nibble.
+    const LETTERS_4: [u8; 16] = [0, 29, 0, 24, 13, 25, 9, 8, 23, 0, 18, 22, 31, 27, 19, 0];
+    /// Field element values of the characters 'P' to '_' indexed by the low nibble.
+    const LETTERS_5: [u8; 16] = [1, 0, 3, 16, 11, 28, 12, 14, 6, 4, 2, 0, 0, 0, 0, 0];
+
+    /// The character classes of `block`, see [`super::classify`].
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn classify(block: &[u8; LANES]) -> Masks {
+        let v = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+
+        let digit = in_range(v, b'0', b'9');
+        let upper = in_range(v, b'A', b'Z');
+        let lower = in_range(v, b'a', b'z');
+        let sep = _mm_cmpeq_epi8(v, _mm_set1_epi8(b'1' as i8));
+        // The letters not in the alphabet, in either case.
+        let folded = _mm_or_si128(v, _mm_set1_epi8(0x20));
+        let excluded = _mm_or_si128(
+            _mm_cmpeq_epi8(folded, _mm_set1_epi8(b'b' as i8)),
+            _mm_or_si128(
+                _mm_cmpeq_epi8(folded, _mm_set1_epi8(b'i' as i8)),
+                _mm_cmpeq_epi8(folded, _mm_set1_epi8(b'o' as i8)),
+            ),
+        );
+        let alnum = _mm_or_si128(digit, _mm_or_si128(upper, lower));
+        let bech32 = _mm_andnot_si128(_mm_or_si128(sep, excluded), alnum);
+
+        Masks {
+            bech32: mask(bech32),
+            upper: mask(upper),
+            lower: mask(lower),
+            sep: mask(sep),
+            hrp: mask(in_range(v, 33, 126)),
+        }
+    }
+
+    /// The field element values of the characters in `block`, see [`super::to_values`].
+    #[inline]
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn to_values(block: &[u8; LANES]) -> [u8; LANES] {
+        let v = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+
+        // Clearing bit 5 maps lowercase to uppercase, and the digits to 0x10 to 0x19.
+        let folded = _mm_and_si128(v, _mm_set1_epi8(!0x20));
+        let low = _mm_and_si128(folded, _mm_set1_epi8(0x0f));
+        let high = _mm_and_si128(_mm_srli_epi16(folded, 4), _mm_set1_epi8(0x0f));
+
+        let values = _mm_or_si128(
+            lookup(&DIGITS, low, high, 1),
+            _mm_or_si128(lookup(&LETTERS_4, low, high, 4), lookup(&LETTERS_5, low, high, 5)),
+        );
+
+        let mut out = [0; LANES];
+        _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, values);
+        out
+    }
+
+    /// Looks up the `low` nibbles in `table`, keeping the bytes whose `high` nibble is `nibble`.
+    #[inline]
+    #[target_feature(enable = "ssse3")]
+    unsafe fn lookup(table: &[u8; 16], low: __m128i, high: __m128i, nibble: i8) -> __m128i {
+        let table = _mm_loadu_si128(table.as_ptr() as *const __m128i);
+        _mm_and_si128(_mm_shuffle_epi8(table, low), _mm_cmpeq_epi8(high, _mm_set1_epi8(nibble)))
+    }
+
+    /// Sets the bytes of `v` that are in `lo..=hi`, which must be ASCII.
+    ///
+    /// Compares as signed bytes, so bytes above 0x7f are negative and never in range.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    unsafe fn in_range(v: __m128i, lo: u8, hi: u8) -> __m128i {
+        let above = _mm_cmpgt_epi8(v, _mm_set1_epi8(lo as i8 - 1));
+        let below = _mm_cmpgt_epi8(_mm_set1_epi8(hi as i8 + 1), v);
+        _mm_and_si128(above, below)
+    }
+
+    /// Returns the high bit of each byte of `v`.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    unsafe fn mask(v: __m128i) -> u16 { _mm_movemask_epi8(v) as u16 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::XorShift;
+    use super::*;
+
+    const ALPHABET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7lQPZRY9X8GF2TVDW0S3JN54KHCE6MUA7L";
+
+    #[test]
+    fn classify_every_byte_in_every_lane() {
+        for b in 0..=255_u8 {
+            for lane in 0..LANES {
+                let mut block = [b'q'; LANES];
+                block[lane] = b;
+                assert_eq!(classify(&block), classify_scalar(&block), "byte {} lane {}", b, lane);
+            }
+        }
+    }
+
+    #[test]
+    fn classify_matches_scalar_on_random_blocks() {
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..100_000 {
+            let mut block = [0; LANES];
+            for b in block.iter_mut() {
+                let r = rng.next();
+                // Half the bytes from the alphabet so that all the classes are mixed.
+                *b = if r & 1 == 0 {
+                    ALPHABET[(r >> 8) as usize % ALPHABET.len()]
+                } else {
+                    (r >> 8) as u8
+                };
+            }
+            assert_eq!(classify(&block), classify_scalar(&block), "{:?}", block);
+        }
+    }
+
+    #[test]
+    fn to_values_matches_scalar() {
+        for &b in ALPHABET.iter() {
+            for lane in 0..LANES {
+                let mut block = [b'q'; LANES];
+                block[lane] = b;
+                assert_eq!(to_values(&block), to_values_scalar(&block), "byte {} lane {}", b, lane);
+            }
+        }
+
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..100_000 {
+            let mut block = [0; LANES];
+            for b in block.iter_mut() {
+                *b = rng.pick(ALPHABET);
+            }
+            assert_eq!(to_values(&block), to_values_scalar(&block), "{:?}", block);
+        }
+    }
+
+    #[test]
+    fn to_values_scalar_matches_fe32() {
+        for &b in ALPHABET.iter() {
+            let values = to_values_scalar(&[b; LANES]);
+            assert_eq!(values[0], crate::Fe32::from_char(char::from(b)).unwrap().to_u8());
+        }
+    }
+}
@@ -40,10 +40,16 @@
 //! }
 //! ```
 
+#![cfg_attr(not(test), deny(clippy::indexing_slicing, clippy::unwrap_used, clippy::expect_used))]
+
+use core::fmt;
 use core::iter::Iterator;
 use core::marker::PhantomData;
 
-use crate::primitives::checksum::HrpFe32Iter;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::checksum::{self, HrpFe32Iter, PackedFe32};
 use crate::primitives::hrp::{self, Hrp};
 use crate::primitives::iter::Checksummed;
 use crate::{Checksum, Fe32};
@@ -71,6 +77,7 @@ use crate::{Checksum, Fe32};
 /// ```
 /// [`Fe32IterExt::with_checksum`]: crate::Fe32IterExt::with_checksum
 #[derive(Clone, PartialEq, Eq)]
+#[must_use = "encoders are lazy and do nothing unless one of their iterators is consumed"]
 pub struct Encoder<'hrp, I, Ck>
 where
     I: Iterator<Item = Fe32>,
@@ -134,6 +141,7 @@ where
 /// Iterator adaptor that just prepends a single character to a field element stream.
 ///
 /// More ergonomic to use than `std::iter::once(fe).chain(iter)`.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct WitnessVersionIter<I>
 where
     I: Iterator<Item = Fe32>,
@@ -164,15 +172,29 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (min, max) = self.iter.size_hint();
         match self.witness_version {
-            Some(_) => (min + 1, max.map(|max| max + 1)),
+            Some(_) => (min.saturating_add(1), max.and_then(|max| max.checked_add(1))),
             None => (min, max),
         }
     }
 }
 
+impl<I> ExactSizeIterator for WitnessVersionIter<I>
+where
+    I: Iterator<Item = Fe32> + ExactSizeIterator,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        match self.witness_version {
+            Some(_) => self.iter.len().saturating_add(1),
+            None => self.iter.len(),
+        }
+    }
+}
+
 /// Iterator adaptor which takes a stream of field elements, converts it to characters prefixed by
 /// an HRP (and separator), and suffixed by the checksum i.e., converts the data in a stream of
 /// field elements into stream of characters representing the encoded bech32 string.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct CharIter<'hrp, I, Ck>
 where
     I: Iterator<Item = Fe32>,
@@ -230,12 +252,13 @@ where
                 let (hrp_min, hrp_max) = hrp_iter.size_hint();
                 let (chk_min, chk_max) = self.checksummed.size_hint();
 
-                let min = hrp_min + 1 + chk_min; // +1 for the separator.
+                let min = hrp_min.saturating_add(1).saturating_add(chk_min); // +1 for the separator.
 
                 // To provide a max boundary we need to have gotten a value from the hrp iter as well as the
                 // checksummed iter, otherwise we have to return None since we cannot know the maximum.
                 let max = match (hrp_max, chk_max) {
-                    (Some(hrp_max), Some(chk_max)) => Some(hrp_max + 1 + chk_max),
+                    (Some(hrp_max), Some(chk_max)) =>
+                        hrp_max.checked_add(1).and_then(|max| max.checked_add(chk_max)),
                     (_, _) => None,
                 };
 
@@ -245,11 +268,28 @@ where
     }
 }
 
+impl<'a, I, Ck> ExactSizeIterator for CharIter<'a, I, Ck>
+where
+    I: Iterator<Item = Fe32> + ExactSizeIterator,
+    Ck: Checksum,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        match &self.hrp_iter {
+            // +1 for the separator.
+            Some(hrp_iter) =>
+                hrp_iter.len().saturating_add(1).saturating_add(self.checksummed.len()),
+            None => self.checksummed.len(),
+        }
+    }
+}
+
 /// Iterator adaptor which takes a stream of ASCII field elements (an encoded string) and yields a stream of bytes.
 ///
 /// This is equivalent to using the `CharsIter` and the casting each character to a byte. Doing
 /// so is technically sound because we only yield ASCII characters but it makes for ugly code so
 /// we provide this iterator also.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct ByteIter<'hrp, I, Ck>
 where
     I: Iterator<Item = Fe32>,
@@ -282,8 +322,18 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) { self.char_iter.size_hint() }
 }
 
+impl<'a, I, Ck> ExactSizeIterator for ByteIter<'a, I, Ck>
+where
+    I: Iterator<Item = Fe32> + ExactSizeIterator,
+    Ck: Checksum,
+{
+    #[inline]
+    fn len(&self) -> usize { self.char_iter.len() }
+}
+
 /// Iterator adaptor for a checksummed iterator that inputs the HRP into the checksum algorithm
 /// before yielding the HRP as field elements followed by the data then checksum.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct Fe32Iter<'hrp, I, Ck>
 where
     I: Iterator<Item = Fe32>,
@@ -336,16 +386,274 @@ where
 
         let data = self.checksummed.size_hint();
 
-        let min = hrp.0 + data.0;
-        let max = hrp.1.zip(data.1).map(|(hrp, data)| hrp + data);
+        let min = hrp.0.saturating_add(data.0);
+        let max = hrp.1.zip(data.1).and_then(|(hrp, data)| hrp.checked_add(data));
 
         (min, max)
     }
 }
 
+/// Builds an iterator that encodes fallible field elements into a bech32 string.
+///
+/// Construct the encoder by calling [`TryFe32IterExt::with_checksum`] on an iterator of
+/// `Result<Fe32, E>`, e.g. from [`TryByteIterExt::try_bytes_to_fes`], then get the encoding with
+/// [`TryEncoder::chars`] or write it with [`TryEncoder::try_write_to`]. The first error of the
+/// data ends the encoding, no checksum is ever computed for data that failed.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::iter::{TryByteIterExt, TryFe32IterExt};
+/// use bech32grs::{Bech32, Fe32, Hrp};
+///
+/// // Bytes read from a stream, which fails part way through.
+/// let bytes = vec![Ok(0x75), Ok(0x1e), Err("connection lost"), Ok(0x76)];
+///
+/// let hrp = Hrp::parse("grs").expect("valid hrp");
+/// let mut s = String::new();
+/// let res = bytes
+///     .into_iter()
+///     .try_bytes_to_fes()
+///     .with_checksum::<Bech32>(&hrp)
+///     .with_witness_version(Fe32::Q)
+///     .try_write_to(&mut s);
+///
+/// assert!(res.is_err());
+/// assert_eq!(s, "grs1qw50"); // Nothing after the error, and no checksum.
+/// ```
+///
+/// [`TryFe32IterExt::with_checksum`]: crate::primitives::iter::TryFe32IterExt::with_checksum
+/// [`TryByteIterExt::try_bytes_to_fes`]: crate::primitives::iter::TryByteIterExt::try_bytes_to_fes
+#[derive(Clone, PartialEq, Eq)]
+#[must_use = "encoders are lazy and do nothing unless one of their iterators is consumed"]
+pub struct TryEncoder<'hrp, I, Ck>
+where
+    Ck: Checksum,
+{
+    /// The fallible field elements to encode.
+    data: I,
+    /// The human-readable part used at the front of the encoding.
+    hrp: &'hrp Hrp,
+    /// The witness version, if present.
+    witness_version: Option<Fe32>,
+    /// Checksum marker.
+    marker: PhantomData<Ck>,
+}
+
+impl<'hrp, I, E, Ck> TryEncoder<'hrp, I, Ck>
+where
+    I: Iterator<Item = Result<Fe32, E>>,
+    Ck: Checksum,
+{
+    /// Constructs a new bech32 encoder of fallible data.
+    #[inline]
+    pub fn new(data: I, hrp: &'hrp Hrp) -> Self {
+        Self { data, hrp, witness_version: None, marker: PhantomData::<Ck> }
+    }
+
+    /// Adds `witness_version` to the encoder (as first byte of encoded data).
+    ///
+    /// Note, caller to guarantee that witness version is within valid range (0-16).
+    #[inline]
+    pub fn with_witness_version(mut self, witness_version: Fe32) -> Self {
+        self.witness_version = Some(witness_version);
+        self
+    }
+
+    /// Returns an iterator that yields the bech32 encoded string as characters, or the first error
+    /// of the data after which it yields nothing.
+    #[inline]
+    pub fn chars(self) -> TryCharIter<'hrp, I, Ck> {
+        let mut engine = checksum::Engine::new();
+        engine.input_hrp(*self.hrp);
+        TryCharIter {
+            hrp_iter: Some(self.hrp.lowercase_char_iter()),
+            witness_version: self.witness_version,
+            data: self.data,
+            data_done: false,
+            failed: false,
+            checksum_remaining: Ck::CHECKSUM_LENGTH,
+            engine,
+        }
+    }
+
+    /// Writes the bech32 encoded string to `w`, stopping at the first error of the data or of `w`.
+    ///
+    /// The characters before the error have already been written to `w` when this returns an
+    /// error, the caller is responsible for discarding them.
+    pub fn try_write_to<W: fmt::Write>(self, w: &mut W) -> Result<(), TryWriteError<E>> {
+        for c in self.chars() {
+            w.write_char(c.map_err(TryWriteError::Source)?).map_err(TryWriteError::Fmt)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator adaptor that encodes fallible field elements into the characters of a bech32 string.
+///
+/// Created by [`TryEncoder::chars`]. Yields `Ok` for each character and, if the data fails, the
+/// error as the last item. The checksum is only computed and yielded once all the data has been
+/// input successfully.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct TryCharIter<'hrp, I, Ck>
+where
+    Ck: Checksum,
+{
+    /// `None` once the hrp has been yielded.
+    hrp_iter: Option<hrp::LowercaseCharIter<'hrp>>,
+    /// The witness version, until it has been yielded.
+    witness_version: Option<Fe32>,
+    /// The fallible field elements to encode.
+    data: I,
+    /// Whether all the data has been input, without an error.
+    data_done: bool,
+    /// Whether the data failed, after which nothing more is yielded.
+    failed: bool,
+    /// The number of checksum characters still to yield.
+    checksum_remaining: usize,
+    /// The checksum engine, fed the hrp and all yielded field elements.
+    engine: checksum::Engine<Ck>,
+}
+
+impl<'hrp, I, E, Ck> Iterator for TryCharIter<'hrp, I, Ck>
+where
+    I: Iterator<Item = Result<Fe32, E>>,
+    Ck: Checksum,
+{
+    type Item = Result<char, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<char, E>> {
+        if self.failed {
+            return None;
+        }
+        if let Some(ref mut hrp_iter) = self.hrp_iter {
+            match hrp_iter.next() {
+                Some(c) => return Some(Ok(c)),
+                None => {
+                    self.hrp_iter = None;
+                    return Some(Ok('1'));
+                }
+            }
+        }
+        if let Some(fe) = self.witness_version.take() {
+            self.engine.input_fe(fe);
+            return Some(Ok(fe.to_char()));
+        }
+        if !self.data_done {
+            match self.data.next() {
+                Some(Ok(fe)) => {
+                    self.engine.input_fe(fe);
+                    return Some(Ok(fe.to_char()));
+                }
+                Some(Err(e)) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.data_done = true;
+                    self.engine.input_target_residue();
+                }
+            }
+        }
+        if self.checksum_remaining == 0 {
+            return None;
+        }
+        self.checksum_remaining -= 1;
+        Some(Ok(Fe32(self.engine.residue().unpack(self.checksum_remaining)).to_char()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            return (0, Some(0));
+        }
+        let (hrp_min, hrp_max) = match &self.hrp_iter {
+            // +1 for the separator.
+            Some(hrp_iter) => {
+                let (min, max) = hrp_iter.size_hint();
+                (min.saturating_add(1), max.and_then(|max| max.checked_add(1)))
+            }
+            None => (0, Some(0)),
+        };
+        let witness_version = usize::from(self.witness_version.is_some());
+        let prefix_min = hrp_min.saturating_add(witness_version);
+        let prefix_max = hrp_max.and_then(|max| max.checked_add(witness_version));
+
+        if self.data_done {
+            let checksum = self.checksum_remaining;
+            return (
+                prefix_min.saturating_add(checksum),
+                prefix_max.and_then(|max| max.checked_add(checksum)),
+            );
+        }
+        // The data may fail straight away, with a single item for the error.
+        let (data_min, data_max) = self.data.size_hint();
+        let min = prefix_min.saturating_add(data_min.min(1));
+        let max = prefix_max
+            .zip(data_max)
+            .and_then(|(prefix, data)| prefix.checked_add(data))
+            .and_then(|max| max.checked_add(self.checksum_remaining));
+        (min, max)
+    }
+}
+
+/// An error while writing a bech32 string of fallible data with [`TryEncoder::try_write_to`].
+///
+/// Generic over the error type of the data so that it is not lost.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum TryWriteError<E> {
+    /// The data to encode failed.
+    Source(E),
+    /// Writing to the formatter failed.
+    Fmt(fmt::Error),
+}
+
+impl<E> TryWriteError<E> {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use TryWriteError::*;
+
+        match *self {
+            Source(_) => Code::Source,
+            Fmt(_) => Code::Fmt,
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for TryWriteError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use TryWriteError::*;
+
+        match *self {
+            Source(ref e) => write!(f, "the data to encode failed: {:?}", e),
+            Fmt(ref e) => write_err!(f, "encode to formatter failed"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: StdError + 'static> StdError for TryWriteError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use TryWriteError::*;
+
+        match *self {
+            Source(ref e) => Some(e),
+            Fmt(ref e) => Some(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Bech32, ByteIterExt, Fe32, Fe32IterExt, Hrp};
+    use super::*;
+    use crate::{Bech32, ByteIterExt, Fe32IterExt};
 
     // Tests below using this data, are based on the test vector (from BIP-173):
     // GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5: 0014751e76e8199196d454941c45d1b3a323f1433bd6
@@ -404,4 +712,136 @@ mod tests {
             assert_eq!(c as u8, b)
         }
     }
+
+    #[test]
+    fn hrpstring_iter_len() {
+        fn check<Ck: Checksum>(want: &str) {
+            let hrp = Hrp::parse_unchecked("grs");
+            let fes = DATA.iter().copied().bytes_to_fes();
+            let mut chars = fes.with_checksum::<Ck>(&hrp).with_witness_version(Fe32::Q).chars();
+            // Check the length after each character, including in the hrp and at the separator.
+            for remaining in (0..=want.len()).rev() {
+                assert_eq!(chars.len(), remaining);
+                assert_eq!(chars.size_hint(), (remaining, Some(remaining)));
+                chars.next();
+            }
+            assert_eq!(chars.next(), None);
+        }
+        check::<Bech32>("grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5");
+        check::<crate::NoChecksum>("grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k");
+    }
+
+    #[test]
+    fn no_checksum_chars() {
+        let hrp = Hrp::parse_unchecked("grs");
+        let fes = DATA.iter().copied().bytes_to_fes();
+        let iter = fes.with_checksum::<crate::NoChecksum>(&hrp).with_witness_version(Fe32::Q);
+        assert!(iter.clone().chars().eq("grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k".chars()));
+        assert!(iter.bytes().eq("grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k".bytes()));
+
+        // With no data there is still the hrp and separator.
+        let chars = core::iter::empty().with_checksum::<crate::NoChecksum>(&hrp).chars();
+        assert_eq!(chars.len(), 4);
+        assert!(chars.eq("grs1".chars()));
+    }
+
+    /// The bytes of `DATA` as a fallible source, failing in place of the byte at `fail_at`.
+    fn failing_source(fail_at: usize) -> impl Iterator<Item = Result<u8, usize>> + Clone {
+        DATA.iter().enumerate().map(move |(i, &b)| if i == fail_at { Err(i) } else { Ok(b) })
+    }
+
+    #[test]
+    fn try_chars_without_error() {
+        use crate::{TryByteIterExt, TryFe32IterExt};
+
+        let hrp = Hrp::parse_unchecked("grs");
+        let source = DATA.iter().map(|&b| Ok::<u8, ()>(b));
+        let mut chars = source.try_bytes_to_fes().with_checksum::<Bech32>(&hrp).chars();
+        let want = DATA.iter().copied().bytes_to_fes().with_checksum::<Bech32>(&hrp).chars();
+
+        let len = want.len();
+        for (i, c) in want.enumerate() {
+            let remaining = len - i;
+            let (min, max) = chars.size_hint();
+            assert!(min <= remaining && max >= Some(remaining));
+            assert_eq!(chars.next(), Some(Ok(c)));
+        }
+        assert_eq!(chars.size_hint(), (0, Some(0)));
+        assert_eq!(chars.next(), None);
+    }
+
+    #[test]
+    fn try_chars_stop_at_error() {
+        use crate::{TryByteIterExt, TryFe32IterExt};
+
+        let hrp = Hrp::parse_unchecked("grs");
+        let want = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5";
+
+        for fail_at in 0..DATA.len() {
+            let source = failing_source(fail_at).try_bytes_to_fes();
+            let mut chars =
+                source.with_checksum::<Bech32>(&hrp).with_witness_version(Fe32::Q).chars();
+
+            let mut got = [0_u8; 64];
+            let mut len = 0;
+            let err = loop {
+                match chars.next().expect("the error is yielded") {
+                    Ok(c) => {
+                        got[len] = c as u8;
+                        len += 1;
+                    }
+                    Err(e) => break e,
+                }
+            };
+            assert_eq!(err, fail_at);
+            // Only the characters made entirely of bytes before the error, no checksum.
+            assert_eq!(len, 5 + fail_at * 8 / 5);
+            assert_eq!(&got[..len], &want.as_bytes()[..len]);
+            assert_eq!(chars.size_hint(), (0, Some(0)));
+            assert_eq!(chars.next(), None);
+        }
+    }
+
+    #[test]
+    fn try_write_to() {
+        use core::fmt::Write;
+
+        use crate::{TryByteIterExt, TryFe32IterExt};
+
+        /// Writes to a fixed buffer.
+        struct Buf {
+            buf: [u8; 64],
+            len: usize,
+            capacity: usize,
+        }
+
+        impl Write for Buf {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                if self.len + s.len() > self.capacity {
+                    return Err(fmt::Error);
+                }
+                self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+                self.len += s.len();
+                Ok(())
+            }
+        }
+
+        let hrp = Hrp::parse_unchecked("grs");
+        let want = "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7k3k4sj5";
+        let encoder = |fail_at| {
+            let source = failing_source(fail_at).try_bytes_to_fes();
+            source.with_checksum::<Bech32>(&hrp).with_witness_version(Fe32::Q)
+        };
+
+        let mut w = Buf { buf: [0; 64], len: 0, capacity: 64 };
+        encoder(DATA.len()).try_write_to(&mut w).expect("no error");
+        assert_eq!(&w.buf[..w.len], want.as_bytes());
+
+        let mut w = Buf { buf: [0; 64], len: 0, capacity: 64 };
+        assert_eq!(encoder(7).try_write_to(&mut w), Err(TryWriteError::Source(7)));
+        assert_eq!(&w.buf[..w.len], "grs1qw508d6qejxt".as_bytes());
+
+        let mut w = Buf { buf: [0; 64], len: 0, capacity: 10 };
+        assert_eq!(encoder(DATA.len()).try_write_to(&mut w), Err(TryWriteError::Fmt(fmt::Error)));
+    }
 }
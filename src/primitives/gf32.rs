@@ -12,13 +12,19 @@
 //!
 //! [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
 
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
 use core::convert::{Infallible, TryFrom};
+use core::hash::{Hash, Hasher};
 use core::{fmt, num, ops};
 
 #[cfg(all(test, mutate))]
 use mutagen::mutate;
 
-use crate::error::write_err;
+use crate::builder::BitCursor;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
 
 /// Logarithm table of each bech32 element, as a power of alpha = Z.
 ///
@@ -41,6 +47,49 @@ const LOG_INV: [u8; 31] = [
     28, 17, 11, 22,  5, 10, 20,
 ];
 
+/// Multiplication table, `MUL_TABLE[a][b]` is the numeric value of `a * b`.
+///
+/// Uses 1 KiB, enable the `small-tables` feature to multiply using [`LOG`] and [`LOG_INV`] instead.
+#[cfg(any(test, not(feature = "small-tables")))]
+const MUL_TABLE: [[u8; 32]; 32] = mul_table();
+
+/// Computes [`MUL_TABLE`] at compile time.
+#[cfg(any(test, not(feature = "small-tables")))]
+const fn mul_table() -> [[u8; 32]; 32] {
+    let mut table = [[0; 32]; 32];
+    let mut a = 0;
+    while a < 32 {
+        let mut b = 0;
+        while b < 32 {
+            table[a][b] = clmul(a as u8, b as u8);
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+}
+
+/// Multiplies the numeric values of two field elements one bit at a time.
+///
+/// Carry-less multiplication of the polynomials, reduced by x^5 + x^3 + 1 (41 as bits).
+#[cfg(any(test, not(feature = "small-tables")))]
+const fn clmul(a: u8, b: u8) -> u8 {
+    let mut a = a;
+    let mut ret = 0;
+    let mut i = 0;
+    while i < 5 {
+        if b & (1 << i) != 0 {
+            ret ^= a;
+        }
+        a <<= 1;
+        if a & 0x20 != 0 {
+            a ^= 41;
+        }
+        i += 1;
+    }
+    ret
+}
+
 /// Mapping from numeric value to bech32 character.
 #[rustfmt::skip]
 const CHARS_LOWER: [char; 32] = [
@@ -50,6 +99,32 @@ const CHARS_LOWER: [char; 32] = [
     'c', 'e', '6', 'm', 'u', 'a', '7', 'l', // +24
 ];
 
+/// Pairs of lowercase bech32 characters that are easily mistaken for one another when read by eye.
+///
+/// Each pair is listed once, in ASCII order, and the pairs are sorted.
+#[rustfmt::skip]
+const CONFUSABLE_PAIRS: [(char, char); 9] = [
+    ('2', 'z'), ('5', 's'), ('6', 'g'), ('9', 'g'), ('9', 'q'),
+    ('g', 'q'), ('m', 'n'), ('u', 'v'), ('v', 'w'),
+];
+
+/// Returns the pairs of lowercase bech32 characters that are easily mistaken for one another.
+///
+/// Intended for user interfaces that warn when an address differs from an expected one by a likely
+/// misreading, see [`segwit::visual_diff`](crate::segwit::visual_diff). Each pair is listed once,
+/// with the characters in ASCII order.
+///
+/// The table is purely lexical and says nothing about which errors the checksum detects.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::gf32;
+/// assert!(gf32::confusable_pairs().contains(&('5', 's')));
+/// ```
+#[inline]
+pub fn confusable_pairs() -> &'static [(char, char)] { &CONFUSABLE_PAIRS }
+
 /// Mapping from bech32 character (either case) to numeric value.
 ///
 /// E.g., 'z' is CHARS_LOWER[2] and is ASCII value 122 so CHARS_INV[122] == 2
@@ -65,8 +140,52 @@ const CHARS_INV: [i8; 128] = [
      1,  0,  3, 16, 11, 28, 12, 14,  6,  4,  2, -1, -1, -1, -1, -1,
 ];
 
+/// Numeric value and character class of every byte, so decoding needs one lookup per character.
+///
+/// The low 5 bits hold the numeric value of the bech32 character (zero for any other byte) and the
+/// high bits are the [`ASCII_BECH32`], [`ASCII_UPPER`] and [`ASCII_LOWER`] class flags.
+///
+/// Both cases of a bech32 letter have the same value, so uppercase strings are converted to field
+/// elements without case folding. Whether a string mixes cases is checked with the class flags.
+pub(crate) const ASCII_TABLE: [u8; 256] = ascii_table();
+
+/// Mask of the numeric value in an [`ASCII_TABLE`] entry.
+pub(crate) const ASCII_VALUE: u8 = 0b0001_1111;
+/// Flag set in an [`ASCII_TABLE`] entry if the byte is a bech32 character (either case).
+pub(crate) const ASCII_BECH32: u8 = 0b0010_0000;
+/// Flag set in an [`ASCII_TABLE`] entry if the byte is an ASCII uppercase letter.
+pub(crate) const ASCII_UPPER: u8 = 0b0100_0000;
+/// Flag set in an [`ASCII_TABLE`] entry if the byte is an ASCII lowercase letter.
+pub(crate) const ASCII_LOWER: u8 = 0b1000_0000;
+
+/// Builds [`ASCII_TABLE`] from [`CHARS_INV`].
+const fn ascii_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < CHARS_INV.len() {
+        let mut entry = 0;
+        if CHARS_INV[i] >= 0 {
+            entry = CHARS_INV[i] as u8 | ASCII_BECH32;
+        }
+        if i >= b'A' as usize && i <= b'Z' as usize {
+            entry |= ASCII_UPPER;
+        } else if i >= b'a' as usize && i <= b'z' as usize {
+            entry |= ASCII_LOWER;
+        }
+        table[i] = entry;
+        i += 1;
+    }
+    table
+}
+
 /// An element in GF(32), the finite field containing elements `[0,31]` inclusive.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(bytecheck(verify))
+)]
 #[repr(transparent)]
 pub struct Fe32(pub(crate) u8);
 
@@ -178,6 +297,13 @@ impl Fe32 {
         .copied()
     }
 
+    /// Creates a field element from a byte.
+    ///
+    /// The `u5::try_from_u8` of `0.9.1`, returning the error of [`Fe32::try_from`].
+    #[deprecated(note = "use `Fe32::try_from` instead")]
+    #[inline]
+    pub fn try_from_u8(value: u8) -> Result<Fe32, TryFromError> { Fe32::try_from(value) }
+
     /// Creates a field element from a single bech32 character.
     ///
     /// # Errors
@@ -199,10 +325,10 @@ impl Fe32 {
 
     /// Creates a field element from a single bech32 character.
     ///
-    /// # Panics
-    ///
-    /// If the input character is not part of the bech32 alphabet.
-    pub fn from_char_unchecked(c: u8) -> Fe32 { Fe32(CHARS_INV[usize::from(c)] as u8) }
+    /// The input is not validated, if it is not part of the bech32 alphabet the returned field
+    /// element is valid but otherwise meaningless. This function never panics.
+    #[inline]
+    pub fn from_char_unchecked(c: u8) -> Fe32 { Fe32(ASCII_TABLE[usize::from(c)] & ASCII_VALUE) }
 
     /// Converts the field element to a lowercase bech32 character.
     #[inline]
@@ -211,6 +337,23 @@ impl Fe32 {
         CHARS_LOWER[usize::from(self.0)]
     }
 
+    /// Returns whether `self` and `other` are different characters that are easily mistaken for one
+    /// another when read by eye, see [`confusable_pairs`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::Fe32;
+    /// assert!(Fe32::Z.visual_confusability(Fe32::_2));
+    /// assert!(!Fe32::Z.visual_confusability(Fe32::Z));
+    /// assert!(!Fe32::Z.visual_confusability(Fe32::L));
+    /// ```
+    #[inline]
+    pub fn visual_confusability(self, other: Fe32) -> bool {
+        let (a, b) = (self.to_char(), other.to_char());
+        CONFUSABLE_PAIRS.iter().any(|&pair| pair == (a, b) || pair == (b, a))
+    }
+
     /// Converts the field element to a 5-bit u8, with bits representing the coefficients
     /// of the polynomial representation.
     #[inline]
@@ -221,8 +364,25 @@ impl Fe32 {
     // Subtraction is the same as addition in a char-2 field.
     fn _sub(self, other: Fe32) -> Fe32 { self + other }
 
+    #[cfg(not(feature = "small-tables"))]
+    #[inline]
+    fn _mul(self, other: Fe32) -> Fe32 { self.mul_table(other) }
+
+    #[cfg(feature = "small-tables")]
+    #[inline]
+    fn _mul(self, other: Fe32) -> Fe32 { self.mul_log(other) }
+
+    /// Multiplies using [`MUL_TABLE`].
+    #[cfg(any(test, not(feature = "small-tables")))]
+    #[cfg_attr(all(test, mutate), mutate)]
+    fn mul_table(self, other: Fe32) -> Fe32 {
+        Fe32(MUL_TABLE[usize::from(self.0)][usize::from(other.0)])
+    }
+
+    /// Multiplies using [`LOG`] and [`LOG_INV`].
+    #[cfg(any(test, feature = "small-tables"))]
     #[cfg_attr(all(test, mutate), mutate)]
-    fn _mul(self, other: Fe32) -> Fe32 {
+    fn mul_log(self, other: Fe32) -> Fe32 {
         if self.0 == 0 || other.0 == 0 {
             Fe32(0)
         } else {
@@ -338,8 +498,53 @@ impl ops::DivAssign for Fe32 {
     fn div_assign(&mut self, other: Fe32) { *self = *self / other; }
 }
 
+/// Serialized as a single byte, deserializing a byte greater than 31 fails.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Fe32 {
+    #[inline]
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        borsh::BorshSerialize::serialize(&self.0, writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Fe32 {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let byte = <u8 as borsh::BorshDeserialize>::deserialize_reader(reader)?;
+        Fe32::try_from(byte).map_err(|_| crate::archive::invalid_data("field element out of range"))
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for Fe32 {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.0, &other.0)
+    }
+}
+
+/// Zeroizing sets the field element to [`Fe32::Q`], the zero element.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Fe32 {
+    #[inline]
+    fn zeroize(&mut self) { self.0.zeroize() }
+}
+
+// SAFETY: `verify` only rejects values, an `ArchivedFe32` has no invariants beyond its byte.
+#[cfg(feature = "rkyv")]
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedFe32
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        crate::archive::check(self.0 <= 31, "field element out of range")
+    }
+}
+
 /// A galois field error when converting from a character.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum FromCharError {
     /// Tried to interpret a character as a GF32 element but it is not an ASCII character.
@@ -348,6 +553,22 @@ pub enum FromCharError {
     Invalid(char),
 }
 
+impl FromCharError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use FromCharError::*;
+
+        match *self {
+            NotAscii(_) => Code::CharNotAscii,
+            Invalid(_) => Code::CharInvalid,
+        }
+    }
+}
+
 impl fmt::Display for FromCharError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use FromCharError::*;
@@ -359,9 +580,9 @@ impl fmt::Display for FromCharError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for FromCharError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for FromCharError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use FromCharError::*;
 
         match *self {
@@ -380,6 +601,36 @@ pub enum TryFromError {
     InvalidByte(u8),
 }
 
+// `num::TryFromIntError` does not implement `Hash`, all its values are equal so hashing the
+// variant is enough.
+impl Hash for TryFromError {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        use TryFromError::*;
+
+        core::mem::discriminant(self).hash(h);
+        match *self {
+            NotAByte(_) => {}
+            InvalidByte(b) => b.hash(h),
+        }
+    }
+}
+
+impl TryFromError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use TryFromError::*;
+
+        match *self {
+            NotAByte(_) => Code::FeNotAByte,
+            InvalidByte(_) => Code::FeInvalidByte,
+        }
+    }
+}
+
 impl fmt::Display for TryFromError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use TryFromError::*;
@@ -391,9 +642,21 @@ impl fmt::Display for TryFromError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for TryFromError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(feature = "defmt")]
+impl defmt::Format for TryFromError {
+    fn format(&self, f: defmt::Formatter) {
+        use TryFromError::*;
+
+        match *self {
+            NotAByte(_) => defmt::write!(f, "invalid field element"),
+            InvalidByte(b) => defmt::write!(f, "invalid byte in field element: {=u8:#x}", b),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for TryFromError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use TryFromError::*;
 
         match *self {
@@ -413,6 +676,541 @@ impl From<Infallible> for TryFromError {
     fn from(i: Infallible) -> Self { match i {} }
 }
 
+/// Reads fixed width fields, most significant bit first, from a slice of field elements.
+///
+/// Each field element holds 5 bits, the reader keeps a cursor counting the bits read so far so
+/// fields can start and end in the middle of a field element. A read that fails returns an error
+/// with the cursor position and leaves the cursor where it was.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::gf32::{Fe32, FeReader};
+///
+/// // A 3 bit tag, a 16 bit length and a padding bit.
+/// let fes = [Fe32::_5, Fe32::F, Fe32::R, Fe32::G];
+/// let mut reader = FeReader::new(&fes);
+/// assert_eq!(reader.read_bits(3), Ok(0b101));
+/// assert_eq!(reader.read_u16(), Ok(0x1234));
+/// assert_eq!(reader.remaining_bits(), 1);
+/// assert!(reader.read_u8().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FeReader<'a> {
+    /// The field elements being read.
+    fes: &'a [Fe32],
+    /// The number of bits read so far.
+    pos: usize,
+}
+
+impl<'a> FeReader<'a> {
+    /// Constructs a reader positioned at the first bit of `fes`.
+    #[inline]
+    pub fn new(fes: &'a [Fe32]) -> Self { FeReader { fes, pos: 0 } }
+
+    /// Returns the number of bits read or skipped so far.
+    #[inline]
+    pub fn position(&self) -> usize { self.pos }
+
+    /// Returns the number of bits left to read.
+    #[inline]
+    pub fn remaining_bits(&self) -> usize { self.fes.len() * 5 - self.pos }
+
+    /// Reads the next `n` bits as an unsigned integer, `n` must be at most 64.
+    pub fn read_bits(&mut self, n: usize) -> Result<u64, FeReaderError> {
+        if n > 64 {
+            return Err(FeReaderError::TooWide { bits: n });
+        }
+        self.check_remaining(n)?;
+
+        let end = self.pos + n;
+        let mut value = 0_u64;
+        while self.pos < end {
+            let offset = self.pos % 5;
+            let take = core::cmp::min(5 - offset, end - self.pos);
+            let bits = (self.fes[self.pos / 5].0 >> (5 - offset - take)) & ((1 << take) - 1);
+            value = (value << take) | u64::from(bits);
+            self.pos += take;
+        }
+        Ok(value)
+    }
+
+    /// Reads the next 8 bits as a `u8`.
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8, FeReaderError> { self.read_bits(8).map(|v| v as u8) }
+
+    /// Reads the next 16 bits as a big-endian `u16`.
+    #[inline]
+    pub fn read_u16(&mut self) -> Result<u16, FeReaderError> {
+        self.read_bits(16).map(|v| v as u16)
+    }
+
+    /// Reads the next 32 bits as a big-endian `u32`.
+    #[inline]
+    pub fn read_u32(&mut self) -> Result<u32, FeReaderError> {
+        self.read_bits(32).map(|v| v as u32)
+    }
+
+    /// Fills `buf` with the next bytes, the cursor must be at a multiple of 8 bits.
+    ///
+    /// Byte strings in bech32 data start on a byte boundary, a misaligned cursor usually means
+    /// an earlier field was read with the wrong width. Use [`Self::read_bytes_unaligned`] for
+    /// formats that pack bytes at any bit position.
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), FeReaderError> {
+        if self.pos % 8 != 0 {
+            return Err(FeReaderError::Misaligned { pos: self.pos });
+        }
+        self.read_bytes_unaligned(buf)
+    }
+
+    /// Fills `buf` with the next bytes, starting at any bit position.
+    pub fn read_bytes_unaligned(&mut self, buf: &mut [u8]) -> Result<(), FeReaderError> {
+        self.check_remaining(buf.len().saturating_mul(8))?;
+        for byte in buf.iter_mut() {
+            *byte = self.read_bits(8)? as u8;
+        }
+        Ok(())
+    }
+
+    /// Moves the cursor forward by `n` bits without reading them.
+    pub fn skip(&mut self, n: usize) -> Result<(), FeReaderError> {
+        self.check_remaining(n)?;
+        self.pos += n;
+        Ok(())
+    }
+
+    /// Returns an error if fewer than `n` bits are left to read.
+    fn check_remaining(&self, n: usize) -> Result<(), FeReaderError> {
+        let remaining = self.remaining_bits();
+        if n > remaining {
+            Err(FeReaderError::OutOfData { pos: self.pos, requested: n, remaining })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An error while reading from a [`FeReader`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum FeReaderError {
+    /// Tried to read or skip more bits than are left.
+    OutOfData {
+        /// The cursor position, in bits.
+        pos: usize,
+        /// The number of bits requested.
+        requested: usize,
+        /// The number of bits left.
+        remaining: usize,
+    },
+    /// Tried to read bytes when the cursor is not on a byte boundary.
+    Misaligned {
+        /// The cursor position, in bits.
+        pos: usize,
+    },
+    /// Tried to read more than 64 bits as an integer.
+    TooWide {
+        /// The number of bits requested.
+        bits: usize,
+    },
+}
+
+impl FeReaderError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use FeReaderError::*;
+
+        match *self {
+            OutOfData { .. } => Code::ReaderOutOfData,
+            Misaligned { .. } => Code::ReaderMisaligned,
+            TooWide { .. } => Code::ReaderBits,
+        }
+    }
+}
+
+impl fmt::Display for FeReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FeReaderError::*;
+
+        match *self {
+            OutOfData { pos, requested, remaining } => write!(
+                f,
+                "tried to read {} bits at bit {} but only {} are left",
+                requested, pos, remaining
+            ),
+            Misaligned { pos } =>
+                write!(f, "tried to read bytes at bit {}, not on a byte boundary", pos),
+            TooWide { bits } => write!(f, "tried to read {} bits, more than 64", bits),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for FeReaderError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use FeReaderError::*;
+
+        match *self {
+            OutOfData { .. } | Misaligned { .. } | TooWide { .. } => None,
+        }
+    }
+}
+
+/// How a [`FeWriter`] or [`FeSliceWriter`] finishes when the bits written do not fill the last
+/// field element.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FePadding {
+    /// Pads the last field element with zero bits, as segwit does for the witness program.
+    Zero,
+    /// No padding, finishing fails unless the bits fill whole field elements.
+    Exact,
+}
+
+impl Default for FePadding {
+    #[inline]
+    fn default() -> Self { FePadding::Zero }
+}
+
+/// Writes fixed width fields, most significant bit first, into a vector of field elements.
+///
+/// The counterpart of [`FeReader`], fields and bytes are written back to back without padding
+/// between them. Only [`Self::finish`] pads, as set by [`Self::with_padding`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::gf32::{Fe32, FeReader, FeWriter};
+///
+/// let mut writer = FeWriter::new();
+/// writer.write_bits(0b101, 3).expect("fits in 3 bits");
+/// writer.write_u16(0x1234);
+/// assert_eq!(writer.bit_len(), 19);
+///
+/// let fes = writer.finish().expect("zero padding");
+/// assert_eq!(fes, [Fe32::_5, Fe32::F, Fe32::R, Fe32::G]);
+/// assert_eq!(FeReader::new(&fes).read_bits(19), Ok(0b101_0001_0010_0011_0100));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[must_use = "a writer does nothing unless it is finished"]
+pub struct FeWriter {
+    /// The complete field elements.
+    fes: Vec<Fe32>,
+    /// The bits written since the last complete field element.
+    cursor: BitCursor,
+    /// How the last field element is padded.
+    padding: FePadding,
+}
+
+#[cfg(feature = "alloc")]
+impl FeWriter {
+    /// Constructs an empty writer that pads with zero bits.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets how [`Self::finish`] pads the last field element.
+    #[inline]
+    pub fn with_padding(mut self, padding: FePadding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the number of bits written so far.
+    #[inline]
+    pub fn bit_len(&self) -> usize { self.fes.len() * 5 + usize::from(self.cursor.len) }
+
+    /// Writes the low `n` bits of `value`, `n` must be at most 64.
+    ///
+    /// # Errors
+    ///
+    /// If `n` is greater than 64 or `value` does not fit in `n` bits.
+    pub fn write_bits(&mut self, value: u64, n: usize) -> Result<&mut Self, FeWriterError> {
+        check_width(value, n)?;
+        let fes = &mut self.fes;
+        write_bits(&mut self.cursor, value, n, |fe| fes.push(fe));
+        Ok(self)
+    }
+
+    /// Writes a `u8` as 8 bits.
+    #[inline]
+    pub fn write_u8(&mut self, value: u8) -> &mut Self { self.write(u64::from(value), 8) }
+
+    /// Writes a `u16` as 16 bits, big-endian.
+    #[inline]
+    pub fn write_u16(&mut self, value: u16) -> &mut Self { self.write(u64::from(value), 16) }
+
+    /// Writes a `u32` as 32 bits, big-endian.
+    #[inline]
+    pub fn write_u32(&mut self, value: u32) -> &mut Self { self.write(u64::from(value), 32) }
+
+    /// Writes `bytes`, 8 bits each, starting at the current bit position.
+    ///
+    /// Unlike [`DataBuilder::push_bytes`](crate::DataBuilder::push_bytes) the bytes are not
+    /// padded to a field element boundary.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.fes.reserve(bytes.len() * 8 / 5);
+        let fes = &mut self.fes;
+        for &b in bytes {
+            self.cursor.push(b, 8, |fe| fes.push(fe));
+        }
+        self
+    }
+
+    /// Returns the field elements, padding the last one as set by [`Self::with_padding`].
+    ///
+    /// # Errors
+    ///
+    /// With [`FePadding::Exact`], if the bits written do not fill whole field elements.
+    pub fn finish(mut self) -> Result<Vec<Fe32>, FeWriterError> {
+        let fes = &mut self.fes;
+        finish(&mut self.cursor, self.padding, |fe| fes.push(fe))?;
+        Ok(self.fes)
+    }
+
+    /// Writes a value already known to fit in `n` bits.
+    fn write(&mut self, value: u64, n: usize) -> &mut Self {
+        let fes = &mut self.fes;
+        write_bits(&mut self.cursor, value, n, |fe| fes.push(fe));
+        self
+    }
+}
+
+/// Writes fixed width fields, most significant bit first, into a caller provided buffer.
+///
+/// The same as [`FeWriter`] without an allocator, every write checks that the field elements,
+/// including any padding, fit in the buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::gf32::{Fe32, FePadding, FeSliceWriter};
+///
+/// let mut buf = [Fe32::Q; 4];
+/// let mut writer = FeSliceWriter::new(&mut buf).with_padding(FePadding::Exact);
+/// writer.write_bits(0b101, 3)?.write_u16(0x1234)?.write_bits(0, 1)?;
+/// assert!(writer.write_u8(0xff).is_err());
+///
+/// let len = writer.finish()?;
+/// assert_eq!(buf[..len], [Fe32::_5, Fe32::F, Fe32::R, Fe32::G]);
+/// # Ok::<(), bech32grs::primitives::gf32::FeWriterError>(())
+/// ```
+#[derive(Debug)]
+#[must_use = "a writer does nothing unless it is finished"]
+pub struct FeSliceWriter<'a> {
+    /// The buffer, the first `len` field elements are complete.
+    buf: &'a mut [Fe32],
+    /// The number of complete field elements.
+    len: usize,
+    /// The bits written since the last complete field element.
+    cursor: BitCursor,
+    /// How the last field element is padded.
+    padding: FePadding,
+}
+
+impl<'a> FeSliceWriter<'a> {
+    /// Constructs a writer that writes to `buf` from its start and pads with zero bits.
+    #[inline]
+    pub fn new(buf: &'a mut [Fe32]) -> Self {
+        FeSliceWriter { buf, len: 0, cursor: BitCursor::default(), padding: FePadding::Zero }
+    }
+
+    /// Sets how [`Self::finish`] pads the last field element.
+    #[inline]
+    pub fn with_padding(mut self, padding: FePadding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the number of bits written so far.
+    #[inline]
+    pub fn bit_len(&self) -> usize { self.len * 5 + usize::from(self.cursor.len) }
+
+    /// Writes the low `n` bits of `value`, `n` must be at most 64.
+    ///
+    /// # Errors
+    ///
+    /// If `n` is greater than 64, `value` does not fit in `n` bits or the buffer is too small.
+    pub fn write_bits(&mut self, value: u64, n: usize) -> Result<&mut Self, FeWriterError> {
+        check_width(value, n)?;
+        self.reserve(n)?;
+        let (buf, len) = (&mut *self.buf, &mut self.len);
+        write_bits(&mut self.cursor, value, n, |fe| {
+            buf[*len] = fe;
+            *len += 1;
+        });
+        Ok(self)
+    }
+
+    /// Writes a `u8` as 8 bits.
+    #[inline]
+    pub fn write_u8(&mut self, value: u8) -> Result<&mut Self, FeWriterError> {
+        self.write_bits(u64::from(value), 8)
+    }
+
+    /// Writes a `u16` as 16 bits, big-endian.
+    #[inline]
+    pub fn write_u16(&mut self, value: u16) -> Result<&mut Self, FeWriterError> {
+        self.write_bits(u64::from(value), 16)
+    }
+
+    /// Writes a `u32` as 32 bits, big-endian.
+    #[inline]
+    pub fn write_u32(&mut self, value: u32) -> Result<&mut Self, FeWriterError> {
+        self.write_bits(u64::from(value), 32)
+    }
+
+    /// Writes `bytes`, 8 bits each, starting at the current bit position.
+    ///
+    /// Nothing is written if the bytes do not fit.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, FeWriterError> {
+        self.reserve(bytes.len().saturating_mul(8))?;
+        for &b in bytes {
+            self.write_bits(u64::from(b), 8)?;
+        }
+        Ok(self)
+    }
+
+    /// Pads the last field element as set by [`Self::with_padding`], returning the number of
+    /// field elements written to the buffer.
+    ///
+    /// # Errors
+    ///
+    /// With [`FePadding::Exact`], if the bits written do not fill whole field elements.
+    pub fn finish(self) -> Result<usize, FeWriterError> {
+        let FeSliceWriter { buf, mut len, mut cursor, padding } = self;
+        // Writes reserve room for the padding.
+        finish(&mut cursor, padding, |fe| {
+            buf[len] = fe;
+            len += 1;
+        })?;
+        Ok(len)
+    }
+
+    /// Returns an error if writing `bits` more bits, and padding them, exceeds the buffer.
+    fn reserve(&self, bits: usize) -> Result<(), FeWriterError> {
+        let required = self.bit_len().saturating_add(bits).saturating_add(4) / 5;
+        if required > self.buf.len() {
+            Err(FeWriterError::Capacity { required, capacity: self.buf.len() })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns an error if `n` is greater than 64 or `value` does not fit in `n` bits.
+fn check_width(value: u64, n: usize) -> Result<(), FeWriterError> {
+    let fits = match n {
+        0..=63 => value >> n == 0,
+        64 => true,
+        _ => false,
+    };
+    if fits {
+        Ok(())
+    } else {
+        Err(FeWriterError::TooWide { value, bits: n })
+    }
+}
+
+/// Pushes the low `n` bits of `value` to `cursor`, at most 8 at a time.
+fn write_bits<F: FnMut(Fe32)>(cursor: &mut BitCursor, value: u64, n: usize, mut f: F) {
+    let mut left = n;
+    while left > 0 {
+        let take = if left % 8 == 0 { 8 } else { left % 8 };
+        left -= take;
+        let bits = (value >> left) as u8 & (0xff >> (8 - take));
+        cursor.push(bits, take as u8, &mut f);
+    }
+}
+
+/// Pads the pending bits of `cursor` as `padding` requires.
+fn finish<F: FnMut(Fe32)>(
+    cursor: &mut BitCursor,
+    padding: FePadding,
+    f: F,
+) -> Result<(), FeWriterError> {
+    match padding {
+        FePadding::Zero => cursor.pad(f),
+        FePadding::Exact if cursor.len > 0 =>
+            return Err(FeWriterError::Unpadded { pending_bits: cursor.len }),
+        FePadding::Exact => {}
+    }
+    Ok(())
+}
+
+/// An error while writing to a [`FeWriter`] or [`FeSliceWriter`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum FeWriterError {
+    /// A value that does not fit in the number of bits it is written as, or more than 64 bits.
+    TooWide {
+        /// The value.
+        value: u64,
+        /// The number of bits.
+        bits: usize,
+    },
+    /// The buffer of a [`FeSliceWriter`] is too small.
+    Capacity {
+        /// The number of field elements required.
+        required: usize,
+        /// The length of the buffer.
+        capacity: usize,
+    },
+    /// Finished with [`FePadding::Exact`] when the bits do not fill the last field element.
+    Unpadded {
+        /// The number of bits written since the last complete field element.
+        pending_bits: u8,
+    },
+}
+
+impl FeWriterError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use FeWriterError::*;
+
+        match *self {
+            TooWide { .. } => Code::WriterBits,
+            Capacity { .. } => Code::Capacity,
+            Unpadded { .. } => Code::WriterUnpadded,
+        }
+    }
+}
+
+impl fmt::Display for FeWriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FeWriterError::*;
+
+        match *self {
+            TooWide { value, bits } => write!(f, "value {} does not fit in {} bits", value, bits),
+            Capacity { required, capacity } =>
+                write!(f, "{} field elements required but the buffer holds {}", required, capacity),
+            Unpadded { pending_bits } =>
+                write!(f, "{} bits left over, not a whole field element", pending_bits),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for FeWriterError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use FeWriterError::*;
+
+        match *self {
+            TooWide { .. } | Capacity { .. } | Unpadded { .. } => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,6 +1285,75 @@ mod tests {
         assert_eq!(&(CHARS_INV[..]), expected_rev_charset.as_slice());
     }
 
+    #[test]
+    fn ascii_table_all_bytes() {
+        for b in 0..=255_u8 {
+            let entry = ASCII_TABLE[usize::from(b)];
+            let c = char::from(b);
+            match Fe32::from_char(c) {
+                Ok(fe) => {
+                    assert_eq!(entry & ASCII_BECH32, ASCII_BECH32, "{:?}", c);
+                    assert_eq!(entry & ASCII_VALUE, fe.to_u8(), "{:?}", c);
+                    assert_eq!(Fe32::from_char_unchecked(b), fe, "{:?}", c);
+                }
+                Err(_) => {
+                    assert_eq!(entry & ASCII_BECH32, 0, "{:?}", c);
+                    assert_eq!(entry & ASCII_VALUE, 0, "{:?}", c);
+                }
+            }
+            assert_eq!(entry & ASCII_UPPER != 0, b.is_ascii_uppercase(), "{:?}", c);
+            assert_eq!(entry & ASCII_LOWER != 0, b.is_ascii_lowercase(), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn confusable_pairs_are_sorted_bech32_chars() {
+        let pairs = confusable_pairs();
+        assert!(pairs.windows(2).all(|w| w[0] < w[1]));
+        for &(a, b) in pairs {
+            assert!(a < b);
+            assert_eq!(Fe32::from_char(a).unwrap().to_char(), a);
+            assert_eq!(Fe32::from_char(b).unwrap().to_char(), b);
+        }
+    }
+
+    #[test]
+    fn visual_confusability_is_symmetric() {
+        let mut count = 0;
+        for a in Fe32::iter_alpha() {
+            assert!(!a.visual_confusability(a));
+            for b in Fe32::iter_alpha() {
+                assert_eq!(a.visual_confusability(b), b.visual_confusability(a));
+                if a.visual_confusability(b) {
+                    count += 1;
+                }
+            }
+        }
+        assert_eq!(count, 2 * confusable_pairs().len());
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn zeroize() {
+        use zeroize::Zeroize;
+
+        let mut fe = Fe32::L;
+        fe.zeroize();
+        assert_eq!(fe, Fe32::Q);
+    }
+
+    #[test]
+    #[cfg(feature = "subtle")]
+    fn subtle_ct_eq() {
+        use subtle::ConstantTimeEq;
+
+        for a in Fe32::iter_alpha() {
+            for b in Fe32::iter_alpha() {
+                assert_eq!(bool::from(a.ct_eq(&b)), a == b);
+            }
+        }
+    }
+
     #[test]
     fn from_char() {
         for c in &CHARS_LOWER[..] {
@@ -494,6 +1361,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_char_unchecked_ignores_case() {
+        for (i, c) in CHARS_LOWER.iter().enumerate() {
+            let want = Fe32(i as u8);
+            assert_eq!(Fe32::from_char_unchecked(*c as u8), want);
+            assert_eq!(Fe32::from_char_unchecked(c.to_ascii_uppercase() as u8), want);
+        }
+    }
+
     #[test]
     fn from_upper_char() {
         let lower = Fe32::from_char('q').expect("failed to create fe32 from lowercase ascii char");
@@ -502,6 +1378,18 @@ mod tests {
         assert_eq!(lower, upper);
     }
 
+    #[test]
+    fn mul_table_and_log_match_clmul() {
+        for a in 0..32 {
+            for b in 0..32 {
+                let want = Fe32(clmul(a, b));
+                assert_eq!(Fe32(a).mul_table(Fe32(b)), want, "{} * {}", a, b);
+                assert_eq!(Fe32(a).mul_log(Fe32(b)), want, "{} * {}", a, b);
+                assert_eq!(Fe32(a) * Fe32(b), want, "{} * {}", a, b);
+            }
+        }
+    }
+
     #[test]
     fn mul_zero() {
         for c in &CHARS_LOWER[..] {
@@ -529,6 +1417,264 @@ mod tests {
             assert_eq!(fe * Fe32::P, fe) // Fe32::P == Fe32(1)
         }
     }
+
+    /// Packs `(value, bits)` fields into field elements, padding the last one with zeros.
+    fn pack(fields: &[(u64, usize)]) -> Vec<Fe32> {
+        let mut bits = Vec::new();
+        for &(value, n) in fields {
+            bits.extend((0..n).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        bits.chunks(5)
+            .map(|chunk| {
+                Fe32(chunk.iter().enumerate().fold(0, |acc, (i, &b)| acc | u8::from(b) << (4 - i)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fe_reader_field_layout() {
+        // A 3 bit version, a flag, a 16 bit length, 4 bits to the next byte boundary, 3 bytes, a
+        // 32 bit timestamp and a 5 bit trailer, 84 bits padded to 85.
+        let fes = pack(&[
+            (0b101, 3),
+            (1, 1),
+            (0xbeef, 16),
+            (0, 4),
+            (0x0a, 8),
+            (0x0b, 8),
+            (0x0c, 8),
+            (0xdead_beef, 32),
+            (0b10011, 5),
+        ]);
+        assert_eq!(fes.len(), 17);
+
+        let mut reader = FeReader::new(&fes);
+        assert_eq!(reader.remaining_bits(), 85);
+        assert_eq!(reader.read_bits(3), Ok(0b101));
+        assert_eq!(reader.read_bits(1), Ok(1));
+        assert_eq!(reader.read_u16(), Ok(0xbeef));
+
+        let mut buf = [0; 3];
+        assert_eq!(reader.read_bytes(&mut buf), Err(FeReaderError::Misaligned { pos: 20 }));
+        assert_eq!(reader.skip(4), Ok(()));
+        assert_eq!(reader.read_bytes(&mut buf), Ok(()));
+        assert_eq!(buf, [0x0a, 0x0b, 0x0c]);
+
+        assert_eq!(reader.read_u32(), Ok(0xdead_beef));
+        assert_eq!(reader.position(), 80);
+        assert_eq!(reader.read_bits(5), Ok(0b10011));
+        assert_eq!(reader.remaining_bits(), 0);
+        assert_eq!(reader.read_bits(0), Ok(0));
+    }
+
+    #[test]
+    fn fe_reader_unaligned_bytes() {
+        let fes = pack(&[(0b11, 2), (0xab, 8), (0xcd, 8), (0b0, 2)]);
+        let mut reader = FeReader::new(&fes);
+        reader.skip(2).unwrap();
+
+        let mut buf = [0; 2];
+        assert_eq!(reader.read_bytes(&mut buf), Err(FeReaderError::Misaligned { pos: 2 }));
+        assert_eq!(reader.read_bytes_unaligned(&mut buf), Ok(()));
+        assert_eq!(buf, [0xab, 0xcd]);
+    }
+
+    #[test]
+    fn fe_reader_errors_keep_cursor() {
+        let fes = [Fe32::L; 14]; // 70 bits.
+        let mut reader = FeReader::new(&fes);
+        assert_eq!(reader.read_bits(65), Err(FeReaderError::TooWide { bits: 65 }));
+        assert_eq!(reader.read_bits(64), Ok(u64::MAX));
+
+        let err = FeReaderError::OutOfData { pos: 64, requested: 8, remaining: 6 };
+        assert_eq!(reader.read_u8(), Err(err));
+        assert_eq!(reader.read_bytes(&mut [0; 1]), Err(err));
+        assert_eq!(reader.skip(8), Err(err));
+        assert_eq!(reader.position(), 64);
+
+        let mut buf = [0; 2];
+        assert_eq!(
+            reader.read_bytes_unaligned(&mut buf),
+            Err(FeReaderError::OutOfData { pos: 64, requested: 16, remaining: 6 })
+        );
+        assert_eq!(buf, [0; 2]);
+        assert_eq!(reader.read_bits(6), Ok(0b11_1111));
+    }
+
+    #[test]
+    fn fe_reader_random_reads_do_not_panic() {
+        // Xorshift, good enough to pick arbitrary lengths and operations.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..2000 {
+            let fes = (0..next() % 40).map(|_| Fe32((next() % 32) as u8)).collect::<Vec<_>>();
+            let mut reader = FeReader::new(&fes);
+            for _ in 0..16 {
+                let before = reader.position();
+                let n = (next() % 80) as usize;
+                let mut buf = vec![0; n / 8];
+                let ok = match next() % 7 {
+                    0 => reader.read_bits(n).is_ok(),
+                    1 => reader.read_u8().is_ok(),
+                    2 => reader.read_u16().is_ok(),
+                    3 => reader.read_u32().is_ok(),
+                    4 => reader.read_bytes(&mut buf).is_ok(),
+                    5 => reader.read_bytes_unaligned(&mut buf).is_ok(),
+                    _ => reader.skip(n).is_ok(),
+                };
+                if !ok {
+                    assert_eq!(reader.position(), before);
+                }
+                assert_eq!(reader.position() + reader.remaining_bits(), fes.len() * 5);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fe_writer_round_trips_random_fields() {
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..500 {
+            let fields = (0..next() % 12)
+                .map(|_| {
+                    let bits = (next() % 65) as usize;
+                    let value = if bits == 64 { next() } else { next() & ((1 << bits) - 1) };
+                    (value, bits)
+                })
+                .collect::<Vec<_>>();
+
+            let mut writer = FeWriter::new();
+            for &(value, bits) in &fields {
+                writer.write_bits(value, bits).expect("value fits");
+            }
+            let bit_len = writer.bit_len();
+            let fes = writer.finish().expect("zero padding");
+            assert_eq!(fes, pack(&fields));
+
+            let mut buf = [Fe32::Q; 128];
+            let mut slice_writer = FeSliceWriter::new(&mut buf);
+            for &(value, bits) in &fields {
+                slice_writer.write_bits(value, bits).expect("fits");
+            }
+            assert_eq!(slice_writer.bit_len(), bit_len);
+            let len = slice_writer.finish().expect("zero padding");
+            assert_eq!(buf[..len], fes[..]);
+
+            let mut reader = FeReader::new(&fes);
+            for &(value, bits) in &fields {
+                assert_eq!(reader.read_bits(bits), Ok(value));
+            }
+            assert!(reader.remaining_bits() < 5);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fe_writer_bytes_and_integers() {
+        let mut writer = FeWriter::new();
+        writer.write_bits(1, 1).unwrap();
+        writer.write_u8(0xab).write_u16(0xcdef).write_u32(0x0123_4567).write_bytes(&[0x89, 0xab]);
+        assert_eq!(writer.bit_len(), 73);
+        let fes = writer.finish().unwrap();
+
+        let mut reader = FeReader::new(&fes);
+        assert_eq!(reader.read_bits(1), Ok(1));
+        assert_eq!(reader.read_u8(), Ok(0xab));
+        assert_eq!(reader.read_u16(), Ok(0xcdef));
+        assert_eq!(reader.read_u32(), Ok(0x0123_4567));
+        let mut buf = [0; 2];
+        reader.read_bytes_unaligned(&mut buf).unwrap();
+        assert_eq!(buf, [0x89, 0xab]);
+        assert_eq!(reader.read_bits(2), Ok(0));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fe_writer_padding_policy() {
+        let mut writer = FeWriter::new().with_padding(FePadding::Exact);
+        writer.write_u8(0xff);
+        assert_eq!(writer.clone().finish(), Err(FeWriterError::Unpadded { pending_bits: 3 }));
+        writer.write_bits(0b01, 2).unwrap();
+        assert_eq!(writer.finish(), Ok(vec![Fe32::L, Fe32::A]));
+
+        let mut writer = FeWriter::new();
+        writer.write_u8(0xff);
+        assert_eq!(writer.finish(), Ok(vec![Fe32::L, Fe32::U]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fe_writer_too_wide() {
+        let mut writer = FeWriter::new();
+        let err = writer.write_bits(4, 2).unwrap_err();
+        assert_eq!(err, FeWriterError::TooWide { value: 4, bits: 2 });
+        let err = writer.write_bits(0, 65).unwrap_err();
+        assert_eq!(err, FeWriterError::TooWide { value: 0, bits: 65 });
+        assert!(writer.write_bits(u64::MAX, 64).is_ok());
+        assert!(writer.write_bits(0, 0).is_ok());
+        assert_eq!(writer.bit_len(), 64);
+    }
+
+    #[test]
+    fn fe_slice_writer_capacity() {
+        let mut buf = [Fe32::Q; 2];
+        let mut writer = FeSliceWriter::new(&mut buf);
+        writer.write_u8(0xff).unwrap();
+        let err = FeWriterError::Capacity { required: 3, capacity: 2 };
+        assert_eq!(writer.write_bits(0, 3).unwrap_err(), err);
+        let err = writer.write_bytes(&[0]).unwrap_err();
+        assert_eq!(err, FeWriterError::Capacity { required: 4, capacity: 2 });
+        assert_eq!(writer.bit_len(), 8);
+        writer.write_bits(0b11, 2).unwrap();
+        assert_eq!(writer.finish(), Ok(2));
+        assert_eq!(buf, [Fe32::L, Fe32::L]);
+    }
+}
+
+#[cfg(bench)]
+mod benches {
+    use test::{black_box, Bencher};
+
+    use super::*;
+
+    /// Evaluates a 90 element codeword at 6 points, like computing the syndromes of a checksum.
+    fn syndromes(mul: fn(Fe32, Fe32) -> Fe32, codeword: &[Fe32]) -> [Fe32; 6] {
+        let mut ret = [Fe32::Q; 6];
+        for (i, syndrome) in ret.iter_mut().enumerate() {
+            let point = Fe32(LOG_INV[i + 1]);
+            for fe in codeword {
+                *syndrome = mul(*syndrome, point) + *fe;
+            }
+        }
+        ret
+    }
+
+    fn codeword() -> Vec<Fe32> { (0..90).map(|i| Fe32(i as u8 & 0x1f)).collect() }
+
+    #[bench]
+    fn bench_syndromes_mul_table(bh: &mut Bencher) {
+        let codeword = codeword();
+        bh.iter(|| black_box(syndromes(Fe32::mul_table, black_box(&codeword))));
+    }
+
+    #[bench]
+    fn bench_syndromes_mul_log(bh: &mut Bencher) {
+        let codeword = codeword();
+        bh.iter(|| black_box(syndromes(Fe32::mul_log, black_box(&codeword))));
+    }
 }
 
 #[cfg(kani)]
@@ -16,8 +16,13 @@ use core::fmt::{self, Write};
 use core::iter::FusedIterator;
 use core::{slice, str};
 
+use crate::error::Code;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::primitives::checksum::HrpFe32Iter;
+
 /// Maximum length of the human-readable part, as defined by BIP-173.
-const MAX_HRP_LEN: usize = 83;
+pub const MAX_HRP_LENGTH: usize = 83;
 
 // Defines HRP constants for the different groestlcoin networks.
 // You can also access these at `crate::hrp::GRS` etc.
@@ -54,11 +59,53 @@ define_hrp_const! {
     pub const GRSRT 5 [103, 114, 115, 114, 116];
 }
 
+/// Constructs an [`Hrp`] from a string literal, checking that it is valid at compile time.
+///
+/// The resulting value can be used in `const` and `static` items. An invalid human-readable part
+/// (empty, longer than 83 characters, containing characters outside of the range [33-126], or
+/// mixed case) fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{hrp, Hrp};
+///
+/// static LNGRS: Hrp = hrp!("lngrs");
+/// assert_eq!(LNGRS, Hrp::parse("lngrs").unwrap());
+/// ```
+///
+/// ```compile_fail
+/// const EMPTY: bech32grs::Hrp = bech32grs::hrp!("");
+/// ```
+///
+/// ```compile_fail
+/// const MIXED_CASE: bech32grs::Hrp = bech32grs::hrp!("gRs");
+/// ```
+#[macro_export]
+macro_rules! hrp {
+    ($hrp:expr) => {{
+        const VALID: bool = $crate::Hrp::__is_valid($hrp);
+        // Indexing out of bounds fails to compile, panicking in a const fn needs Rust 1.57.
+        const _: () = [()][!VALID as usize];
+        // Only parse valid input, so that there is a single error if `$hrp` is too long.
+        if VALID {
+            $crate::Hrp::parse_unchecked($hrp)
+        } else {
+            $crate::Hrp::parse_unchecked("")
+        }
+    }};
+}
+
 /// The human-readable part (human readable prefix before the '1' separator).
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(bytecheck(verify))
+)]
 pub struct Hrp {
     /// ASCII byte values, guaranteed not to be mixed-case.
-    buf: [u8; MAX_HRP_LEN],
+    buf: [u8; MAX_HRP_LENGTH],
     /// Number of characters currently stored in this HRP.
     size: usize,
 }
@@ -78,12 +125,12 @@ impl Hrp {
     pub fn parse(hrp: &str) -> Result<Self, Error> {
         use Error::*;
 
-        let mut new = Hrp { buf: [0_u8; MAX_HRP_LEN], size: 0 };
+        let mut new = Hrp { buf: [0_u8; MAX_HRP_LENGTH], size: 0 };
 
         if hrp.is_empty() {
             return Err(Empty);
         }
-        if hrp.len() > MAX_HRP_LEN {
+        if hrp.len() > MAX_HRP_LENGTH {
             return Err(TooLong(hrp.len()));
         }
 
@@ -119,12 +166,67 @@ impl Hrp {
         Ok(new)
     }
 
+    /// Parses a human-readable part that is safe to display, i.e. only lowercase ASCII letters and
+    /// digits.
+    ///
+    /// [BIP-173] allows any printable ASCII character in the HRP, which is correct for consensus
+    /// but lets punctuation such as `?` or `!` into strings shown to users. Use [`Hrp::parse`] to
+    /// accept everything the specification does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::Hrp;
+    ///
+    /// assert!(Hrp::parse_display_safe("grs").is_ok());
+    /// assert!(Hrp::parse_display_safe("grs!").is_err());
+    /// assert!(Hrp::parse("grs!").is_ok());
+    /// ```
+    ///
+    /// [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
+    pub fn parse_display_safe(hrp: &str) -> Result<Self, Error> {
+        let new = Self::parse(hrp)?;
+        match new.as_bytes().iter().find(|b| !(b.is_ascii_lowercase() || b.is_ascii_digit())) {
+            Some(&b) => Err(Error::NotDisplaySafe(b)),
+            None => Ok(new),
+        }
+    }
+
+    /// Returns whether `hrp` is a valid human-readable part, in a constant context.
+    ///
+    /// Performs the same checks as [`Hrp::parse`], used by the [`hrp!`](crate::hrp!) macro.
+    #[doc(hidden)]
+    pub const fn __is_valid(hrp: &str) -> bool { Self::is_valid_bytes(hrp.as_bytes()) }
+
+    /// Returns whether `hrp_bytes` are the bytes of a valid human-readable part.
+    const fn is_valid_bytes(hrp_bytes: &[u8]) -> bool {
+        if hrp_bytes.is_empty() || hrp_bytes.len() > MAX_HRP_LENGTH {
+            return false;
+        }
+
+        let mut has_lower = false;
+        let mut has_upper = false;
+        let mut i = 0;
+        // Funky code so we can be const.
+        while i < hrp_bytes.len() {
+            let b = hrp_bytes[i];
+            // Valid subset of ASCII, this also excludes the bytes of non-ASCII characters.
+            if b < 33 || b > 126 {
+                return false;
+            }
+            has_lower |= b.is_ascii_lowercase();
+            has_upper |= b.is_ascii_uppercase();
+            i += 1;
+        }
+        !(has_lower && has_upper)
+    }
+
     /// Parses the human-readable part (see [`Hrp::parse`] for full docs).
     ///
     /// Does not check that `hrp` is valid according to BIP-173 but does check for valid ASCII
     /// values, replacing any invalid characters with `X`.
     pub const fn parse_unchecked(hrp: &str) -> Self {
-        let mut new = Hrp { buf: [0_u8; MAX_HRP_LEN], size: 0 };
+        let mut new = Hrp { buf: [0_u8; MAX_HRP_LENGTH], size: 0 };
         let hrp_bytes = hrp.as_bytes();
 
         let mut i = 0;
@@ -152,6 +254,31 @@ impl Hrp {
     #[inline]
     pub fn as_bytes(&self) -> &[u8] { &self.buf[..self.size] }
 
+    /// Returns a four byte tag for this human-readable part, e.g. for a compact database key.
+    ///
+    /// The tag is the first four bytes of the lowercase HRP, zero padded if it is shorter. Tags are
+    /// not unique in general, HRPs that share their first four characters have the same tag, but
+    /// the tags of the Groestlcoin HRPs [`GRS`], [`TGRS`] and [`GRSRT`] are all different, see
+    /// [`KnownHrp::from_tag`](crate::segwit::KnownHrp::from_tag).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::{hrp, Hrp};
+    ///
+    /// assert_eq!(hrp::GRS.tag(), *b"grs\0");
+    /// assert_eq!(hrp::GRSRT.tag(), *b"grsr");
+    /// assert_eq!(Hrp::parse("TGRS").unwrap().tag(), *b"tgrs");
+    /// ```
+    #[inline]
+    pub fn tag(&self) -> [u8; 4] {
+        let mut tag = [0_u8; 4];
+        for (t, b) in tag.iter_mut().zip(self.lowercase_byte_iter()) {
+            *t = b;
+        }
+        tag
+    }
+
     /// Returns this human-readable part as str.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -163,34 +290,76 @@ impl Hrp {
     /// If an uppercase HRP was parsed during object construction then this iterator will yield
     /// uppercase ASCII `char`s. For lowercase bytes see [`Self::lowercase_byte_iter`]
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter { ByteIter { iter: self.buf[..self.size].iter() } }
+    pub fn byte_iter(&self) -> ByteIter<'_> { ByteIter { iter: self.buf[..self.size].iter() } }
 
     /// Creates a character iterator over the ASCII characters of this HRP.
     ///
     /// If an uppercase HRP was parsed during object construction then this iterator will yield
     /// uppercase ASCII `char`s. For lowercase bytes see [`Self::lowercase_char_iter`].
     #[inline]
-    pub fn char_iter(&self) -> CharIter { CharIter { iter: self.byte_iter() } }
+    pub fn char_iter(&self) -> CharIter<'_> { CharIter { iter: self.byte_iter() } }
 
     /// Creates a lowercase iterator over the byte values (ASCII characters) of this HRP.
     #[inline]
-    pub fn lowercase_byte_iter(&self) -> LowercaseByteIter {
+    pub fn lowercase_byte_iter(&self) -> LowercaseByteIter<'_> {
         LowercaseByteIter { iter: self.byte_iter() }
     }
 
     /// Creates a lowercase character iterator over the ASCII characters of this HRP.
     #[inline]
-    pub fn lowercase_char_iter(&self) -> LowercaseCharIter {
+    pub fn lowercase_char_iter(&self) -> LowercaseCharIter<'_> {
         LowercaseCharIter { iter: self.lowercase_byte_iter() }
     }
 
+    /// Creates an iterator over the field elements that a checksum engine is fed for this HRP.
+    ///
+    /// Yields the canonical expansion from [BIP-173]: the high bits of each lowercase character, a
+    /// zero, then the low bits of each lowercase character. Feeding this iterator to
+    /// [`Engine::input_fes`] is equivalent to calling [`Engine::input_hrp`], which is useful when
+    /// comparing traces against other implementations or building an alternate checksum frontend.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::checksum::Engine;
+    /// use bech32grs::{hrp, Bech32, Fe32};
+    ///
+    /// let expansion = hrp::GRS.expansion_iter().map(Fe32::to_u8);
+    /// assert!(expansion.eq([3, 3, 3, 0, 7, 18, 19].iter().copied()));
+    ///
+    /// let mut from_hrp = Engine::<Bech32>::new();
+    /// from_hrp.input_hrp(hrp::GRS);
+    /// let mut from_expansion = Engine::<Bech32>::new();
+    /// from_expansion.input_fes(hrp::GRS.expansion_iter());
+    /// assert_eq!(from_hrp.residue(), from_expansion.residue());
+    /// ```
+    ///
+    /// [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#checksum>
+    /// [`Engine::input_fes`]: crate::primitives::checksum::Engine::input_fes
+    /// [`Engine::input_hrp`]: crate::primitives::checksum::Engine::input_hrp
+    #[inline]
+    pub fn expansion_iter(&self) -> HrpFe32Iter<'_> { HrpFe32Iter::new(self) }
+
     /// Returns the length (number of characters) of the human-readable part.
     ///
     /// Guaranteed to be between 1 and 83 inclusive.
     #[inline]
-    #[allow(clippy::len_without_is_empty)] // HRP is never empty.
     pub fn len(&self) -> usize { self.size }
 
+    /// Returns `false`, a human-readable part is never empty.
+    ///
+    /// The `is_empty` of `0.10.0-alpha`.
+    #[deprecated(note = "a human-readable part is never empty")]
+    #[inline]
+    pub fn is_empty(&self) -> bool { false }
+
+    /// Returns `true` if this HRP contains only ASCII letters and digits.
+    ///
+    /// Since an HRP is never mixed case this is true for HRPs that are all lowercase or all
+    /// uppercase letters and digits, see [`Hrp::parse_display_safe`] to also require lowercase.
+    #[inline]
+    pub fn is_alphanumeric(&self) -> bool { self.as_bytes().iter().all(u8::is_ascii_alphanumeric) }
+
     /// Returns `true` if this HRP is valid according to the bips.
     ///
     /// [BIP-173] states that the HRP must be either "grs" or "tgrs".
@@ -231,6 +400,12 @@ impl fmt::Display for Hrp {
     }
 }
 
+/// Formatted as the human-readable part string, in the case it was parsed in.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Hrp {
+    fn format(&self, f: defmt::Formatter) { defmt::write!(f, "{=str}", self.as_str()) }
+}
+
 /// Case insensitive comparison.
 impl Ord for Hrp {
     #[inline]
@@ -255,14 +430,60 @@ impl PartialEq for Hrp {
 
 impl Eq for Hrp {}
 
+/// Case insensitive hash, consistent with the [`PartialEq`] implementation.
 impl core::hash::Hash for Hrp {
     #[inline]
-    fn hash<H: core::hash::Hasher>(&self, h: &mut H) { self.buf.hash(h) }
+    fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+        self.size.hash(h);
+        self.lowercase_byte_iter().for_each(|b| h.write_u8(b));
+    }
+}
+
+/// Serialized as a string, deserializing an invalid human-readable part fails.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Hrp {
+    #[inline]
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        borsh::BorshSerialize::serialize(self.as_str(), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Hrp {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let mut buf = [0_u8; MAX_HRP_LENGTH];
+        let bytes = crate::archive::read_prefixed(reader, &mut buf, "hrp too long")?;
+        if !Hrp::is_valid_bytes(bytes) {
+            return Err(crate::archive::invalid_data("invalid hrp"));
+        }
+        let size = bytes.len();
+        Ok(Hrp { buf, size })
+    }
+}
+
+// SAFETY: `verify` only rejects values, an `ArchivedHrp` has no invariants beyond its bytes.
+#[cfg(feature = "rkyv")]
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedHrp
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        let size = self.size.to_native() as usize;
+        crate::archive::check(size <= MAX_HRP_LENGTH, "hrp too long")?;
+        let (used, padding) = self.buf.split_at(size);
+        // Zero padding gives each value a single archived representation.
+        crate::archive::check(
+            Hrp::is_valid_bytes(used) && padding.iter().all(|&b| b == 0),
+            "invalid hrp",
+        )
+    }
 }
 
 /// Iterator over bytes (ASCII values) of the human-readable part.
 ///
 /// ASCII byte values as they were initially parsed (i.e., in the original case).
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct ByteIter<'b> {
     iter: slice::Iter<'b, u8>,
 }
@@ -290,6 +511,7 @@ impl<'b> FusedIterator for ByteIter<'b> {}
 /// Iterator over ASCII characters of the human-readable part.
 ///
 /// ASCII `char`s as they were initially parsed (i.e., in the original case).
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct CharIter<'b> {
     iter: ByteIter<'b>,
 }
@@ -315,6 +537,7 @@ impl<'b> DoubleEndedIterator for CharIter<'b> {
 impl<'b> FusedIterator for CharIter<'b> {}
 
 /// Iterator over lowercase bytes (ASCII characters) of the human-readable part.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct LowercaseByteIter<'b> {
     iter: ByteIter<'b>,
 }
@@ -344,6 +567,7 @@ impl<'b> DoubleEndedIterator for LowercaseByteIter<'b> {
 impl<'b> FusedIterator for LowercaseByteIter<'b> {}
 
 /// Iterator over lowercase ASCII characters of the human-readable part.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct LowercaseCharIter<'b> {
     iter: LowercaseByteIter<'b>,
 }
@@ -373,7 +597,8 @@ fn is_ascii_uppercase(b: u8) -> bool { (65..=90).contains(&b) }
 /// Errors encountered while checking the human-readable part as defined by [BIP-173].
 ///
 /// [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#user-content-Bech32>
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
     /// The human-readable part is too long.
@@ -386,6 +611,29 @@ pub enum Error {
     InvalidAsciiByte(u8),
     /// The human-readable part cannot mix upper and lower case.
     MixedCase,
+    /// Found a printable character that is not allowed in a display safe human-readable part, see
+    /// [`Hrp::parse_display_safe`].
+    NotDisplaySafe(u8),
+}
+
+impl Error {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use Error::*;
+
+        match *self {
+            TooLong(_) => Code::HrpTooLong,
+            Empty => Code::HrpEmpty,
+            NonAsciiChar(_) => Code::HrpNonAscii,
+            InvalidAsciiByte(_) => Code::HrpInvalidByte,
+            MixedCase => Code::HrpMixedCase,
+            NotDisplaySafe(_) => Code::HrpNotDisplaySafe,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -393,23 +641,28 @@ impl fmt::Display for Error {
         use Error::*;
 
         match *self {
-            TooLong(len) =>
-                write!(f, "hrp is too long, found {} characters, must be <= {}", len, MAX_HRP_LEN),
+            TooLong(len) => write!(
+                f,
+                "hrp is too long, found {} characters, must be <= {}",
+                len, MAX_HRP_LENGTH
+            ),
             Empty => write!(f, "hrp is empty, must have at least 1 character"),
             NonAsciiChar(c) => write!(f, "found non-ASCII character: {}", c),
             InvalidAsciiByte(b) => write!(f, "byte value is not valid US-ASCII: \'{:x}\'", b),
             MixedCase => write!(f, "hrp cannot mix upper and lower case"),
+            NotDisplaySafe(b) => write!(f, "hrp character is not display safe: \'{}\'", b as char),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use Error::*;
 
         match *self {
-            TooLong(_) | Empty | NonAsciiChar(_) | InvalidAsciiByte(_) | MixedCase => None,
+            TooLong(_) | Empty | NonAsciiChar(_) | InvalidAsciiByte(_) | MixedCase
+            | NotDisplaySafe(_) => None,
         }
     }
 }
@@ -417,6 +670,7 @@ impl std::error::Error for Error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Fe32;
 
     macro_rules! check_parse_ok {
         ($($test_name:ident, $hrp:literal);* $(;)?) => {
@@ -456,6 +710,23 @@ mod tests {
         parse_err_3, "has spaces in it";
     }
 
+    #[test]
+    fn display_safe() {
+        for s in ["grs", "tgrs", "lngrs", "abc123"].iter() {
+            assert!(Hrp::parse_display_safe(s).is_ok());
+            assert!(Hrp::parse(s).unwrap().is_alphanumeric());
+        }
+        for (s, byte) in [("?", b'?'), ("grs!", b'!'), ("GRS", b'G'), ("a-b", b'-')].iter() {
+            assert_eq!(Hrp::parse_display_safe(s), Err(Error::NotDisplaySafe(*byte)));
+            assert!(Hrp::parse(s).is_ok());
+        }
+        assert!(Hrp::parse("GRS").unwrap().is_alphanumeric());
+        assert!(!Hrp::parse("grs!").unwrap().is_alphanumeric());
+        // Errors from the standard checks take precedence.
+        assert_eq!(Hrp::parse_display_safe(""), Err(Error::Empty));
+        assert_eq!(Hrp::parse_display_safe("Grs!"), Err(Error::MixedCase));
+    }
+
     macro_rules! check_iter {
         ($($test_name:ident, $hrp:literal, $len:literal);* $(;)?) => {
             $(
@@ -525,6 +796,86 @@ mod tests {
         assert_eq!(GRSRT, Hrp::parse_unchecked("grsrt"));
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn tag_is_case_insensitive() {
+        const CHARS: &[u8] = b"aZ09!~grsTGRS";
+        // Every string of up to 3 characters from `CHARS`, and longer ones built from them.
+        let mut buf = [0_u8; 9];
+        for len in 1..=9 {
+            for i in 0..CHARS.len().pow(3) {
+                for (j, b) in buf[..len].iter_mut().enumerate() {
+                    *b = CHARS[(i / CHARS.len().pow((j % 3) as u32) + j) % CHARS.len()];
+                }
+                let s = core::str::from_utf8(&buf[..len]).expect("ASCII");
+                let lower = Hrp::parse_unchecked(s).to_lowercase();
+                let upper = lower.to_ascii_uppercase();
+                let tag = Hrp::parse(&lower).expect("valid hrp").tag();
+                assert_eq!(Hrp::parse(&upper).expect("valid hrp").tag(), tag, "{}", s);
+
+                let mut want = [0_u8; 4];
+                let n = len.min(4);
+                want[..n].copy_from_slice(&lower.as_bytes()[..n]);
+                assert_eq!(tag, want);
+            }
+        }
+    }
+
+    #[test]
+    fn length_boundary() {
+        let buf = [b'a'; MAX_HRP_LENGTH + 1];
+        let s = core::str::from_utf8(&buf).expect("ASCII");
+        assert_eq!(Hrp::parse(&s[..MAX_HRP_LENGTH - 1]).unwrap().len(), MAX_HRP_LENGTH - 1);
+        assert_eq!(Hrp::parse(&s[..MAX_HRP_LENGTH]).unwrap().len(), MAX_HRP_LENGTH);
+        assert_eq!(Hrp::parse(s), Err(Error::TooLong(MAX_HRP_LENGTH + 1)));
+        assert_eq!(Hrp::parse_display_safe(s), Err(Error::TooLong(MAX_HRP_LENGTH + 1)));
+    }
+
+    #[test]
+    fn const_validity_matches_parse() {
+        let hrps = [
+            "",
+            "a",
+            "grs",
+            "GRS",
+            "gRs",
+            "a1b2c3",
+            "!",
+            "~",
+            "a b",
+            "a\u{7f}",
+            "\u{e9}",
+            "A-B",
+            "a-B",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber1",
+            "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber1",
+        ];
+        for hrp in hrps.iter() {
+            assert_eq!(Hrp::__is_valid(hrp), Hrp::parse(hrp).is_ok(), "{:?}", hrp);
+        }
+    }
+
+    #[test]
+    fn hrp_macro() {
+        const LOWER: Hrp = crate::hrp!("grs");
+        const UPPER: Hrp = crate::hrp!("GRS");
+        assert_eq!(LOWER, GRS);
+        assert_eq!(UPPER, GRS);
+        assert_eq!(UPPER.as_str(), "GRS");
+    }
+
+    #[test]
+    fn expansion_iter() {
+        let expected = [3, 3, 3, 0, 7, 18, 19];
+        for hrp in [GRS, Hrp::parse("GRS").unwrap()].iter() {
+            let mut iter = hrp.expansion_iter();
+            assert_eq!(iter.size_hint(), (7, Some(7)));
+            assert!(iter.by_ref().take(2).map(Fe32::to_u8).eq(expected[..2].iter().copied()));
+            assert_eq!(iter.size_hint(), (5, Some(5)));
+            assert!(iter.map(Fe32::to_u8).eq(expected[2..].iter().copied()));
+        }
+    }
+
     #[test]
     fn as_str() {
         let s = "arbitraryhrp";
@@ -538,4 +889,22 @@ mod tests {
         let hrp = Hrp::parse_unchecked(s);
         assert_eq!(hrp.as_bytes(), s.as_bytes());
     }
+
+    #[test]
+    fn hash_is_case_insensitive() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(hrp: Hrp) -> u64 {
+            let mut h = DefaultHasher::new();
+            hrp.hash(&mut h);
+            h.finish()
+        }
+
+        let lower = Hrp::parse("grs").unwrap();
+        let upper = Hrp::parse("GRS").unwrap();
+        assert_eq!(lower, upper);
+        assert_eq!(hash(lower), hash(upper));
+        assert_ne!(hash(lower), hash(Hrp::parse("tgrs").unwrap()));
+    }
 }
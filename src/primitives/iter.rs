@@ -6,14 +6,21 @@
 //!
 //! - `BytesToFes`: An iterator over bytes to an iterator over field elements.
 //! - `FesToBytes`: An iterator over field elements to an iterator over bytes.
+//! - `FesToBytesStrict`: As `FesToBytes` but errors if the padding is invalid.
 //! - `Checksummed`: An iterator over field elements that appends the checksum.
+//! - `TryBytesToFes`: As `BytesToFes` but for an iterator over fallible bytes.
 //!
 //! WARNING: This module does not enforce the maximum length of an encoded bech32 string (90 chars).
 //!
+//! The bit conversions never hold more than 16 bits at a time and only shift by less than the width
+//! of the value being shifted, lengths are computed without multiplying by the bit count and
+//! saturate at `usize::MAX`. None of the arithmetic depends on the width of `usize`, so it behaves
+//! the same on 16-bit targets as on 64-bit ones.
+//!
 //! # Examples
 //!
 //! ```
-//! use bech32grs::{Bech32, ByteIterExt, Fe32IterExt, Fe32, Hrp};
+//! use bech32grs::{Bech32, ByteRefIterExt, Fe32IterExt, Fe32, Hrp};
 //!
 //! let data = [
 //!     0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4,
@@ -22,7 +29,7 @@
 //! ];
 //!
 //! // Convert byte data to GF32 field elements.
-//! let fe_iter = data.iter().copied().bytes_to_fes();
+//! let fe_iter = data.iter().bytes_to_fes();
 //!
 //! // Convert field elements back to bytes.
 //! let byte_iter = fe_iter.fes_to_bytes();
@@ -30,8 +37,13 @@
 //! # assert!(data.iter().copied().eq(byte_iter));
 //! ```
 
+#![cfg_attr(not(test), deny(clippy::indexing_slicing, clippy::unwrap_used, clippy::expect_used))]
+
+use core::iter::Copied;
+
 use crate::primitives::checksum::{self, Checksum, PackedFe32};
-use crate::primitives::encode::Encoder;
+use crate::primitives::decode::PaddingError;
+use crate::primitives::encode::{Encoder, TryEncoder};
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::Hrp;
 
@@ -48,6 +60,22 @@ pub trait ByteIterExt: Sized + Iterator<Item = u8> {
 
 impl<I> ByteIterExt for I where I: Iterator<Item = u8> {}
 
+/// Extension trait for iterators over borrowed bytes, such as [`slice::Iter<u8>`].
+///
+/// The same adaptor as [`ByteIterExt`], so that `data.iter().bytes_to_fes()` works without a call
+/// to [`Iterator::copied`] first.
+///
+/// [`slice::Iter<u8>`]: core::slice::Iter
+pub trait ByteRefIterExt<'a>: Sized + Iterator<Item = &'a u8> {
+    /// Adapts the byte iterator to output GF32 field elements instead.
+    ///
+    /// If the total number of bits is not a multiple of 5 we pad with 0s
+    #[inline]
+    fn bytes_to_fes(self) -> BytesToFes<Copied<Self>> { self.copied().bytes_to_fes() }
+}
+
+impl<'a, I> ByteRefIterExt<'a> for I where I: Iterator<Item = &'a u8> {}
+
 /// Extension trait for field element iterators.
 pub trait Fe32IterExt: Sized + Iterator<Item = Fe32> {
     /// Adapts the `Fe32` iterator to output bytes instead.
@@ -59,20 +87,67 @@ pub trait Fe32IterExt: Sized + Iterator<Item = Fe32> {
         FesToBytes { last_fe: self.next(), bit_offset: 0, iter: self }
     }
 
+    /// Adapts the `Fe32` iterator to output bytes, checking the padding.
+    ///
+    /// Like [`Fe32IterExt::fes_to_bytes`] but enforces the BIP-173 padding rules: any trailing
+    /// bits must be 4 bits or less and all zero. If they are not the last item is an error.
+    #[inline]
+    fn fes_to_bytes_strict(self) -> FesToBytesStrict<Self> {
+        FesToBytesStrict { buf: 0, buf_len: 0, done: false, iter: self }
+    }
+
     /// Adapts the Fe32 iterator to encode the field elements into a bech32 address.
+    ///
+    /// With [`NoChecksum`] the encoding is the human-readable part, the separator '1' and the data
+    /// characters, with nothing appended.
+    ///
+    /// [`NoChecksum`]: crate::NoChecksum
     #[inline]
-    fn with_checksum<Ck: Checksum>(self, hrp: &Hrp) -> Encoder<Self, Ck> { Encoder::new(self, hrp) }
+    fn with_checksum<Ck: Checksum>(self, hrp: &Hrp) -> Encoder<'_, Self, Ck> {
+        Encoder::new(self, hrp)
+    }
 }
 
 impl<I> Fe32IterExt for I where I: Iterator<Item = Fe32> {}
 
+/// Extension trait for fallible byte iterators, e.g. bytes read from a stream.
+pub trait TryByteIterExt<E>: Sized + Iterator<Item = Result<u8, E>> {
+    /// Adapts the fallible byte iterator to output GF32 field elements instead.
+    ///
+    /// Like [`ByteIterExt::bytes_to_fes`] but the first error of the byte iterator is yielded in
+    /// place of the field elements that the failed byte was part of, after which the adaptor
+    /// yields nothing.
+    #[inline]
+    fn try_bytes_to_fes(self) -> TryBytesToFes<Self> {
+        TryBytesToFes { buf: 0, buf_len: 0, done: false, iter: self }
+    }
+}
+
+impl<I, E> TryByteIterExt<E> for I where I: Iterator<Item = Result<u8, E>> {}
+
+/// Extension trait for fallible field element iterators.
+pub trait TryFe32IterExt<E>: Sized + Iterator<Item = Result<Fe32, E>> {
+    /// Adapts the fallible Fe32 iterator to encode the field elements into a bech32 string.
+    ///
+    /// Like [`Fe32IterExt::with_checksum`] but the encoding stops at the first error, without a
+    /// checksum.
+    #[inline]
+    fn with_checksum<Ck: Checksum>(self, hrp: &Hrp) -> TryEncoder<'_, Self, Ck> {
+        TryEncoder::new(self, hrp)
+    }
+}
+
+impl<I, E> TryFe32IterExt<E> for I where I: Iterator<Item = Result<Fe32, E>> {}
+
 /// Iterator adaptor that converts bytes to GF32 elements.
 ///
 /// If the total number of bits is not a multiple of 5, it right-pads with 0 bits.
 #[derive(Clone, PartialEq, Eq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct BytesToFes<I: Iterator<Item = u8>> {
     last_byte: Option<u8>,
-    bit_offset: usize,
+    /// The number of bits of `last_byte` already yielded, always less than 8.
+    bit_offset: u8,
     iter: I,
 }
 
@@ -92,6 +167,8 @@ where
             ret
         };
 
+        debug_assert!(bit_offset < 8);
+
         if let Some(last) = self.last_byte {
             match bit_offset.cmp(&3) {
                 Less => Some(Fe32((last >> (3 - bit_offset)) & 0x1f)),
@@ -113,23 +190,37 @@ where
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (min, max) = self.iter.size_hint();
-        let (min, max) = match self.last_byte {
+        match self.last_byte {
             // +1 because we set last_byte with call to `next`.
-            Some(_) => (min + 1, max.map(|max| max + 1)),
-            None => (min, max),
-        };
-
-        let min = bytes_len_to_fes_len(min);
-        let max = max.map(bytes_len_to_fes_len);
-
-        (min, max)
+            Some(_) => {
+                let min = bytes_len_to_fes_len(min.saturating_add(1), self.bit_offset);
+                let max = max
+                    .and_then(|max| max.checked_add(1))
+                    .map(|max| bytes_len_to_fes_len(max, self.bit_offset));
+                (min, max)
+            }
+            None => (0, Some(0)),
+        }
     }
 }
 
-/// The number of fes encoded by n bytes, rounded up because we pad the fes.
-fn bytes_len_to_fes_len(bytes: usize) -> usize {
-    let bits = bytes * 8;
-    (bits + 4) / 5
+/// The number of fes encoded by n bytes, of which the first `bit_offset` bits have already been
+/// encoded, rounded up because we pad the fes.
+///
+/// Computed without overflowing (i.e., without calculating the total number of bits), the result
+/// saturates at `usize::MAX`.
+fn bytes_len_to_fes_len(bytes: usize, bit_offset: u8) -> usize {
+    debug_assert!(bit_offset < 8);
+    let whole = bytes / 5; // Every 5 bytes (40 bits) encode exactly 8 fes.
+    let rem_bits = (bytes % 5) as u8 * 8;
+    let (whole, rem_bits) = if rem_bits >= bit_offset {
+        (whole, rem_bits - bit_offset)
+    } else if whole == 0 {
+        return 0;
+    } else {
+        (whole - 1, rem_bits + 40 - bit_offset)
+    };
+    whole.saturating_mul(8).saturating_add(usize::from((rem_bits + 4) / 5))
 }
 
 impl<I> ExactSizeIterator for BytesToFes<I>
@@ -138,11 +229,81 @@ where
 {
     #[inline]
     fn len(&self) -> usize {
-        let len = match self.last_byte {
-            Some(_) => self.iter.len() + 1,
-            None => self.iter.len(),
+        match self.last_byte {
+            Some(_) => bytes_len_to_fes_len(self.iter.len().saturating_add(1), self.bit_offset),
+            None => 0,
+        }
+    }
+}
+
+/// Iterator adaptor that converts fallible bytes to GF32 elements.
+///
+/// Created by [`TryByteIterExt::try_bytes_to_fes`]. If the total number of bits is not a multiple
+/// of 5, it right-pads with 0 bits. The first error of the bytes is yielded as the last item.
+#[derive(Clone, PartialEq, Eq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct TryBytesToFes<I> {
+    /// The bits read but not yet yielded, the low `buf_len` bits.
+    buf: u16,
+    /// The number of bits in `buf`, less than 8 between calls to `next`.
+    buf_len: u8,
+    /// Whether the bytes have run out or failed.
+    done: bool,
+    iter: I,
+}
+
+impl<I, E> Iterator for TryBytesToFes<I>
+where
+    I: Iterator<Item = Result<u8, E>>,
+{
+    type Item = Result<Fe32, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<Fe32, E>> {
+        if self.done {
+            return None;
+        }
+        while self.buf_len < 5 {
+            match self.iter.next() {
+                Some(Ok(byte)) => {
+                    self.buf = self.buf << 8 | u16::from(byte);
+                    self.buf_len += 8;
+                }
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.done = true;
+                    if self.buf_len == 0 {
+                        return None;
+                    }
+                    let fe = Fe32((self.buf << (5 - self.buf_len)) as u8 & 0x1f);
+                    self.buf_len = 0;
+                    return Some(Ok(fe));
+                }
+            }
+        }
+        self.buf_len -= 5;
+        let fe = Fe32((self.buf >> self.buf_len) as u8 & 0x1f);
+        self.buf &= (1 << self.buf_len) - 1;
+        Some(Ok(fe))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let (min, max) = self.iter.size_hint();
+        // The buffered bits are the end of a byte of which the other bits have been yielded.
+        let fes = |bytes: usize| match self.buf_len {
+            0 => Some(bytes_len_to_fes_len(bytes, 0)),
+            len => bytes.checked_add(1).map(|n| bytes_len_to_fes_len(n, 8 - len)),
         };
-        bytes_len_to_fes_len(len)
+        // Any byte may fail, which ends the iterator with a single item for the error.
+        let min = if self.buf_len >= 5 || min > 0 { 1 } else { 0 };
+        (min, max.and_then(fes))
     }
 }
 
@@ -154,9 +315,11 @@ where
 /// is dropped. If this occurs, the input was an invalid length for a bech32 string, but this
 /// iterator does not do any checks for this.
 #[derive(Clone, PartialEq, Eq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct FesToBytes<I: Iterator<Item = Fe32>> {
     last_fe: Option<Fe32>,
-    bit_offset: usize,
+    /// The number of bits of `last_fe` already yielded, always less than 5.
+    bit_offset: u8,
     iter: I,
 }
 
@@ -173,6 +336,8 @@ where
             ret
         };
 
+        debug_assert!(bit_offset < 5);
+
         if let Some(last) = self.last_fe {
             let mut ret = last.0 << (3 + bit_offset);
 
@@ -198,19 +363,38 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        // If the total number of bits is not a multiple of 8, any trailing bits are dropped.
-        let fes_len_to_bytes_len = |n| n * 5 / 8;
-
         let (fes_min, fes_max) = self.iter.size_hint();
-        // +1 because we set last_fe with call to `next`.
-        let min = fes_len_to_bytes_len(fes_min + 1);
-        let max = fes_max.map(|max| fes_len_to_bytes_len(max + 1));
-        (min, max)
+        match self.last_fe {
+            // +1 because we set last_fe with call to `next`.
+            Some(_) => {
+                let min = fes_len_to_bytes_len(fes_min.saturating_add(1), self.bit_offset);
+                let max = fes_max
+                    .and_then(|max| max.checked_add(1))
+                    .map(|max| fes_len_to_bytes_len(max, self.bit_offset));
+                (min, max)
+            }
+            None => (0, Some(0)),
+        }
     }
 }
 
-// If the total number of bits is not a multiple of 8, any trailing bits are dropped.
-fn fes_len_to_bytes_len(n: usize) -> usize { n * 5 / 8 }
+// The number of bytes encoded by n fes, of which the first `bit_offset` bits have already been
+// decoded. If the total number of bits is not a multiple of 8, any trailing bits are dropped.
+//
+// Computed without overflowing (i.e., without calculating the total number of bits).
+fn fes_len_to_bytes_len(n: usize, bit_offset: u8) -> usize {
+    debug_assert!(bit_offset < 5);
+    let whole = n / 8; // Every 8 fes (40 bits) encode exactly 5 bytes.
+    let rem_bits = (n % 8) as u8 * 5;
+    let (whole, rem_bits) = if rem_bits >= bit_offset {
+        (whole, rem_bits - bit_offset)
+    } else if whole == 0 {
+        return 0;
+    } else {
+        (whole - 1, rem_bits + 40 - bit_offset)
+    };
+    whole * 5 + usize::from(rem_bits / 8)
+}
 
 impl<I> ExactSizeIterator for FesToBytes<I>
 where
@@ -218,17 +402,88 @@ where
 {
     #[inline]
     fn len(&self) -> usize {
-        let len = match self.last_fe {
-            Some(_) => self.iter.len() + 1,
-            None => self.iter.len(),
-        };
-        fes_len_to_bytes_len(len)
+        match self.last_fe {
+            Some(_) => fes_len_to_bytes_len(self.iter.len().saturating_add(1), self.bit_offset),
+            None => 0,
+        }
+    }
+}
+
+/// Iterator adaptor that converts GF32 elements to bytes, enforcing the BIP-173 padding rules.
+///
+/// From BIP-173:
+/// > Re-arrange those bits into groups of 8 bits. Any incomplete group at the
+/// > end MUST be 4 bits or less, MUST be all zeroes, and is discarded.
+///
+/// If the incomplete group breaks these rules the iterator yields a [`PaddingError`] after the
+/// last byte and then stops.
+#[derive(Clone, PartialEq, Eq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct FesToBytesStrict<I: Iterator<Item = Fe32>> {
+    /// Bits read from the field elements but not yet yielded, in the low `buf_len` bits.
+    buf: u16,
+    /// The number of bits in `buf`, always less than 8 between calls to `next` and at most 12.
+    buf_len: u8,
+    /// Whether the padding has been checked.
+    done: bool,
+    iter: I,
+}
+
+impl<I> Iterator for FesToBytesStrict<I>
+where
+    I: Iterator<Item = Fe32>,
+{
+    type Item = Result<u8, PaddingError>;
+
+    fn next(&mut self) -> Option<Result<u8, PaddingError>> {
+        if self.done {
+            return None;
+        }
+        while self.buf_len < 8 {
+            debug_assert!(self.buf_len < 8 && self.buf < 1 << self.buf_len);
+            match self.iter.next() {
+                Some(fe) => {
+                    self.buf = (self.buf << 5) | u16::from(fe.to_u8());
+                    self.buf_len += 5;
+                }
+                None => {
+                    self.done = true;
+                    return if self.buf_len > 4 {
+                        Some(Err(PaddingError::TooMuch))
+                    } else if self.buf != 0 {
+                        Some(Err(PaddingError::NonZero))
+                    } else {
+                        None
+                    };
+                }
+            }
+        }
+        self.buf_len -= 8;
+        let byte = (self.buf >> self.buf_len) as u8;
+        self.buf &= (1 << self.buf_len) - 1;
+        Some(Ok(byte))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let (fes_min, fes_max) = self.iter.size_hint();
+        // The buffered bits are less than two field elements, and there may be an error.
+        let min = fes_len_to_bytes_len(fes_min, 0);
+        let max = fes_max
+            .and_then(|max| max.checked_add(2))
+            .map(|max| fes_len_to_bytes_len(max, 0))
+            .and_then(|max| max.checked_add(1));
+        (min, max)
     }
 }
 
 /// Iterator adaptor for field-element-yielding iterator, which tacks a checksum onto the end of the
 /// yielded data.
 #[derive(Clone, PartialEq, Eq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct Checksummed<I, Ck>
 where
     I: Iterator<Item = Fe32>,
@@ -297,10 +552,19 @@ where
         let add = self.checksum_remaining;
         let (min, max) = self.iter.size_hint();
 
-        (min + add, max.map(|max| max + add))
+        (min.saturating_add(add), max.and_then(|max| max.checked_add(add)))
     }
 }
 
+impl<I, Ck> ExactSizeIterator for Checksummed<I, Ck>
+where
+    I: Iterator<Item = Fe32> + ExactSizeIterator,
+    Ck: Checksum,
+{
+    #[inline]
+    fn len(&self) -> usize { self.iter.len().saturating_add(self.checksum_remaining) }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +594,30 @@ mod tests {
         assert_eq!(DATA.iter().copied().bytes_to_fes().size_hint(), (char_len, Some(char_len)));
     }
 
+    #[test]
+    fn try_bytes_to_fes_matches_bytes_to_fes() {
+        for len in 0..=DATA.len() {
+            let mut fes = DATA[..len].iter().map(|&b| Ok::<u8, ()>(b)).try_bytes_to_fes();
+            let want = DATA[..len].iter().bytes_to_fes();
+            let want_len = want.len();
+            for (i, fe) in want.enumerate() {
+                let (min, max) = fes.size_hint();
+                assert!(min <= want_len - i && max == Some(want_len - i), "len {} fe {}", len, i);
+                assert_eq!(fes.next(), Some(Ok(fe)));
+            }
+            assert_eq!(fes.size_hint(), (0, Some(0)));
+            assert_eq!(fes.next(), None);
+        }
+    }
+
+    #[test]
+    fn try_bytes_to_fes_error() {
+        // The error replaces the fes that the failed byte is part of, nothing follows it.
+        let source = [Ok(0xff), Ok(0xff), Err("fail"), Ok(0xff)];
+        let fes = source.iter().cloned().try_bytes_to_fes().collect::<Vec<_>>();
+        assert_eq!(fes, [Ok(Fe32::L), Ok(Fe32::L), Ok(Fe32::L), Err("fail")]);
+    }
+
     #[test]
     fn fe32_iter_ext() {
         let fe_iter = "w508d6qejxtdg4y5r3zarvary0c5xw7k"
@@ -351,6 +639,134 @@ mod tests {
         assert_eq!(got_hint, want_hint)
     }
 
+    #[test]
+    fn size_hint_does_not_overflow() {
+        let bytes = core::iter::repeat(0_u8);
+        assert_eq!(bytes.clone().bytes_to_fes().size_hint(), (usize::MAX, None));
+
+        let fes = core::iter::repeat(Fe32::Q);
+        assert_eq!(fes.clone().fes_to_bytes().size_hint(), (usize::MAX / 8 * 5 + 4, None));
+        let checksummed = Checksummed::<_, crate::Bech32>::new(fes);
+        assert_eq!(checksummed.size_hint(), (usize::MAX, None));
+    }
+
+    /// An iterator claiming to have `usize::MAX` items left, more than any real input.
+    struct Endless<T>(T);
+
+    impl<T: Copy> Iterator for Endless<T> {
+        type Item = T;
+        fn next(&mut self) -> Option<T> { Some(self.0) }
+        fn size_hint(&self) -> (usize, Option<usize>) { (usize::MAX, Some(usize::MAX)) }
+    }
+
+    impl<T: Copy> ExactSizeIterator for Endless<T> {}
+
+    #[test]
+    fn len_does_not_overflow() {
+        // `next` is called once on construction, which puts one item in `last_byte`/`last_fe`.
+        assert_eq!(Endless(0_u8).bytes_to_fes().len(), usize::MAX);
+        assert_eq!(Endless(Fe32::Q).fes_to_bytes().len(), usize::MAX / 8 * 5 + 4);
+    }
+
+    /// Checks `bytes` roundtrip through the adaptors and that the lengths are exact.
+    fn check_roundtrip<I>(bytes: I)
+    where
+        I: Iterator<Item = u8> + ExactSizeIterator + Clone,
+    {
+        let fes = bytes.clone().bytes_to_fes();
+        let fes_len = (bytes.len() * 8 + 4) / 5;
+        assert_eq!(fes.len(), fes_len);
+        assert_eq!(fes.clone().count(), fes_len);
+
+        let back = fes.clone().fes_to_bytes();
+        assert_eq!(back.len(), bytes.len());
+        assert!(back.eq(bytes.clone()));
+        assert!(fes.fes_to_bytes_strict().eq(bytes.map(Ok)));
+    }
+
+    #[test]
+    fn maximum_length_witness_program_roundtrips() {
+        // 40 bytes, the longest segwit program, is 64 fes with no padding.
+        check_roundtrip([0xff_u8; 40].iter().copied());
+        check_roundtrip([0x00_u8; 40].iter().copied());
+        check_roundtrip((0..40_u8).map(|i| i.wrapping_mul(0x9d)));
+    }
+
+    #[test]
+    fn large_payload_roundtrips() {
+        // 10 KiB, well over any bech32 code length, with every byte value and bit offset.
+        check_roundtrip((0..10 * 1024_u32).map(|i| (i.wrapping_mul(0x9e37_79b9) >> 24) as u8));
+        check_roundtrip(core::iter::repeat(0xff_u8).take(10 * 1024));
+    }
+
+    #[test]
+    fn length_conversions() {
+        for n in 0..100 {
+            assert_eq!(bytes_len_to_fes_len(n, 0), (n * 8 + 4) / 5);
+            assert_eq!(fes_len_to_bytes_len(n, 0), n * 5 / 8);
+            for offset in 0..8 {
+                let want = ((n * 8).saturating_sub(usize::from(offset)) + 4) / 5;
+                assert_eq!(bytes_len_to_fes_len(n, offset), want);
+            }
+            for offset in 0..5 {
+                let want = (n * 5).saturating_sub(usize::from(offset)) / 8;
+                assert_eq!(fes_len_to_bytes_len(n, offset), want);
+            }
+        }
+    }
+
+    #[test]
+    fn len_is_exact_while_iterating() {
+        for n in 0..=20 {
+            let mut fes = (0..n).map(|i| i as u8).bytes_to_fes();
+            for remaining in (0..=(n * 8 + 4) / 5).rev() {
+                assert_eq!(fes.len(), remaining);
+                assert_eq!(fes.size_hint(), (remaining, Some(remaining)));
+                assert_eq!(fes.next().is_some(), remaining > 0);
+            }
+
+            let mut bytes = core::iter::repeat(Fe32::P).take(n).fes_to_bytes();
+            for remaining in (0..=n * 5 / 8).rev() {
+                assert_eq!(bytes.len(), remaining);
+                assert_eq!(bytes.size_hint(), (remaining, Some(remaining)));
+                assert_eq!(bytes.next().is_some(), remaining > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn fes_to_bytes_strict_matches_fes_to_bytes() {
+        let fes = "w508d6qejxtdg4y5r3zarvary0c5xw7k"
+            .bytes()
+            .map(|b| Fe32::from_char(char::from(b)).unwrap());
+        assert!(fes.fes_to_bytes_strict().eq(DATA.iter().copied().map(Ok)));
+
+        for len in 0..DATA.len() {
+            let fes = DATA[..len].iter().bytes_to_fes();
+            assert!(fes.fes_to_bytes_strict().eq(DATA[..len].iter().copied().map(Ok)));
+        }
+    }
+
+    #[test]
+    fn fes_to_bytes_strict_rejects_invalid_padding() {
+        // 3 fes is 15 bits, 7 bits of padding.
+        let mut iter = [Fe32::Q; 3].iter().copied().fes_to_bytes_strict();
+        assert_eq!(iter.next(), Some(Ok(0)));
+        assert_eq!(iter.next(), Some(Err(PaddingError::TooMuch)));
+        assert_eq!(iter.next(), None);
+
+        // 2 fes is 10 bits, 2 bits of padding which must be zero.
+        let mut iter = [Fe32::Q, Fe32::Z].iter().copied().fes_to_bytes_strict();
+        assert_eq!(iter.next(), Some(Ok(0)));
+        assert_eq!(iter.next(), Some(Err(PaddingError::NonZero)));
+        assert_eq!(iter.next(), None);
+
+        // A single fe is not even one byte.
+        let mut iter = core::iter::once(Fe32::Q).fes_to_bytes_strict();
+        assert_eq!(iter.next(), Some(Err(PaddingError::TooMuch)));
+        assert_eq!(iter.next(), None);
+    }
+
     #[test]
     fn padding_bytes_trailing_0_bits_roundtrips() {
         // 5 * 8 % 5 = 0
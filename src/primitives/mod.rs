@@ -29,6 +29,7 @@ pub enum Bech32 {}
 pub enum Bech32m {}
 
 impl Checksum for NoChecksum {
+    const NAME: &'static str = "none";
     type MidstateRepr = PackedNull;
     const CODE_LENGTH: usize = usize::MAX;
     const CHECKSUM_LENGTH: usize = 0;
@@ -38,21 +39,77 @@ impl Checksum for NoChecksum {
 
 // Bech32[m] generator coefficients, copied from Bitcoin Core src/bech32.cpp
 const GEN: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+// Bech32[m] pair shift table, computed at compile time from the generator coefficients.
+const GEN_PAIR_SHIFTS: [u32; 1024] = pair_shifts_u32(GEN, 6);
+
+/// Computes the [`Checksum::PAIR_SHIFTS`] table of a checksum with a `u32` midstate.
+///
+/// Same as calling [`checksum::pair_shift`] for every pair of field elements, but usable in a
+/// const context.
+const fn pair_shifts_u32(generator_sh: [u32; 5], checksum_length: usize) -> [u32; 1024] {
+    const fn generator_shift(generator_sh: [u32; 5], xn: u32) -> u32 {
+        let mut ret = 0;
+        let mut i = 0;
+        while i < 5 {
+            if xn & (1 << i) != 0 {
+                ret ^= generator_sh[i];
+            }
+            i += 1;
+        }
+        ret
+    }
+
+    let top_shift = 5 * (checksum_length - 1);
+    let mask = (1 << (5 * checksum_length)) - 1;
+    let mut table = [0; 1024];
+    let mut i = 0;
+    while i < 1024 {
+        let shift = generator_shift(generator_sh, i as u32 >> 5);
+        let top = shift >> top_shift;
+        table[i] = ((shift << 5) & mask) ^ generator_shift(generator_sh, (i as u32 & 0x1f) ^ top);
+        i += 1;
+    }
+    table
+}
 
 impl Checksum for Bech32 {
+    const NAME: &'static str = "bech32";
     type MidstateRepr = u32;
     const CODE_LENGTH: usize = 1023;
     const CHECKSUM_LENGTH: usize = 6;
     const GENERATOR_SH: [u32; 5] = GEN;
     const TARGET_RESIDUE: u32 = 1;
+    const PAIR_SHIFTS: Option<&'static [u32; 1024]> = Some(&GEN_PAIR_SHIFTS);
 }
 // Same as Bech32 except TARGET_RESIDUE is different
 impl Checksum for Bech32m {
+    const NAME: &'static str = "bech32m";
     type MidstateRepr = u32;
     const CODE_LENGTH: usize = 1023;
     const CHECKSUM_LENGTH: usize = 6;
     const GENERATOR_SH: [u32; 5] = GEN;
     const TARGET_RESIDUE: u32 = 0x2bc830a3;
+    const PAIR_SHIFTS: Option<&'static [u32; 1024]> = Some(&GEN_PAIR_SHIFTS);
+}
+
+/// The bech32 checksum algorithm without the 1023 character limit.
+///
+/// Lightning invoices and LNURL strings use the bech32 checksum but have no maximum length, even
+/// though the checksum only guarantees error detection for strings of up to 1023 characters.
+#[cfg(any(feature = "bolt11", feature = "lnurl", feature = "nip19"))]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Bech32Unlimited {}
+
+// Same as Bech32 except CODE_LENGTH is unlimited.
+#[cfg(any(feature = "bolt11", feature = "lnurl", feature = "nip19"))]
+impl Checksum for Bech32Unlimited {
+    const NAME: &'static str = "bech32";
+    type MidstateRepr = u32;
+    const CODE_LENGTH: usize = usize::MAX;
+    const CHECKSUM_LENGTH: usize = 6;
+    const GENERATOR_SH: [u32; 5] = GEN;
+    const TARGET_RESIDUE: u32 = 1;
+    const PAIR_SHIFTS: Option<&'static [u32; 1024]> = Some(&GEN_PAIR_SHIFTS);
 }
 
 #[cfg(test)]
@@ -64,4 +121,136 @@ mod tests {
 
     #[test]
     fn bech32m_sanity() { Bech32m::sanity_check(); }
+
+    #[test]
+    fn names() {
+        assert_eq!(NoChecksum::NAME, "none");
+        assert_eq!(Bech32::NAME, "bech32");
+        assert_eq!(Bech32m::NAME, "bech32m");
+    }
+
+    #[test]
+    fn pair_shifts_match_single_shifts() {
+        use checksum::Engine;
+        use gf32::Fe32;
+
+        fn check<Ck: Checksum>(fes: &[Fe32]) {
+            let mut single = Engine::<Ck>::new();
+            fes.iter().for_each(|&fe| single.input_fe(fe));
+            let mut pairs = Engine::<Ck>::new();
+            pairs.input_fes(fes.iter().copied());
+            assert!(single.residue() == pairs.residue(), "residues differ for {} fes", fes.len());
+        }
+
+        // Every field element in every position, and odd and even lengths.
+        let fes = (0..1000_u32).map(|i| Fe32((i * 7 + i / 32) as u8 & 0x1f)).collect::<Vec<_>>();
+        for len in [0, 1, 2, 3, 89, 90, 999, 1000].iter() {
+            check::<Bech32>(&fes[..*len]);
+            check::<Bech32m>(&fes[..*len]);
+            check::<NoChecksum>(&fes[..*len]);
+        }
+    }
+
+    #[test]
+    fn input_hrp_is_input_of_expansion() {
+        use checksum::Engine;
+        use hrp::Hrp;
+
+        fn check<Ck: Checksum>(hrp: Hrp) {
+            let mut engine = Engine::<Ck>::new();
+            engine.input_hrp(hrp);
+            let mut expansion = Engine::<Ck>::new();
+            hrp.expansion_iter().for_each(|fe| expansion.input_fe(fe));
+            assert!(engine.residue() == expansion.residue(), "residues differ for {}", hrp);
+        }
+
+        for s in [
+            "a",
+            "grs",
+            "TGRS",
+            "grsrt",
+            "?1!~",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber1",
+        ]
+        .iter()
+        {
+            let hrp = Hrp::parse(s).expect("valid hrp");
+            check::<Bech32>(hrp);
+            check::<Bech32m>(hrp);
+        }
+    }
+
+    #[test]
+    fn engine_midstate_roundtrip() {
+        use checksum::Engine;
+        use gf32::Fe32;
+
+        let mut engine = Engine::<Bech32m>::new();
+        engine.input_hrp(crate::hrp::GRS);
+        engine.input_fes((0..40).map(|i| Fe32(i as u8 & 0x1f)));
+        assert!(Engine::<Bech32m>::from_midstate(engine.midstate()) == engine);
+        assert_eq!(engine.midstate().len(), 4);
+
+        // Bits above the checksum length are ignored.
+        let mut midstate = engine.midstate();
+        midstate[0] |= 0xc0;
+        assert!(Engine::<Bech32m>::from_midstate(midstate) == engine);
+
+        assert!(Engine::<NoChecksum>::new().midstate().is_empty());
+    }
+
+    #[test]
+    fn byte_engine_resume_at_every_byte() {
+        use checksum::{ByteEngine, Engine};
+        use gf32::Fe32;
+        use iter::{ByteIterExt, Fe32IterExt};
+
+        fn check<Ck: Checksum>(program: &[u8]) {
+            let hrp = crate::hrp::TGRS;
+            let want = program
+                .iter()
+                .copied()
+                .bytes_to_fes()
+                .with_checksum::<Ck>(&hrp)
+                .with_witness_version(Fe32::P)
+                .chars()
+                .collect::<String>();
+
+            for split in 0..=program.len() {
+                let mut engine = Engine::<Ck>::new();
+                engine.input_hrp(hrp);
+                engine.input_fe(Fe32::P);
+
+                let mut got = String::from("tgrs1p");
+                let mut bytes = ByteEngine::from_engine(engine);
+                for &byte in &program[..split] {
+                    got.extend(bytes.input_byte(byte).map(Fe32::to_char));
+                }
+                let (midstate, pending) = bytes.midstate();
+                let mut bytes = ByteEngine::<Ck>::from_midstate(midstate, pending).unwrap();
+                for &byte in &program[split..] {
+                    got.extend(bytes.input_byte(byte).map(Fe32::to_char));
+                }
+                got.extend(bytes.finish().map(Fe32::to_char));
+
+                assert_eq!(got, want, "split after {} of {} bytes", split, program.len());
+            }
+        }
+
+        let program = (0..40_u8).map(|i| i.wrapping_mul(151)).collect::<Vec<_>>();
+        for len in [0, 1, 2, 3, 4, 5, 20, 32, 33, 40].iter() {
+            check::<Bech32>(&program[..*len]);
+            check::<Bech32m>(&program[..*len]);
+        }
+    }
+
+    #[test]
+    fn byte_engine_invalid_pending() {
+        use checksum::ByteEngine;
+
+        let midstate = ByteEngine::<Bech32>::new().midstate().0;
+        assert!(ByteEngine::<Bech32>::from_midstate(midstate, 0x4f).is_some());
+        assert!(ByteEngine::<Bech32>::from_midstate(midstate, 0x5f).is_none());
+        assert!(ByteEngine::<Bech32>::from_midstate(midstate, 0x12).is_none());
+    }
 }
@@ -6,8 +6,14 @@
 //! [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
 
 use core::fmt;
+use core::ops::RangeInclusive;
 
+use crate::error::Code;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::primitives::checksum::Checksum;
 use crate::primitives::gf32::Fe32;
+use crate::primitives::Bech32;
 
 /// The maximum enforced string length of a segwit address.
 ///
@@ -17,6 +23,24 @@ use crate::primitives::gf32::Fe32;
 /// ["Checksum design"]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#user-content-Checksum_design>
 pub const MAX_STRING_LENGTH: usize = 90;
 
+/// The minimum length of a witness program, as specified by [BIP-141].
+///
+/// [BIP-141]: <https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program>
+pub const MIN_WITNESS_PROGRAM_LENGTH: usize = 2;
+
+/// The maximum length of a witness program, as specified by [BIP-141].
+///
+/// [BIP-141]: <https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program>
+pub const MAX_WITNESS_PROGRAM_LENGTH: usize = 40;
+
+/// The valid lengths of a segwit version 0 witness program (P2WPKH and P2WSH respectively).
+pub const VERSION_0_PROGRAM_LENGTHS: [usize; 2] = [20, 32];
+
+/// The number of characters in the checksum of a segwit address.
+///
+/// Both bech32 and bech32m checksums are the same length.
+pub const CHECKSUM_LENGTH: usize = Bech32::CHECKSUM_LENGTH;
+
 /// The field element representing segwit version 0.
 pub const VERSION_0: Fe32 = Fe32::Q;
 /// The field element representing segwit version 1 (taproot).
@@ -48,23 +72,76 @@ pub fn validate_witness_program_length(
 ) -> Result<(), WitnessLengthError> {
     use WitnessLengthError::*;
 
-    if length < 2 {
-        return Err(TooShort);
+    if length < MIN_WITNESS_PROGRAM_LENGTH {
+        return Err(TooShort { len: length, min: MIN_WITNESS_PROGRAM_LENGTH });
     }
-    if length > 40 {
-        return Err(TooLong);
+    if length > MAX_WITNESS_PROGRAM_LENGTH {
+        return Err(TooLong { len: length, max: MAX_WITNESS_PROGRAM_LENGTH });
     }
-    if version == VERSION_0 && length != 20 && length != 32 {
-        return Err(InvalidSegwitV0);
+    if !is_valid_program_length(version, length) {
+        return Err(InvalidSegwitV0 { len: length });
     }
     Ok(())
 }
 
-/// Field element does not represent a valid witness version.
+/// Returns the witness program lengths that are valid for witness `version`.
+///
+/// As specified by [BIP-141], version 0 witness programs must be either 20 bytes (P2WPKH) or 32
+/// bytes (P2WSH) long, all other witness versions may have programs between 2 and 40 bytes long
+/// inclusive.
+///
+/// Note this function does not validate `version`, see [`validate_witness_version`].
+///
+/// [BIP-141]: <https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program>
+pub fn valid_program_lengths(version: Fe32) -> ProgramLengthRule {
+    if version == VERSION_0 {
+        ProgramLengthRule::Exact(&VERSION_0_PROGRAM_LENGTHS)
+    } else {
+        ProgramLengthRule::Range(MIN_WITNESS_PROGRAM_LENGTH..=MAX_WITNESS_PROGRAM_LENGTH)
+    }
+}
+
+/// Returns true if `length` is a valid witness program length for witness `version`.
+///
+/// Equivalent to `valid_program_lengths(version).contains(length)`.
+pub fn is_valid_program_length(version: Fe32, length: usize) -> bool {
+    valid_program_lengths(version).contains(length)
+}
+
+/// The rule governing valid witness program lengths for a particular witness version.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramLengthRule {
+    /// The program length must be exactly one of the listed lengths.
+    Exact(&'static [usize]),
+    /// The program length must be within the (inclusive) range.
+    Range(RangeInclusive<usize>),
+}
+
+impl ProgramLengthRule {
+    /// Returns true if `length` satisfies this rule.
+    pub fn contains(&self, length: usize) -> bool {
+        match *self {
+            ProgramLengthRule::Exact(lengths) => lengths.contains(&length),
+            ProgramLengthRule::Range(ref range) => range.contains(&length),
+        }
+    }
+}
+
+/// Field element does not represent a valid witness version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub struct InvalidWitnessVersionError(pub Fe32);
 
+impl InvalidWitnessVersionError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { Code::WitnessVersionInvalid }
+}
+
 #[rustfmt::skip]
 impl fmt::Display for InvalidWitnessVersionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -72,42 +149,211 @@ impl fmt::Display for InvalidWitnessVersionError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for InvalidWitnessVersionError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for InvalidWitnessVersionError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { None }
 }
 
 /// Witness program invalid because of incorrect length.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum WitnessLengthError {
     /// The witness data is too short.
-    TooShort,
+    TooShort {
+        /// The length of the witness program.
+        len: usize,
+        /// The minimum allowed length of a witness program.
+        min: usize,
+    },
     /// The witness data is too long.
-    TooLong,
+    TooLong {
+        /// The length of the witness program.
+        len: usize,
+        /// The maximum allowed length of a witness program.
+        max: usize,
+    },
     /// The segwit v0 witness is not 20 or 32 bytes long.
-    InvalidSegwitV0,
+    ///
+    /// Segwit v0 witness programs must be either 20 bytes (P2WPKH) or 32 bytes (P2WSH) long.
+    InvalidSegwitV0 {
+        /// The length of the witness program.
+        len: usize,
+    },
+}
+
+impl WitnessLengthError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use WitnessLengthError::*;
+
+        match *self {
+            TooShort { .. } => Code::WitnessTooShort,
+            TooLong { .. } => Code::WitnessTooLong,
+            InvalidSegwitV0 { .. } => Code::WitnessV0Length,
+        }
+    }
+
+    /// Returns whether the witness program is shorter than any valid program for its version.
+    ///
+    /// A heuristic for user facing messages, a program that is too short most likely comes from an
+    /// address that was cut off while being copied. Longer invalid programs are more likely from a
+    /// corrupted address or one for another coin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::primitives::segwit::{validate_witness_program_length, VERSION_0, VERSION_1};
+    ///
+    /// let err = validate_witness_program_length(19, VERSION_0).unwrap_err();
+    /// assert!(err.likely_truncated());
+    /// let err = validate_witness_program_length(25, VERSION_0).unwrap_err();
+    /// assert!(!err.likely_truncated());
+    /// let err = validate_witness_program_length(1, VERSION_1).unwrap_err();
+    /// assert!(err.likely_truncated());
+    /// ```
+    pub fn likely_truncated(&self) -> bool {
+        use WitnessLengthError::*;
+
+        match *self {
+            TooShort { .. } => true,
+            TooLong { .. } => false,
+            InvalidSegwitV0 { len } => len < VERSION_0_PROGRAM_LENGTHS[0],
+        }
+    }
 }
 
 impl fmt::Display for WitnessLengthError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use WitnessLengthError::*;
 
+        // All messages are here, including the hint, to keep them easy to find and translate.
+        const TRUNCATED_HINT: &str = "the address may be truncated or corrupted";
+
         match *self {
-            TooShort => write!(f, "witness program is less than 2 bytes long"),
-            TooLong => write!(f, "witness program is more than 40 bytes long"),
-            InvalidSegwitV0 => write!(f, "the segwit v0 witness is not 20 or 32 bytes long"),
+            TooShort { len, min } => write!(
+                f,
+                "witness program is {} bytes long, must be at least {} bytes, {}",
+                len, min, TRUNCATED_HINT
+            ),
+            TooLong { len, max } =>
+                write!(f, "witness program is {} bytes long, must be at most {} bytes", len, max),
+            InvalidSegwitV0 { len } => write!(
+                f,
+                "segwit v0 programs must be exactly 20 bytes (P2WPKH) or 32 bytes (P2WSH); got {}, {}",
+                len, TRUNCATED_HINT
+            ),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for WitnessLengthError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for WitnessLengthError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use WitnessLengthError::*;
 
         match *self {
-            TooShort | TooLong | InvalidSegwitV0 => None,
+            TooShort { .. } | TooLong { .. } | InvalidSegwitV0 { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use super::*;
+
+    #[test]
+    fn witness_length_error_too_short() {
+        let err = validate_witness_program_length(1, VERSION_1).unwrap_err();
+        assert_eq!(err, WitnessLengthError::TooShort { len: 1, min: 2 });
+    }
+
+    #[test]
+    fn witness_length_error_too_long() {
+        let err = validate_witness_program_length(41, VERSION_1).unwrap_err();
+        assert_eq!(err, WitnessLengthError::TooLong { len: 41, max: 40 });
+    }
+
+    #[test]
+    fn witness_length_error_invalid_segwit_v0() {
+        let err = validate_witness_program_length(25, VERSION_0).unwrap_err();
+        assert_eq!(err, WitnessLengthError::InvalidSegwitV0 { len: 25 });
+        // Any length between 2 and 40 is valid for other witness versions.
+        assert!(validate_witness_program_length(25, VERSION_1).is_ok());
+    }
+
+    #[test]
+    fn max_string_length_from_components() {
+        // The longest HRP that can be used with a maximum length witness program.
+        let hrp_len = 18;
+        let program_len = (MAX_WITNESS_PROGRAM_LENGTH * 8 + 4) / 5; // Padded to a whole fe32.
+        let len = hrp_len + 1 + 1 + program_len + CHECKSUM_LENGTH; // +1 separator, +1 witness version.
+        assert_eq!(len, MAX_STRING_LENGTH);
+    }
+
+    #[test]
+    fn program_length_rules() {
+        assert_eq!(valid_program_lengths(VERSION_0), ProgramLengthRule::Exact(&[20, 32]));
+        for v in 1..=16_u8 {
+            let version = Fe32::try_from(v).expect("valid field element");
+            assert_eq!(valid_program_lengths(version), ProgramLengthRule::Range(2..=40));
+        }
+    }
+
+    #[test]
+    fn is_valid_program_length_matches_validation() {
+        for v in 0..=16_u8 {
+            let version = Fe32::try_from(v).expect("valid field element");
+            for len in 0..=42 {
+                assert_eq!(
+                    is_valid_program_length(version, len),
+                    validate_witness_program_length(len, version).is_ok()
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn witness_length_error_display() {
+        use alloc::string::ToString;
+
+        let err = WitnessLengthError::InvalidSegwitV0 { len: 25 };
+        assert_eq!(
+            err.to_string(),
+            "segwit v0 programs must be exactly 20 bytes (P2WPKH) or 32 bytes (P2WSH); got 25, \
+             the address may be truncated or corrupted"
+        );
+        let err = WitnessLengthError::TooShort { len: 1, min: 2 };
+        assert_eq!(
+            err.to_string(),
+            "witness program is 1 bytes long, must be at least 2 bytes, \
+             the address may be truncated or corrupted"
+        );
+        let err = WitnessLengthError::TooLong { len: 41, max: 40 };
+        assert_eq!(err.to_string(), "witness program is 41 bytes long, must be at most 40 bytes");
+    }
+
+    #[test]
+    fn likely_truncated() {
+        for v in 0..=16_u8 {
+            let version = Fe32::try_from(v).expect("valid field element");
+            for len in 0..=42 {
+                if let Err(e) = validate_witness_program_length(len, version) {
+                    let shortest = if v == 0 {
+                        VERSION_0_PROGRAM_LENGTHS[0]
+                    } else {
+                        MIN_WITNESS_PROGRAM_LENGTH
+                    };
+                    assert_eq!(e.likely_truncated(), len < shortest, "v{} {}", v, len);
+                }
+            }
         }
     }
 }
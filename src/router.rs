@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: MIT
+
+//! Dispatching strings to handlers by their human-readable part.
+
+use alloc::collections::BTreeMap;
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{UncheckedHrpstring, UncheckedHrpstringError};
+use crate::primitives::hrp::Hrp;
+
+/// A table of handlers keyed by human-readable part, for services that accept several kinds of
+/// bech32 strings.
+///
+/// Human-readable parts are compared ignoring case, so there is no need to lowercase them before
+/// inserting or routing.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{hrp, Bech32, HrpRouter};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Handler { Wallet, TestHarness }
+///
+/// let mut router = HrpRouter::new();
+/// router.insert(hrp::GRS, Handler::Wallet);
+/// router.insert(hrp::TGRS, Handler::TestHarness);
+///
+/// let (handler, parsed) =
+///     router.route("GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P").expect("registered hrp");
+/// assert_eq!(*handler, Handler::Wallet);
+/// // The handler validates the rest of the string.
+/// let checked = parsed.validate_and_remove_checksum::<Bech32>().expect("valid checksum");
+/// assert!(checked.validate_segwit().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HrpRouter<T> {
+    routes: BTreeMap<Hrp, T>,
+}
+
+impl<T> HrpRouter<T> {
+    /// Constructs an empty router.
+    #[inline]
+    pub fn new() -> Self { HrpRouter { routes: BTreeMap::new() } }
+
+    /// Routes strings with the human-readable part `hrp` to `handler`.
+    ///
+    /// Returns the handler previously registered for `hrp`, if any.
+    #[inline]
+    pub fn insert(&mut self, hrp: Hrp, handler: T) -> Option<T> { self.routes.insert(hrp, handler) }
+
+    /// Returns the handler registered for `hrp`.
+    #[inline]
+    pub fn get(&self, hrp: &Hrp) -> Option<&T> { self.routes.get(hrp) }
+
+    /// Returns an iterator over the registered human-readable parts, in order.
+    #[inline]
+    pub fn hrps(&self) -> impl Iterator<Item = &Hrp> { self.routes.keys() }
+
+    /// Parses `s` and returns the handler registered for its human-readable part.
+    ///
+    /// `s` is only parsed far enough to find its human-readable part, the checksum is not
+    /// validated. The parsed string is returned with the handler for it to validate.
+    ///
+    /// # Errors
+    ///
+    /// If `s` is not a bech32 string, or no handler is registered for its human-readable part.
+    pub fn route<'s>(&self, s: &'s str) -> Result<(&T, UncheckedHrpstring<'s>), RouteError> {
+        let unchecked = UncheckedHrpstring::new(s)?;
+        match self.routes.get(&unchecked.hrp()) {
+            Some(handler) => Ok((handler, unchecked)),
+            None => Err(RouteError::UnknownHrp {
+                hrp: unchecked.hrp(),
+                registered: self.routes.keys().copied().collect(),
+            }),
+        }
+    }
+}
+
+impl<T> Default for HrpRouter<T> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// An error while routing a string with a [`HrpRouter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RouteError {
+    /// The string is not a bech32 string.
+    Parse(UncheckedHrpstringError),
+    /// No handler is registered for the human-readable part.
+    UnknownHrp {
+        /// The human-readable part of the string.
+        hrp: Hrp,
+        /// The human-readable parts that do have a handler.
+        registered: Vec<Hrp>,
+    },
+}
+
+impl RouteError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use RouteError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            UnknownHrp { .. } => Code::UnknownHrp,
+        }
+    }
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RouteError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing failed"; e),
+            UnknownHrp { ref hrp, ref registered } => {
+                write!(f, "unknown human-readable part '{}'", hrp)?;
+                if registered.is_empty() {
+                    return write!(f, ", no human-readable parts are registered");
+                }
+                for (i, registered) in registered.iter().enumerate() {
+                    let sep = if i == 0 { ", expected one of: " } else { ", " };
+                    write!(f, "{}{}", sep, registered)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RouteError {
+    fn format(&self, f: defmt::Formatter) {
+        use RouteError::*;
+
+        match *self {
+            Parse(ref e) => defmt::write!(f, "parsing failed: {}", e),
+            UnknownHrp { ref hrp, ref registered } => defmt::write!(
+                f,
+                "unknown human-readable part '{}', expected one of: {}",
+                hrp,
+                registered.as_slice()
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for RouteError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use RouteError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            UnknownHrp { .. } => None,
+        }
+    }
+}
+
+impl From<UncheckedHrpstringError> for RouteError {
+    #[inline]
+    fn from(e: UncheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::CharError;
+    use crate::{hrp, Bech32};
+
+    #[derive(Debug, PartialEq)]
+    enum Handler {
+        Wallet,
+        TestHarness,
+        Lnurl,
+    }
+
+    fn router() -> HrpRouter<Handler> {
+        let mut router = HrpRouter::new();
+        assert!(router.insert(hrp::GRS, Handler::Wallet).is_none());
+        assert!(router.insert(hrp::TGRS, Handler::TestHarness).is_none());
+        assert!(router.insert(Hrp::parse("LNURL").unwrap(), Handler::Lnurl).is_none());
+        router
+    }
+
+    #[test]
+    fn routes_grs_address() {
+        let router = router();
+        for s in [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P",
+        ]
+        .iter()
+        {
+            let (handler, unchecked) = router.route(s).unwrap();
+            assert_eq!(*handler, Handler::Wallet);
+            assert_eq!(unchecked.hrp(), hrp::GRS);
+            assert!(unchecked
+                .validate_and_remove_checksum::<Bech32>()
+                .unwrap()
+                .validate_segwit()
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn routes_tgrs_address() {
+        let router = router();
+        let s = "tgrs1q09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09us84c5ts";
+        let (handler, unchecked) = router.route(s).unwrap();
+        assert_eq!(*handler, Handler::TestHarness);
+        assert!(unchecked
+            .validate_and_remove_checksum::<Bech32>()
+            .unwrap()
+            .validate_segwit()
+            .is_ok());
+        // The checksum is left to the handler.
+        let (handler, _) = router.route("lnurl1qqqqqq").unwrap();
+        assert_eq!(*handler, Handler::Lnurl);
+    }
+
+    #[test]
+    fn unknown_hrp_lists_registered() {
+        let s = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let err = router().route(s).unwrap_err();
+        assert_eq!(
+            err,
+            RouteError::UnknownHrp {
+                hrp: Hrp::parse("bc").unwrap(),
+                registered: vec![hrp::GRS, Hrp::parse("lnurl").unwrap(), hrp::TGRS],
+            }
+        );
+        assert_eq!(err.code(), "E_UNKNOWN_HRP");
+        assert_eq!(
+            err.to_string(),
+            "unknown human-readable part 'bc', expected one of: grs, LNURL, tgrs"
+        );
+
+        let err = HrpRouter::<Handler>::new().route(s).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown human-readable part 'bc', no human-readable parts are registered"
+        );
+    }
+
+    #[test]
+    fn invalid_string() {
+        assert_eq!(
+            router().route("grs").unwrap_err(),
+            RouteError::Parse(UncheckedHrpstringError::Char(CharError::MissingSeparator))
+        );
+    }
+}
@@ -7,6 +7,10 @@
 //! do posses such knowledge and are doing unusual things you may prefer to use the `primitives`
 //! submodules directly.
 //!
+//! None of the encoding functions panic, the `*_unchecked` ones included whatever their parameters.
+//! The `encode*_to_writer*` and `encode*_to_embedded_writer*` functions do not allocate, they pass
+//! the encoded bytes to the writer in chunks of up to 16 bytes.
+//!
 //! # Examples
 //!
 //! ```
@@ -40,30 +44,124 @@
 //! [`bip_173_test_vectors.rs`]: <https://github.com/rust-bitcoin/rust-bech32/blob/master/tests/bip_173_test_vectors.rs>
 //! [`bip_350_test_vectors.rs`]: <https://github.com/rust-bitcoin/rust-bech32/blob/master/tests/bip_350_test_vectors.rs>
 
+#![cfg_attr(not(test), deny(clippy::indexing_slicing, clippy::unwrap_used, clippy::expect_used))]
+
+#[cfg(feature = "alloc")]
+mod address;
+#[cfg(feature = "alloc")]
+mod address_string;
+mod compact;
+#[cfg(feature = "alloc")]
+mod decoded;
+#[cfg(feature = "heapless")]
+mod fixed_capacity;
+#[cfg(feature = "alloc")]
+mod observer;
+mod program;
+mod redact;
+mod script;
+#[cfg(feature = "metrics")]
+mod stats;
+
 #[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
-use alloc::{string::String, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
 use core::fmt;
 
-use crate::error::write_err;
-use crate::primitives::decode::SegwitCodeLengthError;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+#[cfg(feature = "alloc")]
+use crate::primitives::decode::SegwitErrorKind;
 #[cfg(feature = "alloc")]
-use crate::primitives::decode::{SegwitHrpstring, SegwitHrpstringError};
+use crate::primitives::decode::{CasePolicy, DecodeProfile};
+use crate::primitives::decode::{SegwitCodeLengthError, SegwitHrpstring, SegwitHrpstringError};
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::Hrp;
-use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::primitives::iter::{ByteRefIterExt, Fe32IterExt};
+use crate::primitives::segwit::{InvalidWitnessVersionError, WitnessLengthError};
+use crate::primitives::{segwit, Bech32, Bech32m};
+use crate::CapacityError;
 #[cfg(feature = "alloc")]
-use crate::primitives::segwit;
-use crate::primitives::segwit::{
-    InvalidWitnessVersionError, WitnessLengthError, MAX_STRING_LENGTH,
-};
-use crate::primitives::{Bech32, Bech32m};
+use crate::DecodeErrorWithInput;
 
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
 pub use {
-    crate::primitives::segwit::{VERSION_0, VERSION_1},
+    crate::primitives::segwit::{
+        VERSION_0, VERSION_1, MAX_STRING_LENGTH, MIN_WITNESS_PROGRAM_LENGTH,
+        MAX_WITNESS_PROGRAM_LENGTH, VERSION_0_PROGRAM_LENGTHS,
+    },
+    self::compact::{CompactAddress, CompactAddressError, KnownHrp},
+    self::program::{WitnessProgram, WitnessProgramError},
+    self::redact::{redact, redact_lenient, Redacted, DEFAULT_REDACT_PREFIX, DEFAULT_REDACT_SUFFIX},
+    self::script::{from_script_pubkey, ScriptPubkeyError},
+};
+
+#[cfg(feature = "heapless")]
+#[doc(inline)]
+pub use self::fixed_capacity::{decode_heapless, DecodeHeaplessError};
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::observer::{decode_with_observer, DecodeObserver};
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::script::{script_pubkey, to_script_pubkey_bytes};
+#[cfg(feature = "metrics")]
+#[doc(inline)]
+pub use self::stats::{decode_with_stats, DecodeStats};
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::{address::Address, address_string::AddressString, decoded::Decoded};
+#[cfg(feature = "rkyv")]
+#[doc(inline)]
+pub use self::{
+    compact::{ArchivedCompactAddress, ArchivedKnownHrp},
+    program::ArchivedWitnessProgram,
 };
 
+/// The case of an encoded segwit address.
+///
+/// Addresses are displayed lowercase, except in QR codes where uppercase allows a more compact
+/// encoding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Case {
+    /// All lowercase, the canonical form.
+    Lower,
+    /// All uppercase, as used in QR codes.
+    Upper,
+}
+
+impl Case {
+    /// Returns the case of `s`, which must not be mixed case.
+    ///
+    /// Strings with no letters at all are [`Case::Lower`].
+    #[cfg(feature = "alloc")]
+    fn of(s: &str) -> Self {
+        if s.bytes().any(|b| b.is_ascii_uppercase()) {
+            Case::Upper
+        } else {
+            Case::Lower
+        }
+    }
+}
+
+/// How a string relates to the network it was expected to be an address for, see
+/// [`classify_foreign`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ForeignAddressKind {
+    /// A valid segwit address for the expected network.
+    CorrectNetwork,
+    /// A valid segwit address, but for another known network.
+    WrongNetwork(KnownHrp),
+    /// A valid segwit address with a human-readable part that is not a known network.
+    UnknownHrpValidChecksum(Hrp),
+    /// Not a valid segwit address.
+    Invalid(SegwitHrpstringError),
+}
+
 /// Decodes a segwit address.
 ///
 /// # Returns
@@ -84,6 +182,483 @@ pub fn decode(s: &str) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
     Ok((segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect::<Vec<u8>>()))
 }
 
+/// Decodes a segwit address into a [`Decoded`], naming its parts.
+///
+/// Accepts the same addresses as [`decode`], which returns the parts as a tuple.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, Decoded};
+///
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+/// let Decoded { version, program, .. } = segwit::decode_struct(address).expect("valid address");
+/// assert_eq!(version.to_u8(), 1);
+/// assert_eq!(program.len(), 32);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_struct(s: &str) -> Result<Decoded, DecodeError> {
+    let (hrp, version, program) = decode(s)?;
+    Ok(Decoded { hrp, version, program })
+}
+
+/// Returns the lengths a segwit address with human-readable part `hrp` can have, sorted.
+///
+/// One length for each valid witness program length, 2 to 40 bytes, that fits in
+/// [`MAX_STRING_LENGTH`]. The lengths of version 0 addresses, with 20 or 32 byte programs, are
+/// among them. A string of any other length is not a valid segwit address, which can be checked
+/// before paying for checksum validation.
+///
+/// # Examples
+///
+/// Dropping strings of the wrong length before validating the checksum.
+///
+/// ```
+/// use bech32grs::primitives::decode::UncheckedHrpstring;
+/// use bech32grs::segwit;
+///
+/// /// Returns true if `s` is worth validating as a segwit address.
+/// fn plausible(s: &str) -> bool {
+///     match UncheckedHrpstring::new(s) {
+///         Ok(unchecked) => {
+///             let lengths = segwit::plausible_lengths(&unchecked.hrp());
+///             lengths.contains(&unchecked.total_len())
+///         }
+///         Err(_) => false,
+///     }
+/// }
+///
+/// let address = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// assert!(plausible(address));
+/// assert!(!plausible(&address[..41]));
+/// assert!(!plausible("grs1qqqqqq"));
+///
+/// // Not every plausible length is a usual one, 42 characters is a 17 byte program.
+/// assert!(plausible(&address[..42]));
+/// ```
+#[inline]
+pub fn plausible_lengths(hrp: &Hrp) -> &'static [usize] {
+    crate::primitives::decode::address_lengths(hrp.len())
+}
+
+/// Validates a segwit address without decoding its witness program.
+///
+/// Does every check [`decode`] does, the human-readable part, case, characters, checksum, witness
+/// version, padding and witness program length, and returns the same error on failure.
+///
+/// Unlike `decode(s).map(drop)` it does not allocate for a valid address. An invalid one may still
+/// allocate for the error, with the `alloc` feature an
+/// [`HrpSplitError`](crate::primitives::decode::HrpSplitError) collects the positions of the other
+/// separators into a `Vec`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::SegwitErrorKind;
+/// use bech32grs::segwit;
+///
+/// assert!(segwit::validate("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").is_ok());
+///
+/// let err = segwit::validate("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q").unwrap_err();
+/// assert_eq!(err.kind(), SegwitErrorKind::Checksum);
+/// ```
+#[inline]
+pub fn validate(s: &str) -> Result<(), SegwitHrpstringError> {
+    // Parsing borrows `s`, the witness program is only converted to bytes by `byte_iter`.
+    SegwitHrpstring::new(s).map(|_| ())
+}
+
+/// Decodes a Groestlcoin mainnet segwit address, one with the human-readable part "grs".
+///
+/// Like [`decode`] but a valid address for any other human-readable part, including testnet and
+/// regtest addresses, fails with [`SegwitHrpstringError::WrongHrp`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::SegwitHrpstringError;
+/// use bech32grs::segwit::{self, VERSION_0};
+///
+/// let (version, program) =
+///     segwit::decode_mainnet("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").expect("valid");
+/// assert_eq!(version, VERSION_0);
+/// assert_eq!(program.len(), 20);
+///
+/// let testnet = segwit::encode_testnet(VERSION_0, &program).expect("valid program");
+/// let err = segwit::decode_mainnet(&testnet).unwrap_err();
+/// assert!(matches!(err.0, SegwitHrpstringError::WrongHrp { .. }));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_mainnet(s: &str) -> Result<(Fe32, Vec<u8>), DecodeError> {
+    decode_with_hrp(s, KnownHrp::Mainnet)
+}
+
+/// Decodes a Groestlcoin testnet segwit address, one with the human-readable part "tgrs".
+///
+/// Like [`decode`] but a valid address for any other human-readable part, including mainnet and
+/// regtest addresses, fails with [`SegwitHrpstringError::WrongHrp`].
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_testnet(s: &str) -> Result<(Fe32, Vec<u8>), DecodeError> {
+    decode_with_hrp(s, KnownHrp::Testnets)
+}
+
+/// Decodes a segwit address, checking that its human-readable part is that of `expected`.
+#[cfg(feature = "alloc")]
+fn decode_with_hrp(s: &str, expected: KnownHrp) -> Result<(Fe32, Vec<u8>), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    if segwit.hrp() != expected.to_hrp() {
+        let got = KnownHrp::from_hrp(segwit.hrp());
+        return Err(DecodeError(SegwitHrpstringError::WrongHrp { expected, got }));
+    }
+    Ok((segwit.witness_version(), segwit.byte_iter().collect::<Vec<u8>>()))
+}
+
+/// Decodes a segwit address, returning the witness program in a buffer that is zeroized on drop.
+///
+/// Like [`decode`] but for witness programs that should not linger in memory. The program is
+/// written directly into a buffer of exactly the right size, so it is never reallocated. Nothing
+/// else is wiped: not the input string, nor the few bytes of conversion and checksum state that
+/// decoding keeps on the stack.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_0};
+///
+/// let address = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let (_, version, program) = segwit::decode_zeroizing(address).expect("valid address");
+/// assert_eq!(version, VERSION_0);
+/// assert_eq!(program.len(), 20);
+/// ```
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+pub fn decode_zeroizing(s: &str) -> Result<(Hrp, Fe32, zeroize::Zeroizing<Vec<u8>>), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    let iter = segwit.byte_iter();
+    let mut program = zeroize::Zeroizing::new(Vec::with_capacity(iter.len()));
+    program.extend(iter);
+    Ok((segwit.hrp(), segwit.witness_version(), program))
+}
+
+/// Decodes a segwit address into `out`.
+///
+/// Like [`decode`] but the witness program is written to `out`, which is cleared first, so that a
+/// single vector can be reused across decodes. Allocates only if `out` has less capacity than the
+/// program, [`MAX_WITNESS_PROGRAM_LENGTH`] is always enough. On error `out` is left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_1};
+/// let mut program = Vec::with_capacity(segwit::MAX_WITNESS_PROGRAM_LENGTH);
+///
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+/// let (_hrp, version) = segwit::decode_into(address, &mut program).expect("valid address");
+/// assert_eq!(version, VERSION_1);
+/// assert_eq!(program.len(), 32);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_into(s: &str, out: &mut Vec<u8>) -> Result<(Hrp, Fe32), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    let iter = segwit.byte_iter();
+    out.clear();
+    out.reserve_exact(iter.len());
+    out.extend(iter);
+    Ok((segwit.hrp(), segwit.witness_version()))
+}
+
+/// Decodes a segwit address, returning the witness program as a boxed slice.
+///
+/// Like [`decode`] but the program has no excess capacity, for storing in long lived structs.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+/// let (_hrp, _version, program) = segwit::decode_boxed(address).expect("valid address");
+/// assert_eq!(program.len(), 32);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_boxed(s: &str) -> Result<(Hrp, Fe32, Box<[u8]>), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    let program = segwit.byte_iter().collect::<Vec<u8>>().into_boxed_slice();
+    Ok((segwit.hrp(), segwit.witness_version(), program))
+}
+
+/// Decodes a segwit address, returning the witness version and program as a [`WitnessProgram`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_1};
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+/// let (hrp, program) = segwit::decode_witness_program(address).expect("valid address");
+/// assert_eq!(program.version(), VERSION_1);
+/// assert_eq!(program.to_address(&hrp), address);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_witness_program(s: &str) -> Result<(Hrp, WitnessProgram), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    Ok((segwit.hrp(), WitnessProgram::from(&segwit)))
+}
+
+/// Returns whether `a` and `b` are the same segwit address.
+///
+/// Two addresses are the same if they have the same human-readable part, witness version and
+/// witness program, so strings that differ only in case are the same address. Neither string is
+/// copied, the programs are compared as they are decoded.
+///
+/// # Errors
+///
+/// If either string is not a valid segwit address.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+/// let lower = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let upper = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+/// assert!(segwit::same_address(lower, upper).expect("valid addresses"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn same_address(a: &str, b: &str) -> Result<bool, DecodeError> {
+    let a = SegwitHrpstring::new(a)?;
+    let b = SegwitHrpstring::new(b)?;
+    Ok(a.hrp() == b.hrp()
+        && a.witness_version() == b.witness_version()
+        && a.byte_iter().eq(b.byte_iter()))
+}
+
+/// Compares two segwit addresses by what they pay to, ignoring case.
+///
+/// Addresses are ordered by their lowercase human-readable part, then witness version, then
+/// witness program bytes, the same order as [`CompactAddress`] and, within a network,
+/// [`WitnessProgram`]. This is a total order suitable for sorted address indexes, and will not
+/// change between versions of this crate.
+///
+/// The order differs from lexicographic order of the strings, which depends on case and compares
+/// the base32 encoding and checksum rather than the program bytes.
+///
+/// # Examples
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use bech32grs::segwit;
+///
+/// let v0 = "grs1q4w46h2at4w46h2at4w46h2at4w46h2ath9clv3";
+/// let v1 = "GRS1P4W46H2AT4W46H2AT4W46H2AT4W46H2ATF8LCYC";
+/// assert_eq!(segwit::cmp_addresses(v0, v1), Ok(Ordering::Less));
+/// assert_eq!(segwit::cmp_addresses(v1, &v1.to_lowercase()), Ok(Ordering::Equal));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn cmp_addresses(a: &str, b: &str) -> Result<Ordering, DecodeError> {
+    let a = SegwitHrpstring::new(a)?;
+    let b = SegwitHrpstring::new(b)?;
+    Ok(a.hrp()
+        .cmp(&b.hrp())
+        .then_with(|| a.witness_version().to_u8().cmp(&b.witness_version().to_u8()))
+        .then_with(|| a.byte_iter().cmp(b.byte_iter())))
+}
+
+/// Classifies `s` against the network it is `expected` to be an address for.
+///
+/// The string is fully validated under the human-readable part it contains, so a valid address
+/// for another network is told apart from an invalid string. This lets a user interface say "this
+/// is a testnet address" rather than just rejecting it.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, ForeignAddressKind, KnownHrp};
+///
+/// let testnet = "tgrs1q09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09uhj7te09us84c5ts";
+/// assert_eq!(
+///     segwit::classify_foreign(testnet, KnownHrp::Mainnet),
+///     ForeignAddressKind::WrongNetwork(KnownHrp::Testnets),
+/// );
+/// assert_eq!(
+///     segwit::classify_foreign(testnet, KnownHrp::Testnets),
+///     ForeignAddressKind::CorrectNetwork,
+/// );
+/// ```
+pub fn classify_foreign(s: &str, expected: KnownHrp) -> ForeignAddressKind {
+    let segwit = match SegwitHrpstring::new(s) {
+        Ok(segwit) => segwit,
+        Err(e) => return ForeignAddressKind::Invalid(e),
+    };
+    match KnownHrp::from_hrp(segwit.hrp()) {
+        Some(known) if known == expected => ForeignAddressKind::CorrectNetwork,
+        Some(known) => ForeignAddressKind::WrongNetwork(known),
+        None => ForeignAddressKind::UnknownHrpValidChecksum(segwit.hrp()),
+    }
+}
+
+/// Lists the positions at which `a` and `b` differ, ignoring case.
+///
+/// Returns `None` if the strings have a different number of characters, otherwise the character
+/// index and the differing characters of `a` and `b`, as they appear in the input, and whether they
+/// are a likely misreading of one another (see [`Fe32::visual_confusability`]). This is purely
+/// lexical, neither string needs to be a valid address, and is intended for warning users when an
+/// entered address differs from an expected one. Characters not in the bech32 alphabet are never
+/// flagged as confusable.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+///
+/// let expected = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let entered = "grs1qar05rrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let diff = segwit::visual_diff(expected, entered).expect("same length");
+/// assert_eq!(diff, vec![(8, 's', '5', true)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn visual_diff(a: &str, b: &str) -> Option<Vec<(usize, char, char, bool)>> {
+    if a.chars().count() != b.chars().count() {
+        return None;
+    }
+    let confusable = |ca, cb| match (Fe32::from_char(ca), Fe32::from_char(cb)) {
+        (Ok(fa), Ok(fb)) => fa.visual_confusability(fb),
+        _ => false,
+    };
+    let diff = a
+        .chars()
+        .zip(b.chars())
+        .enumerate()
+        .filter(|&(_, (ca, cb))| !ca.eq_ignore_ascii_case(&cb))
+        .map(|(i, (ca, cb))| (i, ca, cb, confusable(ca, cb)))
+        .collect();
+    Some(diff)
+}
+
+/// Decodes a segwit address, also returning the case it was encoded in.
+///
+/// Like [`decode`] but the returned [`Hrp`] keeps the case of `s`, so [`Hrp::as_str`] matches the
+/// input, and re-encoding with [`encode_with_case`] returns `s` exactly.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, Case};
+/// let address = "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5";
+/// let (hrp, version, program, case) = segwit::decode_preserving_case(address).expect("valid address");
+/// assert_eq!(hrp.as_str(), "GRS");
+/// assert_eq!(case, Case::Upper);
+/// assert_eq!(segwit::encode_with_case(case, hrp, version, &program).unwrap(), address);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_preserving_case(s: &str) -> Result<(Hrp, Fe32, Vec<u8>, Case), DecodeError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    let program = segwit.byte_iter().collect::<Vec<u8>>();
+    Ok((segwit.hrp(), segwit.witness_version(), program, Case::of(s)))
+}
+
+/// Decodes a segwit address with the rules of `profile`.
+///
+/// Like [`decode`] but the maximum length, accepted case and so on are taken from `profile`,
+/// decoding with [`DecodeProfile::BIP350_SEGWIT`] is the same as [`decode`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{segwit, DecodeProfile};
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8F5";
+/// assert!(segwit::decode_with_profile(address, DecodeProfile::BIP350_SEGWIT).is_err());
+/// assert!(segwit::decode_with_profile(address, DecodeProfile::PERMISSIVE).is_ok());
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_with_profile(
+    s: &str,
+    profile: DecodeProfile,
+) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
+    let segwit = SegwitHrpstring::new_with_profile(s, profile)?;
+    Ok((segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect::<Vec<u8>>()))
+}
+
+/// Decodes a segwit address that must be uppercase, as scanned from a QR code.
+///
+/// Like [`decode`] but lowercase letters anywhere in `s` are an error, digits and symbols have no
+/// case and are accepted. QR codes hold addresses in uppercase, so a lowercase letter in scanned
+/// input means the code was generated in the wrong mode or did not come from a QR code at all.
+///
+/// # Errors
+///
+/// [`CharError::NotUppercase`](crate::primitives::decode::CharError::NotUppercase) with the
+/// position of the first lowercase letter, otherwise the same errors as [`decode`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+///
+/// let address = "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5";
+/// let (hrp, _version, program) = segwit::decode_upper(address).expect("valid address");
+/// assert_eq!(hrp.as_str(), "GRS");
+/// assert_eq!(program.len(), 20);
+///
+/// let err = segwit::decode_upper(&address.to_lowercase()).unwrap_err();
+/// assert_eq!(err.code(), "E_UPPERCASE_REQUIRED");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_upper(s: &str) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
+    let profile = DecodeProfile { case: CasePolicy::RequireUpper, ..DecodeProfile::BIP350_SEGWIT };
+    decode_with_profile(s, profile)
+}
+
+/// Decodes a segwit address that may be up to `max` characters long.
+///
+/// Like [`decode`] but with a maximum length of `max` instead of [`MAX_STRING_LENGTH`], see
+/// [`SegwitHrpstring::new_with_max_length`] for the effect on error detection. A `max` above the
+/// code length of [`Bech32m`] (1023 characters) fails with
+/// [`SegwitHrpstringError::InvalidMaxLength`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+///
+/// // A 95 character address with a 55 character HRP.
+/// let address = "asidechainwithaverylonghumanreadablepartforitsaddresses1qw508d6qejxtdg4y5r3zarvary0c5xw7k5hv5xn";
+/// assert!(segwit::decode(address).is_err());
+/// let (hrp, _, program) = segwit::decode_with_max_length(address, 100).expect("valid address");
+/// assert_eq!(hrp.len(), 55);
+/// assert_eq!(program.len(), 20);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_with_max_length(s: &str, max: usize) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
+    let segwit = SegwitHrpstring::new_with_max_length(s, max)?;
+    Ok((segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect::<Vec<u8>>()))
+}
+
+/// Decodes a segwit address, including part of the address in the error.
+///
+/// Like [`decode`] but the returned error includes a truncated copy of `s`, which is useful when
+/// logging errors for many addresses.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+/// let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f6";
+/// let err = segwit::decode_verbose(address).unwrap_err();
+/// assert_eq!(err.input(), "grs1py3m7vwnghyn...jqhnu8f6");
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_verbose(s: &str) -> Result<(Hrp, Fe32, Vec<u8>), DecodeErrorWithInput<DecodeError>> {
+    decode(s).map_err(|e| DecodeErrorWithInput::new(s, e))
+}
+
 /// Encodes a segwit address.
 ///
 /// Does validity checks on the `witness_version`, length checks on the `witness_program`, and
@@ -105,14 +680,35 @@ pub fn encode(
     hrp: Hrp,
     witness_version: Fe32,
     witness_program: &[u8],
+) -> Result<String, EncodeError> {
+    encode_with_case(Case::Lower, hrp, witness_version, witness_program)
+}
+
+/// Encodes a segwit address using `case` for all characters.
+///
+/// Like [`encode`] but can also encode the uppercase form used in QR codes, the partner of
+/// [`decode_preserving_case`]. To write to a formatter instead use [`encode_lower_to_fmt_unchecked`]
+/// or [`encode_upper_to_fmt_unchecked`].
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_with_case(
+    case: Case,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
 ) -> Result<String, EncodeError> {
     segwit::validate_witness_version(witness_version)?;
     segwit::validate_witness_program_length(witness_program.len(), witness_version)?;
 
-    let _ = encoded_length(hrp, witness_version, witness_program)?;
+    let len = encoded_length(hrp, witness_version, witness_program)?;
 
-    let mut buf = String::new();
-    encode_to_fmt_unchecked(&mut buf, hrp, witness_version, witness_program)?;
+    let mut buf = String::with_capacity(len);
+    match case {
+        Case::Lower =>
+            encode_lower_to_fmt_unchecked(&mut buf, hrp, witness_version, witness_program)?,
+        Case::Upper =>
+            encode_upper_to_fmt_unchecked(&mut buf, hrp, witness_version, witness_program)?,
+    }
     Ok(buf)
 }
 
@@ -136,10 +732,48 @@ pub fn encode_v1(hrp: Hrp, witness_program: &[u8]) -> Result<String, EncodeError
     encode(hrp, VERSION_1, witness_program)
 }
 
+/// Encodes a Groestlcoin mainnet segwit address, with the human-readable part "grs".
+///
+/// The same as [`encode`] with [`hrp::GRS`](crate::hrp::GRS), see [`decode_mainnet`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_1};
+///
+/// let address = segwit::encode_mainnet(VERSION_1, &[0xab; 32]).expect("valid program");
+/// assert!(address.starts_with("grs1p"));
+/// assert_eq!(segwit::decode_mainnet(&address).unwrap(), (VERSION_1, vec![0xab; 32]));
+/// assert!(segwit::decode_testnet(&address).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_mainnet(
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<String, EncodeError> {
+    encode(KnownHrp::Mainnet.to_hrp(), witness_version, witness_program)
+}
+
+/// Encodes a Groestlcoin testnet segwit address, with the human-readable part "tgrs".
+///
+/// The same as [`encode`] with [`hrp::TGRS`](crate::hrp::TGRS), see [`decode_testnet`].
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode_testnet(
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<String, EncodeError> {
+    encode(KnownHrp::Testnets.to_hrp(), witness_version, witness_program)
+}
+
 /// Encodes a segwit address to a writer ([`fmt::Write`]) using lowercase characters.
 ///
 /// There are no guarantees that the written string is a valid segwit address unless all the
 /// parameters are valid. See the body of `encode()` to see the validity checks required.
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the address was written before the error is unspecified.
 #[inline]
 pub fn encode_to_fmt_unchecked<W: fmt::Write>(
     fmt: &mut W,
@@ -154,37 +788,18 @@ pub fn encode_to_fmt_unchecked<W: fmt::Write>(
 ///
 /// There are no guarantees that the written string is a valid segwit address unless all the
 /// parameters are valid. See the body of `encode()` to see the validity checks required.
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the address was written before the error is unspecified.
 pub fn encode_lower_to_fmt_unchecked<W: fmt::Write>(
     fmt: &mut W,
     hrp: Hrp,
     witness_version: Fe32,
     witness_program: &[u8],
 ) -> fmt::Result {
-    let mut buf = [0u8; MAX_STRING_LENGTH];
-    let mut pos = 0;
-
-    let iter = witness_program.iter().copied().bytes_to_fes();
-    match witness_version {
-        VERSION_0 => {
-            let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src;
-                pos += 1;
-            });
-        }
-        version => {
-            let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src;
-                pos += 1;
-            });
-        }
-    }
-
-    let s = core::str::from_utf8(&buf[..pos]).expect("we only write ASCII");
-    fmt.write_str(s)?;
-
-    Ok(())
+    encode_to_fmt_with_case_unchecked(fmt, hrp, witness_version, witness_program, |c| {
+        c.to_ascii_lowercase()
+    })
 }
 
 /// Encodes a segwit address to a writer ([`fmt::Write`]) using uppercase characters.
@@ -193,6 +808,9 @@ pub fn encode_lower_to_fmt_unchecked<W: fmt::Write>(
 ///
 /// There are no guarantees that the written string is a valid segwit address unless all the
 /// parameters are valid. See the body of `encode()` to see the validity checks required.
+///
+/// If `fmt` returns an error, this function returns it immediately without writing any more
+/// characters. How much of the address was written before the error is unspecified.
 #[inline]
 pub fn encode_upper_to_fmt_unchecked<W: fmt::Write>(
     fmt: &mut W,
@@ -200,30 +818,52 @@ pub fn encode_upper_to_fmt_unchecked<W: fmt::Write>(
     witness_version: Fe32,
     witness_program: &[u8],
 ) -> fmt::Result {
-    let mut buf = [0u8; MAX_STRING_LENGTH];
-    let mut pos = 0;
+    encode_to_fmt_with_case_unchecked(fmt, hrp, witness_version, witness_program, |c| {
+        c.to_ascii_uppercase()
+    })
+}
+
+/// Encodes a segwit address to a writer ([`fmt::Write`]) using uppercase characters.
+///
+/// The `encode_to_fmt_unchecked_uppercase` of `0.10.0-alpha`, which took the `hrp` by reference.
+#[deprecated(note = "use `encode_upper_to_fmt_unchecked` instead")]
+#[inline]
+pub fn encode_to_fmt_unchecked_uppercase<W: fmt::Write>(
+    fmt: &mut W,
+    hrp: &Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> fmt::Result {
+    encode_upper_to_fmt_unchecked(fmt, *hrp, witness_version, witness_program)
+}
 
-    let iter = witness_program.iter().copied().bytes_to_fes();
+/// Encodes a segwit address to a writer ([`fmt::Write`]), passing each character through `case`.
+///
+/// Shared by the lowercase and uppercase functions so that they cannot diverge, in particular in
+/// stopping at the first error returned by `fmt`.
+fn encode_to_fmt_with_case_unchecked<W: fmt::Write, F: Fn(char) -> char>(
+    fmt: &mut W,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+    case: F,
+) -> fmt::Result {
+    let iter = witness_program.iter().bytes_to_fes();
     match witness_version {
         VERSION_0 => {
-            let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src.to_ascii_uppercase();
-                pos += 1;
-            });
+            let chars = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).chars();
+            for c in chars {
+                fmt.write_char(case(c))?;
+            }
         }
         version => {
-            let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src.to_ascii_uppercase();
-                pos += 1;
-            });
+            let chars = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).chars();
+            for c in chars {
+                fmt.write_char(case(c))?;
+            }
         }
     }
 
-    let s = core::str::from_utf8(&buf[..pos]).expect("we only write ASCII");
-    fmt.write_str(s)?;
-
     Ok(())
 }
 
@@ -258,29 +898,18 @@ pub fn encode_lower_to_writer_unchecked<W: std::io::Write>(
     witness_version: Fe32,
     witness_program: &[u8],
 ) -> std::io::Result<()> {
-    let mut buf = [0u8; MAX_STRING_LENGTH];
-    let mut pos = 0;
-
-    let iter = witness_program.iter().copied().bytes_to_fes();
+    let iter = witness_program.iter().bytes_to_fes();
     match witness_version {
         VERSION_0 => {
             let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src;
-                pos += 1;
-            });
+            crate::write_chunked(bytes, |chunk| w.write_all(chunk))?;
         }
         version => {
             let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src;
-                pos += 1;
-            });
+            crate::write_chunked(bytes, |chunk| w.write_all(chunk))?;
         }
     }
 
-    w.write_all(&buf[..pos])?;
-
     Ok(())
 }
 
@@ -300,30 +929,168 @@ pub fn encode_upper_to_writer_unchecked<W: std::io::Write>(
     witness_version: Fe32,
     witness_program: &[u8],
 ) -> std::io::Result<()> {
-    let mut buf = [0u8; MAX_STRING_LENGTH];
-    let mut pos = 0;
+    let iter = witness_program.iter().bytes_to_fes();
+    match witness_version {
+        VERSION_0 => {
+            let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
+            crate::write_chunked(bytes.map(|b| b.to_ascii_uppercase()), |chunk| {
+                w.write_all(chunk)
+            })?;
+        }
+        version => {
+            let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
+            crate::write_chunked(bytes.map(|b| b.to_ascii_uppercase()), |chunk| {
+                w.write_all(chunk)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes a segwit address to an [`embedded_io::Write`] writer using lowercase characters.
+///
+/// Does validity checks on the `witness_version`, length checks on the `witness_program`, and
+/// checks the total encoded string length, all before anything is written.
+///
+/// Errors from the writer are returned unchanged in [`EncodeEmbeddedError::Write`].
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_to_embedded_writer<W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<(), EncodeEmbeddedError<W::Error>> {
+    segwit::validate_witness_version(witness_version)?;
+    segwit::validate_witness_program_length(witness_program.len(), witness_version)?;
+    let _ = encoded_length(hrp, witness_version, witness_program)?;
 
-    let iter = witness_program.iter().copied().bytes_to_fes();
+    encode_lower_to_embedded_writer_unchecked(w, hrp, witness_version, witness_program)
+        .map_err(EncodeEmbeddedError::Write)
+}
+
+/// Encodes a segwit address to an [`embedded_io::Write`] writer using lowercase characters.
+///
+/// There are no guarantees that the written string is a valid segwit address unless all the
+/// parameters are valid. See the body of `encode()` to see the validity checks required.
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_to_embedded_writer_unchecked<W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<(), W::Error> {
+    encode_lower_to_embedded_writer_unchecked(w, hrp, witness_version, witness_program)
+}
+
+/// Encodes a segwit address to an [`embedded_io::Write`] writer using lowercase characters.
+///
+/// There are no guarantees that the written string is a valid segwit address unless all the
+/// parameters are valid. See the body of `encode()` to see the validity checks required.
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_lower_to_embedded_writer_unchecked<W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<(), W::Error> {
+    let iter = witness_program.iter().bytes_to_fes();
     match witness_version {
         VERSION_0 => {
             let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src.to_ascii_uppercase();
-                pos += 1;
-            });
+            crate::write_chunked(bytes, |chunk| w.write_all(chunk))
         }
         version => {
             let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
-            buf.iter_mut().zip(bytes).for_each(|(dst, src)| {
-                *dst = src.to_ascii_uppercase();
-                pos += 1;
-            });
+            crate::write_chunked(bytes, |chunk| w.write_all(chunk))
         }
     }
+}
 
-    w.write_all(&buf[..pos])?;
+/// Encodes a segwit address to an [`embedded_io::Write`] writer using uppercase characters.
+///
+/// This is provided for use when creating QR codes.
+///
+/// There are no guarantees that the written string is a valid segwit address unless all the
+/// parameters are valid. See the body of `encode()` to see the validity checks required.
+#[cfg(feature = "embedded-io")]
+#[inline]
+pub fn encode_upper_to_embedded_writer_unchecked<W: embedded_io::Write>(
+    w: &mut W,
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+) -> Result<(), W::Error> {
+    let iter = witness_program.iter().bytes_to_fes();
+    match witness_version {
+        VERSION_0 => {
+            let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
+            crate::write_chunked(bytes.map(|b| b.to_ascii_uppercase()), |chunk| w.write_all(chunk))
+        }
+        version => {
+            let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
+            crate::write_chunked(bytes.map(|b| b.to_ascii_uppercase()), |chunk| w.write_all(chunk))
+        }
+    }
+}
 
-    Ok(())
+/// Encodes a segwit address using lowercase characters, writing the ASCII bytes into `out`.
+///
+/// Does validity checks on the `witness_version`, length checks on the `witness_program`, and
+/// checks the total encoded string length, all before anything is written. If `out` is too small
+/// it is left untouched.
+///
+/// For targets with a byte buffer but no [`fmt::Write`] implementation, this does not use the
+/// formatting machinery at all.
+///
+/// # Returns
+///
+/// The number of bytes written, the address is `out[..len]`.
+///
+/// # Errors
+///
+/// Returns [`EncodeSliceError::Capacity`], holding the required length, if the address does not
+/// fit in `out`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::{hrp, segwit};
+///
+/// let mut buf = [0_u8; segwit::MAX_STRING_LENGTH];
+/// let len = segwit::encode_to_slice(hrp::GRS, segwit::VERSION_1, &[0xab; 32], &mut buf)
+///     .expect("valid address");
+/// assert_eq!(&buf[..len], b"grs1p4w46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w4s5je9vk");
+/// ```
+pub fn encode_to_slice(
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: &[u8],
+    out: &mut [u8],
+) -> Result<usize, EncodeSliceError> {
+    segwit::validate_witness_version(witness_version)?;
+    segwit::validate_witness_program_length(witness_program.len(), witness_version)?;
+
+    let len = encoded_length(hrp, witness_version, witness_program)?;
+    let capacity = out.len();
+    let out = out.get_mut(..len).ok_or(CapacityError { required: len, capacity })?;
+
+    let iter = witness_program.iter().bytes_to_fes();
+    match witness_version {
+        VERSION_0 => {
+            let bytes = iter.with_checksum::<Bech32>(&hrp).with_witness_version(VERSION_0).bytes();
+            out.iter_mut().zip(bytes).for_each(|(slot, b)| *slot = b);
+        }
+        version => {
+            let bytes = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).bytes();
+            out.iter_mut().zip(bytes).for_each(|(slot, b)| *slot = b);
+        }
+    }
+
+    Ok(len)
 }
 
 /// Returns the length of the address after encoding HRP, witness version and program.
@@ -349,10 +1116,25 @@ pub fn encoded_length(
 
 /// An error while decoding a segwit address.
 #[cfg(feature = "alloc")]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub struct DecodeError(pub SegwitHrpstringError);
 
+#[cfg(feature = "alloc")]
+impl DecodeError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code { self.0.error_code() }
+
+    /// Returns the coarse category of this error, see [`SegwitHrpstringError::kind`].
+    #[inline]
+    pub fn kind(&self) -> SegwitErrorKind { self.0.kind() }
+}
+
 #[cfg(feature = "alloc")]
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -360,9 +1142,19 @@ impl fmt::Display for DecodeError {
     }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for DecodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> { Some(&self.0) }
+}
+
+/// Converts to an [`std::io::Error`] of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// The original error can be recovered with [`get_ref`](std::io::Error::get_ref) and a downcast.
 #[cfg(feature = "std")]
-impl std::error::Error for DecodeError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { Some(&self.0) }
+impl From<DecodeError> for std::io::Error {
+    #[inline]
+    fn from(e: DecodeError) -> Self { std::io::Error::new(std::io::ErrorKind::InvalidData, e) }
 }
 
 #[cfg(feature = "alloc")]
@@ -372,7 +1164,7 @@ impl From<SegwitHrpstringError> for DecodeError {
 }
 
 /// An error while constructing a [`SegwitHrpstring`] type.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 #[cfg(feature = "alloc")]
 pub enum EncodeError {
@@ -386,6 +1178,25 @@ pub enum EncodeError {
     Fmt(fmt::Error),
 }
 
+#[cfg(feature = "alloc")]
+impl EncodeError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeError::*;
+
+        match *self {
+            WitnessVersion(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+            TooLong(ref e) => e.error_code(),
+            Fmt(_) => Code::Fmt,
+        }
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl fmt::Display for EncodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -400,10 +1211,25 @@ impl fmt::Display for EncodeError {
     }
 }
 
-#[cfg(feature = "std")]
 #[cfg(feature = "alloc")]
-impl std::error::Error for EncodeError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+#[cfg(feature = "defmt")]
+impl defmt::Format for EncodeError {
+    fn format(&self, f: defmt::Formatter) {
+        use EncodeError::*;
+
+        match *self {
+            WitnessVersion(ref e) => defmt::write!(f, "witness version: {}", e),
+            WitnessLength(ref e) => defmt::write!(f, "witness length: {}", e),
+            TooLong(ref e) => defmt::write!(f, "encode error: {}", e),
+            Fmt(_) => defmt::write!(f, "writing to formatter failed"),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for EncodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         use EncodeError::*;
 
         match *self {
@@ -415,6 +1241,25 @@ impl std::error::Error for EncodeError {
     }
 }
 
+/// Converts to an [`std::io::Error`], the original error can be recovered with
+/// [`get_ref`](std::io::Error::get_ref) and a downcast.
+///
+/// An invalid witness version or program, or input that is too long to encode, is
+/// [`InvalidInput`](std::io::ErrorKind::InvalidInput), a failed write is
+/// [`Other`](std::io::ErrorKind::Other).
+#[cfg(feature = "std")]
+impl From<EncodeError> for std::io::Error {
+    fn from(e: EncodeError) -> Self {
+        use EncodeError::*;
+
+        let kind = match e {
+            WitnessVersion(_) | WitnessLength(_) | TooLong(_) => std::io::ErrorKind::InvalidInput,
+            Fmt(_) => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl From<InvalidWitnessVersionError> for EncodeError {
     #[inline]
@@ -439,10 +1284,185 @@ impl From<fmt::Error> for EncodeError {
     fn from(e: fmt::Error) -> Self { Self::Fmt(e) }
 }
 
+/// An error while encoding a segwit address to an [`embedded_io::Write`] writer.
+///
+/// Generic over the writer's error type so that it is not lost.
+#[cfg(feature = "embedded-io")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EncodeEmbeddedError<E> {
+    /// Invalid witness version (must be 0-16 inclusive).
+    WitnessVersion(InvalidWitnessVersionError),
+    /// Invalid witness length.
+    WitnessLength(WitnessLengthError),
+    /// Encoding HRP, witver, and program into a bech32 string exceeds maximum allowed.
+    TooLong(SegwitCodeLengthError),
+    /// Encode to writer failed.
+    Write(E),
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> EncodeEmbeddedError<E> {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            WitnessVersion(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+            TooLong(ref e) => e.error_code(),
+            Write(_) => Code::Write,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E: fmt::Debug> fmt::Display for EncodeEmbeddedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            WitnessVersion(ref e) => write_err!(f, "witness version"; e),
+            WitnessLength(ref e) => write_err!(f, "witness length"; e),
+            TooLong(ref e) => write_err!(f, "encode error"; e),
+            Write(ref e) => write!(f, "encode to writer failed: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: StdError + 'static> StdError for EncodeEmbeddedError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use EncodeEmbeddedError::*;
+
+        match *self {
+            WitnessVersion(ref e) => Some(e),
+            WitnessLength(ref e) => Some(e),
+            TooLong(ref e) => Some(e),
+            Write(ref e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> From<InvalidWitnessVersionError> for EncodeEmbeddedError<E> {
+    #[inline]
+    fn from(e: InvalidWitnessVersionError) -> Self { Self::WitnessVersion(e) }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> From<WitnessLengthError> for EncodeEmbeddedError<E> {
+    #[inline]
+    fn from(e: WitnessLengthError) -> Self { Self::WitnessLength(e) }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> From<SegwitCodeLengthError> for EncodeEmbeddedError<E> {
+    #[inline]
+    fn from(e: SegwitCodeLengthError) -> Self { Self::TooLong(e) }
+}
+
+/// An error while encoding a segwit address into a byte slice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EncodeSliceError {
+    /// Invalid witness version (must be 0-16 inclusive).
+    WitnessVersion(InvalidWitnessVersionError),
+    /// Invalid witness length.
+    WitnessLength(WitnessLengthError),
+    /// Encoding HRP, witver, and program into a bech32 string exceeds maximum allowed.
+    TooLong(SegwitCodeLengthError),
+    /// The slice is too small to hold the address.
+    Capacity(CapacityError),
+}
+
+impl EncodeSliceError {
+    /// Returns the length of slice required to hold the address, if it was too small.
+    #[inline]
+    pub fn required_len(&self) -> Option<usize> {
+        match *self {
+            EncodeSliceError::Capacity(ref e) => Some(e.required),
+            _ => None,
+        }
+    }
+
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use EncodeSliceError::*;
+
+        match *self {
+            WitnessVersion(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+            TooLong(ref e) => e.error_code(),
+            Capacity(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for EncodeSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeSliceError::*;
+
+        match *self {
+            WitnessVersion(ref e) => write_err!(f, "witness version"; e),
+            WitnessLength(ref e) => write_err!(f, "witness length"; e),
+            TooLong(ref e) => write_err!(f, "encode error"; e),
+            Capacity(ref e) => write_err!(f, "encode to slice failed"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for EncodeSliceError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use EncodeSliceError::*;
+
+        match *self {
+            WitnessVersion(ref e) => Some(e),
+            WitnessLength(ref e) => Some(e),
+            TooLong(ref e) => Some(e),
+            Capacity(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<InvalidWitnessVersionError> for EncodeSliceError {
+    #[inline]
+    fn from(e: InvalidWitnessVersionError) -> Self { Self::WitnessVersion(e) }
+}
+
+impl From<WitnessLengthError> for EncodeSliceError {
+    #[inline]
+    fn from(e: WitnessLengthError) -> Self { Self::WitnessLength(e) }
+}
+
+impl From<SegwitCodeLengthError> for EncodeSliceError {
+    #[inline]
+    fn from(e: SegwitCodeLengthError) -> Self { Self::TooLong(e) }
+}
+
+impl From<CapacityError> for EncodeSliceError {
+    #[inline]
+    fn from(e: CapacityError) -> Self { Self::Capacity(e) }
+}
+
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
+    use core::convert::TryFrom;
+
     use super::*;
-    use crate::primitives::decode::{SegwitCodeLengthError, SegwitHrpstringError};
+    use crate::primitives::decode::{CharError, SegwitCodeLengthError, SegwitHrpstringError};
     use crate::primitives::hrp;
 
     #[test]
@@ -456,12 +1476,225 @@ mod tests {
         ];
 
         for address in addresses {
-            let (hrp, version, program) = decode(address).expect("failed to decode valid address");
+            let Decoded { hrp, version, program } =
+                decode_struct(address).expect("failed to decode valid address");
             let encoded = encode(hrp, version, &program).expect("failed to encode address");
             assert_eq!(encoded, address);
         }
     }
 
+    #[test]
+    fn validate_matches_decode() {
+        let addresses = [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "GRS1P0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQDDT7AT",
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q",
+            "grs1qar0srrr7xfkvy5l643lYdnw9re59gtzzncg65p",
+            "grs1zw508d6qejxtdg4y5r3zarvaryvg6kdaj",
+            "grs1",
+            "",
+        ];
+        for address in addresses.iter() {
+            let want = decode(address).map(drop).map_err(|e| e.0);
+            assert_eq!(validate(address), want, "{}", address);
+        }
+    }
+
+    #[test]
+    fn classify_foreign_address() {
+        let mainnet = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let testnet = "TGRS1Q09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09US84C5TS";
+
+        assert_eq!(
+            classify_foreign(mainnet, KnownHrp::Mainnet),
+            ForeignAddressKind::CorrectNetwork
+        );
+        assert_eq!(
+            classify_foreign(testnet, KnownHrp::Mainnet),
+            ForeignAddressKind::WrongNetwork(KnownHrp::Testnets)
+        );
+        assert_eq!(
+            classify_foreign(testnet, KnownHrp::Testnets),
+            ForeignAddressKind::CorrectNetwork
+        );
+        assert_eq!(
+            classify_foreign(mainnet, KnownHrp::Testnets),
+            ForeignAddressKind::WrongNetwork(KnownHrp::Mainnet)
+        );
+        assert_eq!(
+            classify_foreign(mainnet, KnownHrp::Regtest),
+            ForeignAddressKind::WrongNetwork(KnownHrp::Mainnet)
+        );
+    }
+
+    #[test]
+    fn classify_foreign_unknown_or_invalid() {
+        // A valid Bitcoin address.
+        let bitcoin = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert_eq!(
+            classify_foreign(bitcoin, KnownHrp::Mainnet),
+            ForeignAddressKind::UnknownHrpValidChecksum(Hrp::parse_unchecked("bc"))
+        );
+
+        // The mainnet address with a typo.
+        let typo = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+        assert!(matches!(
+            classify_foreign(typo, KnownHrp::Mainnet),
+            ForeignAddressKind::Invalid(SegwitHrpstringError::Checksum(_))
+        ));
+        // A typo under the testnet human-readable part is not a testnet address.
+        let typo = "tgrs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert!(matches!(
+            classify_foreign(typo, KnownHrp::Mainnet),
+            ForeignAddressKind::Invalid(SegwitHrpstringError::Checksum(_))
+        ));
+    }
+
+    #[test]
+    fn network_encode_decode() {
+        let mainnet = encode_mainnet(VERSION_0, &[0x11; 20]).unwrap();
+        assert_eq!(mainnet, encode(hrp::GRS, VERSION_0, &[0x11; 20]).unwrap());
+        assert_eq!(decode_mainnet(&mainnet).unwrap(), (VERSION_0, vec![0x11; 20]));
+        assert_eq!(decode_mainnet(&mainnet.to_uppercase()).unwrap(), (VERSION_0, vec![0x11; 20]));
+
+        let testnet = encode_testnet(VERSION_1, &[0x22; 32]).unwrap();
+        assert_eq!(testnet, encode(hrp::TGRS, VERSION_1, &[0x22; 32]).unwrap());
+        assert_eq!(decode_testnet(&testnet).unwrap(), (VERSION_1, vec![0x22; 32]));
+
+        let too_long = encode(hrp::GRS, VERSION_0, &[0x11; 21]);
+        assert_eq!(encode_mainnet(VERSION_0, &[0x11; 21]), too_long);
+    }
+
+    #[test]
+    fn network_decode_wrong_hrp() {
+        let mainnet = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let testnet = "TGRS1Q09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09UHJ7TE09US84C5TS";
+        let regtest = encode(hrp::GRSRT, VERSION_0, &[0x33; 20]).unwrap();
+        let wrong_hrp = |expected, got| SegwitHrpstringError::WrongHrp { expected, got };
+
+        let err = decode_mainnet(testnet).unwrap_err();
+        assert_eq!(err.0, wrong_hrp(KnownHrp::Mainnet, Some(KnownHrp::Testnets)));
+        let err = decode_mainnet(&regtest).unwrap_err();
+        assert_eq!(err.0, wrong_hrp(KnownHrp::Mainnet, Some(KnownHrp::Regtest)));
+        let err = decode_testnet(mainnet).unwrap_err();
+        assert_eq!(err.0, wrong_hrp(KnownHrp::Testnets, Some(KnownHrp::Mainnet)));
+        let err = decode_testnet(&regtest).unwrap_err();
+        assert_eq!(err.0, wrong_hrp(KnownHrp::Testnets, Some(KnownHrp::Regtest)));
+
+        // A valid Bitcoin address.
+        let bitcoin = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let err = decode_mainnet(bitcoin).unwrap_err();
+        assert_eq!(err.0, wrong_hrp(KnownHrp::Mainnet, None));
+
+        let err = decode_testnet(mainnet).unwrap_err();
+        assert_eq!(err.kind(), SegwitErrorKind::Hrp);
+        assert_eq!(err.code(), "E_WRONG_HRP");
+
+        // Invalid addresses fail as they do with `decode`, whatever their human-readable part.
+        let typo = "tgrs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(decode_mainnet(typo).unwrap_err(), decode(typo).unwrap_err());
+        assert_eq!(decode_testnet(typo).unwrap_err(), decode(typo).unwrap_err());
+    }
+
+    #[test]
+    fn roundtrip_preserving_case() {
+        let addresses = [
+            ("grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay", Case::Lower),
+            ("GRS1Q2S3RJWVAM9DT2FTT4SQXQJF3TWAV0GDX0K0Q2ETXFLX38C3X8TNSLKYLAY", Case::Upper),
+            ("GRS1PY3M7VWNGHYNE9GNVCJW82J7GQT2RAFGDMLMWMQNN3HVCMDM09RJQHNU8F5", Case::Upper),
+        ];
+
+        for &(address, want) in addresses.iter() {
+            let (hrp, version, program, case) =
+                decode_preserving_case(address).expect("failed to decode valid address");
+            assert_eq!(case, want);
+            assert_eq!(hrp.as_str(), &address[..3]);
+            let encoded = encode_with_case(case, hrp, version, &program).expect("valid address");
+            assert_eq!(encoded, address);
+            assert_eq!(encoded.capacity(), encoded.len());
+        }
+    }
+
+    #[test]
+    fn case_without_letters() {
+        assert_eq!(Case::of("?1ezyfcl"), Case::Lower);
+        assert_eq!(Case::of("?1EZYFCL"), Case::Upper);
+    }
+
+    fn not_uppercase(pos: usize) -> DecodeError {
+        DecodeError(SegwitHrpstringError::Unchecked(CharError::NotUppercase { pos }.into()))
+    }
+
+    #[test]
+    fn plausible_lengths_grs() {
+        // 3 + 1 + 1 + 6 and the length of the program, 2 to 40 bytes, in field elements.
+        let want = [
+            15, 16, 18, 19, 21, 23, 24, 26, 27, 29, 31, 32, 34, 35, 37, 39, 40, 42, 43, 45, 47, 48,
+            50, 51, 53, 55, 56, 58, 59, 61, 63, 64, 66, 67, 69, 71, 72, 74, 75,
+        ];
+        assert_eq!(plausible_lengths(&hrp::GRS), want);
+
+        // The BIP-350 valid vectors for the "grs" human-readable part.
+        let vectors = [
+            "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5",
+            "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+        ];
+        for address in vectors.iter() {
+            assert!(plausible_lengths(&hrp::GRS).contains(&address.len()));
+        }
+    }
+
+    #[test]
+    fn plausible_lengths_are_cut_at_max_length() {
+        for hrp_len in 1..=hrp::MAX_HRP_LENGTH {
+            let hrp = Hrp::parse(&"a".repeat(hrp_len)).unwrap();
+            let lengths = plausible_lengths(&hrp);
+            assert!(lengths.windows(2).all(|w| w[0] < w[1]));
+            assert!(lengths.iter().all(|&len| len <= MAX_STRING_LENGTH));
+            for len in MIN_WITNESS_PROGRAM_LENGTH..=MAX_WITNESS_PROGRAM_LENGTH {
+                let program = vec![0; len];
+                let fits = encode(hrp, VERSION_1, &program).map(|s| s.len());
+                assert_eq!(fits.as_ref().map_or(false, |len| lengths.contains(len)), fits.is_ok());
+            }
+        }
+        assert_eq!(plausible_lengths(&Hrp::parse(&"a".repeat(83)).unwrap()), [] as [usize; 0]);
+    }
+
+    #[test]
+    fn decode_upper_bip_350_vector() {
+        let upper = "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5";
+        let (hrp, version, program) = decode_upper(upper).expect("uppercase address");
+        assert_eq!((hrp, version, program), decode(upper).unwrap());
+
+        let lower = upper.to_lowercase();
+        let err = decode_upper(&lower).unwrap_err();
+        assert_eq!(err, not_uppercase(0));
+        assert!(decode(&lower).is_ok());
+
+        // Lowercase is reported even where decoding would fail on mixed case.
+        let err = decode_upper("GRS1QW508D6QEJXTDG4Y5R3ZARVARy0C5XW7K3K4SJ5").unwrap_err();
+        assert_eq!(err, not_uppercase(29));
+    }
+
+    #[test]
+    fn decode_upper_digits_only_hrp() {
+        let hrp = Hrp::parse("123").unwrap();
+        let address =
+            encode_with_case(Case::Upper, hrp, VERSION_1, &[0xab; 32]).expect("valid address");
+        assert!(address.starts_with("1231P"));
+
+        let (got, version, program) = decode_upper(&address).expect("hrp has no case");
+        assert_eq!(got, hrp);
+        assert_eq!(version, VERSION_1);
+        assert_eq!(program, [0xab; 32]);
+
+        // The first letter is after the separator.
+        let lower = address.to_lowercase();
+        let err = decode_upper(&lower).unwrap_err();
+        assert_eq!(err, not_uppercase(4));
+        assert_eq!(err.code(), "E_UPPERCASE_REQUIRED");
+    }
+
     fn witness_program() -> [u8; 20] {
         [
             0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3,
@@ -491,6 +1724,124 @@ mod tests {
         assert_eq!(address, want);
     }
 
+    /// A writer that fails any write which would take it over `limit` bytes.
+    struct FailingWriter {
+        buf: String,
+        limit: usize,
+        failed_calls: usize,
+    }
+
+    impl FailingWriter {
+        fn new(limit: usize) -> Self {
+            FailingWriter { buf: String::new(), limit, failed_calls: 0 }
+        }
+    }
+
+    impl fmt::Write for FailingWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if self.buf.len() + s.len() > self.limit {
+                self.failed_calls += 1;
+                return Err(fmt::Error);
+            }
+            self.buf.push_str(s);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn encode_to_fmt_stops_on_error() {
+        let program = witness_program();
+        for &version in [VERSION_0, VERSION_1].iter() {
+            let program = if version == VERSION_0 { &program[..] } else { &[0xab; 32][..] };
+            let lower = encode(hrp::GRS, version, program).unwrap();
+            let upper = lower.to_ascii_uppercase();
+
+            // Fail within the hrp, at the separator, at the witness version, within the program
+            // and within the checksum.
+            for &limit in [0, 2, 3, 4, 5, 20, lower.len() - 6, lower.len() - 1].iter() {
+                let mut w = FailingWriter::new(limit);
+                assert!(encode_lower_to_fmt_unchecked(&mut w, hrp::GRS, version, program).is_err());
+                assert_eq!(w.buf, lower[..limit]);
+                assert_eq!(w.failed_calls, 1);
+
+                let mut w = FailingWriter::new(limit);
+                assert!(encode_upper_to_fmt_unchecked(&mut w, hrp::GRS, version, program).is_err());
+                assert_eq!(w.buf, upper[..limit]);
+                assert_eq!(w.failed_calls, 1);
+            }
+
+            let mut w = FailingWriter::new(lower.len());
+            assert!(encode_to_fmt_unchecked(&mut w, hrp::GRS, version, program).is_ok());
+            assert_eq!(w.buf, lower);
+            assert_eq!(w.failed_calls, 0);
+        }
+    }
+
+    #[test]
+    fn decode_with_max_length_roundtrips() {
+        let hrp =
+            Hrp::parse("asidechainwithaverylonghumanreadablepartforitsaddresses").expect("valid");
+        let program = witness_program();
+
+        let mut address = String::new();
+        encode_to_fmt_unchecked(&mut address, hrp, VERSION_0, &program).expect("writing to string");
+        assert_eq!(address.len(), 95);
+
+        let decoded = decode_with_max_length(&address, 95).expect("valid address");
+        assert_eq!(decoded, (hrp, VERSION_0, program.to_vec()));
+        assert_eq!(decode(&address).unwrap_err().kind(), SegwitErrorKind::TooLong);
+    }
+
+    #[test]
+    fn encode_to_slice_matches_encode() {
+        let program = witness_program();
+        for &version in [VERSION_0, VERSION_1, Fe32::S].iter() {
+            let want = encode(hrp::GRS, version, &program).expect("valid address");
+
+            // Exact size.
+            let mut exact = vec![0_u8; want.len()];
+            let len = encode_to_slice(hrp::GRS, version, &program, &mut exact).expect("fits");
+            assert_eq!(len, want.len());
+            assert_eq!(exact, want.as_bytes());
+
+            // Oversize, the rest of the buffer is not touched.
+            let mut over = [0xff_u8; MAX_STRING_LENGTH];
+            let len = encode_to_slice(hrp::GRS, version, &program, &mut over).expect("fits");
+            assert_eq!(&over[..len], want.as_bytes());
+            assert!(over[len..].iter().all(|&b| b == 0xff));
+
+            // Undersize, nothing is written.
+            let mut under = vec![0xff_u8; want.len() - 1];
+            let err = encode_to_slice(hrp::GRS, version, &program, &mut under).unwrap_err();
+            assert_eq!(
+                err,
+                EncodeSliceError::Capacity(CapacityError {
+                    required: want.len(),
+                    capacity: want.len() - 1
+                })
+            );
+            assert_eq!(err.required_len(), Some(want.len()));
+            assert!(under.iter().all(|&b| b == 0xff));
+        }
+    }
+
+    #[test]
+    fn encode_to_slice_invalid_input() {
+        let mut buf = [0_u8; MAX_STRING_LENGTH];
+        assert!(matches!(
+            encode_to_slice(hrp::GRS, Fe32::_3, &witness_program(), &mut buf),
+            Err(EncodeSliceError::WitnessVersion(_))
+        ));
+        assert!(matches!(
+            encode_to_slice(hrp::GRS, VERSION_0, &[0xab; 21], &mut buf),
+            Err(EncodeSliceError::WitnessLength(_))
+        ));
+        assert_eq!(
+            encode_to_slice(hrp::GRS, VERSION_0, &[0xab; 21], &mut buf).unwrap_err().required_len(),
+            None
+        );
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn encode_lower_to_writer() {
@@ -531,6 +1882,36 @@ mod tests {
         assert_eq!(address, want);
     }
 
+    #[test]
+    fn hrp_length_boundary() {
+        let hrp = |len: usize| Hrp::parse(&"a".repeat(len)).unwrap();
+        let long = |hrp: Hrp, program: &[u8]| {
+            let len = hrp.len() + 1 + 1 + (program.len() * 8 + 4) / 5 + 6;
+            Err(EncodeError::TooLong(SegwitCodeLengthError(len)))
+        };
+        // The longest hrp for each witness program length, and one character longer.
+        let cases: [(usize, Fe32, &[u8]); 3] = [
+            (78, VERSION_1, &[0xab; 2]),
+            (50, VERSION_0, &[0xab; 20]),
+            (30, VERSION_1, &[0xab; 32]),
+        ];
+        for &(max, version, program) in &cases {
+            let s = encode(hrp(max), version, program).expect("longest hrp fits");
+            assert_eq!(s.len(), MAX_STRING_LENGTH);
+            assert_eq!(decode(&s).expect("valid address"), (hrp(max), version, program.to_vec()));
+            assert!(encode(hrp(max - 1), version, program).is_ok());
+
+            assert_eq!(encode(hrp(max + 1), version, program), long(hrp(max + 1), program));
+            assert_eq!(
+                encoded_length(hrp(max + 1), version, program),
+                Err(SegwitCodeLengthError(MAX_STRING_LENGTH + 1))
+            );
+        }
+        // No witness program is short enough for an 83 character hrp.
+        assert_eq!(encode(hrp(83), VERSION_1, &[0xab; 2]), long(hrp(83), &[0xab; 2]));
+        assert_eq!(encode(hrp(83), VERSION_0, &[0xab; 20]), long(hrp(83), &[0xab; 20]));
+    }
+
     #[test]
     fn encoded_length_works() {
         let addresses = vec![
@@ -539,7 +1920,7 @@ mod tests {
         ];
 
         for address in addresses {
-            let (hrp, version, program) = decode(address).expect("valid address");
+            let Decoded { hrp, version, program } = decode_struct(address).expect("valid address");
 
             let encoded = encode(hrp, version, &program).expect("valid data");
             let want = encoded.len();
@@ -549,9 +1930,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encode_allocates_exact_capacity() {
+        for hrp in [hrp::GRS, hrp::TGRS, hrp::GRSRT].iter() {
+            for len in MIN_WITNESS_PROGRAM_LENGTH..=MAX_WITNESS_PROGRAM_LENGTH {
+                let program = vec![0xab; len];
+                let address = encode(*hrp, VERSION_1, &program).expect("valid address");
+                assert_eq!(address.capacity(), address.len(), "program length {}", len);
+            }
+            for len in [20, 32].iter() {
+                let address = encode_v0(*hrp, &vec![0xab; *len]).expect("valid address");
+                assert_eq!(address.capacity(), address.len(), "program length {}", len);
+            }
+        }
+    }
+
     #[test]
     fn can_encode_maximum_length_address() {
-        let program = [0_u8; 40]; // Maximum witness program length.
+        let program = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
         let hrp = Hrp::parse_unchecked("anhrpthatis18chars");
         let addr = encode(hrp, VERSION_1, &program).expect("valid data");
         assert_eq!(addr.len(), MAX_STRING_LENGTH);
@@ -565,13 +1961,58 @@ mod tests {
         ];
 
         for (hrp, len) in tcs {
-            let program = [0_u8; 40]; // Maximum witness program length.
+            let program = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
             let hrp = Hrp::parse_unchecked(hrp);
             let err = encode(hrp, VERSION_1, &program).unwrap_err();
             assert_eq!(err, EncodeError::TooLong(SegwitCodeLengthError(len)));
         }
     }
 
+    #[test]
+    fn can_not_encode_invalid_segwit_v0_program_length() {
+        let program = [0_u8; 25];
+        let err = encode(hrp::GRS, VERSION_0, &program).unwrap_err();
+        assert_eq!(
+            err,
+            EncodeError::WitnessLength(WitnessLengthError::InvalidSegwitV0 { len: 25 })
+        );
+    }
+
+    #[test]
+    fn encode_does_not_panic() {
+        let hrps = [
+            hrp::GRS,
+            // The longest possible HRP, the encoded address will always be too long.
+            Hrp::parse_unchecked(
+                "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio",
+            ),
+        ];
+        let program = [0xab_u8; MAX_WITNESS_PROGRAM_LENGTH + 1];
+
+        for hrp in &hrps {
+            for v in 0..32_u8 {
+                let version = Fe32::try_from(v).expect("valid field element");
+                for len in 0..=program.len() {
+                    let program = &program[..len];
+
+                    if let Ok(address) = encode(*hrp, version, program) {
+                        let decoded = decode_struct(&address).expect("valid address");
+                        assert_eq!(decoded.version, version);
+                        assert_eq!(decoded.program, program);
+                        assert_eq!(decoded.encode(), address);
+                    }
+
+                    let mut address = String::new();
+                    encode_to_fmt_unchecked(&mut address, *hrp, version, program)
+                        .expect("writing to string succeeds");
+                    // hrp + separator + witness version + program + checksum
+                    let want = hrp.len() + 1 + 1 + (len * 8 + 4) / 5 + 6;
+                    assert_eq!(address.len(), want);
+                }
+            }
+        }
+    }
+
     #[test]
     fn can_decode_maximum_length_address() {
         let address = "anhrpthatisnineteen1pqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqghfyyfz";
@@ -585,6 +2026,123 @@ mod tests {
         let address = "anhrpthatistwentycha1pqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgqfrwjz";
         assert_eq!(address.len(), MAX_STRING_LENGTH + 1);
 
-        assert_eq!(decode(address).unwrap_err(), DecodeError(SegwitHrpstringError::TooLong(91)));
+        assert_eq!(
+            decode(address).unwrap_err(),
+            DecodeError(SegwitHrpstringError::TooLong { len: 91, max: 90 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn decode_zeroizing_matches_decode() {
+        let addresses = [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x",
+        ];
+        for address in addresses.iter() {
+            let (hrp, version, program) = decode_zeroizing(address).unwrap();
+            assert_eq!(program.capacity(), program.len());
+            assert_eq!((hrp, version, program.to_vec()), decode(address).unwrap());
+        }
+        let invalid = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+        assert_eq!(decode_zeroizing(invalid).unwrap_err(), decode(invalid).unwrap_err());
+    }
+
+    #[test]
+    fn visual_diff_flags_confusables() {
+        let expected = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert!(decode(expected).is_ok());
+
+        assert_eq!(visual_diff(expected, expected), Some(vec![]));
+        assert_eq!(visual_diff(expected, &expected.to_uppercase()), Some(vec![]));
+        assert_eq!(visual_diff(expected, &expected[1..]), None);
+
+        let confusable = "grs1qar05rrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(visual_diff(expected, confusable), Some(vec![(8, 's', '5', true)]));
+        // Either case.
+        let upper = confusable.to_uppercase();
+        assert_eq!(visual_diff(expected, &upper), Some(vec![(8, 's', '5', true)]));
+
+        let other = "grs1qar0lrrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(visual_diff(expected, other), Some(vec![(8, 's', 'l', false)]));
+
+        // Characters outside the alphabet are never confusable.
+        let invalid = "grs1qar0brrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(visual_diff(expected, invalid), Some(vec![(8, 's', 'b', false)]));
+    }
+
+    #[test]
+    fn same_address_ignores_case() {
+        let lower = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+        let upper = lower.to_uppercase();
+        assert!(same_address(lower, &upper).unwrap());
+        assert!(same_address(lower, lower).unwrap());
+    }
+
+    #[test]
+    fn same_address_compares_all_parts() {
+        let program = [0xab; 20];
+        let address = encode_v0(hrp::GRS, &program).unwrap();
+        let other_hrp = encode_v0(hrp::TGRS, &program).unwrap();
+        let other_version = encode_v1(hrp::GRS, &program).unwrap();
+        let other_program = encode_v0(hrp::GRS, &[0xcd; 20]).unwrap();
+
+        assert!(!same_address(&address, &other_hrp).unwrap());
+        assert!(!same_address(&address, &other_version).unwrap());
+        assert!(!same_address(&address, &other_program).unwrap());
+        assert!(same_address(&address, "not an address").is_err());
+    }
+
+    #[test]
+    fn cmp_addresses_ignores_case() {
+        let lower = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+        let upper = lower.to_uppercase();
+        assert_eq!(cmp_addresses(lower, &upper), Ok(Ordering::Equal));
+        assert_eq!(cmp_addresses(&upper, lower), Ok(Ordering::Equal));
+        assert!(cmp_addresses(lower, "not an address").is_err());
+    }
+
+    #[test]
+    fn cmp_addresses_orders_by_hrp_version_program() {
+        let program = [0xab; 20];
+        let address = encode_v0(hrp::GRS, &program).unwrap();
+        // A v0 address sorts before a v1 address with the same program.
+        let v1 = encode_v1(hrp::GRS, &program).unwrap();
+        assert_eq!(cmp_addresses(&address, &v1), Ok(Ordering::Less));
+        assert_eq!(cmp_addresses(&v1, &address), Ok(Ordering::Greater));
+
+        // The hrp comes first.
+        let testnet = encode_v0(hrp::TGRS, &program).unwrap();
+        assert_eq!(cmp_addresses(&v1, &testnet), Ok(Ordering::Less));
+
+        // Then the program bytes, not the program length.
+        let longer = encode_v0(hrp::GRS, &[0x00; 32]).unwrap();
+        assert_eq!(cmp_addresses(&address, &longer), Ok(Ordering::Greater));
+        let prefix = encode_v1(hrp::GRS, &[0xab; 2]).unwrap();
+        assert_eq!(cmp_addresses(&prefix, &v1), Ok(Ordering::Less));
+    }
+
+    #[test]
+    fn decode_into_and_boxed_match_decode() {
+        let mut out = vec![0xff; 3];
+        for address in [
+            "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay",
+            "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P",
+        ]
+        .iter()
+        {
+            let (hrp, version, program) = decode(address).unwrap();
+            assert_eq!(decode_into(address, &mut out).unwrap(), (hrp, version));
+            assert_eq!(out, program);
+            let (boxed_hrp, boxed_version, boxed) = decode_boxed(address).unwrap();
+            assert_eq!((boxed_hrp, boxed_version), (hrp, version));
+            assert_eq!(&*boxed, &program[..]);
+        }
+
+        let before = out.clone();
+        let invalid = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+        assert_eq!(decode_into(invalid, &mut out).unwrap_err(), decode(invalid).unwrap_err());
+        assert_eq!(out, before);
+        assert_eq!(decode_boxed(invalid).unwrap_err(), decode(invalid).unwrap_err());
     }
 }
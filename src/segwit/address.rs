@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT
+
+//! An owned, validated segwit address.
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::{fmt, str};
+
+use super::{DecodeError, EncodeError};
+use crate::primitives::decode::SegwitHrpstring;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::segwit;
+
+/// A segwit address, the human-readable part, witness version, and witness program.
+///
+/// Can only be constructed from valid parts, either by [`Address::new`] or by parsing a string.
+///
+/// Addresses are compared and hashed by human-readable part (ignoring case), witness version and
+/// witness program, so the uppercase and lowercase forms of an address are equal.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32grs::segwit::{Address, VERSION_1};
+///
+/// let s = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+/// let address = s.parse::<Address>().expect("valid address");
+///
+/// assert_eq!(address.witness_version(), VERSION_1);
+/// assert_eq!(address.witness_program(), &[0xab; 20]);
+/// assert_eq!(address.to_string(), s);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Address {
+    hrp: Hrp,
+    witness_version: Fe32,
+    witness_program: Vec<u8>,
+}
+
+impl Address {
+    /// Constructs a new segwit address from its parts.
+    ///
+    /// Does the same validity checks as [`encode`](super::encode).
+    pub fn new(
+        hrp: Hrp,
+        witness_version: Fe32,
+        witness_program: Vec<u8>,
+    ) -> Result<Self, EncodeError> {
+        segwit::validate_witness_version(witness_version)?;
+        segwit::validate_witness_program_length(witness_program.len(), witness_version)?;
+
+        let _ = super::encoded_length(hrp, witness_version, &witness_program)?;
+
+        Ok(Address { hrp, witness_version, witness_program })
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.hrp }
+
+    /// Returns the witness version.
+    #[inline]
+    pub fn witness_version(&self) -> Fe32 { self.witness_version }
+
+    /// Returns the witness program.
+    #[inline]
+    pub fn witness_program(&self) -> &[u8] { &self.witness_program }
+
+    /// Consumes the address and returns its parts.
+    #[inline]
+    pub fn into_parts(self) -> (Hrp, Fe32, Vec<u8>) {
+        (self.hrp, self.witness_version, self.witness_program)
+    }
+}
+
+impl str::FromStr for Address {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segwit = SegwitHrpstring::new(s)?;
+        Ok(Address {
+            hrp: segwit.hrp(),
+            witness_version: segwit.witness_version(),
+            witness_program: segwit.byte_iter().collect(),
+        })
+    }
+}
+
+/// Formats the address using lowercase characters.
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::encode_lower_to_fmt_unchecked(
+            f,
+            self.hrp,
+            self.witness_version,
+            &self.witness_program,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::hrp;
+    use crate::segwit::{VERSION_0, VERSION_1};
+
+    #[test]
+    fn new_validates() {
+        assert!(Address::new(hrp::GRS, VERSION_0, vec![0; 20]).is_ok());
+        assert!(matches!(
+            Address::new(hrp::GRS, VERSION_0, vec![0; 21]),
+            Err(EncodeError::WitnessLength(_))
+        ));
+        assert!(matches!(
+            Address::new(hrp::GRS, Fe32::L, vec![0; 20]),
+            Err(EncodeError::WitnessVersion(_))
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let address = Address::new(hrp::GRS, VERSION_1, vec![0xab; 32]).expect("valid address");
+        let parsed = address.to_string().parse::<Address>().expect("valid address");
+        assert_eq!(parsed, address);
+    }
+
+    #[test]
+    fn equality_ignores_case() {
+        use std::collections::HashSet;
+
+        let lower = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        let a = lower.parse::<Address>().expect("valid address");
+        let b = lower.to_uppercase().parse::<Address>().expect("valid address");
+        assert_eq!(a, b);
+
+        let set: HashSet<Address> = vec![a, b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        let program = set.into_iter().next().unwrap().witness_program().to_vec();
+        let testnet = Address::new(hrp::TGRS, VERSION_0, program).expect("valid address");
+        assert_ne!(testnet, lower.parse::<Address>().unwrap());
+    }
+}
@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: MIT
+
+//! An owned segwit address string.
+
+#[cfg(all(feature = "schemars", not(feature = "std"), not(test)))]
+use alloc::boxed::Box;
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::{fmt, ops, str};
+
+use super::{CompactAddressError, DecodeError, KnownHrp};
+use crate::primitives::decode::SegwitHrpstring;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+
+/// A valid segwit address, stored as its canonical lowercase string.
+///
+/// Unlike [`Address`](super::Address), which stores the parts of an address, this type derefs to
+/// the address string. It parses with [`str::parse`], so can be used directly as a command line
+/// argument or with any serialization framework that uses [`FromStr`](str::FromStr) and
+/// [`Display`](fmt::Display).
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{AddressString, VERSION_0};
+///
+/// # fn main() -> Result<(), bech32grs::segwit::DecodeError> {
+/// let a: AddressString = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P".parse()?;
+/// assert_eq!(a.witness_version(), VERSION_0);
+/// assert_eq!(&*a, "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+/// assert!(a.starts_with("grs1"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Parsing an invalid address returns an error saying why:
+///
+/// ```
+/// use bech32grs::primitives::decode::SegwitErrorKind;
+/// use bech32grs::segwit::AddressString;
+///
+/// let err = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q".parse::<AddressString>().unwrap_err();
+/// assert_eq!(err.kind(), SegwitErrorKind::Checksum);
+/// assert!(err.to_string().starts_with("decoding segwit address failed"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AddressString {
+    /// The lowercase address.
+    s: String,
+    /// The human-readable part, lowercase.
+    hrp: Hrp,
+    /// The witness version.
+    witness_version: Fe32,
+}
+
+impl AddressString {
+    /// Parses `s` as a segwit address for one of the [`KnownHrp`] networks.
+    ///
+    /// Like [`str::parse`] but also rejects addresses with any other human-readable part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32grs::segwit::{AddressString, CompactAddressError};
+    ///
+    /// assert!(AddressString::new_known("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").is_ok());
+    ///
+    /// let bitcoin = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+    /// assert!(bitcoin.parse::<AddressString>().is_ok());
+    /// assert!(matches!(AddressString::new_known(bitcoin), Err(CompactAddressError::UnknownHrp(_))));
+    /// ```
+    pub fn new_known(s: &str) -> Result<Self, CompactAddressError> {
+        let segwit = SegwitHrpstring::new(s)?;
+        if KnownHrp::from_hrp(segwit.hrp()).is_none() {
+            return Err(CompactAddressError::UnknownHrp(segwit.hrp()));
+        }
+        Ok(Self::from_segwit(s, &segwit))
+    }
+
+    /// Constructs an [`AddressString`] from `s` and the result of parsing it.
+    fn from_segwit(s: &str, segwit: &SegwitHrpstring) -> Self {
+        let s = s.to_ascii_lowercase();
+        // The hrp is the start of the string, and lowercasing keeps it valid.
+        let hrp = s.get(..segwit.hrp().len()).map_or(segwit.hrp(), Hrp::parse_unchecked);
+        AddressString { s, hrp, witness_version: segwit.witness_version() }
+    }
+
+    /// Returns the address as a lowercase string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.s }
+
+    /// Returns the human-readable part, in lowercase.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.hrp }
+
+    /// Returns the witness version.
+    #[inline]
+    pub fn witness_version(&self) -> Fe32 { self.witness_version }
+
+    /// Consumes the address, returning the lowercase string.
+    #[inline]
+    pub fn into_string(self) -> String { self.s }
+}
+
+impl str::FromStr for AddressString {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segwit = SegwitHrpstring::new(s)?;
+        Ok(Self::from_segwit(s, &segwit))
+    }
+}
+
+impl<'s> TryFrom<&'s str> for AddressString {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(s: &'s str) -> Result<Self, Self::Error> { s.parse() }
+}
+
+/// Formats the address using lowercase characters.
+impl fmt::Display for AddressString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(&self.s) }
+}
+
+impl AsRef<str> for AddressString {
+    #[inline]
+    fn as_ref(&self) -> &str { &self.s }
+}
+
+impl ops::Deref for AddressString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str { &self.s }
+}
+
+impl From<AddressString> for String {
+    #[inline]
+    fn from(a: AddressString) -> Self { a.s }
+}
+
+/// Described as a string of at most [`MAX_STRING_LENGTH`](super::MAX_STRING_LENGTH) characters,
+/// a lowercase human-readable part, the separator and at least 11 characters of the bech32
+/// alphabet, with the format `grs-address`.
+///
+/// The pattern only checks the shape of the string, not the checksum or the witness program.
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for AddressString {
+    fn schema_name() -> String { "AddressString".into() }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, StringValidation};
+
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("grs-address".into()),
+            string: Some(Box::new(StringValidation {
+                max_length: Some(super::MAX_STRING_LENGTH as u32),
+                min_length: Some(SCHEMA_MIN_LENGTH),
+                pattern: Some(SCHEMA_PATTERN.into()),
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A one character human-readable part, the separator, the witness version, a two byte program
+/// and the checksum.
+#[cfg(feature = "schemars")]
+const SCHEMA_MIN_LENGTH: u32 = 1 + 1 + 1 + 4 + 6;
+
+/// Printable ASCII except uppercase letters, the separator, then the bech32 alphabet.
+#[cfg(feature = "schemars")]
+const SCHEMA_PATTERN: &str = "^[!-@\\[-~]{1,83}1[02-9ac-hj-np-z]{11,}$";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::hrp;
+    use crate::segwit::VERSION_1;
+
+    const ADDR: &str = "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at";
+
+    #[test]
+    fn parse_is_canonical() {
+        let lower = ADDR.parse::<AddressString>().unwrap();
+        let upper = ADDR.to_uppercase().parse::<AddressString>().unwrap();
+        assert_eq!(lower, upper);
+        assert_eq!(upper.as_str(), ADDR);
+        assert_eq!(upper.to_string(), ADDR);
+        assert_eq!(upper.hrp().as_str(), "grs");
+        assert_eq!(upper.witness_version(), VERSION_1);
+        assert_eq!(String::from(upper), ADDR);
+    }
+
+    #[test]
+    fn try_from_matches_parse() {
+        assert_eq!(AddressString::try_from(ADDR), ADDR.parse());
+        assert_eq!(AddressString::try_from("grs1"), "grs1".parse::<AddressString>());
+        assert!(AddressString::try_from("grs1").is_err());
+    }
+
+    #[test]
+    fn known_hrp() {
+        assert_eq!(AddressString::new_known(ADDR).unwrap().hrp(), hrp::GRS);
+
+        let testnet = "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq";
+        assert!(AddressString::new_known(testnet).is_ok());
+
+        let program = [0xab; 20];
+        let other = crate::segwit::encode_v0(Hrp::parse("abc").unwrap(), &program).unwrap();
+        assert!(other.parse::<AddressString>().is_ok());
+        assert_eq!(
+            AddressString::new_known(&other).unwrap_err(),
+            CompactAddressError::UnknownHrp(Hrp::parse("abc").unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "schemars", test_deps))]
+    fn json_schema() {
+        let schema = schemars::schema_for!(AddressString);
+        let want = r#"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "AddressString",
+  "type": "string",
+  "format": "grs-address",
+  "maxLength": 90,
+  "minLength": 13,
+  "pattern": "^[!-@\\[-~]{1,83}1[02-9ac-hj-np-z]{11,}$"
+}"#;
+        assert_eq!(serde_json::to_string_pretty(&schema).unwrap(), want);
+    }
+}
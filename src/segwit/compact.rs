@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: MIT
+
+//! A fixed size, `Copy` representation of a segwit address.
+
+use core::cmp::Ordering;
+use core::{fmt, str};
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{SegwitHrpstring, SegwitHrpstringError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+use crate::primitives::segwit::MAX_WITNESS_PROGRAM_LENGTH;
+#[cfg(feature = "borsh")]
+use crate::segwit::WitnessProgram;
+
+/// The human-readable parts that can be stored in a [`CompactAddress`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[repr(u8)]
+pub enum KnownHrp {
+    /// The Groestlcoin mainnet network ([`hrp::GRS`]).
+    Mainnet,
+    /// The Groestlcoin testnet networks, testnet and signet ([`hrp::TGRS`]).
+    Testnets,
+    /// The Groestlcoin regtest network ([`hrp::GRSRT`]).
+    Regtest,
+}
+
+impl KnownHrp {
+    /// Returns the known network for `hrp`, if there is one.
+    pub fn from_hrp(hrp: Hrp) -> Option<Self> {
+        if hrp == hrp::GRS {
+            Some(KnownHrp::Mainnet)
+        } else if hrp == hrp::TGRS {
+            Some(KnownHrp::Testnets)
+        } else if hrp == hrp::GRSRT {
+            Some(KnownHrp::Regtest)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the human-readable part for this network.
+    pub fn to_hrp(self) -> Hrp {
+        match self {
+            KnownHrp::Mainnet => hrp::GRS,
+            KnownHrp::Testnets => hrp::TGRS,
+            KnownHrp::Regtest => hrp::GRSRT,
+        }
+    }
+
+    /// Returns the known network with the tag `tag`, see [`Hrp::tag`].
+    ///
+    /// The tags of the known networks are all different so this is the inverse of
+    /// [`KnownHrp::tag`]. An unknown HRP may share the tag of a known network, use
+    /// [`KnownHrp::from_hrp`] to check an HRP.
+    pub fn from_tag(tag: [u8; 4]) -> Option<Self> {
+        [KnownHrp::Mainnet, KnownHrp::Testnets, KnownHrp::Regtest]
+            .iter()
+            .copied()
+            .find(|known| known.tag() == tag)
+    }
+
+    /// Returns the tag of the human-readable part for this network, see [`Hrp::tag`].
+    #[inline]
+    pub fn tag(self) -> [u8; 4] { self.to_hrp().tag() }
+}
+
+/// A segwit address stored inline, without allocating.
+///
+/// Holds the network, witness version, and witness program in 43 bytes, making it suitable for use
+/// as a key in large address indexes. Only the human-readable parts in [`KnownHrp`] are supported.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{CompactAddress, KnownHrp, VERSION_1};
+///
+/// let s = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+/// let address = s.parse::<CompactAddress>().expect("valid address");
+///
+/// assert_eq!(address.known_hrp(), KnownHrp::Mainnet);
+/// assert_eq!(address.witness_version(), VERSION_1);
+/// assert_eq!(address.as_program(), &[0xab; 20]);
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!(address.to_string(), s);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(bytecheck(verify))
+)]
+pub struct CompactAddress {
+    /// The network the address is for.
+    hrp: KnownHrp,
+    /// The witness version as a byte, [`Fe32`] does not implement `Ord`.
+    version: u8,
+    /// The number of bytes of `program` in use.
+    len: u8,
+    /// The witness program, zero padded to the maximum program length.
+    program: [u8; MAX_WITNESS_PROGRAM_LENGTH],
+}
+
+impl CompactAddress {
+    /// Returns the network this address is for.
+    #[inline]
+    pub fn known_hrp(&self) -> KnownHrp { self.hrp }
+
+    /// Returns the human-readable part of this address.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.hrp.to_hrp() }
+
+    /// Returns the witness version.
+    #[inline]
+    pub fn witness_version(&self) -> Fe32 { Fe32(self.version) }
+
+    /// Returns the witness program.
+    #[inline]
+    pub fn as_program(&self) -> &[u8] { self.program.get(..usize::from(self.len)).unwrap_or(&[]) }
+}
+
+/// Orders by lowercase human-readable part, witness version, then program bytes, the same order as
+/// [`segwit::cmp_addresses`].
+///
+/// [`segwit::cmp_addresses`]: crate::segwit::cmp_addresses
+impl Ord for CompactAddress {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The tags of the known networks order the same as their lowercase human-readable parts.
+        self.hrp.tag().cmp(&other.hrp.tag()).then_with(|| {
+            (self.version, self.as_program()).cmp(&(other.version, other.as_program()))
+        })
+    }
+}
+
+/// Orders by lowercase human-readable part, witness version, then program bytes, the same order as
+/// [`segwit::cmp_addresses`].
+///
+/// [`segwit::cmp_addresses`]: crate::segwit::cmp_addresses
+impl PartialOrd for CompactAddress {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl str::FromStr for CompactAddress {
+    type Err = CompactAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segwit = SegwitHrpstring::new(s)?;
+        let hrp = KnownHrp::from_hrp(segwit.hrp())
+            .ok_or_else(|| CompactAddressError::UnknownHrp(segwit.hrp()))?;
+
+        // `SegwitHrpstring` enforces the maximum witness program length.
+        let mut program = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
+        let mut len = 0_u8;
+        for (slot, b) in program.iter_mut().zip(segwit.byte_iter()) {
+            *slot = b;
+            len += 1;
+        }
+
+        Ok(CompactAddress { hrp, version: segwit.witness_version().to_u8(), len, program })
+    }
+}
+
+impl fmt::Display for CompactAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::encode_lower_to_fmt_unchecked(
+            f,
+            self.hrp(),
+            self.witness_version(),
+            self.as_program(),
+        )
+    }
+}
+
+/// Serialized as the [`KnownHrp`] discriminant byte followed by a [`WitnessProgram`].
+///
+/// Deserializing an unknown network, or an invalid witness version or program length fails.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for CompactAddress {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        borsh::BorshSerialize::serialize(&(self.hrp as u8), writer)?;
+        borsh::BorshSerialize::serialize(&self.version, writer)?;
+        borsh::BorshSerialize::serialize(self.as_program(), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for CompactAddress {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let hrp = match <u8 as borsh::BorshDeserialize>::deserialize_reader(reader)? {
+            0 => KnownHrp::Mainnet,
+            1 => KnownHrp::Testnets,
+            2 => KnownHrp::Regtest,
+            _ => return Err(crate::archive::invalid_data("unknown network")),
+        };
+        let program = <WitnessProgram as borsh::BorshDeserialize>::deserialize_reader(reader)?;
+
+        let mut buf = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
+        let mut len = 0_u8;
+        for (slot, &b) in buf.iter_mut().zip(program.program()) {
+            *slot = b;
+            len += 1;
+        }
+        Ok(CompactAddress { hrp, version: program.version().to_u8(), len, program: buf })
+    }
+}
+
+// SAFETY: `verify` only rejects values, an `ArchivedCompactAddress` has no invariants beyond its
+// bytes. The network discriminant is checked by `CheckBytes`.
+#[cfg(feature = "rkyv")]
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedCompactAddress
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        crate::archive::check(
+            super::program::is_valid_stored(self.version, self.len, &self.program),
+            "invalid witness program",
+        )
+    }
+}
+
+/// An error while parsing a [`CompactAddress`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum CompactAddressError {
+    /// Decoding the segwit address failed.
+    Decode(SegwitHrpstringError),
+    /// The human-readable part is valid but is not one of the [`KnownHrp`] networks.
+    UnknownHrp(Hrp),
+}
+
+impl CompactAddressError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use CompactAddressError::*;
+
+        match *self {
+            Decode(ref e) => e.error_code(),
+            UnknownHrp(_) => Code::UnknownHrp,
+        }
+    }
+}
+
+impl fmt::Display for CompactAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CompactAddressError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "decoding segwit address failed"; e),
+            UnknownHrp(ref hrp) => write!(
+                f,
+                "unknown human-readable part '{}', compact addresses only support {}, {} and {}",
+                hrp,
+                hrp::GRS,
+                hrp::TGRS,
+                hrp::GRSRT
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for CompactAddressError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use CompactAddressError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            UnknownHrp(_) => None,
+        }
+    }
+}
+
+impl From<SegwitHrpstringError> for CompactAddressError {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self { Self::Decode(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem;
+
+    use super::*;
+
+    #[test]
+    fn size() {
+        assert!(mem::size_of::<CompactAddress>() <= 44);
+        assert_eq!(mem::size_of::<KnownHrp>(), 1);
+    }
+
+    #[test]
+    fn known_hrp_roundtrip() {
+        for known in &[KnownHrp::Mainnet, KnownHrp::Testnets, KnownHrp::Regtest] {
+            assert_eq!(KnownHrp::from_hrp(known.to_hrp()), Some(*known));
+        }
+        assert_eq!(KnownHrp::from_hrp(Hrp::parse_unchecked("bc")), None);
+    }
+
+    #[test]
+    fn known_hrp_tags() {
+        let known = [KnownHrp::Mainnet, KnownHrp::Testnets, KnownHrp::Regtest];
+        for (i, a) in known.iter().enumerate() {
+            assert_eq!(KnownHrp::from_tag(a.tag()), Some(*a));
+            for b in known[i + 1..].iter() {
+                assert_ne!(a.tag(), b.tag(), "{:?} and {:?} have the same tag", a, b);
+                // Comparing tags is the same as comparing human-readable parts.
+                assert_eq!(a.tag().cmp(&b.tag()), a.to_hrp().cmp(&b.to_hrp()));
+            }
+        }
+        assert_eq!(KnownHrp::from_tag(Hrp::parse_unchecked("bc").tag()), None);
+        assert_eq!(KnownHrp::from_tag(*b"grs1"), None);
+
+        // Tags are not unique, an unknown hrp can have the tag of a known one.
+        let unknown = Hrp::parse_unchecked("tgrsx");
+        assert_eq!(KnownHrp::from_hrp(unknown), None);
+        assert_eq!(KnownHrp::from_tag(unknown.tag()), Some(KnownHrp::Testnets));
+    }
+
+    #[test]
+    fn uppercase_hrp_is_known() {
+        assert_eq!(KnownHrp::from_hrp(Hrp::parse_unchecked("GRS")), Some(KnownHrp::Mainnet));
+    }
+
+    #[test]
+    fn unknown_hrp() {
+        // BIP-173 mainnet address, valid but not a Groestlcoin address.
+        let s = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let err = s.parse::<CompactAddress>().unwrap_err();
+        assert_eq!(err, CompactAddressError::UnknownHrp(Hrp::parse_unchecked("bc")));
+    }
+
+    #[test]
+    fn ord_compares_hrp_version_then_program() {
+        let parse = |s: &str| s.parse::<CompactAddress>().unwrap();
+        let v0 = parse("grs1q4w46h2at4w46h2at4w46h2at4w46h2ath9clv3");
+        let v1 = parse("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc");
+
+        assert_eq!(v0.cmp(&parse("GRS1Q4W46H2AT4W46H2AT4W46H2AT4W46H2ATH9CLV3")), Ordering::Equal);
+        assert!(v0 < v1);
+        // Regtest ("grsrt") sorts before the testnets ("tgrs"), unlike `KnownHrp`.
+        let regtest = CompactAddress { hrp: KnownHrp::Regtest, ..v1 };
+        let testnet = CompactAddress { hrp: KnownHrp::Testnets, ..v0 };
+        assert!(v1 < regtest);
+        assert!(regtest < testnet);
+        assert!(KnownHrp::Testnets < KnownHrp::Regtest);
+    }
+
+    #[test]
+    fn invalid_address() {
+        let err = "grs1".parse::<CompactAddress>().unwrap_err();
+        assert!(matches!(err, CompactAddressError::Decode(_)));
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MIT
+
+//! The parts of a decoded segwit address, with names.
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+
+use super::EncodeError;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::segwit;
+
+/// A decoded segwit address, as returned by [`decode_struct`](super::decode_struct).
+///
+/// Holds the same parts as the tuple returned by [`decode`](super::decode) but names them, so they
+/// can not be mixed up when destructuring. Converts into that tuple with [`From`], and from it with
+/// [`TryFrom`] which does the same validity checks as [`encode`](super::encode).
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, Decoded, VERSION_0};
+///
+/// let address = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let Decoded { hrp, version, program } = segwit::decode_struct(address).expect("valid address");
+/// assert_eq!(hrp.as_str(), "grs");
+/// assert_eq!(version, VERSION_0);
+/// assert_eq!(program.len(), 20);
+///
+/// let decoded = Decoded { hrp, version, program };
+/// assert_eq!(decoded.encode(), address);
+/// assert_eq!(<(_, _, _)>::from(decoded), segwit::decode(address).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decoded {
+    /// The human-readable part, in the case of the decoded string.
+    pub hrp: Hrp,
+    /// The witness version.
+    pub version: Fe32,
+    /// The witness program.
+    pub program: Vec<u8>,
+}
+
+impl Decoded {
+    /// Returns the length of the witness program in bytes.
+    #[inline]
+    pub fn program_len(&self) -> usize { self.program.len() }
+
+    /// Encodes the address as a lowercase string.
+    ///
+    /// The parts are not validated. If they were changed since decoding, to an invalid witness
+    /// version or program length, the returned string is not a valid address. Use
+    /// [`encode`](super::encode) to check them.
+    #[inline]
+    pub fn encode(&self) -> String {
+        // +1 for the separator, +1 for the witness version and 6 for the checksum.
+        let len = self.hrp.len() + 8 + (self.program.len() * 8 + 4) / 5;
+        let mut s = String::with_capacity(len);
+        // Writing to a string never fails.
+        let _ = super::encode_lower_to_fmt_unchecked(&mut s, self.hrp, self.version, &self.program);
+        s
+    }
+}
+
+impl From<Decoded> for (Hrp, Fe32, Vec<u8>) {
+    #[inline]
+    fn from(d: Decoded) -> Self { (d.hrp, d.version, d.program) }
+}
+
+impl TryFrom<(Hrp, Fe32, Vec<u8>)> for Decoded {
+    type Error = EncodeError;
+
+    #[inline]
+    fn try_from((hrp, version, program): (Hrp, Fe32, Vec<u8>)) -> Result<Self, Self::Error> {
+        segwit::validate_witness_version(version)?;
+        segwit::validate_witness_program_length(program.len(), version)?;
+
+        let _ = super::encoded_length(hrp, version, &program)?;
+
+        Ok(Decoded { hrp, version, program })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segwit::{decode, decode_struct, VERSION_1};
+
+    #[test]
+    fn tuple_round_trip() {
+        let address = "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at";
+        let decoded = decode_struct(address).expect("valid address");
+        assert_eq!(decoded.version, VERSION_1);
+        assert_eq!(decoded.program_len(), 32);
+
+        let tuple = <(Hrp, Fe32, Vec<u8>)>::from(decoded.clone());
+        assert_eq!(tuple, decode(address).unwrap());
+        assert_eq!(Decoded::try_from(tuple), Ok(decoded));
+    }
+
+    #[test]
+    fn try_from_validates() {
+        let hrp = Hrp::parse("grs").unwrap();
+        let v17 = Fe32::try_from(17_u8).unwrap();
+
+        assert!(Decoded::try_from((hrp, VERSION_1, vec![0xab; 2])).is_ok());
+        assert!(matches!(
+            Decoded::try_from((hrp, v17, vec![0xab; 2])),
+            Err(EncodeError::WitnessVersion(_))
+        ));
+        assert!(matches!(
+            Decoded::try_from((hrp, VERSION_1, vec![0xab; 1])),
+            Err(EncodeError::WitnessLength(_))
+        ));
+        let long = Hrp::parse(&"a".repeat(83)).unwrap();
+        assert!(matches!(
+            Decoded::try_from((long, VERSION_1, vec![0xab; 2])),
+            Err(EncodeError::TooLong(_))
+        ));
+    }
+
+    #[test]
+    fn encode_is_lowercase() {
+        let decoded = decode_struct("GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P").unwrap();
+        assert_eq!(decoded.hrp.as_str(), "GRS");
+        assert_eq!(decoded.encode(), "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MIT
+
+//! Decoding segwit addresses into fixed capacity [`heapless`] buffers.
+//!
+//! Enabled by the `heapless` feature, usable without an allocator.
+
+use core::fmt;
+
+use heapless::Vec;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::fixed_capacity::collect_bytes;
+use crate::primitives::decode::{SegwitHrpstring, SegwitHrpstringError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::CapacityError;
+
+/// Decodes a segwit address into a [`heapless::Vec`] with capacity `N`.
+///
+/// A capacity of [`MAX_WITNESS_PROGRAM_LENGTH`] is always sufficient for a valid address.
+///
+/// # Errors
+///
+/// As well as the usual segwit decoding errors, returns [`DecodeHeaplessError::Capacity`] if the
+/// witness program does not fit in `N` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, MAX_WITNESS_PROGRAM_LENGTH};
+///
+/// let address = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+/// let (_hrp, version, program) =
+///     segwit::decode_heapless::<MAX_WITNESS_PROGRAM_LENGTH>(address).expect("valid address");
+/// assert_eq!(version, segwit::VERSION_1);
+/// assert_eq!(program, [0xab; 20]);
+/// ```
+///
+/// [`MAX_WITNESS_PROGRAM_LENGTH`]: crate::segwit::MAX_WITNESS_PROGRAM_LENGTH
+#[inline]
+pub fn decode_heapless<const N: usize>(
+    s: &str,
+) -> Result<(Hrp, Fe32, Vec<u8, N>), DecodeHeaplessError> {
+    let segwit = SegwitHrpstring::new(s)?;
+    let program = collect_bytes(segwit.byte_iter())?;
+    Ok((segwit.hrp(), segwit.witness_version(), program))
+}
+
+/// An error while decoding a segwit address into a [`heapless::Vec`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DecodeHeaplessError {
+    /// Decoding the segwit address failed.
+    Decode(SegwitHrpstringError),
+    /// Witness program does not fit in the buffer.
+    Capacity(CapacityError),
+}
+
+impl DecodeHeaplessError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => e.error_code(),
+            Capacity(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeHeaplessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "decoding segwit address failed"; e),
+            Capacity(ref e) => write_err!(f, "witness program does not fit in buffer"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for DecodeHeaplessError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use DecodeHeaplessError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            Capacity(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<SegwitHrpstringError> for DecodeHeaplessError {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self { Self::Decode(e) }
+}
+
+impl From<CapacityError> for DecodeHeaplessError {
+    #[inline]
+    fn from(e: CapacityError) -> Self { Self::Capacity(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segwit::{MAX_WITNESS_PROGRAM_LENGTH, VERSION_0};
+
+    const V0: &str = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+
+    #[test]
+    fn decode_max_capacity() {
+        let (hrp, version, program) =
+            decode_heapless::<MAX_WITNESS_PROGRAM_LENGTH>(V0).expect("valid address");
+        assert_eq!(hrp, crate::hrp::GRS);
+        assert_eq!(version, VERSION_0);
+        assert_eq!(program.len(), 32);
+    }
+
+    #[test]
+    fn decode_insufficient_capacity() {
+        let err = decode_heapless::<20>(V0).unwrap_err();
+        assert_eq!(
+            err,
+            DecodeHeaplessError::Capacity(CapacityError { required: 32, capacity: 20 })
+        );
+    }
+
+    #[test]
+    fn decode_invalid_address() {
+        let err = decode_heapless::<MAX_WITNESS_PROGRAM_LENGTH>("grs1").unwrap_err();
+        assert!(matches!(err, DecodeHeaplessError::Decode(_)));
+    }
+}
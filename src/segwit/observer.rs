@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MIT
+
+//! Observing how segwit addresses fail to decode, without seeing the addresses.
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+
+use super::DecodeError;
+use crate::primitives::decode::{
+    ChecksumVariant, SegwitHrpstring, SegwitHrpstringError, UncheckedHrpstring,
+};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+
+/// Receives what happened while decoding a segwit address with [`decode_with_observer`].
+///
+/// Exactly one method is called for each decode. The arguments are positions, lengths and codes,
+/// never any part of the input, so they can be collected into statistics where logging user input
+/// is not allowed. All methods do nothing by default.
+///
+/// `()` is the observer that does nothing, [`decode_with_observer`] with `&mut ()` compiles down to
+/// [`decode`](super::decode).
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::primitives::decode::ChecksumVariant;
+/// use bech32grs::segwit::{self, DecodeObserver};
+///
+/// #[derive(Default)]
+/// struct Counts {
+///     char_errors: usize,
+///     checksum_failures: usize,
+/// }
+///
+/// impl DecodeObserver for Counts {
+///     fn on_char_error(&mut self, _pos: Option<usize>) { self.char_errors += 1 }
+///     fn on_checksum_failure(&mut self, _: ChecksumVariant) { self.checksum_failures += 1 }
+/// }
+///
+/// let mut counts = Counts::default();
+/// let bad_checksum = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+/// let bad_char = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg6bp";
+/// for s in &[bad_checksum, bad_char] {
+///     assert!(segwit::decode_with_observer(s, &mut counts).is_err());
+/// }
+/// assert_eq!(counts.char_errors, 1);
+/// assert_eq!(counts.checksum_failures, 1);
+/// ```
+pub trait DecodeObserver {
+    /// Called when the characters of the string are invalid, before the checksum is checked.
+    ///
+    /// `pos` is the byte position of the offending character, if the error is for a single one, for
+    /// an invalid or control character or the first letter of the wrong case.
+    #[inline]
+    fn on_char_error(&mut self, pos: Option<usize>) { let _ = pos; }
+
+    /// Called when the checksum is invalid, with the algorithm the witness version requires.
+    #[inline]
+    fn on_checksum_failure(&mut self, variant_tried: ChecksumVariant) { let _ = variant_tried; }
+
+    /// Called when decoding fails for any other reason, with the [`code`](DecodeError::code) of
+    /// the error.
+    ///
+    /// For example an invalid human-readable part, an invalid witness version or a witness program
+    /// of the wrong length.
+    #[inline]
+    fn on_other_error(&mut self, code: &'static str) { let _ = code; }
+
+    /// Called when the address is valid, with its witness version and witness program length.
+    #[inline]
+    fn on_success(&mut self, version: Fe32, program_len: usize) { let _ = (version, program_len); }
+}
+
+impl DecodeObserver for () {}
+
+impl<O: DecodeObserver + ?Sized> DecodeObserver for &mut O {
+    #[inline]
+    fn on_char_error(&mut self, pos: Option<usize>) { (**self).on_char_error(pos) }
+    #[inline]
+    fn on_checksum_failure(&mut self, variant_tried: ChecksumVariant) {
+        (**self).on_checksum_failure(variant_tried)
+    }
+    #[inline]
+    fn on_other_error(&mut self, code: &'static str) { (**self).on_other_error(code) }
+    #[inline]
+    fn on_success(&mut self, version: Fe32, program_len: usize) {
+        (**self).on_success(version, program_len)
+    }
+}
+
+/// Decodes a segwit address like [`decode`](super::decode), telling `observer` how it went.
+///
+/// Returns the same as [`decode`](super::decode), see [`DecodeObserver`] for which method is called
+/// for which outcome.
+#[inline]
+pub fn decode_with_observer<O: DecodeObserver + ?Sized>(
+    s: &str,
+    observer: &mut O,
+) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
+    match SegwitHrpstring::new(s) {
+        Ok(segwit) => {
+            let program = segwit.byte_iter().collect::<Vec<u8>>();
+            observer.on_success(segwit.witness_version(), program.len());
+            Ok((segwit.hrp(), segwit.witness_version(), program))
+        }
+        Err(e) => {
+            observe_error(s, &e, observer);
+            Err(DecodeError(e))
+        }
+    }
+}
+
+/// Calls the method of `observer` for the error `e`, returned when decoding `s`.
+///
+/// Kept out of line so the success path of [`decode_with_observer`] stays small.
+#[inline(never)]
+fn observe_error<O: DecodeObserver + ?Sized>(s: &str, e: &SegwitHrpstringError, observer: &mut O) {
+    use SegwitHrpstringError::*;
+
+    use crate::primitives::decode::UncheckedHrpstringError as UncheckedError;
+
+    match *e {
+        Unchecked(ref unchecked @ UncheckedError::Char(_))
+        | Unchecked(ref unchecked @ UncheckedError::HrpMixedCase(_)) =>
+            observer.on_char_error(unchecked.position(s)),
+        Checksum(_) | ProbablyTruncated { .. } => {
+            // The string parsed far enough to check the checksum, so it has a witness version.
+            let version = UncheckedHrpstring::new(s).ok().and_then(|u| u.witness_version());
+            match version {
+                Some(super::VERSION_0) => observer.on_checksum_failure(ChecksumVariant::Bech32),
+                Some(_) => observer.on_checksum_failure(ChecksumVariant::Bech32m),
+                None => observer.on_other_error(e.code()),
+            }
+        }
+        _ => observer.on_other_error(e.code()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segwit::{VERSION_0, VERSION_1};
+
+    /// A call to one of the [`DecodeObserver`] methods.
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Char(Option<usize>),
+        Checksum(ChecksumVariant),
+        Other(&'static str),
+        Success(Fe32, usize),
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<Event>);
+
+    impl DecodeObserver for Recorder {
+        fn on_char_error(&mut self, pos: Option<usize>) { self.0.push(Event::Char(pos)) }
+        fn on_checksum_failure(&mut self, variant_tried: ChecksumVariant) {
+            self.0.push(Event::Checksum(variant_tried))
+        }
+        fn on_other_error(&mut self, code: &'static str) { self.0.push(Event::Other(code)) }
+        fn on_success(&mut self, version: Fe32, program_len: usize) {
+            self.0.push(Event::Success(version, program_len))
+        }
+    }
+
+    #[test]
+    fn callback_sequence() {
+        let inputs = [
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p",
+            "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+            "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg6bp",
+            "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7au",
+            "grs1qar0srrr7xfkvy5l643lYdnw9re59gtzzncg65p",
+        ];
+        let mut recorder = Recorder::default();
+        for s in &inputs {
+            assert_eq!(decode_with_observer(s, &mut recorder), crate::segwit::decode(s));
+        }
+        assert_eq!(
+            recorder.0,
+            [
+                Event::Success(VERSION_0, 20),
+                Event::Success(VERSION_1, 32),
+                Event::Char(Some(41)),
+                Event::Checksum(ChecksumVariant::Bech32m),
+                Event::Char(Some(24)),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_errors() {
+        let mut recorder = Recorder::default();
+        for s in &["grs1", "grs1cqqqqqqqqqqqq", "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65Q"] {
+            assert!(decode_with_observer(s, &mut recorder).is_err());
+        }
+        assert_eq!(
+            recorder.0,
+            [
+                Event::Char(None),
+                Event::Other("E_WITNESS_VERSION_INVALID"),
+                Event::Checksum(ChecksumVariant::Bech32),
+            ]
+        );
+    }
+
+    #[test]
+    fn noop_observer() {
+        let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+        assert_eq!(decode_with_observer(s, &mut ()), crate::segwit::decode(s));
+    }
+}
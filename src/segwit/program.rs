@@ -0,0 +1,369 @@
+// SPDX-License-Identifier: MIT
+
+//! A witness version and witness program that are known to be valid together.
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::SegwitHrpstring;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::segwit::{
+    self, InvalidWitnessVersionError, WitnessLengthError, MAX_WITNESS_PROGRAM_LENGTH, VERSION_0,
+    VERSION_1,
+};
+
+/// A witness version and witness program, stored inline.
+///
+/// Can only be constructed from a valid pair, i.e. a witness version from 0 to 16 and a witness
+/// program of a valid length for that version.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::hrp;
+/// use bech32grs::segwit::{WitnessProgram, VERSION_1};
+///
+/// let program = WitnessProgram::new_p2tr([0xab; 32]);
+/// assert_eq!(program.version(), VERSION_1);
+/// assert_eq!(program.program(), &[0xab; 32]);
+///
+/// # #[cfg(feature = "alloc")]
+/// assert!(program.to_address(&hrp::GRS).starts_with("grs1p"));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(bytecheck(verify))
+)]
+pub struct WitnessProgram {
+    /// The witness version as a byte, [`Fe32`] does not implement `Ord`.
+    version: u8,
+    /// The number of bytes of `program` in use.
+    len: u8,
+    /// The witness program, zero padded to the maximum program length.
+    program: [u8; MAX_WITNESS_PROGRAM_LENGTH],
+}
+
+impl WitnessProgram {
+    /// Constructs a witness program, checking that `program` is a valid length for `version`.
+    pub fn new(version: Fe32, program: &[u8]) -> Result<Self, WitnessProgramError> {
+        segwit::validate_witness_version(version)?;
+        segwit::validate_witness_program_length(program.len(), version)?;
+        Ok(Self::new_unchecked(version, program))
+    }
+
+    /// Constructs a segwit version 0 pay-to-witness-public-key-hash program.
+    #[inline]
+    pub fn new_p2wpkh(hash: [u8; 20]) -> Self { Self::new_unchecked(VERSION_0, &hash) }
+
+    /// Constructs a segwit version 0 pay-to-witness-script-hash program.
+    #[inline]
+    pub fn new_p2wsh(hash: [u8; 32]) -> Self { Self::new_unchecked(VERSION_0, &hash) }
+
+    /// Constructs a segwit version 1 pay-to-taproot program from the output key.
+    #[inline]
+    pub fn new_p2tr(output_key: [u8; 32]) -> Self { Self::new_unchecked(VERSION_1, &output_key) }
+
+    /// Constructs a witness program from a valid pair.
+    fn new_unchecked(version: Fe32, program: &[u8]) -> Self {
+        let mut buf = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
+        let mut len = 0_u8;
+        for (slot, &b) in buf.iter_mut().zip(program) {
+            *slot = b;
+            len += 1;
+        }
+        WitnessProgram { version: version.to_u8(), len, program: buf }
+    }
+
+    /// Returns the witness version.
+    #[inline]
+    pub fn version(&self) -> Fe32 { Fe32(self.version) }
+
+    /// Returns the witness program.
+    #[inline]
+    pub fn program(&self) -> &[u8] { self.program.get(..usize::from(self.len)).unwrap_or(&[]) }
+
+    /// Returns the lowercase segwit address of this program on the network `hrp`.
+    ///
+    /// The address is only valid if it is at most 90 characters long, which it always is if `hrp`
+    /// is at most 18 characters long.
+    #[cfg(feature = "alloc")]
+    pub fn to_address(&self, hrp: &Hrp) -> String {
+        // +1 for the separator, +1 for the witness version and 6 for the checksum.
+        let len = hrp.len() + 8 + (self.program().len() * 8 + 4) / 5;
+        let mut s = String::with_capacity(len);
+        // Writing to a string never fails.
+        let _ = self.fmt_address(hrp, &mut s);
+        s
+    }
+
+    /// Writes the lowercase segwit address of this program on the network `hrp` to `w`.
+    ///
+    /// See [`Self::to_address`] for the validity of the written address.
+    #[inline]
+    pub fn fmt_address<W: fmt::Write>(&self, hrp: &Hrp, w: &mut W) -> fmt::Result {
+        super::encode_lower_to_fmt_unchecked(w, *hrp, self.version(), self.program())
+    }
+}
+
+/// Orders by witness version, then program bytes, see [`segwit::cmp_addresses`].
+///
+/// [`segwit::cmp_addresses`]: crate::segwit::cmp_addresses
+impl Ord for WitnessProgram {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.version, self.program()).cmp(&(other.version, other.program()))
+    }
+}
+
+/// Orders by witness version, then program bytes, see [`segwit::cmp_addresses`].
+///
+/// [`segwit::cmp_addresses`]: crate::segwit::cmp_addresses
+impl PartialOrd for WitnessProgram {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<'s> From<&SegwitHrpstring<'s>> for WitnessProgram {
+    /// `SegwitHrpstring` has already validated the witness version and program length.
+    fn from(segwit: &SegwitHrpstring<'s>) -> Self {
+        let mut program = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
+        let mut len = 0_u8;
+        for (slot, b) in program.iter_mut().zip(segwit.byte_iter()) {
+            *slot = b;
+            len += 1;
+        }
+        WitnessProgram { version: segwit.witness_version().to_u8(), len, program }
+    }
+}
+
+/// Serialized as the witness version byte followed by the witness program as a byte string.
+///
+/// Deserializing an invalid witness version or program length fails.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for WitnessProgram {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        borsh::BorshSerialize::serialize(&self.version(), writer)?;
+        borsh::BorshSerialize::serialize(self.program(), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for WitnessProgram {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let version = <Fe32 as borsh::BorshDeserialize>::deserialize_reader(reader)?;
+        let mut buf = [0_u8; MAX_WITNESS_PROGRAM_LENGTH];
+        let program = crate::archive::read_prefixed(reader, &mut buf, "witness program too long")?;
+        WitnessProgram::new(version, program)
+            .map_err(|_| crate::archive::invalid_data("invalid witness program"))
+    }
+}
+
+/// Zeroizing overwrites the program bytes with zeros, the version and length are kept so that the
+/// value stays valid.
+///
+/// `WitnessProgram` is `Copy`, so it can not be [`ZeroizeOnDrop`](zeroize::ZeroizeOnDrop) and
+/// copies made by moving it around are not zeroized. Call `zeroize` explicitly, or wrap it in
+/// [`Zeroizing`](zeroize::Zeroizing).
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for WitnessProgram {
+    #[inline]
+    fn zeroize(&mut self) { self.program.zeroize() }
+}
+
+// SAFETY: `verify` only rejects values, an `ArchivedWitnessProgram` has no invariants beyond its
+// bytes.
+#[cfg(feature = "rkyv")]
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedWitnessProgram
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        crate::archive::check(
+            is_valid_stored(self.version, self.len, &self.program),
+            "invalid witness program",
+        )
+    }
+}
+
+/// Returns whether the fields of a stored witness program are valid, including the zero padding.
+///
+/// The padding is compared and hashed, it must be zero for equal programs to be equal values.
+#[cfg(feature = "rkyv")]
+pub(crate) fn is_valid_stored(
+    version: u8,
+    len: u8,
+    program: &[u8; MAX_WITNESS_PROGRAM_LENGTH],
+) -> bool {
+    let version = Fe32(version);
+    let len = usize::from(len);
+    segwit::validate_witness_version(version).is_ok()
+        && segwit::validate_witness_program_length(len, version).is_ok()
+        && program.get(len..).map_or(false, |padding| padding.iter().all(|&b| b == 0))
+}
+
+/// An error while constructing a [`WitnessProgram`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum WitnessProgramError {
+    /// Invalid witness version (must be 0-16 inclusive).
+    WitnessVersion(InvalidWitnessVersionError),
+    /// Invalid witness program length for the witness version.
+    WitnessLength(WitnessLengthError),
+}
+
+impl WitnessProgramError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use WitnessProgramError::*;
+
+        match *self {
+            WitnessVersion(ref e) => e.error_code(),
+            WitnessLength(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for WitnessProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use WitnessProgramError::*;
+
+        match *self {
+            WitnessVersion(ref e) => write_err!(f, "witness version"; e),
+            WitnessLength(ref e) => write_err!(f, "witness length"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for WitnessProgramError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use WitnessProgramError::*;
+
+        match *self {
+            WitnessVersion(ref e) => Some(e),
+            WitnessLength(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<InvalidWitnessVersionError> for WitnessProgramError {
+    #[inline]
+    fn from(e: InvalidWitnessVersionError) -> Self { Self::WitnessVersion(e) }
+}
+
+impl From<WitnessLengthError> for WitnessProgramError {
+    #[inline]
+    fn from(e: WitnessLengthError) -> Self { Self::WitnessLength(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem;
+
+    use super::*;
+    use crate::hrp;
+
+    #[test]
+    fn size() {
+        assert!(mem::size_of::<WitnessProgram>() <= 42);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn zeroize() {
+        use zeroize::{Zeroize, Zeroizing};
+
+        let mut program = WitnessProgram::new_p2tr([0xab; 32]);
+        program.zeroize();
+        assert_eq!(program, WitnessProgram::new_p2tr([0; 32]));
+
+        let program = Zeroizing::new(WitnessProgram::new_p2wpkh([0xab; 20]));
+        assert_eq!(program.program(), &[0xab; 20]);
+        drop(program);
+    }
+
+    #[test]
+    fn invalid_combinations() {
+        let invalid = [
+            (Fe32::_3, &[0; 20][..], InvalidWitnessVersionError(Fe32::_3).into()), // Version 17.
+            (VERSION_0, &[0; 21][..], WitnessLengthError::InvalidSegwitV0 { len: 21 }.into()),
+            (VERSION_0, &[0; 33][..], WitnessLengthError::InvalidSegwitV0 { len: 33 }.into()),
+            (VERSION_1, &[0; 1][..], WitnessLengthError::TooShort { len: 1, min: 2 }.into()),
+            (VERSION_1, &[0; 41][..], WitnessLengthError::TooLong { len: 41, max: 40 }.into()),
+            (Fe32::P, &[][..], WitnessLengthError::TooShort { len: 0, min: 2 }.into()),
+        ];
+        for (version, program, err) in invalid.iter() {
+            let got: WitnessProgramError = WitnessProgram::new(*version, program).unwrap_err();
+            assert_eq!(got, *err);
+        }
+    }
+
+    #[test]
+    fn valid_combinations() {
+        for &(version, len) in
+            [(VERSION_0, 20), (VERSION_0, 32), (VERSION_1, 2), (Fe32::S, 40)].iter()
+        {
+            let program = WitnessProgram::new(version, &[0xab; 40][..len]).expect("valid");
+            assert_eq!(program.version(), version);
+            assert_eq!(program.program(), &[0xab; 40][..len]);
+        }
+        assert_eq!(
+            WitnessProgram::new_p2wpkh([1; 20]),
+            WitnessProgram::new(VERSION_0, &[1; 20]).unwrap()
+        );
+        assert_eq!(
+            WitnessProgram::new_p2wsh([1; 32]),
+            WitnessProgram::new(VERSION_0, &[1; 32]).unwrap()
+        );
+        assert_eq!(
+            WitnessProgram::new_p2tr([1; 32]),
+            WitnessProgram::new(VERSION_1, &[1; 32]).unwrap()
+        );
+    }
+
+    #[test]
+    fn ordering_is_by_contents() {
+        let short = WitnessProgram::new(VERSION_1, &[0xff; 2]).unwrap();
+        let long = WitnessProgram::new(VERSION_1, &[0x00; 3]).unwrap();
+        let v0 = WitnessProgram::new_p2wsh([0xff; 32]);
+        assert!(v0 < short);
+        // The program bytes are compared before the length.
+        assert!(long < short);
+        assert!(long < WitnessProgram::new(VERSION_1, &[0x00; 4]).unwrap());
+        assert_ne!(WitnessProgram::new_p2wsh([0; 32]), WitnessProgram::new_p2tr([0; 32]));
+        assert_eq!(v0.cmp(&WitnessProgram::new_p2wsh([0xff; 32])), Ordering::Equal);
+    }
+
+    #[test]
+    fn address_roundtrip() {
+        let s = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+        let segwit = SegwitHrpstring::new(s).expect("valid address");
+        let program = WitnessProgram::from(&segwit);
+        assert_eq!(program, WitnessProgram::new(VERSION_1, &[0xab; 20]).unwrap());
+
+        let mut buf = String::new();
+        program.fmt_address(&hrp::GRS, &mut buf).expect("writing to a string never fails");
+        assert_eq!(buf, s);
+
+        #[cfg(feature = "alloc")]
+        {
+            let address = program.to_address(&hrp::GRS);
+            assert_eq!(address, s);
+            assert_eq!(address.capacity(), address.len());
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: MIT
+
+//! Partial display of addresses, for logs that must not contain whole addresses.
+
+use core::fmt;
+
+use crate::primitives::decode::UncheckedHrpstring;
+
+/// The number of characters shown at the start of a redacted address by default.
+pub const DEFAULT_REDACT_PREFIX: usize = 8;
+
+/// The number of characters shown at the end of a redacted address by default.
+pub const DEFAULT_REDACT_SUFFIX: usize = 6;
+
+/// Replaces the hidden characters of a redacted string.
+const ELLIPSIS: &str = "…";
+
+/// Displays the first 8 and last 6 characters of the address `s`, e.g. `grs1q2s3…lkylay`.
+///
+/// Only strings that look like a bech32 string, i.e. that [`UncheckedHrpstring::new`] accepts, are
+/// partially shown. Anything else is displayed as just `…` so that arbitrary text, which may be
+/// sensitive, is never made to look like an address. The checksum is not validated. Use
+/// [`Redacted::with_widths`] to show a different number of characters.
+///
+/// Checking `s` does not allocate if it looks like an address, otherwise it may allocate for the
+/// parse error that is discarded.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+///
+/// let addr = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+/// assert_eq!(segwit::redact(addr).to_string(), "grs1q2s3…lkylay");
+/// assert_eq!(segwit::redact(addr).with_widths(4, 4).to_string(), "grs1…ylay");
+///
+/// assert_eq!(segwit::redact("alice@example.com").to_string(), "…");
+/// ```
+#[inline]
+pub fn redact(s: &str) -> Redacted<'_> {
+    Redacted {
+        s,
+        prefix: DEFAULT_REDACT_PREFIX,
+        suffix: DEFAULT_REDACT_SUFFIX,
+        plausible: UncheckedHrpstring::new(s).is_ok(),
+    }
+}
+
+/// Displays the first 8 and last 6 characters of `s`, without checking it looks like an address.
+///
+/// For error paths, where the string that failed to parse should still be logged partially.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+///
+/// let typo = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylab";
+/// assert_eq!(segwit::redact_lenient(typo).to_string(), "grs1q2s3…lkylab");
+/// ```
+#[inline]
+pub fn redact_lenient(s: &str) -> Redacted<'_> {
+    Redacted { s, prefix: DEFAULT_REDACT_PREFIX, suffix: DEFAULT_REDACT_SUFFIX, plausible: true }
+}
+
+/// A partially hidden string, returned by [`redact`] and [`redact_lenient`].
+///
+/// Displays the first and last few characters of the string with `…` in between. If the string is
+/// not longer than the shown characters combined it would be displayed in full, so it is displayed
+/// as just `…` instead. Formatting does not allocate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Redacted<'s> {
+    /// The string to redact.
+    s: &'s str,
+    /// The number of characters shown at the start.
+    prefix: usize,
+    /// The number of characters shown at the end.
+    suffix: usize,
+    /// Whether the string may be shown at all.
+    plausible: bool,
+}
+
+impl<'s> Redacted<'s> {
+    /// Shows `prefix` characters at the start and `suffix` characters at the end of the string.
+    #[inline]
+    pub fn with_widths(self, prefix: usize, suffix: usize) -> Self {
+        Redacted { prefix, suffix, ..self }
+    }
+
+    /// Returns the shown start and end of the string, `None` if nothing should be shown.
+    fn parts(&self) -> Option<(&'s str, &'s str)> {
+        if !self.plausible {
+            return None;
+        }
+        let len = self.s.chars().count();
+        if len <= self.prefix.saturating_add(self.suffix) {
+            return None;
+        }
+        let start = self.s.char_indices().nth(self.prefix).map(|(i, _)| i)?;
+        let end = self.s.char_indices().nth(len - self.suffix).map_or(self.s.len(), |(i, _)| i);
+        Some((self.s.get(..start)?, self.s.get(end..)?))
+    }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.parts() {
+            Some((start, end)) => {
+                f.write_str(start)?;
+                f.write_str(ELLIPSIS)?;
+                f.write_str(end)
+            }
+            None => f.write_str(ELLIPSIS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: &str = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+
+    #[test]
+    fn redacts_address() {
+        assert_eq!(redact(ADDR).to_string(), "grs1q2s3…lkylay");
+        assert_eq!(redact_lenient(ADDR).to_string(), "grs1q2s3…lkylay");
+        // Uppercase addresses are shown as they are.
+        let upper = ADDR.to_ascii_uppercase();
+        assert_eq!(redact(&upper).to_string(), "GRS1Q2S3…LKYLAY");
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(redact(ADDR).with_widths(0, 0).to_string(), "…");
+        assert_eq!(redact(ADDR).with_widths(3, 0).to_string(), "grs…");
+        assert_eq!(redact(ADDR).with_widths(0, 2).to_string(), "…ay");
+        assert_eq!(redact(ADDR).with_widths(usize::MAX, usize::MAX).to_string(), "…");
+    }
+
+    #[test]
+    fn short_strings_are_fully_redacted() {
+        // 14 characters, exactly the default prefix plus suffix.
+        let s = "a1qqqqqqqqqqqq";
+        assert_eq!(s.len(), DEFAULT_REDACT_PREFIX + DEFAULT_REDACT_SUFFIX);
+        assert_eq!(redact(s).to_string(), "…");
+        assert_eq!(redact_lenient(s).to_string(), "…");
+        assert_eq!(redact(s).with_widths(2, 2).to_string(), "a1…qq");
+
+        let s = "a1qqqqqqqqqqqqq";
+        assert_eq!(redact(s).to_string(), "a1qqqqqq…qqqqqq");
+    }
+
+    #[test]
+    fn implausible_strings_are_fully_redacted() {
+        let tcs = [
+            "",
+            "alice@example.com",
+            "no separator here at all",
+            "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylab!",
+            "Grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay",
+        ];
+        for s in tcs.iter() {
+            assert_eq!(redact(s).to_string(), "…", "{}", s);
+        }
+        assert_eq!(redact_lenient("alice@example.com").to_string(), "alice@ex…le.com");
+    }
+
+    #[test]
+    fn lenient_counts_chars() {
+        assert_eq!(redact_lenient("ééééééééé-ééééééé").to_string(), "éééééééé…éééééé");
+        assert_eq!(redact_lenient("ééééééééé-ééééééé").with_widths(9, 0).to_string(), "ééééééééé…");
+    }
+}
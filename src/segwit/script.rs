@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: MIT
+
+//! Conversion between a witness program and the scriptPubKey that pays to it.
+//!
+//! Only the byte layout from [BIP-141] is handled, there is no script interpreter: a witness
+//! output script is the witness version opcode followed by a direct push of the witness program.
+//!
+//! [BIP-141]: <https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::segwit::{self, WitnessLengthError};
+#[cfg(feature = "alloc")]
+use crate::segwit::WitnessProgramError;
+
+/// The `OP_0` opcode, the witness version 0 opcode.
+const OP_0: u8 = 0x00;
+/// The `OP_1` opcode, `OP_2` to `OP_16` follow it and are the witness versions 1 to 16 opcodes.
+const OP_1: u8 = 0x51;
+/// The `OP_16` opcode.
+const OP_16: u8 = 0x60;
+
+/// Appends the scriptPubKey paying to the witness `program` with `version` to `out`.
+///
+/// The script is `OP_0`, or `OP_1` to `OP_16`, followed by a direct push of `program`.
+///
+/// # Errors
+///
+/// If `version` is not a valid witness version or `program` is not a valid length for it, in which
+/// case nothing is appended to `out`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_0};
+///
+/// let mut script = Vec::new();
+/// segwit::script_pubkey(VERSION_0, &[0xab; 20], &mut script).expect("valid program");
+/// assert_eq!(script[..2], [0x00, 0x14]);
+/// assert_eq!(segwit::from_script_pubkey(&script), Ok((VERSION_0, &[0xab; 20][..])));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn script_pubkey(
+    version: Fe32,
+    program: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), WitnessProgramError> {
+    segwit::validate_witness_version(version)?;
+    segwit::validate_witness_program_length(program.len(), version)?;
+
+    let opcode = if version == segwit::VERSION_0 { OP_0 } else { OP_1 - 1 + version.to_u8() };
+    out.reserve(2 + program.len());
+    out.push(opcode);
+    out.push(program.len() as u8); // Cast OK, at most 40 bytes checked above.
+    out.extend_from_slice(program);
+    Ok(())
+}
+
+/// Returns the scriptPubKey paying to the witness `program` with `version`.
+///
+/// See [`script_pubkey`] to append to an existing buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, VERSION_1};
+///
+/// let script = segwit::to_script_pubkey_bytes(VERSION_1, &[0xab; 32]).expect("valid program");
+/// assert_eq!(script.len(), 34);
+/// assert_eq!(script[..2], [0x51, 0x20]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_script_pubkey_bytes(
+    version: Fe32,
+    program: &[u8],
+) -> Result<Vec<u8>, WitnessProgramError> {
+    let mut out = Vec::new();
+    script_pubkey(version, program, &mut out)?;
+    Ok(out)
+}
+
+/// Returns the witness version and program that `script` pays to.
+///
+/// Checks the rules of BIP-141: `script` must be a version opcode followed by a single direct push
+/// of the whole remaining script, which must be a valid program length for the version.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, ScriptPubkeyError, VERSION_0};
+///
+/// let mut script = vec![0x00, 0x14];
+/// script.extend_from_slice(&[0xab; 20]);
+/// assert_eq!(segwit::from_script_pubkey(&script), Ok((VERSION_0, &[0xab; 20][..])));
+///
+/// // Pay to public key hash, not a witness output.
+/// let p2pkh = [0x76, 0xa9, 0x14];
+/// assert_eq!(segwit::from_script_pubkey(&p2pkh), Err(ScriptPubkeyError::InvalidOpcode(0x76)));
+/// ```
+pub fn from_script_pubkey(script: &[u8]) -> Result<(Fe32, &[u8]), ScriptPubkeyError> {
+    let (opcode, push, program) = match script {
+        [opcode, push, program @ ..] => (*opcode, *push, program),
+        _ => return Err(ScriptPubkeyError::TooShort { len: script.len() }),
+    };
+
+    let version = match opcode {
+        OP_0 => segwit::VERSION_0,
+        OP_1..=OP_16 => Fe32(opcode - OP_1 + 1),
+        _ => return Err(ScriptPubkeyError::InvalidOpcode(opcode)),
+    };
+    if usize::from(push) != program.len() {
+        return Err(ScriptPubkeyError::PushLength { push, len: program.len() });
+    }
+    segwit::validate_witness_program_length(program.len(), version)?;
+
+    Ok((version, program))
+}
+
+/// An error while reading a witness program from a scriptPubKey.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ScriptPubkeyError {
+    /// The script is too short to hold a version opcode and a push.
+    TooShort {
+        /// The length of the script.
+        len: usize,
+    },
+    /// The first opcode is not `OP_0` or `OP_1` to `OP_16`.
+    InvalidOpcode(u8),
+    /// The push opcode does not push exactly the rest of the script.
+    PushLength {
+        /// The push opcode, for a direct push the number of bytes pushed.
+        push: u8,
+        /// The number of bytes after the push opcode.
+        len: usize,
+    },
+    /// The witness program is not a valid length for the witness version.
+    WitnessLength(WitnessLengthError),
+}
+
+impl ScriptPubkeyError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use ScriptPubkeyError::*;
+
+        match *self {
+            TooShort { .. } | PushLength { .. } => Code::ScriptPush,
+            InvalidOpcode(_) => Code::ScriptOpcode,
+            WitnessLength(ref e) => e.error_code(),
+        }
+    }
+}
+
+impl fmt::Display for ScriptPubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ScriptPubkeyError::*;
+
+        match *self {
+            TooShort { len } =>
+                write!(f, "script of {} bytes is too short for a witness program", len),
+            InvalidOpcode(op) => write!(f, "opcode {:#04x} is not a witness version opcode", op),
+            PushLength { push, len } =>
+                write!(f, "push opcode {:#04x} does not push the remaining {} bytes", push, len),
+            WitnessLength(ref e) => write_err!(f, "witness length"; e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for ScriptPubkeyError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use ScriptPubkeyError::*;
+
+        match *self {
+            TooShort { .. } | InvalidOpcode(_) | PushLength { .. } => None,
+            WitnessLength(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<WitnessLengthError> for ScriptPubkeyError {
+    #[inline]
+    fn from(e: WitnessLengthError) -> Self { Self::WitnessLength(e) }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_opcodes_roundtrip() {
+        for v in 0..=16 {
+            let version = Fe32(v);
+            let script = to_script_pubkey_bytes(version, &[0xab; 32]).expect("valid program");
+            let want = if v == 0 { 0x00 } else { 0x50 + v };
+            assert_eq!(script[0], want);
+            assert_eq!(from_script_pubkey(&script), Ok((version, &script[2..])));
+        }
+    }
+
+    #[test]
+    fn invalid_program_appends_nothing() {
+        let mut out = vec![0xff];
+        assert!(matches!(
+            script_pubkey(Fe32(17), &[0xab; 20], &mut out),
+            Err(WitnessProgramError::WitnessVersion(_))
+        ));
+        assert!(matches!(
+            script_pubkey(segwit::VERSION_0, &[0xab; 21], &mut out),
+            Err(WitnessProgramError::WitnessLength(_))
+        ));
+        assert_eq!(out, [0xff]);
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        use ScriptPubkeyError::*;
+
+        let mut p2wpkh = vec![0x00, 0x14];
+        p2wpkh.extend_from_slice(&[0xab; 20]);
+
+        assert_eq!(from_script_pubkey(&[]), Err(TooShort { len: 0 }));
+        assert_eq!(from_script_pubkey(&[0x51]), Err(TooShort { len: 1 }));
+        // OP_1NEGATE, OP_RESERVED and OP_NOP sit either side of the version opcodes.
+        for &op in [0x4f, 0x50, 0x61].iter() {
+            assert_eq!(from_script_pubkey(&[op, 0x02, 0xab, 0xab]), Err(InvalidOpcode(op)));
+        }
+        // Push too long, too short, and a trailing byte.
+        assert_eq!(from_script_pubkey(&p2wpkh[..21]), Err(PushLength { push: 0x14, len: 19 }));
+        p2wpkh.push(0xab);
+        assert_eq!(from_script_pubkey(&p2wpkh), Err(PushLength { push: 0x14, len: 21 }));
+        // OP_PUSHDATA1 is not a direct push.
+        assert_eq!(
+            from_script_pubkey(&[0x51, 0x4c, 0x02, 0xab, 0xab]),
+            Err(PushLength { push: 0x4c, len: 3 })
+        );
+        // Pushes that are the wrong length for the version.
+        assert_eq!(
+            from_script_pubkey(&[0x00, 0x02, 0xab, 0xab]),
+            Err(WitnessLength(WitnessLengthError::InvalidSegwitV0 { len: 2 }))
+        );
+        assert_eq!(
+            from_script_pubkey(&[0x51, 0x01, 0xab]),
+            Err(WitnessLength(WitnessLengthError::TooShort { len: 1, min: 2 }))
+        );
+        let mut long = vec![0x51, 41];
+        long.extend_from_slice(&[0xab; 41]);
+        assert_eq!(
+            from_script_pubkey(&long),
+            Err(WitnessLength(WitnessLengthError::TooLong { len: 41, max: 40 }))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT
+
+//! Statistics about decoding segwit addresses, for capacity planning and monitoring.
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::convert::TryFrom;
+
+use super::{Case, DecodeError};
+use crate::primitives::decode::{SegwitHrpstring, SegwitHrpstringError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+
+/// The result of decoding, as returned by [`decode`](super::decode).
+type DecodeResult = Result<(Hrp, Fe32, Vec<u8>), DecodeError>;
+
+/// Statistics about a single call to [`decode_with_stats`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DecodeStats {
+    /// The length of the input string, in bytes.
+    pub input_len: usize,
+    /// The length of the data part, everything after the last separator, in bytes.
+    ///
+    /// Zero if the input has no separator.
+    pub data_len: usize,
+    /// The case of the input, `None` if it is mixed case.
+    pub case: Option<Case>,
+    /// Whether decoding got far enough to verify the checksum, whether or not it was valid.
+    pub checksum_checked: bool,
+    /// The time taken to decode, in nanoseconds.
+    ///
+    /// Only measured with the `std` feature, always `None` otherwise.
+    pub ns_total: Option<u64>,
+}
+
+/// Decodes a segwit address like [`decode`](super::decode), also returning statistics about it.
+///
+/// The statistics are returned whether or not decoding succeeds, so that rejected inputs can be
+/// analyzed too. [`decode`](super::decode) itself is unchanged by the `metrics` feature.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit::{self, Case};
+///
+/// let (res, stats) = segwit::decode_with_stats("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q");
+/// assert!(res.is_err());
+/// assert_eq!(stats.input_len, 43);
+/// assert_eq!(stats.case, Some(Case::Lower));
+/// assert!(stats.checksum_checked);
+/// ```
+pub fn decode_with_stats(s: &str) -> (DecodeResult, DecodeStats) {
+    #[cfg(feature = "std")]
+    let start = std::time::Instant::now();
+
+    let res = SegwitHrpstring::new(s)
+        .map(|segwit| (segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect()));
+
+    #[cfg(feature = "std")]
+    let ns_total = Some(u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX));
+    #[cfg(not(feature = "std"))]
+    let ns_total = None;
+
+    let checksum_checked = match res {
+        Ok(_) => true,
+        Err(ref e) => checksum_checked(e),
+    };
+    let stats = DecodeStats {
+        input_len: s.len(),
+        data_len: s.rfind('1').map_or(0, |pos| s.len() - pos - 1),
+        case: case_of(s),
+        checksum_checked,
+        ns_total,
+    };
+    (res.map_err(DecodeError), stats)
+}
+
+/// Returns whether the checksum had been verified when parsing failed with `e`.
+fn checksum_checked(e: &SegwitHrpstringError) -> bool {
+    use SegwitHrpstringError::*;
+
+    match *e {
+        Unchecked(_) | NoData | TooLong { .. } | InvalidMaxLength(_) | InvalidWitnessVersion(_) =>
+            false,
+        Checksum(_)
+        | WitnessLength(_)
+        | Padding(_)
+        | WrongHrp { .. }
+        | ProbablyTruncated { .. } => true,
+    }
+}
+
+/// Returns the case of `s`, or `None` if it is mixed case.
+fn case_of(s: &str) -> Option<Case> {
+    let upper = s.bytes().any(|b| b.is_ascii_uppercase());
+    let lower = s.bytes().any(|b| b.is_ascii_lowercase());
+    match (upper, lower) {
+        (true, true) => None,
+        (true, false) => Some(Case::Upper),
+        (false, _) => Some(Case::Lower),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::{CharError, UncheckedHrpstringError};
+    use crate::segwit::VERSION_0;
+
+    #[test]
+    fn valid() {
+        let s = "GRS1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZNCG65P";
+        let (res, stats) = decode_with_stats(s);
+
+        assert_eq!(res, crate::segwit::decode(s));
+        assert_eq!(res.unwrap().1, VERSION_0);
+        assert_eq!(stats.input_len, 43);
+        assert_eq!(stats.data_len, 39);
+        assert_eq!(stats.case, Some(Case::Upper));
+        assert!(stats.checksum_checked);
+        #[cfg(feature = "std")]
+        assert!(stats.ns_total.is_some());
+        #[cfg(not(feature = "std"))]
+        assert_eq!(stats.ns_total, None);
+    }
+
+    #[test]
+    fn invalid() {
+        let s = "grs1qar0srrr7xfkvy5l643lYdnw9re59gtzzncg65p";
+        let (res, stats) = decode_with_stats(s);
+
+        let e = DecodeError(SegwitHrpstringError::Unchecked(UncheckedHrpstringError::Char(
+            CharError::MixedCase,
+        )));
+        assert_eq!(res.unwrap_err(), e);
+        assert_eq!(stats.input_len, 43);
+        assert_eq!(stats.data_len, 39);
+        assert_eq!(stats.case, None);
+        assert!(!stats.checksum_checked);
+
+        let (res, stats) = decode_with_stats("no separator");
+        assert!(res.is_err());
+        assert_eq!(stats.data_len, 0);
+        assert!(!stats.checksum_checked);
+
+        let (res, stats) = decode_with_stats("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q");
+        assert!(matches!(res, Err(DecodeError(SegwitHrpstringError::Checksum(_)))));
+        assert_eq!(stats.case, Some(Case::Lower));
+        assert!(stats.checksum_checked);
+    }
+}
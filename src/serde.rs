@@ -0,0 +1,10 @@
+// SPDX-License-Identifier: MIT
+
+//! Helpers for (de)serializing with [`serde`].
+//!
+//! Enabled by the `serde` feature, requires `alloc`. The helper modules are intended for use with
+//! the `#[serde(with = "...")]` field attribute.
+//!
+//! [`serde`]: <https://docs.rs/serde>
+
+pub mod segwit;
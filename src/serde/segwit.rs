@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: MIT
+
+//! Serde helper for [`segwit::Address`] fields.
+//!
+//! Human-readable formats (e.g. JSON) use the bech32 encoded address string. Binary formats (e.g.
+//! bincode) use a tuple of the human-readable part as a lowercase string, the witness version as a
+//! `u8`, and the witness program as bytes. Both are canonical, an address parsed from an uppercase
+//! string serializes the same as the lowercase one.
+//!
+//! Deserialization does the same validation as parsing or constructing an [`segwit::Address`],
+//! validation errors are returned using the error type of the deserializer.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(test_deps)] {
+//! use bech32grs::segwit::Address;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Payout {
+//!     #[serde(with = "bech32grs::serde::segwit")]
+//!     address: Address,
+//!     amount: u64,
+//! }
+//!
+//! let json = r#"{"address":"grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc","amount":1000}"#;
+//! let payout = serde_json::from_str::<Payout>(json).expect("valid address");
+//! assert_eq!(payout.address.witness_program(), &[0xab; 20]);
+//! # }
+//! ```
+//!
+//! [`segwit::Address`]: crate::segwit::Address
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::segwit::Address;
+
+/// Serializes `address` as a string for human-readable formats, or as parts for binary formats.
+pub fn serialize<S: Serializer>(address: &Address, s: S) -> Result<S::Ok, S::Error> {
+    if s.is_human_readable() {
+        s.collect_str(address)
+    } else {
+        let mut tuple = s.serialize_tuple(3)?;
+        tuple.serialize_element(&address.hrp().to_lowercase())?;
+        tuple.serialize_element(&address.witness_version().to_u8())?;
+        tuple.serialize_element(&Bytes(address.witness_program()))?;
+        tuple.end()
+    }
+}
+
+/// Deserializes and validates an address serialized with [`serialize`].
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Address, D::Error> {
+    if d.is_human_readable() {
+        d.deserialize_str(AddressStrVisitor)
+    } else {
+        d.deserialize_tuple(3, AddressPartsVisitor)
+    }
+}
+
+/// Serializes a witness program using the serde bytes type rather than as a sequence.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a witness program from the serde bytes type, or a sequence of `u8`s.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+struct ByteBufVisitor;
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = ByteBuf;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("witness program bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(ByteBuf(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> { Ok(ByteBuf(v)) }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(64));
+        while let Some(b) = seq.next_element::<u8>()? {
+            v.push(b);
+        }
+        Ok(ByteBuf(v))
+    }
+}
+
+struct AddressStrVisitor;
+
+impl<'de> Visitor<'de> for AddressStrVisitor {
+    type Value = Address;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("a segwit address") }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse::<Address>().map_err(E::custom)
+    }
+}
+
+struct AddressPartsVisitor;
+
+impl<'de> Visitor<'de> for AddressPartsVisitor {
+    type Value = Address;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a tuple of human-readable part, witness version, and witness program")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let hrp =
+            seq.next_element::<String>()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version =
+            seq.next_element::<u8>()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let program =
+            seq.next_element::<ByteBuf>()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+        let hrp = Hrp::parse(&hrp).map_err(de::Error::custom)?;
+        let version = Fe32::try_from(version).map_err(de::Error::custom)?;
+        Address::new(hrp, version, program.0).map_err(de::Error::custom)
+    }
+}
+
+// The derive and the formats are dev-dependencies that need a newer toolchain than the MSRV.
+#[cfg(all(test, test_deps))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::primitives::hrp;
+    use crate::segwit::{Address, VERSION_0};
+
+    const ADDRESS: &str = "grs1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnslkylay";
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Payout {
+        #[serde(with = "crate::serde::segwit")]
+        address: Address,
+        amount: u64,
+    }
+
+    fn payout() -> Payout {
+        Payout { address: ADDRESS.parse().expect("valid address"), amount: 100_000 }
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let json = serde_json::to_string(&payout()).expect("failed to serialize");
+        assert_eq!(json, format!("{{\"address\":\"{}\",\"amount\":100000}}", ADDRESS));
+
+        let got = serde_json::from_str::<Payout>(&json).expect("failed to deserialize");
+        assert_eq!(got, payout());
+    }
+
+    #[test]
+    fn json_uppercase_address() {
+        let json = format!("{{\"address\":\"{}\",\"amount\":100000}}", ADDRESS.to_uppercase());
+        let got = serde_json::from_str::<Payout>(&json).expect("failed to deserialize");
+        assert_eq!(got, payout());
+    }
+
+    #[test]
+    fn json_invalid_address() {
+        // Last character of the checksum changed.
+        let address = ADDRESS.replace("lay", "laz");
+        let json = format!("{{\"address\":\"{}\",\"amount\":100000}}", address);
+        let err = serde_json::from_str::<Payout>(&json).unwrap_err();
+        assert!(err.to_string().contains("decoding segwit address failed"));
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let bytes = bincode::serialize(&payout()).expect("failed to serialize");
+        let got = bincode::deserialize::<Payout>(&bytes).expect("failed to deserialize");
+        assert_eq!(got, payout());
+    }
+
+    #[test]
+    fn bincode_serializes_parts() {
+        let bytes = bincode::serialize(&payout()).expect("failed to serialize");
+        let (hrp, version, program, amount) =
+            bincode::deserialize::<(String, u8, Vec<u8>, u64)>(&bytes)
+                .expect("failed to deserialize");
+
+        assert_eq!(hrp, hrp::GRS.as_str());
+        assert_eq!(version, VERSION_0.to_u8());
+        assert_eq!(program, payout().address.witness_program());
+        assert_eq!(amount, 100_000);
+    }
+
+    #[test]
+    fn bincode_uppercase_address() {
+        let upper = Payout { address: ADDRESS.to_uppercase().parse().unwrap(), amount: 100_000 };
+        let bytes = bincode::serialize(&upper).expect("failed to serialize");
+        assert_eq!(bytes, bincode::serialize(&payout()).unwrap());
+
+        let (hrp, ..) = bincode::deserialize::<(String, u8, Vec<u8>, u64)>(&bytes).unwrap();
+        assert_eq!(hrp, "grs");
+    }
+
+    #[test]
+    fn bincode_invalid_witness_length() {
+        // Segwit v0 witness program must be 20 or 32 bytes long.
+        let bytes = bincode::serialize(&("grs", 0_u8, vec![0_u8; 21], 100_000_u64))
+            .expect("failed to serialize");
+        assert!(bincode::deserialize::<Payout>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+// SPDX-License-Identifier: MIT
+
+//! Encoding and decoding of [BIP-352] silent payment addresses.
+//!
+//! A silent payment address is the bech32m encoding of a version followed by the receiver's scan
+//! and spend public keys. Addresses can be up to 1023 characters long, version 0 addresses are
+//! 116 characters long on mainnet so they are rejected by the segwit decoding functions.
+//!
+//! The keys are returned as serialized compressed public keys, they are not validated.
+//!
+//! Enabled by the `sp` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32grs::sp::{self, Network};
+//!
+//! // From the test vectors in BIP-352.
+//! let address = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+//!
+//! let decoded = sp::decode(address).expect("valid address");
+//! assert_eq!(decoded.network(), Network::Mainnet);
+//! assert_eq!(sp::encode(decoded.network(), decoded.scan_key(), decoded.spend_key()), address);
+//! ```
+//!
+//! [BIP-352]: <https://github.com/bitcoin/bips/blob/master/bip-0352.mediawiki>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+use crate::error::{write_err, Code};
+use crate::primitives::decode::{CheckedHrpstringError, PaddingError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::{Bech32m, Checksum};
+
+/// The maximum length of a silent payment address.
+pub const MAX_LENGTH: usize = 1023;
+
+/// The length of a serialized compressed public key.
+pub const KEY_LENGTH: usize = 33;
+
+/// The only version of silent payment addresses defined so far.
+pub const VERSION_0: Fe32 = Fe32::Q;
+
+/// The version reserved for a backwards incompatible change, never valid.
+const VERSION_31: Fe32 = Fe32::L;
+
+/// The network of a silent payment address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Network {
+    /// Mainnet, addresses start with `sp`.
+    Mainnet,
+    /// Testnet and signet, addresses start with `tsp`.
+    Testnet,
+}
+
+impl Network {
+    /// Returns the human-readable part of addresses for this network.
+    #[inline]
+    pub fn hrp(self) -> Hrp {
+        match self {
+            Network::Mainnet => Hrp::parse_unchecked("sp"),
+            Network::Testnet => Hrp::parse_unchecked("tsp"),
+        }
+    }
+
+    /// Returns the network that uses `hrp`, the comparison is case-insensitive.
+    pub fn from_hrp(hrp: &Hrp) -> Option<Self> {
+        [Network::Mainnet, Network::Testnet].iter().copied().find(|network| network.hrp() == *hrp)
+    }
+}
+
+/// A decoded silent payment address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SilentPaymentAddress {
+    /// The network the address is for.
+    network: Network,
+    /// The address version.
+    version: Fe32,
+    /// The serialized scan public key.
+    scan_key: [u8; KEY_LENGTH],
+    /// The serialized spend public key.
+    spend_key: [u8; KEY_LENGTH],
+}
+
+impl SilentPaymentAddress {
+    /// Returns the network the address is for.
+    #[inline]
+    pub fn network(&self) -> Network { self.network }
+
+    /// Returns the address version.
+    #[inline]
+    pub fn version(&self) -> Fe32 { self.version }
+
+    /// Returns the serialized scan public key.
+    #[inline]
+    pub fn scan_key(&self) -> &[u8; KEY_LENGTH] { &self.scan_key }
+
+    /// Returns the serialized spend public key.
+    #[inline]
+    pub fn spend_key(&self) -> &[u8; KEY_LENGTH] { &self.spend_key }
+}
+
+/// Encodes a version 0 silent payment address as a lowercase string.
+pub fn encode(
+    network: Network,
+    scan_key: &[u8; KEY_LENGTH],
+    spend_key: &[u8; KEY_LENGTH],
+) -> String {
+    let hrp = network.hrp();
+    let data_len = 1 + (2 * KEY_LENGTH * 8 + 4) / 5; // +1 for the version
+    let len = hrp.len() + 1 + data_len + Bech32m::CHECKSUM_LENGTH; // +1 for separator
+
+    let iter = scan_key.iter().chain(spend_key.iter()).copied().bytes_to_fes();
+    let mut s = String::with_capacity(len);
+    s.extend(iter.with_checksum::<Bech32m>(&hrp).with_witness_version(VERSION_0).chars());
+    s
+}
+
+/// Decodes a silent payment address.
+///
+/// Version 0 addresses must contain exactly two keys. As specified by BIP-352, addresses with a
+/// version from 1 to 30 may contain more data after the keys which is ignored, and version 31
+/// addresses are invalid.
+pub fn decode(s: &str) -> Result<SilentPaymentAddress, SpError> {
+    let checked = crate::decode_long::<Bech32m>(s, MAX_LENGTH)?;
+
+    let hrp = checked.hrp();
+    let network = Network::from_hrp(&hrp).ok_or(SpError::UnknownHrp(hrp))?;
+
+    // Versions go up to 31 so we cannot use `remove_witness_version`, which stops at 16.
+    let (&first, data) =
+        checked.data_part_ascii_no_checksum().split_first().ok_or(SpError::NoData)?;
+    let version = Fe32::from_char_unchecked(first);
+    if version == VERSION_31 {
+        return Err(SpError::InvalidVersion(version));
+    }
+    validate_padding(data)?;
+
+    let len = data.len() * 5 / 8;
+    if len < 2 * KEY_LENGTH || (version == VERSION_0 && len != 2 * KEY_LENGTH) {
+        return Err(SpError::InvalidLength { version, len });
+    }
+
+    let mut scan_key = [0_u8; KEY_LENGTH];
+    let mut spend_key = [0_u8; KEY_LENGTH];
+    let bytes = data.iter().map(|&b| Fe32::from_char_unchecked(b)).fes_to_bytes();
+    for (dst, src) in scan_key.iter_mut().chain(spend_key.iter_mut()).zip(bytes) {
+        *dst = src;
+    }
+
+    Ok(SilentPaymentAddress { network, version, scan_key, spend_key })
+}
+
+/// Validates the padding of `data`, the ASCII characters after the version.
+///
+/// Same rules as [`CheckedHrpstring::validate_segwit_padding`]: at most 4 bits of padding, all
+/// zero.
+///
+/// [`CheckedHrpstring::validate_segwit_padding`]: crate::primitives::decode::CheckedHrpstring::validate_segwit_padding
+fn validate_padding(data: &[u8]) -> Result<(), PaddingError> {
+    let padding_len = data.len() * 5 % 8;
+    if padding_len > 4 {
+        return Err(PaddingError::TooMuch);
+    }
+    let last = data.last().map(|&b| Fe32::from_char_unchecked(b).to_u8()).unwrap_or(0);
+    if last & ((1 << padding_len) - 1) != 0 {
+        return Err(PaddingError::NonZero);
+    }
+    Ok(())
+}
+
+/// An error while decoding a silent payment address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum SpError {
+    /// Parsing the bech32m string failed.
+    Parse(CheckedHrpstringError),
+    /// The human-readable part is not `sp` or `tsp`.
+    UnknownHrp(Hrp),
+    /// The address has no data, not even a version.
+    NoData,
+    /// The address version is 31, which is reserved.
+    InvalidVersion(Fe32),
+    /// The padding of the data is invalid.
+    Padding(PaddingError),
+    /// The length of the data, in bytes, is invalid for the address version.
+    InvalidLength {
+        /// The address version.
+        version: Fe32,
+        /// The length of the data after the version, in bytes.
+        len: usize,
+    },
+}
+
+impl SpError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use SpError::*;
+
+        match *self {
+            Parse(ref e) => e.error_code(),
+            UnknownHrp(_) => Code::SpUnknownHrp,
+            NoData => Code::NoData,
+            InvalidVersion(_) => Code::SpVersion,
+            Padding(ref e) => e.error_code(),
+            InvalidLength { .. } => Code::SpLength,
+        }
+    }
+}
+
+impl fmt::Display for SpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SpError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing silent payment address failed"; e),
+            UnknownHrp(ref hrp) => write!(f, "unknown silent payment human-readable part: {}", hrp),
+            NoData => write!(f, "no data found in silent payment address"),
+            InvalidVersion(v) => write!(f, "invalid silent payment address version: {}", v),
+            Padding(ref e) => write_err!(f, "invalid silent payment address padding"; e),
+            InvalidLength { version, len } => write!(
+                f,
+                "invalid data length {} bytes for silent payment address version {}",
+                len,
+                version.to_u8()
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for SpError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use SpError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            Padding(ref e) => Some(e),
+            UnknownHrp(_) | NoData | InvalidVersion(_) | InvalidLength { .. } => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for SpError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+impl From<PaddingError> for SpError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the test vectors in BIP-352.
+    const ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+    #[rustfmt::skip]
+    const SCAN_KEY: [u8; KEY_LENGTH] = [
+        0x02, 0x20, 0xbc, 0xfa, 0xc5, 0xb9, 0x9e, 0x04, 0xad, 0x1a, 0x06, 0xdd, 0xfb, 0x01, 0x6e,
+        0xe1, 0x35, 0x82, 0x60, 0x9d, 0x60, 0xb6, 0x29, 0x1e, 0x98, 0xd0, 0x1a, 0x9b, 0xc9, 0xa1,
+        0x6c, 0x96, 0xd4,
+    ];
+    #[rustfmt::skip]
+    const SPEND_KEY: [u8; KEY_LENGTH] = [
+        0x02, 0x5c, 0xc9, 0x85, 0x6d, 0x6f, 0x83, 0x75, 0x35, 0x0e, 0x12, 0x39, 0x78, 0xda, 0xac,
+        0x20, 0x0c, 0x26, 0x0c, 0xb5, 0xb5, 0xae, 0x83, 0x10, 0x6c, 0xab, 0x90, 0x48, 0x4d, 0xcd,
+        0x8f, 0xcf, 0x36,
+    ];
+
+    /// Encodes `data` with `version` and the `hrp` of `network`, without any length checks.
+    fn encode_raw(network: Network, version: Fe32, data: &[u8]) -> String {
+        let hrp = network.hrp();
+        let iter = data.iter().copied().bytes_to_fes();
+        iter.with_checksum::<Bech32m>(&hrp).with_witness_version(version).chars().collect()
+    }
+
+    #[test]
+    fn bip352_vector() {
+        let decoded = decode(ADDRESS).expect("valid address");
+        assert_eq!(decoded.network(), Network::Mainnet);
+        assert_eq!(decoded.version(), VERSION_0);
+        assert_eq!(decoded.scan_key(), &SCAN_KEY);
+        assert_eq!(decoded.spend_key(), &SPEND_KEY);
+
+        assert_eq!(decode(&ADDRESS.to_uppercase()).expect("valid address"), decoded);
+        assert_eq!(encode(Network::Mainnet, &SCAN_KEY, &SPEND_KEY), ADDRESS);
+        assert!(crate::segwit::decode(ADDRESS).is_err());
+    }
+
+    #[test]
+    fn testnet_roundtrip() {
+        let s = encode(Network::Testnet, &SCAN_KEY, &SPEND_KEY);
+        assert!(s.starts_with("tsp1q"));
+        assert_eq!(s.capacity(), s.len());
+
+        let decoded = decode(&s).expect("valid address");
+        assert_eq!(decoded.network(), Network::Testnet);
+        assert_eq!(decoded.scan_key(), &SCAN_KEY);
+        assert_eq!(decoded.spend_key(), &SPEND_KEY);
+    }
+
+    #[test]
+    fn future_versions_ignore_extra_data() {
+        let mut data = [0xab_u8; 100];
+        data[..KEY_LENGTH].copy_from_slice(&SCAN_KEY);
+        data[KEY_LENGTH..2 * KEY_LENGTH].copy_from_slice(&SPEND_KEY);
+
+        let s = encode_raw(Network::Mainnet, Fe32::P, &data);
+        let decoded = decode(&s).expect("valid address");
+        assert_eq!(decoded.version(), Fe32::P);
+        assert_eq!(decoded.scan_key(), &SCAN_KEY);
+        assert_eq!(decoded.spend_key(), &SPEND_KEY);
+
+        let s = encode_raw(Network::Mainnet, VERSION_0, &data);
+        assert_eq!(
+            decode(&s).unwrap_err(),
+            SpError::InvalidLength { version: VERSION_0, len: 100 }
+        );
+
+        let s = encode_raw(Network::Mainnet, VERSION_31, &data);
+        assert_eq!(decode(&s).unwrap_err(), SpError::InvalidVersion(VERSION_31));
+    }
+
+    #[test]
+    fn invalid_addresses() {
+        let s = encode_raw(Network::Mainnet, VERSION_0, &[0; 2 * KEY_LENGTH - 1]);
+        assert_eq!(decode(&s).unwrap_err(), SpError::InvalidLength { version: VERSION_0, len: 65 });
+
+        let s = encode_raw(Network::Mainnet, Fe32::P, &[0; 2 * KEY_LENGTH - 1]);
+        assert_eq!(decode(&s).unwrap_err(), SpError::InvalidLength { version: Fe32::P, len: 65 });
+
+        let s =
+            crate::encode::<Bech32m>(Hrp::parse_unchecked("sprt"), &[0; 67]).expect("valid length");
+        assert_eq!(decode(&s).unwrap_err(), SpError::UnknownHrp(Hrp::parse_unchecked("sprt")));
+
+        let s = crate::encode::<Bech32m>(Network::Mainnet.hrp(), &[]).expect("valid length");
+        assert_eq!(decode(&s).unwrap_err(), SpError::NoData);
+
+        let s =
+            crate::encode::<crate::Bech32>(Network::Mainnet.hrp(), &[0; 67]).expect("valid length");
+        assert!(matches!(decode(&s).unwrap_err(), SpError::Parse(_)));
+    }
+
+    #[test]
+    fn invalid_padding() {
+        let hrp = Network::Mainnet.hrp();
+        let encode_fes = |fes: &[Fe32]| -> String {
+            let iter = core::iter::once(VERSION_0).chain(fes.iter().copied());
+            iter.with_checksum::<Bech32m>(&hrp).chars().collect()
+        };
+
+        let mut fes = [Fe32::Q; 107];
+        assert!(decode(&encode_fes(&fes[..106])).is_ok());
+        assert_eq!(decode(&encode_fes(&fes)).unwrap_err(), SpError::Padding(PaddingError::TooMuch));
+
+        fes[105] = Fe32::P;
+        assert_eq!(
+            decode(&encode_fes(&fes[..106])).unwrap_err(),
+            SpError::Padding(PaddingError::NonZero)
+        );
+    }
+}
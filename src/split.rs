@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MIT
+
+//! Splitting a bech32 string into its human-readable part, data and checksum.
+//!
+//! Nothing other than the position of the separator is checked, so the parts of invalid strings
+//! can be located too, e.g. to highlight them in a user interface.
+
+use core::fmt;
+use core::ops::Range;
+
+use crate::error::Code;
+#[cfg(any(feature = "std", feature = "core-error"))]
+use crate::error::StdError;
+
+/// The separator between the human-readable part and the data part.
+const SEP: char = '1';
+
+/// The number of checksum characters of the bech32 and bech32m checksums.
+const CHECKSUM_LENGTH: usize = 6;
+
+/// The byte ranges of the parts of a bech32 string, see [`split`].
+///
+/// The separator is the single byte between `hrp` and `data`. The ranges are always on character
+/// boundaries so can be used to slice the string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spans {
+    /// The human-readable part, everything before the separator.
+    pub hrp: Range<usize>,
+    /// The data, everything between the separator and the checksum.
+    pub data: Range<usize>,
+    /// The checksum, the last characters of the string.
+    pub checksum: Range<usize>,
+}
+
+/// Splits `s` into its human-readable part, data and a six character checksum.
+///
+/// The separator is the last `1` in the string. Neither the characters nor the checksum are
+/// validated, use [`split_with_checksum_length`] for checksums of a length other than six.
+///
+/// # Errors
+///
+/// If `s` has no separator, or fewer than six characters after the separator.
+///
+/// # Examples
+///
+/// ```
+/// let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+/// let spans = bech32grs::split(s).expect("has separator and checksum");
+/// assert_eq!(&s[spans.hrp], "grs");
+/// assert_eq!(&s[spans.data], "qar0srrr7xfkvy5l643lydnw9re59gtzz");
+/// assert_eq!(&s[spans.checksum], "ncg65p");
+///
+/// // Invalid characters and checksums are not a problem.
+/// let s = "GRS1invalid!!!";
+/// let spans = bech32grs::split(s).expect("has separator and checksum");
+/// assert_eq!(&s[spans.data], "inva");
+/// ```
+#[inline]
+pub fn split(s: &str) -> Result<Spans, SplitError> {
+    split_with_checksum_length(s, CHECKSUM_LENGTH)
+}
+
+/// Splits `s` into its human-readable part, data and a checksum of `checksum_length` characters.
+///
+/// See [`split`], a `checksum_length` of zero gives an empty checksum span.
+pub fn split_with_checksum_length(s: &str, checksum_length: usize) -> Result<Spans, SplitError> {
+    let sep = s.rfind(SEP).ok_or(SplitError::MissingSeparator)?;
+    let data_start = sep + 1; // The separator is a single byte.
+
+    // Count characters not bytes, so that the spans are on character boundaries.
+    let data_part = &s[data_start..];
+    let checksum_start = if checksum_length == 0 {
+        s.len()
+    } else {
+        match data_part.char_indices().rev().nth(checksum_length - 1) {
+            Some((i, _)) => data_start + i,
+            None => {
+                let len = data_part.chars().count();
+                return Err(SplitError::TooShort { len, checksum_length });
+            }
+        }
+    };
+
+    Ok(Spans { hrp: 0..sep, data: data_start..checksum_start, checksum: checksum_start..s.len() })
+}
+
+/// An error while splitting a bech32 string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum SplitError {
+    /// String does not contain the separator character.
+    MissingSeparator,
+    /// Fewer characters after the separator than the length of the checksum.
+    TooShort {
+        /// The number of characters after the separator.
+        len: usize,
+        /// The number of characters in the checksum.
+        checksum_length: usize,
+    },
+}
+
+impl SplitError {
+    /// Returns the machine-readable code of this error, see [`Error::code`](crate::Error::code).
+    #[inline]
+    pub fn code(&self) -> &'static str { self.error_code().as_str() }
+
+    /// Returns the code of this error.
+    pub(crate) fn error_code(&self) -> Code {
+        use SplitError::*;
+
+        match *self {
+            MissingSeparator => Code::MissingSeparator,
+            TooShort { .. } => Code::ChecksumLength,
+        }
+    }
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SplitError::*;
+
+        match *self {
+            MissingSeparator => write!(f, "missing human-readable separator, \"{}\"", SEP),
+            TooShort { len, checksum_length } => write!(
+                f,
+                "{} characters after the separator is too short for a {} character checksum",
+                len, checksum_length
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for SplitError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use SplitError::*;
+
+        match *self {
+            MissingSeparator | TooShort { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the parts of `s` as string slices.
+    fn parts(s: &str) -> (&str, &str, &str) {
+        let spans = split(s).expect("valid split");
+        assert_eq!(spans.hrp.end + 1, spans.data.start, "one byte separator");
+        assert_eq!(spans.data.end, spans.checksum.start);
+        assert_eq!(spans.checksum.end, s.len());
+        (&s[spans.hrp], &s[spans.data], &s[spans.checksum])
+    }
+
+    #[test]
+    fn valid_strings() {
+        let valid = [
+            ("A12UEL5L", ("A", "", "2UEL5L")),
+            ("a12uel5l", ("a", "", "2uel5l")),
+            ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", ("abcdef", "qpzry9x8gf2tvdw0s3jn54khce6mua7l", "mqqqxw")),
+            ("11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j", ("1", "qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq", "c8247j")),
+            ("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", ("split", "checkupstagehandshakeupstreamerranterredcaperred", "2y9e3w")),
+            ("?1ezyfcl", ("?", "", "ezyfcl")),
+            ("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p", ("grs", "qar0srrr7xfkvy5l643lydnw9re59gtzz", "ncg65p")),
+        ];
+        for (s, want) in valid.iter() {
+            assert_eq!(parts(s), *want, "{}", s);
+        }
+    }
+
+    #[test]
+    fn broken_strings() {
+        let broken = [
+            ("1qqqqqq", ("", "", "qqqqqq")),           // Empty hrp.
+            ("grs1bbio!!!", ("grs", "b", "bio!!!")),   // Invalid characters.
+            ("gRs1QaR0sRrR", ("gRs", "Qa", "R0sRrR")), // Mixed case.
+            (
+                "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q",
+                ("grs", "qar0srrr7xfkvy5l643lydnw9re59gtzz", "ncg65q"),
+            ), // Bad checksum.
+            ("\u{e9}1qqqqqq\u{e9}\u{e9}", ("\u{e9}", "qq", "qqqq\u{e9}\u{e9}")), // Non-ASCII.
+            ("a 1 qqqqqq", ("a ", " ", "qqqqqq")),     // Whitespace.
+        ];
+        for (s, want) in broken.iter() {
+            assert_eq!(parts(s), *want, "{}", s);
+        }
+    }
+
+    #[test]
+    fn last_separator_is_used() {
+        assert_eq!(parts("a1b1c1qqqqqq"), ("a1b1c", "", "qqqqqq"));
+        // A '1' in the checksum region still counts as the separator.
+        assert_eq!(
+            split("abc1qqqqq1qq").unwrap_err(),
+            SplitError::TooShort { len: 2, checksum_length: 6 }
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(split("").unwrap_err(), SplitError::MissingSeparator);
+        assert_eq!(split("qqqqqqqqq").unwrap_err(), SplitError::MissingSeparator);
+        assert_eq!(split("grs1").unwrap_err(), SplitError::TooShort { len: 0, checksum_length: 6 });
+        assert_eq!(
+            split("grs1qqqqq").unwrap_err(),
+            SplitError::TooShort { len: 5, checksum_length: 6 }
+        );
+        // Characters not bytes.
+        let s = "grs1\u{e9}\u{e9}\u{e9}";
+        assert_eq!(split(s).unwrap_err(), SplitError::TooShort { len: 3, checksum_length: 6 });
+    }
+
+    #[test]
+    fn other_checksum_lengths() {
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        let spans = split_with_checksum_length(s, 13).expect("valid split");
+        assert_eq!(&s[spans.hrp], "ms");
+        assert_eq!(&s[spans.data], "0testsxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        assert_eq!(&s[spans.checksum], "4nzvca9cmczlw");
+
+        let spans = split_with_checksum_length("lno1pqps7", 0).expect("valid split");
+        assert_eq!(spans, Spans { hrp: 0..3, data: 4..9, checksum: 9..9 });
+        assert!(split_with_checksum_length("a1", 0).expect("valid split").data.is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT
+
+//! Generated segwit address test vectors for downstream test suites.
+//!
+//! [`generate_vectors`] returns valid Groestlcoin segwit addresses of every shape, for wallets and
+//! other users of this crate to test their own address handling against. The vectors are encoded
+//! by this crate and are deterministic, the same seed always gives the same vectors.
+//!
+//! This module is enabled by the `test-utils` feature.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+use crate::primitives::segwit::{
+    MAX_WITNESS_PROGRAM_LENGTH, MIN_WITNESS_PROGRAM_LENGTH, VERSION_0_PROGRAM_LENGTHS,
+};
+use crate::segwit::{self, Case, VERSION_0};
+
+/// The highest segwit witness version.
+const MAX_WITNESS_VERSION: u8 = 16;
+
+/// A valid segwit address, returned by [`generate_vectors`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct GeneratedVector {
+    /// The human-readable part, [`hrp::GRS`] or [`hrp::TGRS`].
+    pub hrp: Hrp,
+    /// The witness version.
+    pub version: Fe32,
+    /// The witness program.
+    pub program: Vec<u8>,
+    /// The address in its canonical lowercase form.
+    pub lower: String,
+    /// The address in uppercase, as used in QR codes.
+    pub upper: String,
+}
+
+/// Generates valid segwit addresses covering every witness version and program length.
+///
+/// There is one vector for each combination of human-readable part ([`hrp::GRS`] and
+/// [`hrp::TGRS`]), witness version and valid program length, in that order. The program bytes are
+/// pseudo-random, derived from `seed`.
+///
+/// # Examples
+///
+/// ```
+/// use bech32grs::segwit;
+/// use bech32grs::testutils::generate_vectors;
+///
+/// for v in generate_vectors(0) {
+///     let (hrp, version, program) = segwit::decode(&v.upper).expect("valid address");
+///     assert_eq!((hrp, version, program), (v.hrp, v.version, v.program));
+/// }
+/// ```
+pub fn generate_vectors(seed: u64) -> Vec<GeneratedVector> {
+    let mut rng = SplitMix64(seed);
+    let mut vectors = Vec::new();
+
+    for &hrp in [hrp::GRS, hrp::TGRS].iter() {
+        for v in 0..=MAX_WITNESS_VERSION {
+            let version = Fe32(v);
+            let lengths: Vec<usize> = if version == VERSION_0 {
+                VERSION_0_PROGRAM_LENGTHS.to_vec()
+            } else {
+                (MIN_WITNESS_PROGRAM_LENGTH..=MAX_WITNESS_PROGRAM_LENGTH).collect()
+            };
+            for len in lengths {
+                let program: Vec<u8> = (0..len).map(|_| rng.next_u8()).collect();
+                let lower = segwit::encode(hrp, version, &program)
+                    .expect("valid witness version and program length");
+                let upper = segwit::encode_with_case(Case::Upper, hrp, version, &program)
+                    .expect("valid witness version and program length");
+                vectors.push(GeneratedVector { hrp, version, program, lower, upper });
+            }
+        }
+    }
+    vectors
+}
+
+/// The SplitMix64 pseudo-random number generator, small and fixed so that vectors are stable.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u8(&mut self) -> u8 { (self.next_u64() >> 56) as u8 }
+}
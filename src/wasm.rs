@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT
+
+//! JavaScript bindings for encoding and decoding segwit addresses.
+//!
+//! Enabled by the `wasm` feature, intended for use with `wasm-pack` or `wasm-bindgen` when
+//! targeting `wasm32-unknown-unknown`.
+//!
+//! ```js
+//! import { encodeSegwit, decodeSegwit } from "bech32grs";
+//!
+//! const address = encodeSegwit("grs", 1, new Uint8Array(32));
+//! const decoded = decodeSegwit(address);
+//! console.log(decoded.hrp, decoded.version, decoded.program);
+//! ```
+
+// The `wasm` feature is not built on the MSRV toolchain, and `wasm_bindgen` generates code that
+// uses newer APIs.
+#![allow(clippy::incompatible_msrv)]
+
+use core::convert::TryFrom;
+use std::error::Error;
+
+use wasm_bindgen::prelude::*;
+
+use crate::primitives::decode::SegwitHrpstring;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::segwit;
+
+/// Encodes a segwit address, throws an `Error` if any of the parts are invalid.
+#[wasm_bindgen(js_name = encodeSegwit)]
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> Result<String, JsError> {
+    let hrp = Hrp::parse(hrp).map_err(|e| js_error(&e))?;
+    let version = Fe32::try_from(version).map_err(|e| js_error(&e))?;
+    segwit::encode(hrp, version, program).map_err(|e| js_error(&e))
+}
+
+/// Decodes a segwit address, throws an `Error` if the address is invalid.
+#[wasm_bindgen(js_name = decodeSegwit)]
+pub fn decode_segwit(address: &str) -> Result<DecodedAddress, JsError> {
+    let segwit = SegwitHrpstring::new(address).map_err(|e| js_error(&e))?;
+    Ok(DecodedAddress {
+        hrp: segwit.hrp().to_lowercase(),
+        version: segwit.witness_version().to_u8(),
+        program: segwit.byte_iter().collect(),
+    })
+}
+
+/// The parts of a decoded segwit address.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedAddress {
+    hrp: String,
+    version: u8,
+    program: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl DecodedAddress {
+    /// The human-readable part, lowercase.
+    #[wasm_bindgen(getter)]
+    pub fn hrp(&self) -> String { self.hrp.clone() }
+
+    /// The witness version.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> u8 { self.version }
+
+    /// The witness program, a `Uint8Array` in JavaScript.
+    #[wasm_bindgen(getter)]
+    pub fn program(&self) -> Vec<u8> { self.program.clone() }
+}
+
+/// Creates a `JsError` with the messages of `e` and all its sources.
+fn js_error(e: &(dyn Error + 'static)) -> JsError { JsError::new(&error_message(e)) }
+
+/// Joins the `Display` messages of `e` and all its sources.
+fn error_message(e: &(dyn Error + 'static)) -> String {
+    let mut msg = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        msg.push_str(": ");
+        msg.push_str(&e.to_string());
+        source = e.source();
+    }
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::SegwitHrpstringError;
+
+    #[test]
+    fn error_message_includes_sources() {
+        let e = segwit::DecodeError(SegwitHrpstringError::NoData);
+        assert_eq!(
+            error_message(&e),
+            "decoding segwit address failed: no data found after removing the checksum"
+        );
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    const ADDRESS: &str = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+
+    #[wasm_bindgen_test]
+    fn encode() {
+        let address = encode_segwit("grs", 1, &[0xab; 20]).expect("valid address");
+        assert_eq!(address, ADDRESS);
+    }
+
+    #[wasm_bindgen_test]
+    fn encode_invalid() {
+        assert!(encode_segwit("", 1, &[0xab; 20]).is_err());
+        assert!(encode_segwit("grs", 17, &[0xab; 20]).is_err());
+        assert!(encode_segwit("grs", 0, &[0xab; 21]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn decode() {
+        let decoded = decode_segwit(&ADDRESS.to_uppercase()).expect("valid address");
+        assert_eq!(decoded.hrp(), "grs");
+        assert_eq!(decoded.version(), 1);
+        assert_eq!(decoded.program(), vec![0xab; 20]);
+    }
+
+    #[wasm_bindgen_test]
+    fn decode_invalid() {
+        assert!(decode_segwit("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyd").is_err());
+    }
+}
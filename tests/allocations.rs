@@ -0,0 +1,116 @@
+// Checks the number of allocations made by the allocating encode and decode functions.
+
+#![cfg(feature = "std")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use bech32grs::{hrp, segwit, Bech32m, Hrp};
+
+/// Allocator that counts the allocations (including reallocations) made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f` and the number of allocations it made.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(|n| n.get());
+    let ret = f();
+    let after = ALLOCATIONS.with(|n| n.get());
+    (ret, after - before)
+}
+
+#[test]
+fn segwit_encode_allocates_once() {
+    let program = [0xab; 32];
+    for version in [segwit::VERSION_0, segwit::VERSION_1].iter() {
+        let (address, allocations) =
+            count_allocations(|| segwit::encode(hrp::GRS, *version, &program));
+        assert!(address.is_ok());
+        assert_eq!(allocations, 1);
+    }
+}
+
+#[test]
+fn encode_allocates_once() {
+    let hrp = Hrp::parse_unchecked("test");
+    let data = [0xab; 500];
+
+    let (s, allocations) = count_allocations(|| bech32grs::encode::<Bech32m>(hrp, &data));
+    assert!(s.is_ok());
+    assert_eq!(allocations, 1);
+
+    let (s, allocations) = count_allocations(|| bech32grs::encode_upper::<Bech32m>(hrp, &data));
+    assert!(s.is_ok());
+    assert_eq!(allocations, 1);
+}
+
+#[test]
+fn segwit_decode_into_reuses_capacity() {
+    let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+    let mut program = Vec::with_capacity(segwit::MAX_WITNESS_PROGRAM_LENGTH);
+
+    for _ in 0..3 {
+        let (res, allocations) = count_allocations(|| segwit::decode_into(address, &mut program));
+        assert!(res.is_ok());
+        assert_eq!(allocations, 0);
+        assert_eq!(program.len(), 32);
+    }
+
+    // An empty vector is grown to exactly the program length.
+    let mut program = Vec::new();
+    let (res, allocations) = count_allocations(|| segwit::decode_into(address, &mut program));
+    assert!(res.is_ok());
+    assert_eq!(allocations, 1);
+    assert_eq!(program.capacity(), 32);
+}
+
+#[test]
+fn segwit_decode_boxed_allocates_once() {
+    let address = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+    let (res, allocations) = count_allocations(|| segwit::decode_boxed(address));
+    assert_eq!(res.expect("valid address").2.len(), 32);
+    assert_eq!(allocations, 1);
+}
+
+#[test]
+fn segwit_validate_does_not_allocate() {
+    let valid = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+    let (res, allocations) = count_allocations(|| segwit::validate(valid));
+    assert!(res.is_ok());
+    assert_eq!(allocations, 0);
+
+    let (res, allocations) = count_allocations(|| segwit::decode(valid).map(drop));
+    assert!(res.is_ok());
+    assert_eq!(allocations, 1);
+
+    let bad_checksum = "grs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f4";
+    let (res, allocations) = count_allocations(|| segwit::validate(bad_checksum));
+    assert!(res.is_err());
+    assert_eq!(allocations, 0);
+
+    // The error for an invalid hrp with more than one separator lists the other separators.
+    let split = "g s1qgrs1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqhnu8f5";
+    let (res, allocations) = count_allocations(|| segwit::validate(split));
+    assert!(res.is_err());
+    assert_eq!(allocations, 1);
+}
@@ -0,0 +1,231 @@
+//! Checks at compile time that the public types are `Send`, `Sync` and `Unpin`.
+//!
+//! These auto traits are part of the public API, a field that removes one is a breaking change.
+//! Every type is also `UnwindSafe` and `RefUnwindSafe` except the few listed with the reason.
+
+use core::iter::Copied;
+use core::slice;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use bech32grs::primitives::checksum::{
+    ByteEngine, Engine, HrpFe32Iter, PackedNull, StaticChecksum,
+};
+use bech32grs::primitives::decode::{
+    self, CharCheckedHrpstring, CharError, CheckedHrpstring, CheckedHrpstringError, ChecksumError,
+    ChecksumVariant, CodeLengthError, HrpMixedCaseError, HrpSplitError, PaddingError,
+    SegwitCodeLengthError, SegwitErrorKind, SegwitHrpstring, SegwitHrpstringError,
+    UncheckedHrpstring, UncheckedHrpstringError,
+};
+use bech32grs::primitives::encode::{self, Encoder, TryCharIter, TryEncoder, TryWriteError};
+use bech32grs::primitives::gf32::{
+    FePadding, FeReader, FeReaderError, FeSliceWriter, FeWriterError, FromCharError, TryFromError,
+};
+use bech32grs::primitives::hrp;
+use bech32grs::primitives::iter::{
+    BytesToFes, Checksummed, FesToBytes, FesToBytesStrict, TryBytesToFes,
+};
+use bech32grs::primitives::segwit::{
+    InvalidWitnessVersionError, ProgramLengthRule, WitnessLengthError,
+};
+use bech32grs::segwit::{
+    self, CompactAddress, CompactAddressError, KnownHrp, Redacted, ScriptPubkeyError,
+    WitnessProgram, WitnessProgramError,
+};
+use bech32grs::{Bech32, Bech32m, Fe32, Hrp, NoChecksum};
+
+/// Fails to compile if `T` can not be moved to, or shared with, another thread.
+fn assert_thread_safe<T: Send + Sync + Unpin>() {}
+
+/// Fails to compile if `T` is missing any of the auto traits we guarantee for most types.
+fn assert_auto_traits<T: Send + Sync + Unpin + UnwindSafe + RefUnwindSafe>() {}
+
+/// Field elements from a slice, the source of the iterator adaptors below.
+type Fes = Copied<slice::Iter<'static, Fe32>>;
+/// Bytes from a slice.
+type Bytes = Copied<slice::Iter<'static, u8>>;
+/// Fallible bytes from a slice.
+type TryBytes = Copied<slice::Iter<'static, Result<u8, ()>>>;
+/// Fallible field elements from a slice.
+type TryFes = Copied<slice::Iter<'static, Result<Fe32, ()>>>;
+
+#[test]
+fn primitives() {
+    assert_auto_traits::<Hrp>();
+    assert_auto_traits::<Fe32>();
+    assert_auto_traits::<FePadding>();
+    assert_auto_traits::<FeReader<'static>>();
+    assert_auto_traits::<UncheckedHrpstring<'static>>();
+    assert_auto_traits::<CharCheckedHrpstring<'static>>();
+    assert_auto_traits::<CheckedHrpstring<'static>>();
+    assert_auto_traits::<SegwitHrpstring<'static>>();
+    assert_auto_traits::<bech32grs::DecodeProfile>();
+    assert_auto_traits::<bech32grs::CasePolicy>();
+    assert_auto_traits::<ChecksumVariant>();
+    assert_auto_traits::<SegwitErrorKind>();
+    assert_auto_traits::<ProgramLengthRule>();
+    assert_auto_traits::<Bech32>();
+    assert_auto_traits::<Bech32m>();
+    assert_auto_traits::<NoChecksum>();
+    assert_auto_traits::<Engine<Bech32>>();
+    assert_auto_traits::<ByteEngine<Bech32m>>();
+    assert_auto_traits::<StaticChecksum<Bech32>>();
+    assert_auto_traits::<PackedNull>();
+
+    // Holds a mutable borrow of the buffer, which is not `UnwindSafe`.
+    assert_thread_safe::<FeSliceWriter<'static>>();
+}
+
+#[test]
+fn iterators() {
+    assert_auto_traits::<hrp::ByteIter<'static>>();
+    assert_auto_traits::<hrp::CharIter<'static>>();
+    assert_auto_traits::<hrp::LowercaseByteIter<'static>>();
+    assert_auto_traits::<hrp::LowercaseCharIter<'static>>();
+    assert_auto_traits::<HrpFe32Iter<'static>>();
+
+    assert_auto_traits::<decode::ByteIter<'static>>();
+    assert_auto_traits::<decode::Fe32Iter<'static>>();
+    assert_auto_traits::<decode::AsciiToFe32Iter<'static>>();
+
+    assert_auto_traits::<BytesToFes<Bytes>>();
+    assert_auto_traits::<TryBytesToFes<TryBytes>>();
+    assert_auto_traits::<FesToBytes<Fes>>();
+    assert_auto_traits::<FesToBytesStrict<Fes>>();
+    assert_auto_traits::<Checksummed<Fes, Bech32>>();
+
+    assert_auto_traits::<Encoder<'static, Fes, Bech32>>();
+    assert_auto_traits::<encode::WitnessVersionIter<Fes>>();
+    assert_auto_traits::<encode::CharIter<'static, Fes, Bech32m>>();
+    assert_auto_traits::<encode::ByteIter<'static, Fes, Bech32m>>();
+    assert_auto_traits::<encode::Fe32Iter<'static, Fes, Bech32m>>();
+    assert_auto_traits::<TryEncoder<'static, TryFes, Bech32>>();
+    assert_auto_traits::<TryCharIter<'static, TryFes, Bech32>>();
+
+    assert_auto_traits::<bech32grs::ChecksumIter<Bech32>>();
+}
+
+#[test]
+fn segwit_types() {
+    assert_auto_traits::<segwit::Case>();
+    assert_auto_traits::<segwit::ForeignAddressKind>();
+    assert_auto_traits::<KnownHrp>();
+    assert_auto_traits::<CompactAddress>();
+    assert_auto_traits::<WitnessProgram>();
+    assert_auto_traits::<Redacted<'static>>();
+    assert_auto_traits::<bech32grs::Spans>();
+}
+
+#[test]
+fn errors() {
+    assert_auto_traits::<bech32grs::Error>();
+    assert_auto_traits::<hrp::Error>();
+    assert_auto_traits::<FromCharError>();
+    assert_auto_traits::<TryFromError>();
+    assert_auto_traits::<FeReaderError>();
+    assert_auto_traits::<FeWriterError>();
+    assert_auto_traits::<InvalidWitnessVersionError>();
+    assert_auto_traits::<WitnessLengthError>();
+    assert_auto_traits::<UncheckedHrpstringError>();
+    assert_auto_traits::<CheckedHrpstringError>();
+    assert_auto_traits::<SegwitHrpstringError>();
+    assert_auto_traits::<CharError>();
+    assert_auto_traits::<HrpMixedCaseError>();
+    assert_auto_traits::<HrpSplitError>();
+    assert_auto_traits::<ChecksumError>();
+    assert_auto_traits::<CodeLengthError>();
+    assert_auto_traits::<SegwitCodeLengthError>();
+    assert_auto_traits::<PaddingError>();
+    assert_auto_traits::<TryWriteError<()>>();
+    assert_auto_traits::<bech32grs::EncodeError>();
+    assert_auto_traits::<bech32grs::EncodeSliceError>();
+    assert_auto_traits::<bech32grs::CapacityError>();
+    assert_auto_traits::<bech32grs::SplitError>();
+    assert_auto_traits::<bech32grs::DataBuilderError>();
+    assert_auto_traits::<CompactAddressError>();
+    assert_auto_traits::<WitnessProgramError>();
+    assert_auto_traits::<ScriptPubkeyError>();
+    assert_auto_traits::<segwit::EncodeSliceError>();
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn alloc_types() {
+    assert_auto_traits::<bech32grs::DataBuilder>();
+    assert_auto_traits::<bech32grs::primitives::gf32::FeWriter>();
+    assert_auto_traits::<bech32grs::HrpRouter<u32>>();
+    assert_auto_traits::<segwit::Address>();
+    assert_auto_traits::<segwit::AddressString>();
+    assert_auto_traits::<segwit::Decoded>();
+
+    assert_auto_traits::<bech32grs::DecodeError>();
+    assert_auto_traits::<bech32grs::RouteError>();
+    assert_auto_traits::<bech32grs::DecodeErrorWithInput<CheckedHrpstringError>>();
+    assert_auto_traits::<bech32grs::DecodeErrorWithInput<segwit::DecodeError>>();
+    assert_auto_traits::<segwit::DecodeError>();
+    assert_auto_traits::<segwit::EncodeError>();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn std_errors() {
+    // Wraps a `std::io::Error`, whose custom errors are not `UnwindSafe`.
+    assert_thread_safe::<bech32grs::EncodeIoError>();
+}
+
+#[test]
+#[cfg(feature = "embedded-io")]
+fn embedded_io_errors() {
+    assert_auto_traits::<bech32grs::EncodeEmbeddedError<()>>();
+    assert_auto_traits::<segwit::EncodeEmbeddedError<()>>();
+}
+
+#[test]
+#[cfg(feature = "heapless")]
+fn heapless_types() {
+    assert_auto_traits::<bech32grs::HeaplessDataBuilder<33>>();
+    assert_auto_traits::<bech32grs::HeaplessDataBuilderError>();
+    assert_auto_traits::<bech32grs::DecodeHeaplessError>();
+    assert_auto_traits::<segwit::DecodeHeaplessError>();
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn metrics_types() { assert_auto_traits::<segwit::DecodeStats>(); }
+
+#[test]
+#[cfg(feature = "bolt11")]
+fn bolt11_types() {
+    assert_auto_traits::<bech32grs::bolt11::Bolt11Raw>();
+    assert_auto_traits::<bech32grs::bolt11::ParseError>();
+}
+
+#[test]
+#[cfg(feature = "bolt12")]
+fn bolt12_types() { assert_auto_traits::<bech32grs::bolt12::ParseError>(); }
+
+#[test]
+#[cfg(feature = "codex32")]
+fn codex32_types() {
+    assert_auto_traits::<bech32grs::codex32::Codex32>();
+    assert_auto_traits::<bech32grs::codex32::Share>();
+    assert_auto_traits::<bech32grs::codex32::ShareError>();
+}
+
+#[test]
+#[cfg(feature = "lnurl")]
+fn lnurl_types() { assert_auto_traits::<bech32grs::lnurl::LnurlError>(); }
+
+#[test]
+#[cfg(feature = "nip19")]
+fn nip19_types() {
+    assert_auto_traits::<bech32grs::nip19::Nip19Kind>();
+    assert_auto_traits::<bech32grs::nip19::Nip19Error>();
+}
+
+#[test]
+#[cfg(feature = "sp")]
+fn sp_types() {
+    assert_auto_traits::<bech32grs::sp::Network>();
+    assert_auto_traits::<bech32grs::sp::SilentPaymentAddress>();
+    assert_auto_traits::<bech32grs::sp::SpError>();
+}
@@ -3,9 +3,9 @@
 #![cfg(feature = "alloc")]
 
 use bech32grs::primitives::decode::{
-    CheckedHrpstring, ChecksumError, SegwitHrpstring, UncheckedHrpstring,
+    CheckedHrpstring, ChecksumError, ChecksumVariant, SegwitHrpstring, UncheckedHrpstring,
 };
-use bech32grs::{Bech32, Bech32m, ByteIterExt, Fe32IterExt};
+use bech32grs::{Bech32, Bech32m, ByteIterExt, CasePolicy, DecodeProfile, Fe32IterExt};
 
 // This is a separate test because we correctly identify this string as invalid but not for the
 // reason given in the bip.
@@ -29,6 +29,14 @@ fn bip_173_checksum_calculated_with_uppercase_form() {
     );
 }
 
+#[test]
+fn bip_173_invalid_checksum_has_no_variant() {
+    for s in ["A1G7SGD8", "li1dgmt3"].iter() {
+        let unchecked = UncheckedHrpstring::new(s).expect("valid characters");
+        assert_eq!(unchecked.checksum_variant(), None);
+    }
+}
+
 macro_rules! check_valid_bech32 {
     ($($test_name:ident, $valid_bech32:literal);* $(;)?) => {
         $(
@@ -36,8 +44,16 @@ macro_rules! check_valid_bech32 {
             fn $test_name() {
                 let p = UncheckedHrpstring::new($valid_bech32).unwrap();
                 p.validate_checksum::<Bech32>().expect("valid bech32");
+                let p = UncheckedHrpstring::new_with_profile($valid_bech32, DecodeProfile::BIP350_SEGWIT).unwrap();
+                p.validate_checksum::<Bech32>().expect("valid bech32");
                 // Valid bech32 strings are by definition invalid bech32m.
                 assert_eq!(p.validate_checksum::<Bech32m>().unwrap_err(), ChecksumError::InvalidResidue);
+                assert_eq!(p.checksum_variant(), Some(ChecksumVariant::Bech32));
+
+                let checked = CheckedHrpstring::new::<Bech32>($valid_bech32).unwrap();
+                assert_eq!(checked.as_original_str(), $valid_bech32);
+                let encoded = checked.fe32_iter::<std::iter::Empty<u8>>().with_checksum::<Bech32>(&checked.hrp()).chars().collect::<String>();
+                assert_eq!(checked.to_canonical_string(), encoded);
             }
         )*
     }
@@ -61,6 +77,10 @@ macro_rules! check_valid_address_roundtrip {
                 // bech32 checksum algorithm can be used with any witness version, and this is
                 // tested by the test vectors. However when BIP-350 came into effect only witness
                 // version 0 uses bech32 (and this is enforced by encode/decode).
+                assert_eq!(
+                    bech32grs::segwit::decode_with_profile($addr, DecodeProfile::BIP350_SEGWIT),
+                    bech32grs::segwit::decode($addr)
+                );
                 if let Ok((hrp, bech32grs::Fe32::Q, program)) = bech32grs::segwit::decode($addr) {
                     let encoded = bech32grs::segwit::encode_v0(hrp, &program).expect("failed to encode address");
                     // The bips specifically say that encoder should output lowercase characters so we uppercase manually.
@@ -70,6 +90,9 @@ macro_rules! check_valid_address_roundtrip {
                     }
                 }
 
+                let unchecked = UncheckedHrpstring::new($addr).expect("valid address");
+                assert_eq!(unchecked.checksum_variant(), Some(ChecksumVariant::Bech32));
+
                 let hrpstring = SegwitHrpstring::new_bech32($addr).expect("valid address");
                 let hrp = hrpstring.hrp();
                 let witness_version = hrpstring.witness_version();
@@ -104,6 +127,14 @@ macro_rules! check_invalid_address {
                     // SegwitHrpstring so must explicitly do check.
                     Ok(segwit) => assert!(!segwit.has_valid_hrp()),
                 }
+                match SegwitHrpstring::new_with_profile($addr, DecodeProfile::BIP350_SEGWIT) {
+                    Err(_) => {},
+                    Ok(segwit) => assert!(!segwit.has_valid_hrp()),
+                }
+                match SegwitHrpstring::new_with_case($addr, CasePolicy::default()) {
+                    Err(_) => {},
+                    Ok(segwit) => assert!(!segwit.has_valid_hrp()),
+                }
             }
         )*
     }
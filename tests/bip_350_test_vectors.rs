@@ -3,10 +3,11 @@
 #![cfg(feature = "alloc")]
 
 use bech32grs::primitives::decode::{
-    CheckedHrpstring, CheckedHrpstringError, ChecksumError, SegwitHrpstring, SegwitHrpstringError,
-    UncheckedHrpstring,
+    CheckedHrpstring, CheckedHrpstringError, ChecksumError, ChecksumVariant, SegwitHrpstring,
+    SegwitHrpstringError, UncheckedHrpstring,
 };
-use bech32grs::{Bech32, Bech32m};
+use bech32grs::segwit::CompactAddress;
+use bech32grs::{Bech32, Bech32m, ByteIterExt, CasePolicy, DecodeProfile, Fe32IterExt};
 
 // This is a separate test because we correctly identify this string as invalid but not for the
 // reason given in the bip.
@@ -26,6 +27,52 @@ fn bip_350_checksum_calculated_with_uppercase_form() {
     );
 }
 
+#[test]
+fn bip_350_invalid_checksum_has_no_variant() {
+    for s in ["M1VUXWEZ", "in1muywd"].iter() {
+        let unchecked = UncheckedHrpstring::new(s).expect("valid characters");
+        assert_eq!(unchecked.checksum_variant(), None);
+    }
+}
+
+// The checksums of these vectors were not recomputed for the Groestlcoin hrps, so while the bip
+// describes them as using the wrong algorithm they are valid for neither.
+#[test]
+fn bip_350_invalid_address_checksum_has_no_variant() {
+    let invalid = [
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+        "tgrs1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+        "GRS1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+        "grs1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+        "tgrs1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+    ];
+    for s in invalid.iter() {
+        let unchecked = UncheckedHrpstring::new(s).expect("valid characters");
+        assert_eq!(unchecked.checksum_variant(), None);
+    }
+}
+
+#[test]
+fn bip_350_wrong_checksum_variant() {
+    let (hrp, version, program) = bech32grs::segwit::decode(
+        "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+    )
+    .expect("valid address");
+    // A witness version 1 address with a bech32 checksum, instead of bech32m.
+    let bech32 = program
+        .iter()
+        .copied()
+        .bytes_to_fes()
+        .with_checksum::<Bech32>(&hrp)
+        .with_witness_version(version)
+        .chars()
+        .collect::<String>();
+
+    let unchecked = UncheckedHrpstring::new(&bech32).expect("valid characters");
+    assert_eq!(unchecked.validate_checksum::<Bech32m>(), Err(ChecksumError::InvalidResidue));
+    assert_eq!(unchecked.checksum_variant(), Some(ChecksumVariant::Bech32));
+}
+
 macro_rules! check_valid_bech32m {
     ($($test_name:ident, $valid_bech32m:literal);* $(;)?) => {
         $(
@@ -33,8 +80,16 @@ macro_rules! check_valid_bech32m {
             fn $test_name() {
                 let p = UncheckedHrpstring::new($valid_bech32m).unwrap();
                 p.validate_checksum::<Bech32m>().expect("valid bech32m");
+                let p = UncheckedHrpstring::new_with_profile($valid_bech32m, DecodeProfile::BIP350_SEGWIT).unwrap();
+                p.validate_checksum::<Bech32m>().expect("valid bech32m");
                 // Valid bech32m strings are by definition invalid bech32.
                 assert_eq!(p.validate_checksum::<Bech32>().unwrap_err(), ChecksumError::InvalidResidue);
+                assert_eq!(p.checksum_variant(), Some(ChecksumVariant::Bech32m));
+
+                let checked = CheckedHrpstring::new::<Bech32m>($valid_bech32m).unwrap();
+                assert_eq!(checked.as_original_str(), $valid_bech32m);
+                let encoded = checked.fe32_iter::<std::iter::Empty<u8>>().with_checksum::<Bech32m>(&checked.hrp()).chars().collect::<String>();
+                assert_eq!(checked.to_canonical_string(), encoded);
             }
         )*
     }
@@ -56,8 +111,40 @@ macro_rules! check_valid_address_roundtrip {
             #[cfg(feature = "alloc")]
             fn $test_name() {
                 let (hrp, version, program) = bech32grs::segwit::decode($addr).expect("failed to decode valid address");
+                let decoded = bech32grs::segwit::decode_with_profile($addr, DecodeProfile::BIP350_SEGWIT);
+                let variant = if version == bech32grs::Fe32::Q { ChecksumVariant::Bech32 } else { ChecksumVariant::Bech32m };
+                assert_eq!(UncheckedHrpstring::new($addr).unwrap().checksum_variant(), Some(variant));
+                assert_eq!(decoded.expect("failed to decode valid address"), (hrp, version, program.clone()));
                 let encoded = bech32grs::segwit::encode(hrp, version, &program).expect("failed to encode address");
 
+                let segwit = SegwitHrpstring::new($addr).expect("valid address");
+                assert_eq!(segwit.as_original_str(), $addr);
+                assert_eq!(segwit.to_canonical_string(), encoded);
+
+                let unchecked = UncheckedHrpstring::new($addr).expect("valid characters");
+                assert_eq!(unchecked.total_len(), $addr.len());
+                assert!(bech32grs::segwit::plausible_lengths(&unchecked.hrp()).contains(&$addr.len()));
+
+                for n in [1, 3, 10].iter() {
+                    let truncated = &$addr[..$addr.len() - n];
+                    match SegwitHrpstring::new(truncated).unwrap_err() {
+                        SegwitHrpstringError::ProbablyTruncated { expected_lengths, got } => {
+                            assert!(expected_lengths.contains(&$addr.len()));
+                            assert_eq!(got, truncated.len());
+                        }
+                        e => panic!("{} not reported as truncated: {:?}", truncated, e),
+                    }
+                }
+
+                let (witness_hrp, witness_program) = bech32grs::segwit::decode_witness_program($addr).expect("failed to decode valid address");
+                assert_eq!(witness_hrp, hrp);
+                assert_eq!(witness_program.version(), version);
+                assert_eq!(witness_program.program(), &program[..]);
+                assert_eq!(witness_program.to_address(&witness_hrp), encoded);
+
+                let (hrp, version, program, case) = bech32grs::segwit::decode_preserving_case($addr).expect("failed to decode valid address");
+                assert_eq!(bech32grs::segwit::encode_with_case(case, hrp, version, &program).expect("failed to encode address"), $addr);
+
                 // The bips specifically say that encoder should output lowercase characters so we uppercase manually.
                 if encoded != $addr {
                     let got = encoded.to_uppercase();
@@ -76,6 +163,57 @@ check_valid_address_roundtrip! {
     bip_350_valid_address_roundtrip_4, "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at";
 }
 
+macro_rules! check_valid_address_script_pubkey {
+    ($($test_name:ident, $addr:literal, $script_pubkey:literal);* $(;)?) => {
+        $(
+            #[test]
+            fn $test_name() {
+                let (_, version, program) = bech32grs::segwit::decode($addr).expect("failed to decode valid address");
+                let script = bech32grs::segwit::to_script_pubkey_bytes(version, &program).expect("valid witness program");
+                let hex = script.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+                assert_eq!(hex, $script_pubkey);
+                assert_eq!(bech32grs::segwit::from_script_pubkey(&script), Ok((version, &program[..])));
+            }
+        )*
+    }
+}
+// The BIP-350 valid addresses with their scriptPubKeys, the witness programs are unchanged.
+check_valid_address_script_pubkey! {
+    bip_350_valid_address_script_pubkey_0, "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5", "0014751e76e8199196d454941c45d1b3a323f1433bd6";
+    bip_350_valid_address_script_pubkey_1, "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq", "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262";
+    bip_350_valid_address_script_pubkey_2, "tgrs1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsess668a6", "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433";
+    bip_350_valid_address_script_pubkey_3, "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x", "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433";
+    bip_350_valid_address_script_pubkey_4, "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at", "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+}
+
+macro_rules! check_valid_compact_address_roundtrip {
+    ($($test_name:ident, $addr:literal);* $(;)?) => {
+        $(
+            #[test]
+            fn $test_name() {
+                let compact = $addr.parse::<CompactAddress>().expect("failed to parse valid address");
+                let (hrp, version, program) = bech32grs::segwit::decode($addr).expect("failed to decode valid address");
+                assert_eq!(compact.hrp(), hrp);
+                assert_eq!(compact.witness_version(), version);
+                assert_eq!(compact.as_program(), &program[..]);
+
+                let encoded = compact.to_string();
+                if encoded != $addr {
+                    let got = encoded.to_uppercase();
+                    assert_eq!(got, $addr)
+                }
+            }
+        )*
+    }
+}
+check_valid_compact_address_roundtrip! {
+    bip_350_valid_compact_address_roundtrip_0, "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5";
+    bip_350_valid_compact_address_roundtrip_1, "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq";
+    bip_350_valid_compact_address_roundtrip_2, "tgrs1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsess668a6";
+    bip_350_valid_compact_address_roundtrip_3, "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x";
+    bip_350_valid_compact_address_roundtrip_4, "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at";
+}
+
 macro_rules! check_invalid_address {
     ($($test_name:ident, $addr:literal);* $(;)?) => {
         $(
@@ -88,6 +226,14 @@ macro_rules! check_invalid_address {
                     // SegwitHrpstring so must explicitly do check.
                     Ok(segwit) => assert!(!segwit.has_valid_hrp()),
                 }
+                match SegwitHrpstring::new_with_profile($addr, DecodeProfile::BIP350_SEGWIT) {
+                    Err(_) => {},
+                    Ok(segwit) => assert!(!segwit.has_valid_hrp()),
+                }
+                match SegwitHrpstring::new_with_case($addr, CasePolicy::default()) {
+                    Err(_) => {},
+                    Ok(segwit) => assert!(!segwit.has_valid_hrp()),
+                }
             }
         )*
     }
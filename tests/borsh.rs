@@ -0,0 +1,98 @@
+//! Tests that the value types round trip through `borsh` and that corrupted data is rejected.
+
+#![cfg(feature = "borsh")]
+
+use core::convert::TryFrom;
+
+use bech32grs::segwit::{CompactAddress, WitnessProgram, VERSION_0};
+use bech32grs::{hrp, Fe32, Hrp};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Asserts that `value` round trips, returning the serialized bytes.
+fn roundtrip<T>(value: &T) -> Vec<u8>
+where
+    T: BorshSerialize + BorshDeserialize + PartialEq + std::fmt::Debug,
+{
+    let bytes = borsh::to_vec(value).expect("serializing to a vec never fails");
+    let got = borsh::from_slice::<T>(&bytes).expect("valid data");
+    assert_eq!(got, *value);
+    bytes
+}
+
+/// Asserts that `bytes` are rejected as invalid data, not as truncated input.
+fn assert_invalid<T: BorshDeserialize + std::fmt::Debug>(bytes: &[u8]) {
+    let err = borsh::from_slice::<T>(bytes).expect_err("invalid data");
+    assert_eq!(err.kind(), borsh::io::ErrorKind::InvalidData, "{:?}", bytes);
+}
+
+#[test]
+fn fe32() {
+    for b in 0..32_u8 {
+        let fe = Fe32::try_from(b).unwrap();
+        assert_eq!(roundtrip(&fe), [b]);
+    }
+    assert_invalid::<Fe32>(&[32]);
+    assert_invalid::<Fe32>(&[0xff]);
+}
+
+#[test]
+fn human_readable_part() {
+    assert_eq!(roundtrip(&hrp::GRS), [3, 0, 0, 0, b'g', b'r', b's']);
+    roundtrip(&Hrp::parse("TGRS").unwrap());
+    roundtrip(&Hrp::parse(&"a".repeat(83)).unwrap());
+
+    assert_invalid::<Hrp>(&[0, 0, 0, 0]); // Empty.
+    assert_invalid::<Hrp>(&[3, 0, 0, 0, b'g', b'R', b's']); // Mixed case.
+    assert_invalid::<Hrp>(&[3, 0, 0, 0, b'g', b' ', b's']); // Invalid character.
+    assert_invalid::<Hrp>(&[3, 0, 0, 0, b'g', 0xc3, 0xa9]); // Non-ASCII.
+
+    let mut too_long = vec![84, 0, 0, 0];
+    too_long.extend_from_slice(&[b'a'; 84]);
+    assert_invalid::<Hrp>(&too_long);
+    // The length is checked before reading, a huge length does not allocate.
+    assert_invalid::<Hrp>(&[0xff, 0xff, 0xff, 0xff]);
+}
+
+#[test]
+fn witness_program() {
+    let program = WitnessProgram::new_p2wpkh([0xab; 20]);
+    let bytes = roundtrip(&program);
+    assert_eq!(bytes[..5], [0, 20, 0, 0, 0]);
+    assert_eq!(bytes[5..], [0xab; 20]);
+    roundtrip(&WitnessProgram::new_p2tr([0xcd; 32]));
+    roundtrip(&WitnessProgram::new(Fe32::S, &[1; 40]).unwrap());
+
+    let mut corrupted = bytes.clone();
+    corrupted[0] = 17; // Version 17.
+    assert_invalid::<WitnessProgram>(&corrupted);
+    corrupted[0] = 32; // Not a field element.
+    assert_invalid::<WitnessProgram>(&corrupted);
+
+    // A version 0 program of 21 bytes.
+    let mut corrupted = vec![0, 21, 0, 0, 0];
+    corrupted.extend_from_slice(&[0xab; 21]);
+    assert_invalid::<WitnessProgram>(&corrupted);
+    // A program of 41 bytes.
+    let mut corrupted = vec![1, 41, 0, 0, 0];
+    corrupted.extend_from_slice(&[0xab; 41]);
+    assert_invalid::<WitnessProgram>(&corrupted);
+}
+
+#[test]
+fn compact_address() {
+    let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+    let address = s.parse::<CompactAddress>().expect("valid address");
+    let bytes = roundtrip(&address);
+    assert_eq!(bytes[..2], [0, VERSION_0.to_u8()]);
+    assert_eq!(bytes[2..], borsh::to_vec(address.as_program()).unwrap()[..]);
+
+    let testnet = "tgrs1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsess668a6";
+    roundtrip(&testnet.parse::<CompactAddress>().expect("valid address"));
+
+    let mut corrupted = bytes.clone();
+    corrupted[0] = 3; // Unknown network.
+    assert_invalid::<CompactAddress>(&corrupted);
+    let mut corrupted = bytes;
+    corrupted[1] = 17; // Version 17.
+    assert_invalid::<CompactAddress>(&corrupted);
+}
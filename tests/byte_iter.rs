@@ -0,0 +1,31 @@
+//! Tests that `bytes_to_fes` is available on iterators of bytes and of byte references.
+
+#![cfg(feature = "alloc")]
+
+use bech32grs::{hrp, segwit, Bech32m, ByteIterExt, ByteRefIterExt, Fe32, Fe32IterExt};
+
+const PROGRAM: [u8; 32] = [0xab; 32];
+
+#[test]
+fn bytes_to_fes_call_shapes() {
+    let copied = PROGRAM.iter().copied().bytes_to_fes().collect::<Vec<Fe32>>();
+    let borrowed = PROGRAM.iter().bytes_to_fes().collect::<Vec<Fe32>>();
+    let owned = PROGRAM.to_vec().into_iter().bytes_to_fes().collect::<Vec<Fe32>>();
+
+    assert_eq!(copied.len(), 52);
+    assert_eq!(borrowed, copied);
+    assert_eq!(owned, copied);
+}
+
+#[test]
+fn borrowed_bytes_match_segwit_encoder() {
+    let chars = PROGRAM
+        .iter()
+        .bytes_to_fes()
+        .with_checksum::<Bech32m>(&hrp::GRS)
+        .with_witness_version(segwit::VERSION_1)
+        .chars();
+    let address = segwit::encode_v1(hrp::GRS, &PROGRAM).expect("valid program");
+
+    assert_eq!(chars.collect::<String>(), address);
+}
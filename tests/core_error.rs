@@ -0,0 +1,38 @@
+//! Tests that errors convert into `Box<dyn core::error::Error>` with `?` without the `std` feature.
+//!
+//! Run with `cargo test --no-default-features --features="alloc core-error" --test core_error`.
+
+#![cfg(all(feature = "core-error", feature = "alloc"))]
+
+use core::error::Error;
+
+use bech32grs::primitives::decode::CheckedHrpstring;
+use bech32grs::{segwit, Bech32m};
+
+fn decode_address(s: &str) -> Result<usize, Box<dyn Error>> {
+    let (_, _, program) = segwit::decode(s)?;
+    Ok(program.len())
+}
+
+fn decode_any(s: &str) -> Result<usize, Box<dyn Error>> {
+    let checked = CheckedHrpstring::new::<Bech32m>(s)?;
+    Ok(checked.byte_iter().len())
+}
+
+fn decode_umbrella(s: &str) -> Result<usize, Box<dyn Error>> {
+    let program = segwit::decode(s).map_err(bech32grs::Error::from)?.2;
+    Ok(program.len())
+}
+
+#[test]
+fn question_mark_into_boxed_core_error() {
+    let address = "grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyc";
+    assert_eq!(decode_address(address).unwrap(), 20);
+    assert!(decode_any(address).is_ok());
+    assert_eq!(decode_umbrella(address).unwrap(), 20);
+
+    let e = decode_address("grs1p4w46h2at4w46h2at4w46h2at4w46h2atf8lcyq").unwrap_err();
+    assert!(e.source().is_some());
+    assert!(decode_any("grs1").is_err());
+    assert!(decode_umbrella("not an address").is_err());
+}
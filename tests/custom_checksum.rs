@@ -0,0 +1,54 @@
+//! Tests that `Checksum` can be implemented outside the crate, the extension point for formats with
+//! their own checksum.
+
+#![cfg(feature = "alloc")]
+
+use bech32grs::primitives::decode::{CheckedHrpstring, CheckedHrpstringError, ChecksumError};
+use bech32grs::{Bech32, Checksum, Hrp};
+
+/// The bech32 generator with a different target residue, so strings are not valid bech32.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Custom {}
+
+// Leaves `NAME` to its default, like an implementation written before it was added.
+impl Checksum for Custom {
+    type MidstateRepr = u32;
+    const CODE_LENGTH: usize = 90;
+    const CHECKSUM_LENGTH: usize = 6;
+    const GENERATOR_SH: [u32; 5] =
+        [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    const TARGET_RESIDUE: u32 = 0x1234_5678;
+}
+
+#[test]
+fn custom_checksum_sanity() {
+    Custom::sanity_check();
+    assert_eq!(Custom::NAME, "custom");
+}
+
+#[test]
+fn custom_checksum_roundtrips() {
+    let hrp = Hrp::parse("custom").expect("valid hrp");
+    let s = bech32grs::encode::<Custom>(hrp, &[0xab; 20]).expect("valid data");
+
+    let checked = CheckedHrpstring::new::<Custom>(&s).expect("valid custom checksum");
+    assert_eq!(checked.hrp(), hrp);
+    assert!(checked.byte_iter().eq([0xab; 20].iter().copied()));
+
+    assert_eq!(
+        CheckedHrpstring::new::<Bech32>(&s).unwrap_err(),
+        CheckedHrpstringError::Checksum(ChecksumError::InvalidResidue)
+    );
+}
+
+#[test]
+fn checksum_name_in_generic_code() {
+    fn validated_as<Ck: Checksum>(s: &str) -> Option<&'static str> {
+        CheckedHrpstring::new::<Ck>(s).ok().map(|_| Ck::NAME)
+    }
+
+    let hrp = Hrp::parse("custom").expect("valid hrp");
+    let s = bech32grs::encode::<Custom>(hrp, &[]).expect("valid data");
+    assert_eq!(validated_as::<Custom>(&s), Some("custom"));
+    assert_eq!(validated_as::<Bech32>(&s), None);
+}
@@ -0,0 +1,107 @@
+//! Tests that the deprecated functions keep the behavior of the releases they come from until they
+//! are removed.
+
+#![cfg(feature = "alloc")]
+#![allow(deprecated)]
+
+use bech32grs::primitives::decode::PaddingError;
+use bech32grs::primitives::gf32::{FeWriterError, TryFromError};
+use bech32grs::{hrp, segwit, u5, Error, Fe32, Hrp, Variant};
+
+/// The field elements of the data part "qpzry".
+const FES: [u5; 5] = [Fe32::Q, Fe32::P, Fe32::Z, Fe32::R, Fe32::Y];
+
+#[test]
+fn encode_without_checksum() {
+    assert_eq!(bech32grs::encode_without_checksum("abc", FES).unwrap(), "abc1qpzry");
+    // Like 0.9.1 the human-readable part is lowercased, but may not be mixed case.
+    assert_eq!(bech32grs::encode_without_checksum("ABC", &FES[..]).unwrap(), "abc1qpzry");
+    assert!(bech32grs::encode_without_checksum("aBc", FES).is_err());
+    assert_eq!(bech32grs::encode_without_checksum("abc", []).unwrap(), "abc1");
+
+    let mut s = String::new();
+    bech32grs::encode_without_checksum_to_fmt(&mut s, "abc", FES).unwrap().unwrap();
+    assert_eq!(s, "abc1qpzry");
+}
+
+#[test]
+fn decode_without_checksum() {
+    let want = (String::from("abc"), FES.to_vec());
+    assert_eq!(bech32grs::decode_without_checksum("abc1qpzry").unwrap(), want);
+    assert_eq!(bech32grs::decode_without_checksum("ABC1QPZRY").unwrap(), want);
+    assert_eq!(bech32grs::decode_without_checksum("abc1").unwrap(), (String::from("abc"), vec![]));
+    assert!(bech32grs::decode_without_checksum("abc1QPZRY").is_err());
+    assert!(bech32grs::decode_without_checksum("abcqpzry").is_err());
+}
+
+#[test]
+fn segwit_encode_to_fmt_unchecked_uppercase() {
+    let program = [
+        0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3, 0xa3,
+        0x23, 0xf1, 0x43, 0x3b, 0xd6,
+    ];
+
+    let mut s = String::new();
+    segwit::encode_to_fmt_unchecked_uppercase(&mut s, &hrp::GRS, segwit::VERSION_0, &program)
+        .unwrap();
+    assert_eq!(s, "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5");
+}
+
+#[test]
+fn encode_to_fmt_anycase() {
+    // The BIP-173 and BIP-350 test vectors with an empty data part, the hrp keeps its case.
+    for &(hrp, variant, want) in [
+        ("a", Variant::Bech32, "a12uel5l"),
+        ("A", Variant::Bech32, "A12uel5l"),
+        ("a", Variant::Bech32m, "a1lqfn3a"),
+        ("A", Variant::Bech32m, "A1lqfn3a"),
+    ]
+    .iter()
+    {
+        let mut s = String::new();
+        let hrp = Hrp::parse(hrp).unwrap();
+        bech32grs::encode_to_fmt_anycase(&mut s, hrp, [], variant).unwrap().unwrap();
+        assert_eq!(s, want);
+    }
+
+    let mut s = String::new();
+    let hrp = Hrp::parse("abc").unwrap();
+    bech32grs::encode_to_fmt_anycase(&mut s, hrp, FES, Variant::Bech32m).unwrap().unwrap();
+    assert_eq!(bech32grs::decode(&s).unwrap(), (hrp, vec![0x00, 0x44, 0x32]));
+}
+
+#[test]
+fn convert_bits() {
+    assert_eq!(bech32grs::convert_bits(&[0xff_u8], 8, 5, true).unwrap(), [31, 28]);
+    assert_eq!(bech32grs::convert_bits(&[Fe32::L, Fe32::U], 5, 8, false).unwrap(), [0xff]);
+    assert_eq!(bech32grs::convert_bits::<u8>(&[], 8, 5, false).unwrap(), Vec::<u8>::new());
+
+    assert_eq!(
+        bech32grs::convert_bits(&[0xff_u8], 8, 5, false).unwrap_err(),
+        Error::Padding(PaddingError::NonZero)
+    );
+    assert_eq!(
+        bech32grs::convert_bits(&[0_u8; 2], 5, 8, false).unwrap(),
+        [0],
+        "two bits of zero padding"
+    );
+    assert_eq!(
+        bech32grs::convert_bits(&[0_u8; 3], 5, 8, false).unwrap_err(),
+        Error::Padding(PaddingError::TooMuch)
+    );
+    assert_eq!(
+        bech32grs::convert_bits(&[32_u8], 5, 8, true).unwrap_err(),
+        Error::FeWriter(FeWriterError::TooWide { value: 32, bits: 5 })
+    );
+}
+
+#[test]
+fn u5_try_from_u8() {
+    assert_eq!(u5::try_from_u8(31), Ok(Fe32::L));
+    assert_eq!(u5::try_from_u8(32), Err(TryFromError::InvalidByte(32)));
+}
+
+#[test]
+fn hrp_is_empty() {
+    assert!(!hrp::GRS.is_empty());
+}
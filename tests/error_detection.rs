@@ -0,0 +1,135 @@
+// Error detection guarantees of the segwit checksums, checked on the BIP-350 test vectors.
+//
+// Bech32 and bech32m guarantee detection of any error affecting at most four characters of a
+// string up to 90 characters long. Every string below must be rejected by `segwit::decode`, if one
+// is accepted that is a consensus-grade bug and the failure message contains the string.
+//
+// Exhaustively checking every pair of substitutions is slow, a sample of them is checked by default
+// and all of them with `cargo test --test error_detection -- --ignored`.
+
+#![cfg(feature = "alloc")]
+
+use bech32grs::segwit;
+
+/// The valid segwit addresses from BIP-350, with the Groestlcoin human-readable parts.
+const VALID: [&str; 5] = [
+    "GRS1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7K3K4SJ5",
+    "tgrs1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3quvjfuq",
+    "tgrs1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsess668a6",
+    "tgrs1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvses6d6w9x",
+    "grs1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqddt7at",
+];
+
+const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Returns the bech32 alphabet in the same case as `addr`.
+fn alphabet(addr: &str) -> Vec<char> {
+    if addr.bytes().any(|b| b.is_ascii_uppercase()) {
+        CHARSET.to_ascii_uppercase().chars().collect()
+    } else {
+        CHARSET.chars().collect()
+    }
+}
+
+/// Returns `addr` with the character at index `i` replaced by `c`.
+fn substitute(addr: &str, i: usize, c: char) -> String {
+    let mut s = String::from(addr);
+    s.replace_range(i..i + 1, c.encode_utf8(&mut [0; 4]));
+    s
+}
+
+/// Asserts that `corrupted`, made from the valid address `addr` by `corruption`, is rejected.
+fn assert_rejected(addr: &str, corrupted: &str, corruption: &str) {
+    assert!(
+        segwit::decode(corrupted).is_err(),
+        "undetected {} of {}, reproduce with: segwit::decode({:?})",
+        corruption,
+        addr,
+        corrupted,
+    );
+}
+
+#[test]
+fn vectors_are_valid() {
+    for addr in VALID.iter() {
+        segwit::decode(addr).expect("valid address");
+    }
+}
+
+#[test]
+fn single_substitutions_are_detected() {
+    for addr in VALID.iter() {
+        let alphabet = alphabet(addr);
+        for (i, old) in addr.char_indices() {
+            for &c in alphabet.iter().filter(|&&c| c != old) {
+                assert_rejected(addr, &substitute(addr, i, c), "substitution");
+            }
+        }
+    }
+}
+
+// BIP-350 does not guarantee that insertions and deletions are detected, but bech32m fixes the
+// weakness of bech32 that allowed them to go undetected at the end of a string, and the witness
+// program length checks catch most of the rest. For these vectors every one is detected.
+#[test]
+fn single_insertions_are_detected() {
+    for addr in VALID.iter() {
+        for i in 0..=addr.len() {
+            for c in alphabet(addr) {
+                let mut corrupted = String::from(*addr);
+                corrupted.insert(i, c);
+                assert_rejected(addr, &corrupted, "insertion");
+            }
+        }
+    }
+}
+
+#[test]
+fn single_deletions_are_detected() {
+    for addr in VALID.iter() {
+        for i in 0..addr.len() {
+            let mut corrupted = String::from(*addr);
+            corrupted.remove(i);
+            assert_rejected(addr, &corrupted, "deletion");
+        }
+    }
+}
+
+/// Checks every pair of positions, substituting the characters picked by `pick`.
+fn check_double_substitutions<F>(pick: F)
+where
+    F: Fn(usize, usize, &[char]) -> Vec<(char, char)>,
+{
+    for addr in VALID.iter() {
+        let alphabet = alphabet(addr);
+        let chars = addr.chars().collect::<Vec<_>>();
+        for i in 0..chars.len() {
+            for j in i + 1..chars.len() {
+                for (a, b) in pick(i, j, &alphabet) {
+                    if a == chars[i] || b == chars[j] {
+                        continue; // A single substitution, checked above.
+                    }
+                    let corrupted = substitute(&substitute(addr, i, a), j, b);
+                    assert_rejected(addr, &corrupted, "double substitution");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn double_substitutions_sample_is_detected() {
+    // Two substitutions for each pair of positions, spread over the alphabet.
+    check_double_substitutions(|i, j, alphabet| {
+        let n = alphabet.len();
+        (0..2).map(|k| (alphabet[(i * 7 + k * 11) % n], alphabet[(j * 13 + k * 5) % n])).collect()
+    });
+}
+
+#[test]
+#[ignore] // Slow, checks every pair of substitutions.
+fn double_substitutions_are_detected() {
+    check_double_substitutions(|_, _, alphabet| {
+        alphabet.iter().flat_map(|&a| alphabet.iter().map(move |&b| (a, b))).collect()
+    });
+}
@@ -0,0 +1,99 @@
+// Checks at compile time that all public error types implement the common traits.
+
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use bech32grs::primitives::decode::{
+    CharError, CheckedHrpstringError, ChecksumError, CodeLengthError, HrpMixedCaseError,
+    HrpSplitError, PaddingError, SegwitCodeLengthError, SegwitHrpstringError,
+    UncheckedHrpstringError,
+};
+use bech32grs::primitives::gf32::{FromCharError, TryFromError};
+use bech32grs::primitives::hrp;
+use bech32grs::primitives::segwit::{InvalidWitnessVersionError, WitnessLengthError};
+use bech32grs::segwit::{CompactAddressError, WitnessProgramError};
+
+/// Fails to compile if `E` is missing any of the traits we implement for all error types.
+fn assert_error_traits<E: Debug + Clone + PartialEq + Eq + Hash>() {}
+
+#[test]
+fn primitives_errors() {
+    assert_error_traits::<FromCharError>();
+    assert_error_traits::<TryFromError>();
+    assert_error_traits::<hrp::Error>();
+    assert_error_traits::<InvalidWitnessVersionError>();
+    assert_error_traits::<WitnessLengthError>();
+    assert_error_traits::<SegwitHrpstringError>();
+    assert_error_traits::<CheckedHrpstringError>();
+    assert_error_traits::<UncheckedHrpstringError>();
+    assert_error_traits::<CharError>();
+    assert_error_traits::<HrpMixedCaseError>();
+    assert_error_traits::<HrpSplitError>();
+    assert_error_traits::<ChecksumError>();
+    assert_error_traits::<CodeLengthError>();
+    assert_error_traits::<SegwitCodeLengthError>();
+    assert_error_traits::<PaddingError>();
+}
+
+#[test]
+fn top_level_errors() {
+    assert_error_traits::<bech32grs::Error>();
+    assert_error_traits::<bech32grs::EncodeError>();
+    assert_error_traits::<bech32grs::EncodeSliceError>();
+    assert_error_traits::<bech32grs::CapacityError>();
+    assert_error_traits::<bech32grs::SplitError>();
+    assert_error_traits::<bech32grs::DataBuilderError>();
+    assert_error_traits::<CompactAddressError>();
+    assert_error_traits::<WitnessProgramError>();
+    assert_error_traits::<bech32grs::segwit::ScriptPubkeyError>();
+    assert_error_traits::<bech32grs::segwit::EncodeSliceError>();
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn alloc_errors() {
+    assert_error_traits::<bech32grs::DecodeError>();
+    assert_error_traits::<bech32grs::segwit::DecodeError>();
+    assert_error_traits::<bech32grs::segwit::EncodeError>();
+    assert_error_traits::<bech32grs::DecodeErrorWithInput<CheckedHrpstringError>>();
+    assert_error_traits::<bech32grs::DecodeErrorWithInput<bech32grs::segwit::DecodeError>>();
+    assert_error_traits::<bech32grs::RouteError>();
+}
+
+#[test]
+#[cfg(feature = "heapless")]
+fn heapless_errors() {
+    assert_error_traits::<bech32grs::DecodeHeaplessError>();
+    assert_error_traits::<bech32grs::segwit::DecodeHeaplessError>();
+    assert_error_traits::<bech32grs::HeaplessDataBuilderError>();
+}
+
+#[test]
+#[cfg(feature = "bolt11")]
+fn bolt11_errors() { assert_error_traits::<bech32grs::bolt11::ParseError>(); }
+
+#[test]
+#[cfg(feature = "bolt12")]
+fn bolt12_errors() { assert_error_traits::<bech32grs::bolt12::ParseError>(); }
+
+#[test]
+#[cfg(feature = "codex32")]
+fn codex32_errors() { assert_error_traits::<bech32grs::codex32::ShareError>(); }
+
+#[test]
+#[cfg(feature = "lnurl")]
+fn lnurl_errors() { assert_error_traits::<bech32grs::lnurl::LnurlError>(); }
+
+#[test]
+#[cfg(feature = "nip19")]
+fn nip19_errors() { assert_error_traits::<bech32grs::nip19::Nip19Error>(); }
+
+#[test]
+#[cfg(feature = "sp")]
+fn sp_errors() { assert_error_traits::<bech32grs::sp::SpError>(); }
+
+#[test]
+#[cfg(feature = "embedded-io")]
+fn embedded_io_errors() {
+    assert_error_traits::<bech32grs::EncodeEmbeddedError<core::fmt::Error>>();
+}
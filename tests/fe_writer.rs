@@ -0,0 +1,43 @@
+//! Tests that `FeWriter` builds the same data parts as the iterator adaptors.
+
+#![cfg(feature = "alloc")]
+
+use bech32grs::primitives::gf32::{FePadding, FeSliceWriter, FeWriter};
+use bech32grs::{hrp, segwit, ByteIterExt, Fe32, Fe32IterExt};
+
+#[test]
+fn segwit_v0_data_part() {
+    let (_hrp, version, program) =
+        segwit::decode("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").expect("valid address");
+
+    let mut writer = FeWriter::new();
+    writer.write_bits(u64::from(version.to_u8()), 5).expect("fits in 5 bits");
+    writer.write_bytes(&program);
+    let fes = writer.finish().expect("zero padding");
+
+    let want = core::iter::once(version)
+        .chain(program.iter().copied().bytes_to_fes())
+        .collect::<Vec<Fe32>>();
+    assert_eq!(fes, want);
+
+    let mut buf = [Fe32::Q; 33];
+    let mut slice_writer = FeSliceWriter::new(&mut buf);
+    slice_writer.write_bits(0, 5).expect("fits").write_bytes(&program).expect("fits");
+    assert_eq!(slice_writer.finish(), Ok(33));
+    assert_eq!(buf[..], want[..]);
+
+    let address = fes.into_iter().with_checksum::<bech32grs::Bech32>(&hrp::GRS).chars();
+    assert_eq!(address.collect::<String>(), "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p");
+}
+
+#[test]
+fn exact_padding() {
+    // 5 + 160 bits fill 33 field elements, 5 + 256 bits do not fill 53.
+    let mut writer = FeWriter::new().with_padding(FePadding::Exact);
+    writer.write_bits(0, 5).expect("fits in 5 bits").write_bytes(&[0xab; 20]);
+    assert_eq!(writer.finish().map(|fes| fes.len()), Ok(33));
+
+    let mut writer = FeWriter::new().with_padding(FePadding::Exact);
+    writer.write_bits(0, 5).expect("fits in 5 bits").write_bytes(&[0xab; 32]);
+    assert!(writer.finish().is_err());
+}
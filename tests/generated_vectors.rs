@@ -0,0 +1,57 @@
+// Pins the output of `testutils::generate_vectors` so that changes to the encoder are noticed.
+//
+// If the change is intended, regenerate the golden file with
+// `UPDATE_GOLDEN=1 cargo test --features=test-utils --test generated_vectors`.
+
+#![cfg(feature = "test-utils")]
+
+use bech32grs::primitives::decode::SegwitHrpstring;
+use bech32grs::primitives::segwit::MAX_STRING_LENGTH;
+use bech32grs::segwit;
+use bech32grs::testutils::generate_vectors;
+
+const GOLDEN_PATH: &str = "tests/data/generated_vectors_seed_0.txt";
+const GOLDEN: &str = include_str!("data/generated_vectors_seed_0.txt");
+
+#[test]
+fn seed_0_matches_golden_file() {
+    let got: String = generate_vectors(0).iter().map(|v| format!("{}\n", v.lower)).collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(GOLDEN_PATH, &got).expect("failed to write golden file");
+        return;
+    }
+    assert!(got == GOLDEN, "generated vectors differ from {}", GOLDEN_PATH);
+}
+
+#[test]
+fn vectors_are_valid_and_cover_every_shape() {
+    let vectors = generate_vectors(0);
+    // Two human-readable parts, two version 0 lengths and 39 lengths for each other version.
+    assert_eq!(vectors.len(), 2 * (2 + 16 * 39));
+
+    for v in &vectors {
+        assert_eq!(v.upper, v.lower.to_uppercase());
+        assert!(v.lower.len() <= MAX_STRING_LENGTH);
+        let decoded = segwit::decode(&v.lower).expect("valid address");
+        assert_eq!(decoded, (v.hrp, v.version, v.program.clone()));
+
+        // Both cases have the same canonical form, the re-encoded address.
+        for s in [&v.lower, &v.upper].iter() {
+            let segwit = SegwitHrpstring::new(s).expect("valid address");
+            assert_eq!(segwit.as_original_str(), s.as_str());
+            assert_eq!(segwit.to_canonical_string(), v.lower);
+        }
+    }
+}
+
+#[test]
+fn seed_changes_programs_only() {
+    let a = generate_vectors(0);
+    let b = generate_vectors(1);
+    assert_eq!(a, generate_vectors(0));
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b.iter()) {
+        assert_eq!((a.hrp, a.version, a.program.len()), (b.hrp, b.version, b.program.len()));
+    }
+    assert_ne!(a, b);
+}
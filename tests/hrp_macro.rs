@@ -0,0 +1,35 @@
+//! Tests for the `hrp!` macro.
+//!
+//! The compile fail tests need the `trybuild` dev-dependency, run them with:
+//! `RUSTFLAGS='--cfg=trybuild' cargo test --test hrp_macro`
+
+use bech32grs::{hrp, Hrp};
+
+static LNGRS: Hrp = hrp!("lngrs");
+
+const TGRS_UPPER: Hrp = hrp!("TGRS");
+
+#[test]
+fn usable_in_static() {
+    assert_eq!(LNGRS, Hrp::parse("lngrs").unwrap());
+    assert_eq!(LNGRS.as_str(), "lngrs");
+}
+
+#[test]
+fn usable_in_const() {
+    assert_eq!(TGRS_UPPER, bech32grs::hrp::TGRS);
+    assert_eq!(TGRS_UPPER.as_str(), "TGRS");
+}
+
+#[test]
+fn usable_in_expression() {
+    let hrp = hrp!("grs");
+    assert_eq!(hrp, bech32grs::hrp::GRS);
+}
+
+#[cfg(trybuild)]
+#[test]
+fn invalid_literals_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/hrp_*.rs");
+}
@@ -0,0 +1,85 @@
+//! Tests converting errors to `std::io::Error` and downcasting back to the original error.
+
+#![cfg(feature = "std")]
+
+use std::error::Error as _;
+use std::fmt::Debug;
+use std::io;
+
+use bech32grs::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, SegwitHrpstring, SegwitHrpstringError,
+    UncheckedHrpstring, UncheckedHrpstringError,
+};
+use bech32grs::{segwit, Bech32, DecodeError, EncodeError, Fe32, Hrp};
+
+/// Converts `e` to an `io::Error`, checks its kind and that it downcasts back to `e`.
+fn assert_roundtrip<E>(e: E, kind: io::ErrorKind)
+where
+    E: Into<io::Error> + std::error::Error + Clone + PartialEq + Debug + Send + Sync + 'static,
+{
+    let display = e.to_string();
+    let io_error: io::Error = e.clone().into();
+    assert_eq!(io_error.kind(), kind, "{:?}", e);
+    assert_eq!(io_error.to_string(), display);
+
+    let inner = io_error.get_ref().and_then(|inner| inner.downcast_ref::<E>());
+    assert_eq!(inner, Some(&e));
+    // The source chain is preserved.
+    assert_eq!(io_error.source().map(|s| s.to_string()), e.source().map(|s| s.to_string()));
+
+    let owned = io_error.into_inner().expect("has inner error").downcast::<E>().expect("is E");
+    assert_eq!(*owned, e);
+}
+
+#[test]
+fn decode_errors_are_invalid_data() {
+    const BAD_CHECKSUM: &str = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+
+    let e: SegwitHrpstringError = SegwitHrpstring::new(BAD_CHECKSUM).unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+    let e: SegwitHrpstringError = SegwitHrpstring::new("grs1").unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+
+    let e: CheckedHrpstringError = CheckedHrpstring::new::<Bech32>(BAD_CHECKSUM).unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+
+    let e: UncheckedHrpstringError = UncheckedHrpstring::new("no separator").unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+
+    let e: DecodeError = bech32grs::decode(BAD_CHECKSUM).unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+
+    let e: segwit::DecodeError = segwit::decode(BAD_CHECKSUM).unwrap_err();
+    assert_roundtrip(e, io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn encode_errors() {
+    let hrp = Hrp::parse("grs").unwrap();
+
+    let e: EncodeError = bech32grs::encode::<Bech32>(hrp, &[0; 1000]).unwrap_err();
+    assert!(matches!(e, EncodeError::TooLong(_)));
+    assert_roundtrip(e, io::ErrorKind::InvalidInput);
+    assert_roundtrip(EncodeError::Fmt(std::fmt::Error), io::ErrorKind::Other);
+
+    let e = segwit::encode(hrp, Fe32::_3, &[0; 20]).unwrap_err();
+    assert!(matches!(e, segwit::EncodeError::WitnessVersion(_)));
+    assert_roundtrip(e, io::ErrorKind::InvalidInput);
+    let e = segwit::encode(hrp, segwit::VERSION_0, &[0; 21]).unwrap_err();
+    assert!(matches!(e, segwit::EncodeError::WitnessLength(_)));
+    assert_roundtrip(e, io::ErrorKind::InvalidInput);
+    assert_roundtrip(segwit::EncodeError::Fmt(std::fmt::Error), io::ErrorKind::Other);
+}
+
+#[test]
+fn question_mark_in_io_functions() {
+    fn program(address: &str) -> io::Result<Vec<u8>> {
+        let segwit = SegwitHrpstring::new(address)?;
+        Ok(segwit.byte_iter().collect())
+    }
+
+    assert_eq!(program("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p").unwrap().len(), 20);
+    let err = program("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<SegwitHrpstringError>());
+}
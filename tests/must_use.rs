@@ -0,0 +1,30 @@
+//! Tests that ignoring lazy iterators, parsed strings and `fmt::Result`s is reported.
+//!
+//! The compile fail tests need the `trybuild` dev-dependency, run them with:
+//! `RUSTFLAGS='--cfg=trybuild' cargo test --test must_use`
+
+#![deny(unused_must_use)]
+
+use bech32grs::primitives::decode::UncheckedHrpstring;
+use bech32grs::{hrp, segwit, Bech32, ByteIterExt, Fe32IterExt};
+
+#[test]
+fn used_values_compile() {
+    let data = [0xab_u8; 20];
+    let chars = data.iter().copied().bytes_to_fes().with_checksum::<Bech32>(&hrp::GRS).chars();
+    assert_eq!(chars.count(), 3 + 1 + 32 + 6);
+
+    let mut s = String::new();
+    segwit::encode_to_fmt_unchecked(&mut s, hrp::GRS, segwit::VERSION_0, &data)
+        .expect("writing to a string");
+
+    let chars = UncheckedHrpstring::new(&s).expect("valid characters").parse_data_chars();
+    assert!(chars.verify::<Bech32>().is_ok());
+}
+
+#[cfg(trybuild)]
+#[test]
+fn ignored_values_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/must_use_*.rs");
+}
@@ -0,0 +1,358 @@
+// Differential tests against the reference implementation from BIP-173 and BIP-350.
+//
+// Random strings are decoded, and random data encoded, by both this crate and the reference, the
+// two must make the same accept/reject decisions and produce the same output.
+//
+// Each case is generated from its own seed, which is printed on failure. To rerun a single case
+// set `BECH32_REFERENCE_SEED` to that seed and `BECH32_REFERENCE_ITERATIONS` to 1.
+
+#![cfg(feature = "std")]
+
+use std::convert::TryFrom;
+use std::env;
+
+use bech32grs::primitives::decode::UncheckedHrpstring;
+use bech32grs::{segwit, Bech32, Bech32m, DecodeProfile, Fe32, Hrp};
+
+/// A straight transcription of the Python reference implementation of BIP-173 and BIP-350.
+///
+/// Deliberately slow and shares no code with the crate, it is the oracle the crate is tested
+/// against so must stay obviously correct. Do not optimize it.
+mod bip {
+    const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Encoding {
+        Bech32,
+        Bech32m,
+    }
+
+    fn bech32_polymod(values: &[u8]) -> u32 {
+        let generator = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+        let mut chk: u32 = 1;
+        for &value in values {
+            let top = chk >> 25;
+            chk = (chk & 0x1ff_ffff) << 5 ^ u32::from(value);
+            for (i, g) in generator.iter().enumerate() {
+                if (top >> i) & 1 == 1 {
+                    chk ^= g;
+                }
+            }
+        }
+        chk
+    }
+
+    fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+        let mut ret: Vec<u8> = hrp.bytes().map(|x| x >> 5).collect();
+        ret.push(0);
+        ret.extend(hrp.bytes().map(|x| x & 31));
+        ret
+    }
+
+    fn bech32_verify_checksum(hrp: &str, data: &[u8]) -> Option<Encoding> {
+        let mut values = bech32_hrp_expand(hrp);
+        values.extend_from_slice(data);
+        match bech32_polymod(&values) {
+            1 => Some(Encoding::Bech32),
+            BECH32M_CONST => Some(Encoding::Bech32m),
+            _ => None,
+        }
+    }
+
+    fn bech32_create_checksum(hrp: &str, data: &[u8], spec: Encoding) -> Vec<u8> {
+        let mut values = bech32_hrp_expand(hrp);
+        values.extend_from_slice(data);
+        values.extend_from_slice(&[0; 6]);
+        let constant = if spec == Encoding::Bech32m { BECH32M_CONST } else { 1 };
+        let polymod = bech32_polymod(&values) ^ constant;
+        (0..6).map(|i| ((polymod >> (5 * (5 - i))) & 31) as u8).collect()
+    }
+
+    pub fn bech32_encode(hrp: &str, data: &[u8], spec: Encoding) -> String {
+        let mut combined = data.to_vec();
+        combined.extend(bech32_create_checksum(hrp, data, spec));
+        let chars: String =
+            combined.iter().map(|&d| CHARSET.as_bytes()[d as usize] as char).collect();
+        format!("{}1{}", hrp, chars)
+    }
+
+    pub fn bech32_decode(bech: &str) -> Option<(String, Vec<u8>, Encoding)> {
+        if bech.chars().any(|x| (x as u32) < 33 || (x as u32) > 126) {
+            return None;
+        }
+        if bech.to_lowercase() != bech && bech.to_uppercase() != bech {
+            return None;
+        }
+        let bech = bech.to_lowercase();
+        let pos = bech.rfind('1')?;
+        if pos < 1 || pos + 7 > bech.len() || bech.len() > 90 {
+            return None;
+        }
+        if !bech[pos + 1..].chars().all(|x| CHARSET.contains(x)) {
+            return None;
+        }
+        let hrp = bech[..pos].to_string();
+        let data: Vec<u8> =
+            bech[pos + 1..].chars().map(|x| CHARSET.find(x).unwrap() as u8).collect();
+        let spec = bech32_verify_checksum(&hrp, &data)?;
+        Some((hrp, data[..data.len() - 6].to_vec(), spec))
+    }
+
+    pub fn convertbits(data: &[u8], frombits: u32, tobits: u32, pad: bool) -> Option<Vec<u8>> {
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        let mut ret = vec![];
+        let maxv = (1 << tobits) - 1;
+        let max_acc = (1 << (frombits + tobits - 1)) - 1;
+        for &value in data {
+            if u32::from(value) >> frombits != 0 {
+                return None;
+            }
+            acc = ((acc << frombits) | u32::from(value)) & max_acc;
+            bits += frombits;
+            while bits >= tobits {
+                bits -= tobits;
+                ret.push(((acc >> bits) & maxv) as u8);
+            }
+        }
+        if pad {
+            if bits > 0 {
+                ret.push(((acc << (tobits - bits)) & maxv) as u8);
+            }
+        } else if bits >= frombits || ((acc << (tobits - bits)) & maxv) != 0 {
+            return None;
+        }
+        Some(ret)
+    }
+
+    pub fn decode(hrp: &str, addr: &str) -> Option<(u8, Vec<u8>)> {
+        let (hrpgot, data, spec) = bech32_decode(addr)?;
+        if hrpgot != hrp {
+            return None;
+        }
+        let decoded = convertbits(data.get(1..).unwrap_or(&[]), 5, 8, false)?;
+        if decoded.len() < 2 || decoded.len() > 40 {
+            return None;
+        }
+        if data[0] > 16 {
+            return None;
+        }
+        if data[0] == 0 && decoded.len() != 20 && decoded.len() != 32 {
+            return None;
+        }
+        if data[0] == 0 && spec != Encoding::Bech32 || data[0] != 0 && spec != Encoding::Bech32m {
+            return None;
+        }
+        Some((data[0], decoded))
+    }
+
+    pub fn encode(hrp: &str, witver: u8, witprog: &[u8]) -> Option<String> {
+        let spec = if witver == 0 { Encoding::Bech32 } else { Encoding::Bech32m };
+        let mut data = vec![witver];
+        data.extend(convertbits(witprog, 8, 5, true)?);
+        let ret = bech32_encode(hrp, &data, spec);
+        decode(hrp, &ret)?;
+        Some(ret)
+    }
+}
+
+use bip::Encoding;
+
+/// Tiny xorshift PRNG so the cases are reproducible from a seed and need no dependencies.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // Xorshift gets stuck on zero, and similar seeds give similar first outputs.
+        let mut rng = XorShift(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1);
+        for _ in 0..4 {
+            rng.next();
+        }
+        rng
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize { (self.next() % n as u64) as usize }
+
+    fn pick<T: Copy>(&mut self, from: &[T]) -> T { from[self.below(from.len())] }
+}
+
+/// Runs `case` with one seed per iteration, printing the seed if the case panics.
+fn run(name: &str, case: impl Fn(&mut XorShift) + std::panic::RefUnwindSafe) {
+    let var = |key: &str, default: u64| match env::var(key) {
+        Ok(v) => v.parse().unwrap_or_else(|_| panic!("{} is not a number: {}", key, v)),
+        Err(_) => default,
+    };
+    let base = var("BECH32_REFERENCE_SEED", 0x2545_f491_4f6c_dd1d);
+    let iterations = var("BECH32_REFERENCE_ITERATIONS", 2_000);
+
+    for i in 0..iterations {
+        let seed = base.wrapping_add(i);
+        let result = std::panic::catch_unwind(|| case(&mut XorShift::new(seed)));
+        if let Err(e) = result {
+            eprintln!("{} failed with BECH32_REFERENCE_SEED={}", name, seed);
+            std::panic::resume_unwind(e);
+        }
+    }
+}
+
+/// Returns a random lowercase human-readable part.
+fn random_hrp(rng: &mut XorShift, max_len: usize) -> String {
+    let len = 1 + rng.below(max_len);
+    // Mostly letters, like real hrps, but any printable ASCII character other than uppercase.
+    (0..len)
+        .map(|_| match rng.below(4) {
+            0 => (33 + rng.below(94)) as u8 as char,
+            _ => (b'a' + rng.below(26) as u8) as char,
+        })
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn random_bytes(rng: &mut XorShift, max_len: usize) -> Vec<u8> {
+    let len = rng.below(max_len + 1);
+    (0..len).map(|_| rng.next() as u8).collect()
+}
+
+/// Changes `s` in a small random way, or not at all.
+fn mutate(rng: &mut XorShift, s: &str) -> String {
+    // Valid characters, characters that are only invalid in some places, and invalid characters.
+    let chars: Vec<char> =
+        "qpzry9x8gf2tvdw0s3jn54khce6mua7lQPZRY9X8GF2TVDW0S3JN54KHCE6MUA7L1bioBIO ~!\u{7f}é"
+            .chars()
+            .collect();
+
+    let mut chars_of_s: Vec<char> = s.chars().collect();
+    let len = chars_of_s.len();
+    match rng.below(8) {
+        0 => chars_of_s[rng.below(len)] = rng.pick(&chars),
+        1 => chars_of_s.insert(rng.below(len + 1), rng.pick(&chars)),
+        2 => {
+            chars_of_s.remove(rng.below(len));
+        }
+        3 => {
+            let i = rng.below(len);
+            chars_of_s[i] = chars_of_s[i].to_ascii_uppercase();
+        }
+        4 => return s.to_uppercase(),
+        5 => {
+            let (i, j) = (rng.below(len), rng.below(len));
+            chars_of_s.swap(i, j);
+        }
+        _ => {}
+    }
+    chars_of_s.into_iter().collect()
+}
+
+/// Decodes `s` with the crate, returning the same as [`bip::bech32_decode`].
+fn crate_bech32_decode(s: &str) -> Option<(String, Vec<u8>, Encoding)> {
+    let unchecked = UncheckedHrpstring::new_with_profile(s, DecodeProfile::BIP173).ok()?;
+    let hrp = unchecked.hrp().to_lowercase();
+
+    let (spec, checked) = if unchecked.has_valid_checksum::<Bech32>() {
+        (Encoding::Bech32, unchecked.remove_checksum::<Bech32>())
+    } else if unchecked.has_valid_checksum::<Bech32m>() {
+        (Encoding::Bech32m, unchecked.remove_checksum::<Bech32m>())
+    } else {
+        return None;
+    };
+    let data = checked
+        .data_part_ascii_no_checksum()
+        .iter()
+        .map(|&b| Fe32::from_char(char::from(b)).expect("valid char").to_u8())
+        .collect();
+
+    Some((hrp, data, spec))
+}
+
+/// Decodes `s` with the crate, returning the same as [`bip::decode`] with the decoded hrp.
+fn crate_segwit_decode(s: &str) -> Option<(String, u8, Vec<u8>)> {
+    let (hrp, version, program) = segwit::decode(s).ok()?;
+    Some((hrp.to_lowercase(), version.to_u8(), program))
+}
+
+#[test]
+fn encode_matches_reference() {
+    run("encode_matches_reference", |rng| {
+        let hrp = random_hrp(rng, 20);
+        let data = random_bytes(rng, 50);
+        let fes = bip::convertbits(&data, 8, 5, true).unwrap();
+
+        let parsed = Hrp::parse(&hrp).expect("valid hrp");
+        let got = bech32grs::encode::<Bech32>(parsed, &data).expect("valid length");
+        assert_eq!(got, bip::bech32_encode(&hrp, &fes, Encoding::Bech32));
+        let got = bech32grs::encode::<Bech32m>(parsed, &data).expect("valid length");
+        assert_eq!(got, bip::bech32_encode(&hrp, &fes, Encoding::Bech32m));
+    });
+}
+
+#[test]
+fn decode_matches_reference() {
+    run("decode_matches_reference", |rng| {
+        let hrp = random_hrp(rng, 20);
+        let fes: Vec<u8> = (0..rng.below(60)).map(|_| rng.below(32) as u8).collect();
+        let spec = if rng.below(2) == 0 { Encoding::Bech32 } else { Encoding::Bech32m };
+
+        let s = mutate(rng, &bip::bech32_encode(&hrp, &fes, spec));
+        assert_eq!(crate_bech32_decode(&s), bip::bech32_decode(&s), "{:?}", s);
+    });
+}
+
+#[test]
+fn decode_random_ascii_matches_reference() {
+    run("decode_random_ascii_matches_reference", |rng| {
+        // Short enough that a valid checksum is not vanishingly rare.
+        let len = rng.below(12);
+        let mut s: String = (0..len).map(|_| (rng.below(128) as u8) as char).collect();
+        s.insert(rng.below(len + 1), '1');
+        assert_eq!(crate_bech32_decode(&s), bip::bech32_decode(&s), "{:?}", s);
+    });
+}
+
+#[test]
+fn segwit_encode_matches_reference() {
+    run("segwit_encode_matches_reference", |rng| {
+        let hrp = random_hrp(rng, 10);
+        let version = rng.below(17) as u8;
+        let program = random_bytes(rng, 42);
+
+        let got = segwit::encode(
+            Hrp::parse(&hrp).expect("valid hrp"),
+            Fe32::try_from(version).expect("valid version"),
+            &program,
+        );
+        assert_eq!(got.ok(), bip::encode(&hrp, version, &program));
+    });
+}
+
+#[test]
+fn segwit_decode_matches_reference() {
+    run("segwit_decode_matches_reference", |rng| {
+        let hrp = random_hrp(rng, 10);
+        // Include invalid versions, lengths and checksums, and programs with bad padding.
+        let version = rng.below(20) as u8;
+        let mut fes = vec![version];
+        match rng.below(3) {
+            0 => fes.extend((0..rng.below(70)).map(|_| rng.below(32) as u8)),
+            _ => fes.extend(bip::convertbits(&random_bytes(rng, 42), 8, 5, true).unwrap()),
+        }
+        let spec = match (version, rng.below(8)) {
+            (_, 0) => Encoding::Bech32,
+            (_, 1) => Encoding::Bech32m,
+            (0, _) => Encoding::Bech32,
+            _ => Encoding::Bech32m,
+        };
+
+        let s = mutate(rng, &bip::bech32_encode(&hrp, &fes, spec));
+        let want = bip::bech32_decode(&s)
+            .and_then(|(hrp, _, _)| bip::decode(&hrp, &s).map(|(v, p)| (hrp, v, p)));
+        assert_eq!(crate_segwit_decode(&s), want, "{:?}", s);
+    });
+}
@@ -0,0 +1,110 @@
+//! Tests that the value types round trip through `rkyv` and that corrupted archives are rejected.
+
+// Serializing needs the `alloc` feature of the `rkyv` dev-dependency, which needs a newer toolchain
+// than the MSRV.
+#![cfg(all(feature = "rkyv", test_deps))]
+
+use core::convert::TryFrom;
+
+use bech32grs::primitives::gf32::ArchivedFe32;
+use bech32grs::primitives::hrp::ArchivedHrp;
+use bech32grs::segwit::{
+    ArchivedCompactAddress, ArchivedWitnessProgram, CompactAddress, WitnessProgram,
+};
+use bech32grs::{hrp, Fe32, Hrp};
+use rkyv::rancor::Error;
+
+/// Asserts that the value round trips, returning the archive.
+macro_rules! roundtrip {
+    ($ty:ty, $value:expr) => {{
+        let value: $ty = $value;
+        let bytes = rkyv::to_bytes::<Error>(&value).expect("serializing to a vec never fails");
+        let got = rkyv::from_bytes::<$ty, Error>(&bytes).expect("valid archive");
+        assert_eq!(got, value);
+        bytes
+    }};
+}
+
+#[test]
+fn fe32() {
+    for b in 0..32_u8 {
+        let fe = Fe32::try_from(b).unwrap();
+        assert_eq!(&roundtrip!(Fe32, fe)[..], [b]);
+    }
+
+    let mut bytes = roundtrip!(Fe32, Fe32::L);
+    for &b in [32, 0xff].iter() {
+        bytes[0] = b;
+        assert!(rkyv::access::<ArchivedFe32, Error>(&bytes).is_err());
+        assert!(rkyv::from_bytes::<Fe32, Error>(&bytes).is_err());
+    }
+}
+
+#[test]
+fn human_readable_part() {
+    roundtrip!(Hrp, hrp::GRS);
+    roundtrip!(Hrp, Hrp::parse("TGRS").unwrap());
+    roundtrip!(Hrp, Hrp::parse(&"a".repeat(83)).unwrap());
+
+    let valid = roundtrip!(Hrp, hrp::GRS);
+    // The archive is the 83 byte buffer followed by the length, as a 4 byte aligned `u32`.
+    let size = valid.len() - 4;
+    let corruptions: [(usize, u8); 6] = [
+        (0, b'G'),  // Mixed case.
+        (1, b' '),  // Invalid character.
+        (1, 0xc3),  // Non-ASCII.
+        (3, b's'),  // Non-zero padding.
+        (size, 0),  // Empty.
+        (size, 84), // Too long.
+    ];
+    for &(i, b) in corruptions.iter() {
+        let mut bytes = valid.clone();
+        bytes[i] = b;
+        assert!(rkyv::access::<ArchivedHrp, Error>(&bytes).is_err(), "{} {}", i, b);
+        assert!(rkyv::from_bytes::<Hrp, Error>(&bytes).is_err(), "{} {}", i, b);
+    }
+}
+
+#[test]
+fn witness_program() {
+    // The archive is the version, the length and the zero padded program.
+    let valid = roundtrip!(WitnessProgram, WitnessProgram::new_p2wpkh([0xab; 20]));
+    assert_eq!(valid.len(), 42);
+    roundtrip!(WitnessProgram, WitnessProgram::new_p2tr([0xcd; 32]));
+    roundtrip!(WitnessProgram, WitnessProgram::new(Fe32::S, &[1; 40]).unwrap());
+
+    let corruptions: [(usize, u8); 5] = [
+        (0, 17), // Version 17.
+        (0, 32), // Not a field element.
+        (1, 21), // A version 0 program of 21 bytes.
+        (1, 41), // A program of 41 bytes.
+        (30, 1), // Non-zero padding.
+    ];
+    for &(i, b) in corruptions.iter() {
+        let mut bytes = valid.clone();
+        bytes[i] = b;
+        assert!(rkyv::access::<ArchivedWitnessProgram, Error>(&bytes).is_err(), "{} {}", i, b);
+        assert!(rkyv::from_bytes::<WitnessProgram, Error>(&bytes).is_err(), "{} {}", i, b);
+    }
+}
+
+#[test]
+fn compact_address() {
+    let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+    // The archive is the network, the version, the length and the zero padded program.
+    let valid = roundtrip!(CompactAddress, s.parse().expect("valid address"));
+    assert_eq!(valid.len(), 43);
+
+    let corruptions: [(usize, u8); 4] = [
+        (0, 3),  // Unknown network.
+        (1, 17), // Version 17.
+        (2, 21), // A version 0 program of 21 bytes.
+        (40, 1), // Non-zero padding.
+    ];
+    for &(i, b) in corruptions.iter() {
+        let mut bytes = valid.clone();
+        bytes[i] = b;
+        assert!(rkyv::access::<ArchivedCompactAddress, Error>(&bytes).is_err(), "{} {}", i, b);
+        assert!(rkyv::from_bytes::<CompactAddress, Error>(&bytes).is_err(), "{} {}", i, b);
+    }
+}
@@ -0,0 +1,75 @@
+//! Tests that decode failures emit `tracing` events.
+
+// The subscriber is a dev-dependency that needs a newer toolchain than the MSRV.
+#![cfg(all(feature = "tracing", test_deps))]
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use bech32grs::primitives::decode::{CheckedHrpstring, SegwitHrpstring, UncheckedHrpstring};
+use bech32grs::Bech32;
+
+/// A writer that keeps everything written to it, for inspecting the formatted events.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().expect("not poisoned").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// Runs `f` with a subscriber that captures debug events, returning the formatted events.
+fn events<F: FnOnce()>(f: F) -> String {
+    let buf = Buffer::default();
+    let writer = buf.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_max_level(tracing::Level::DEBUG)
+        .finish();
+    tracing::subscriber::with_default(subscriber, f);
+
+    let bytes = buf.0.lock().expect("not poisoned").clone();
+    String::from_utf8(bytes).expect("events are formatted as utf8")
+}
+
+const VALID: &str = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+const BAD_CHECKSUM: &str = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65q";
+
+#[test]
+fn no_event_on_success() {
+    let logged = events(|| {
+        assert!(UncheckedHrpstring::new(VALID).is_ok());
+        assert!(CheckedHrpstring::new::<Bech32>(VALID).is_ok());
+        assert!(SegwitHrpstring::new(VALID).is_ok());
+    });
+    assert_eq!(logged, "");
+}
+
+#[test]
+fn event_on_bad_checksum() {
+    let logged = events(|| {
+        assert!(SegwitHrpstring::new(BAD_CHECKSUM).is_err());
+    });
+    assert_eq!(logged.lines().count(), 1, "{}", logged);
+    assert!(logged.contains("DEBUG"), "{}", logged);
+    assert!(logged.contains("parser=\"CheckedHrpstring\""), "{}", logged);
+    assert!(logged.contains("code=\"E_CHECKSUM_INVALID\""), "{}", logged);
+    assert!(logged.contains("input_len=43"), "{}", logged);
+    assert!(!logged.contains(BAD_CHECKSUM), "the input must never be logged: {}", logged);
+}
+
+#[test]
+fn event_includes_position_of_invalid_char() {
+    let logged = events(|| {
+        assert!(
+            CheckedHrpstring::new::<Bech32>("grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg6bp").is_err()
+        );
+    });
+    assert_eq!(logged.lines().count(), 1, "{}", logged);
+    assert!(logged.contains("parser=\"UncheckedHrpstring\""), "{}", logged);
+    assert!(logged.contains("position=41"), "{}", logged);
+}
@@ -0,0 +1,5 @@
+use bech32grs::{hrp, Hrp};
+
+const EMPTY: Hrp = hrp!("");
+
+fn main() {}
@@ -0,0 +1,5 @@
+use bech32grs::{hrp, Hrp};
+
+const INVALID_CHAR: Hrp = hrp!("grs test");
+
+fn main() {}
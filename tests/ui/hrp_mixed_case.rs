@@ -0,0 +1,5 @@
+use bech32grs::{hrp, Hrp};
+
+const MIXED_CASE: Hrp = hrp!("gRs");
+
+fn main() {}
@@ -0,0 +1,5 @@
+use bech32grs::{hrp, Hrp};
+
+const TOO_LONG: Hrp = hrp!("an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber1");
+
+fn main() {}
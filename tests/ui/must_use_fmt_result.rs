@@ -0,0 +1,8 @@
+#![deny(unused_must_use)]
+
+use bech32grs::{hrp, segwit};
+
+fn main() {
+    let mut s = String::new();
+    segwit::encode_to_fmt_unchecked(&mut s, hrp::GRS, segwit::VERSION_0, &[0; 20]);
+}
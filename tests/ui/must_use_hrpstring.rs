@@ -0,0 +1,8 @@
+#![deny(unused_must_use)]
+
+use bech32grs::primitives::decode::UncheckedHrpstring;
+
+fn main() {
+    let s = "grs1qar0srrr7xfkvy5l643lydnw9re59gtzzncg65p";
+    UncheckedHrpstring::new(s).expect("valid characters").parse_data_chars();
+}
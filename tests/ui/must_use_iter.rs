@@ -0,0 +1,8 @@
+#![deny(unused_must_use)]
+
+use bech32grs::{hrp, Bech32, ByteIterExt, Fe32IterExt};
+
+fn main() {
+    let data = [0xab_u8; 20];
+    data.iter().copied().bytes_to_fes().with_checksum::<Bech32>(&hrp::GRS).chars();
+}